@@ -0,0 +1,118 @@
+use cosmwasm_std::{
+    Addr, Decimal, DepsMut, Env, Event, Order, Response, StdResult, Storage, Uint128,
+};
+use eris::circuit_breaker::{self, PausableAction};
+use eris::DecimalCheckedOps;
+use eris_chain_adapter::types::CustomQueryType;
+
+use crate::error::{ContractError, ContractResult};
+use crate::state::State;
+
+/// Pauses or resumes an action of all assets. The guardian can only pause, resuming requires the
+/// owner.
+pub fn set_paused(
+    deps: DepsMut<CustomQueryType>,
+    sender: Addr,
+    action: PausableAction,
+    paused: bool,
+) -> ContractResult {
+    let state = State::default();
+
+    if paused {
+        state.assert_guardian_or_owner(deps.storage, &sender)?;
+    } else {
+        state.assert_owner(deps.storage, &sender)?;
+    }
+
+    circuit_breaker::set_paused(deps.storage, &state.paused, action, paused)?;
+
+    Ok(Response::new()
+        .add_event(
+            Event::new("erishub/paused_updated")
+                .add_attribute("pausable_action", action.as_str())
+                .add_attribute("paused", paused.to_string()),
+        )
+        .add_attribute("action", "erishub/set_paused"))
+}
+
+pub fn assert_not_paused(
+    storage: &dyn Storage,
+    action: PausableAction,
+) -> Result<(), ContractError> {
+    Ok(circuit_breaker::assert_not_paused(storage, &State::default().paused, action)?)
+}
+
+/// Adds the amount to the rolling volume of the action of the asset and verifies it stays within
+/// its limit.
+pub fn track_volume(
+    storage: &mut dyn Storage,
+    env: &Env,
+    asset: &str,
+    action: PausableAction,
+    amount: Uint128,
+) -> Result<(), ContractError> {
+    let state = State::default();
+    Ok(circuit_breaker::track_volume(
+        storage,
+        &state.circuit_breaker,
+        &state.volumes,
+        &format!("{0}:{1}", action.as_str(), asset),
+        env.block.time.seconds(),
+        action,
+        amount,
+    )?)
+}
+
+/// Adds the unbonded ustake to the rolling unbond volume of the asset, valued in utoken at the
+/// last recorded exchange rate like the bond volume.
+pub fn track_unbond_volume(
+    storage: &mut dyn Storage,
+    env: &Env,
+    asset: &str,
+    ustake_amount: Uint128,
+) -> Result<(), ContractError> {
+    let exchange_rate = last_exchange_rate(storage, asset)?.unwrap_or_else(Decimal::one);
+    track_volume(
+        storage,
+        env,
+        asset,
+        PausableAction::Unbond,
+        exchange_rate.checked_mul_uint(ustake_amount)?,
+    )
+}
+
+fn last_exchange_rate(storage: &dyn Storage, asset: &str) -> StdResult<Option<Decimal>> {
+    Ok(State::default()
+        .asset_exchange_history
+        .prefix(asset)
+        .range(storage, None, None, Order::Descending)
+        .next()
+        .transpose()?
+        .map(|(_, exchange_rate)| exchange_rate))
+}
+
+/// Compares the exchange rate of the asset with its last recorded one. If it moved by more than
+/// the allowed change, all actions are paused until the owner resumes them.
+pub fn check_exchange_rate(
+    storage: &mut dyn Storage,
+    asset: &str,
+    exchange_rate: Decimal,
+) -> StdResult<Option<Event>> {
+    let state = State::default();
+
+    let previous = last_exchange_rate(storage, asset)?;
+    let tripped = circuit_breaker::check_exchange_rate(
+        storage,
+        &state.circuit_breaker,
+        &state.paused,
+        previous,
+        exchange_rate,
+    )?;
+
+    Ok(previous.filter(|_| tripped).map(|previous| {
+        Event::new("erishub/circuit_breaker_tripped")
+            .add_attribute("asset", asset)
+            .add_attribute("previous_exchange_rate", previous.to_string())
+            .add_attribute("exchange_rate", exchange_rate.to_string())
+    }))
+}
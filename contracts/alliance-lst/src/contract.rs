@@ -11,7 +11,7 @@ use crate::claim::exec_claim;
 use crate::constants::{CONTRACT_NAME, CONTRACT_VERSION};
 use crate::error::{ContractError, ContractResult};
 use crate::state::State;
-use crate::{execute, queries};
+use crate::{circuit_breaker, execute, queries, slashing};
 
 #[entry_point]
 pub fn instantiate(
@@ -59,21 +59,36 @@ pub fn execute(
             validators,
             withdrawals,
             stages,
-        } => execute::harvest(deps, env, validators, withdrawals, stages, info.sender),
+            asset,
+        } => execute::harvest(deps, env, validators, withdrawals, stages, info.sender, asset),
         ExecuteMsg::TuneDelegations {} => execute::tune_delegations(deps, env, info.sender),
         ExecuteMsg::Rebalance {
             min_redelegation,
-        } => execute::rebalance(deps, env, info.sender, min_redelegation),
-        ExecuteMsg::Reconcile {} => execute::reconcile(deps, env),
+            asset,
+        } => execute::rebalance(deps, env, info.sender, min_redelegation, asset),
+        ExecuteMsg::Reconcile {
+            asset,
+        } => execute::reconcile(deps, env, asset),
         ExecuteMsg::CheckSlashing {
             delegations,
             state_total_utoken_bonded,
-        } => {
-            execute::check_slashing(deps, env, info.sender, delegations, state_total_utoken_bonded)
-        },
+            asset,
+        } => slashing::check_slashing(
+            deps,
+            env,
+            info.sender,
+            delegations,
+            state_total_utoken_bonded,
+            asset,
+        ),
         ExecuteMsg::SubmitBatch {
             undelegations,
-        } => execute::submit_batch(deps, env, info.sender, undelegations),
+            asset,
+        } => execute::submit_batch(deps, env, info.sender, undelegations, asset),
+        ExecuteMsg::AddAsset {
+            utoken,
+            denom,
+        } => execute::add_asset(deps, env, info.sender, utoken, denom),
         ExecuteMsg::Callback(callback_msg) => callback(deps, env, info, callback_msg),
         ExecuteMsg::UpdateConfig {
             protocol_fee_contract,
@@ -87,8 +102,12 @@ pub fn execute(
             epoch_period,
             unbond_period,
             validator_proxy,
+            guardian,
+            remove_guardian,
+            circuit_breaker,
         } => execute::update_config(
             deps,
+            env,
             info.sender,
             protocol_fee_contract,
             protocol_reward_fee,
@@ -101,28 +120,31 @@ pub fn execute(
             epoch_period,
             unbond_period,
             validator_proxy,
+            guardian,
+            remove_guardian,
+            circuit_breaker,
         ),
+        ExecuteMsg::SetPaused {
+            action,
+            paused,
+        } => circuit_breaker::set_paused(deps, info.sender, action, paused),
         ExecuteMsg::QueueUnbond {
             receiver,
         } => {
             let state = State::default();
-            let stake_token = state.stake_token.load(deps.storage)?;
 
             if info.funds.len() != 1 {
                 return Err(ContractError::ExpectingSingleCoin {});
             }
 
-            if info.funds[0].denom != stake_token.denom {
-                return Err(ContractError::ExpectingAllianceStakeToken(
-                    info.funds[0].denom.to_string(),
-                ));
-            }
+            let asset = state.asset_key_by_denom(deps.storage, &info.funds[0].denom)?;
 
             execute::queue_unbond(
                 deps,
                 env,
                 api.addr_validate(&receiver.unwrap_or_else(|| info.sender.to_string()))?,
                 info.funds[0].amount,
+                asset,
             )
         },
         ExecuteMsg::Claim {
@@ -163,8 +185,13 @@ fn callback(
 pub fn query(deps: Deps<CustomQueryType>, env: Env, msg: QueryMsg) -> StdResult<Binary> {
     match msg {
         QueryMsg::Config {} => to_json_binary(&queries::config(deps)?),
-        QueryMsg::State {} => to_json_binary(&queries::state(deps, env)?),
-        QueryMsg::PendingBatch {} => to_json_binary(&queries::pending_batch(deps)?),
+        QueryMsg::State {
+            asset,
+        } => to_json_binary(&queries::state(deps, env, asset)?),
+        QueryMsg::Assets {} => to_json_binary(&queries::assets(deps)?),
+        QueryMsg::PendingBatch {
+            asset,
+        } => to_json_binary(&queries::pending_batch(deps, asset)?),
         QueryMsg::PreviousBatch(id) => to_json_binary(&queries::previous_batch(deps, id)?),
         QueryMsg::PreviousBatches {
             start_after,
@@ -192,25 +219,36 @@ pub fn query(deps: Deps<CustomQueryType>, env: Env, msg: QueryMsg) -> StdResult<
             limit,
             env,
         )?),
-        QueryMsg::WantedDelegations {} => to_json_binary(&queries::wanted_delegations(deps, env)?),
+        QueryMsg::WantedDelegations {
+            asset,
+        } => to_json_binary(&queries::wanted_delegations(deps, env, asset)?),
         QueryMsg::SimulateWantedDelegations {
             period,
-        } => to_json_binary(&queries::simulate_wanted_delegations(deps, env, period)?),
+            asset,
+        } => to_json_binary(&queries::simulate_wanted_delegations(deps, env, period, asset)?),
 
         QueryMsg::ExchangeRates {
             start_after,
             limit,
-        } => to_json_binary(&queries::query_exchange_rates(deps, env, start_after, limit)?),
-        QueryMsg::Delegations {} => to_json_binary(&queries::delegations(deps, env)?),
-        QueryMsg::SimulateUndelegations {} => {
-            to_json_binary(&queries::simulate_undelegations(deps, env)?)
-        },
+            asset,
+        } => to_json_binary(&queries::query_exchange_rates(deps, env, start_after, limit, asset)?),
+        QueryMsg::Delegations {
+            asset,
+        } => to_json_binary(&queries::delegations(deps, env, asset)?),
+        QueryMsg::SimulateUndelegations {
+            asset,
+        } => to_json_binary(&queries::simulate_undelegations(deps, env, asset)?),
+        QueryMsg::SlashingEvents {
+            start_after,
+            limit,
+        } => to_json_binary(&slashing::query_slashing_events(deps, start_after, limit)?),
     }
 }
 
 #[entry_point]
 pub fn migrate(deps: DepsMut, _env: Env, _msg: MigrateMsg) -> ContractResult {
     set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+    State::default().migrate_primary_asset(deps.storage)?;
 
     Ok(Response::new()
         .add_attribute("new_contract_name", CONTRACT_NAME)
@@ -1,4 +1,5 @@
 use cosmwasm_std::{OverflowError, Response, StdError};
+use eris::circuit_breaker::CircuitBreakerError;
 use eris_chain_adapter::types::CustomMsgType;
 use thiserror::Error;
 
@@ -25,6 +26,9 @@ pub enum ContractError {
     #[error("Unauthorized: sender is not operator")]
     UnauthorizedSenderNotOperator {},
 
+    #[error("Unauthorized: sender is not guardian")]
+    UnauthorizedSenderNotGuardian {},
+
     #[error("Expecting only single coin")]
     ExpectingSingleCoin {},
 
@@ -93,4 +97,25 @@ pub enum ContractError {
 
     #[error("Submit Batch Failed: {0}")]
     SubmitBatchFailure(String),
+
+    #[error("Asset {0} already exists")]
+    AssetAlreadyExists(String),
+
+    #[error("{0} is paused")]
+    Paused(String),
+
+    #[error("{0} volume limit exceeded")]
+    VolumeLimitExceeded(String),
+}
+
+impl From<CircuitBreakerError> for ContractError {
+    fn from(err: CircuitBreakerError) -> Self {
+        match err {
+            CircuitBreakerError::Std(err) => ContractError::Std(err),
+            CircuitBreakerError::Paused(action) => ContractError::Paused(action.as_str().into()),
+            CircuitBreakerError::VolumeLimitExceeded(action) => {
+                ContractError::VolumeLimitExceeded(action.as_str().into())
+            },
+        }
+    }
 }
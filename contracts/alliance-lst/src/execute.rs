@@ -6,12 +6,13 @@ use cosmwasm_std::{
     Order, Response, StdResult, Uint128, WasmMsg,
 };
 use cw2::set_contract_version;
-use eris::alliance_lst::{AllianceStakeToken, InstantiateMsg, Undelegation};
+use eris::alliance_lst::{AllianceStakeToken, ExecuteMsg, InstantiateMsg, Undelegation};
+use eris::circuit_breaker::{CircuitBreakerConfig, PausableAction};
 use eris::helper::validate_received_funds;
 use eris::{CustomEvent, CustomMsgExt, CustomResponse, DecimalCheckedOps};
 
 use eris::hub::{
-    Batch, CallbackMsg, DelegationStrategy, ExecuteMsg, FeeConfig, PendingBatch, SingleSwapConfig,
+    Batch, CallbackMsg, DelegationStrategy, FeeConfig, PendingBatch, SingleSwapConfig,
     UnbondRequest,
 };
 use eris_chain_adapter::types::{
@@ -20,6 +21,9 @@ use eris_chain_adapter::types::{
 };
 use itertools::Itertools;
 
+use crate::circuit_breaker::{
+    assert_not_paused, check_exchange_rate, track_unbond_volume, track_volume,
+};
 use crate::constants::get_reward_fee_cap;
 use crate::error::{ContractError, ContractResult};
 use crate::helpers::{get_wanted_delegations, query_all_delegations, query_delegations};
@@ -27,6 +31,7 @@ use crate::math::{
     compute_mint_amount, compute_redelegations_for_rebalancing, compute_unbond_amount,
     compute_undelegations, get_utoken_per_validator, mark_reconciled_batches, reconcile_batches,
 };
+use crate::slashing::detect_slashing;
 use crate::state::State;
 use crate::types::alliance_delegations::AllianceDelegations;
 use crate::types::gauges::TuneInfoGaugeLoader;
@@ -67,12 +72,6 @@ pub fn instantiate(
     state.operator.save(deps.storage, &deps.api.addr_validate(&msg.operator)?)?;
     state.epoch_period.save(deps.storage, &msg.epoch_period)?;
     state.unbond_period.save(deps.storage, &msg.unbond_period)?;
-    state.alliance_delegations.save(
-        deps.storage,
-        &AllianceDelegations {
-            delegations: HashMap::new(),
-        },
-    )?;
 
     // by default donations are set to false
     state.allow_donations.save(deps.storage, &false)?;
@@ -88,14 +87,22 @@ pub fn instantiate(
 
     let validators = state.get_validators(deps.storage, &deps.querier)?;
 
-    state.pending_batch.save(
+    state.asset_pending_batches.save(
         deps.storage,
+        &msg.utoken,
         &PendingBatch {
             id: 1,
             ustake_to_burn: Uint128::zero(),
             est_unbond_start_time: env.block.time.seconds() + msg.epoch_period,
         },
     )?;
+    state.asset_delegations.save(
+        deps.storage,
+        &msg.utoken,
+        &AllianceDelegations {
+            delegations: HashMap::new(),
+        },
+    )?;
 
     let delegation_strategy = msg.delegation_strategy.unwrap_or(DelegationStrategy::Uniform);
     state
@@ -104,10 +111,12 @@ pub fn instantiate(
 
     let sub_denom = msg.denom;
     let full_denom = chain.get_token_denom(env.contract.address, sub_denom.clone());
-    state.stake_token.save(
+    state.primary_asset.save(deps.storage, &msg.utoken)?;
+    state.assets.save(
         deps.storage,
+        &msg.utoken,
         &AllianceStakeToken {
-            utoken: msg.utoken,
+            utoken: msg.utoken.clone(),
             denom: full_denom.clone(),
             total_supply: Uint128::zero(),
             total_utoken_bonded: Uint128::zero(),
@@ -136,11 +145,21 @@ pub fn bond(
     funds: &[Coin],
     donate: bool,
 ) -> ContractResult {
+    assert_not_paused(deps.storage, PausableAction::Bond)?;
+
     let state = State::default();
-    let mut stake = state.stake_token.load(deps.storage)?;
-    let alliance_delegations = state.alliance_delegations.load(deps.storage)?;
+    // funds of an additional asset are bonded to it, anything else is validated against the primary asset
+    let asset = match funds.first() {
+        Some(coin) if state.assets.has(deps.storage, &coin.denom) => coin.denom.clone(),
+        _ => state.primary_asset.load(deps.storage)?,
+    };
+    let mut stake = state.assets.load(deps.storage, &asset)?;
+    let alliance_delegations = state.asset_delegations.load(deps.storage, &asset)?;
 
     let token_to_bond = validate_received_funds(funds, &stake.utoken)?;
+    if !donate {
+        track_volume(deps.storage, &env, &asset, PausableAction::Bond, token_to_bond)?;
+    }
 
     let new_delegation = find_new_delegation(
         &state,
@@ -180,8 +199,8 @@ pub fn bond(
     };
 
     stake.total_utoken_bonded = stake.total_utoken_bonded.checked_add(token_to_bond)?;
-    state.stake_token.save(deps.storage, &stake)?;
-    alliance_delegations.delegate(&new_delegation)?.save(&state, deps.storage)?;
+    state.assets.save(deps.storage, &asset, &stake)?;
+    alliance_delegations.delegate(&new_delegation)?.save(&state, deps.storage, &asset)?;
 
     Ok(Response::new()
         .add_message(new_delegation.to_cosmos_msg(env.contract.address.to_string()))
@@ -198,9 +217,14 @@ pub fn harvest(
     withdrawals: Option<Vec<(WithdrawType, DenomType)>>,
     stages: Option<Vec<Vec<SingleSwapConfig>>>,
     sender: Addr,
+    asset: Option<String>,
 ) -> ContractResult {
+    assert_not_paused(deps.storage, PausableAction::Harvest)?;
+
     let state = State::default();
-    let stake = state.stake_token.load(deps.storage)?;
+    let asset = state.asset_key(deps.storage, asset)?;
+    let slashing_events = detect_slashing(deps.storage, &*deps.querier, &env, &asset)?;
+    let stake = state.assets.load(deps.storage, &asset)?;
 
     // 1. Withdraw delegation rewards
     let withdraw_submsgs: Vec<CosmosMsg<CustomMsgType>> = if let Some(validators) = validators {
@@ -217,7 +241,7 @@ pub fn harvest(
             .collect()
     } else {
         query_all_delegations(
-            &state.alliance_delegations.load(deps.storage)?,
+            &state.asset_delegations.load(deps.storage, &asset)?,
             &deps.querier,
             &env.contract.address,
             &stake.utoken,
@@ -268,12 +292,7 @@ pub fn harvest(
         // 3 swap - multiple single stage swaps
         .add_optional_callbacks(&env, swap_msgs)?
         // 4. apply received total utoken to unlocked_coins
-        .add_message(check_received_coin_msg(
-            &deps,
-            &env,
-            state.stake_token.load(deps.storage)?,
-            None,
-        )?)
+        .add_message(check_received_coin_msg(&deps, &env, stake, None)?)
         // 5. restake unlocked_coins
         .add_callback(
             &env,
@@ -281,6 +300,7 @@ pub fn harvest(
                 skip_fee,
             },
         )?
+        .add_events(slashing_events)
         .add_attribute("action", "erishub/harvest"))
 }
 
@@ -397,10 +417,14 @@ fn validate_no_utoken_or_ustake_swap(
     Ok(())
 }
 
-fn validate_no_belief_price(stages: &Vec<Vec<SingleSwapConfig>>) -> Result<(), ContractError> {
+fn validate_no_belief_price(
+    env: &Env,
+    stages: &Vec<Vec<SingleSwapConfig>>,
+) -> Result<(), ContractError> {
+    let chain = chain(env);
     for stage in stages {
-        for (_, _, belief_price, _, _) in stage {
-            if belief_price.is_some() {
+        for (stage_type, denom, belief_price, _, _) in stage {
+            if belief_price.is_some() && !chain.requires_belief_price(stage_type, denom) {
                 return Err(ContractError::BeliefPriceNotAllowed {});
             }
         }
@@ -450,10 +474,10 @@ pub fn reinvest(deps: DepsMut<CustomQueryType>, env: Env, skip_fee: bool) -> Con
     let state = State::default();
     let fee_config = state.fee_config.load(deps.storage)?;
     let mut unlocked_coins = state.unlocked_coins.load(deps.storage)?;
-    let mut stake = state.stake_token.load(deps.storage)?;
-    let mut alliance_delegations = state.alliance_delegations.load(deps.storage)?;
+    let assets = state.all_assets(deps.storage)?;
 
     if unlocked_coins.is_empty() {
+        let stake = &assets[0];
         return Err(ContractError::NoTokensAvailable(format!(
             "{0}, {1}",
             stake.utoken, stake.denom
@@ -469,71 +493,94 @@ pub fn reinvest(deps: DepsMut<CustomQueryType>, env: Env, skip_fee: bool) -> Con
         fee_config.protocol_reward_fee
     };
 
-    for coin in unlocked_coins.iter() {
-        let available = coin.amount;
-        let protocol_fee = protocol_reward_fee.checked_mul_uint(available)?;
-        let remaining = available.saturating_sub(protocol_fee);
-
-        let send_fee = if coin.denom == stake.utoken {
-            let to_bond = remaining;
-            // if receiving normal utoken -> restake
-            let new_delegation = find_new_delegation(
-                &state,
-                &deps,
-                &env,
-                &alliance_delegations,
-                to_bond,
-                &stake.utoken,
-            )?;
+    let mut exchange_rate_attrs: Vec<Attribute> = vec![];
+    let mut circuit_breaker_events: Vec<Event> = vec![];
+    for (i, mut stake) in assets.into_iter().enumerate() {
+        let asset = stake.utoken.clone();
+        let mut alliance_delegations = state.asset_delegations.load(deps.storage, &asset)?;
+
+        for coin in unlocked_coins.iter() {
+            let available = coin.amount;
+            let protocol_fee = protocol_reward_fee.checked_mul_uint(available)?;
+            let remaining = available.saturating_sub(protocol_fee);
+
+            let send_fee = if coin.denom == stake.utoken {
+                let to_bond = remaining;
+                // if receiving normal utoken -> restake
+                let new_delegation = find_new_delegation(
+                    &state,
+                    &deps,
+                    &env,
+                    &alliance_delegations,
+                    to_bond,
+                    &stake.utoken,
+                )?;
 
-            event = event
-                .add_attribute("utoken_bonded", to_bond)
-                .add_attribute("utoken_protocol_fee", protocol_fee);
-
-            stake.total_utoken_bonded += to_bond;
-            alliance_delegations =
-                alliance_delegations.delegate(&new_delegation)?.save(&state, deps.storage)?;
-            msgs.push(new_delegation.to_cosmos_msg(env.contract.address.to_string()));
-            true
-        } else if coin.denom == stake.denom {
-            // if receiving ustake (staked utoken) -> burn
-            event = event
-                .add_attribute("ustake_burned", remaining)
-                .add_attribute("ustake_protocol_fee", protocol_fee);
-
-            stake.total_supply = stake.total_supply.checked_sub(remaining)?;
-            msgs.push(chain(&env).create_burn_msg(stake.denom.clone(), remaining));
-            true
-        } else {
-            // we can ignore other coins as we will only store utoken and ustake there
-            false
-        };
+                event = event
+                    .add_attribute("utoken_bonded", to_bond)
+                    .add_attribute("utoken_protocol_fee", protocol_fee);
 
-        if send_fee && !protocol_fee.is_zero() {
-            let send_fee = SendFee::new(
-                fee_config.protocol_fee_contract.clone(),
-                protocol_fee.u128(),
-                coin.denom.clone(),
-            );
-            msgs.push(send_fee.to_cosmos_msg());
+                stake.total_utoken_bonded += to_bond;
+                alliance_delegations = alliance_delegations.delegate(&new_delegation)?.save(
+                    &state,
+                    deps.storage,
+                    &asset,
+                )?;
+                msgs.push(new_delegation.to_cosmos_msg(env.contract.address.to_string()));
+                true
+            } else if coin.denom == stake.denom {
+                // if receiving ustake (staked utoken) -> burn
+                event = event
+                    .add_attribute("ustake_burned", remaining)
+                    .add_attribute("ustake_protocol_fee", protocol_fee);
+
+                stake.total_supply = stake.total_supply.checked_sub(remaining)?;
+                msgs.push(chain(&env).create_burn_msg(stake.denom.clone(), remaining));
+                true
+            } else {
+                // we can ignore other coins as we will only store utoken and ustake there
+                false
+            };
+
+            if send_fee && !protocol_fee.is_zero() {
+                let send_fee = SendFee::new(
+                    fee_config.protocol_fee_contract.clone(),
+                    protocol_fee.u128(),
+                    coin.denom.clone(),
+                );
+                msgs.push(send_fee.to_cosmos_msg());
+            }
         }
-    }
 
-    state.stake_token.save(deps.storage, &stake)?;
+        state.assets.save(deps.storage, &asset, &stake)?;
 
-    // remove the converted coins. Unlocked_coins track utoken ([TOKEN]) and ustake (amp[TOKEN]).
-    unlocked_coins.retain(|coin| coin.denom != stake.utoken && coin.denom != stake.denom);
-    state.unlocked_coins.save(deps.storage, &unlocked_coins)?;
+        // remove the converted coins. Unlocked_coins track utoken ([TOKEN]) and ustake (amp[TOKEN]).
+        unlocked_coins.retain(|coin| coin.denom != stake.utoken && coin.denom != stake.denom);
 
-    // update exchange_rate history
-    let exchange_rate = calc_current_exchange_rate(stake)?;
-    state.exchange_history.save(deps.storage, env.block.time.seconds(), &exchange_rate)?;
+        // update exchange_rate history, the primary asset comes first
+        let key = if i == 0 {
+            "exchange_rate".to_string()
+        } else {
+            format!("exchange_rate_{0}", asset)
+        };
+        let exchange_rate = calc_current_exchange_rate(stake)?;
+        circuit_breaker_events.extend(check_exchange_rate(deps.storage, &asset, exchange_rate)?);
+        state.asset_exchange_history.save(
+            deps.storage,
+            (&asset, env.block.time.seconds()),
+            &exchange_rate,
+        )?;
+        exchange_rate_attrs.push(attr(key, exchange_rate.to_string()));
+    }
+
+    state.unlocked_coins.save(deps.storage, &unlocked_coins)?;
 
     Ok(Response::new()
         .add_messages(msgs)
         .add_event(event)
+        .add_events(circuit_breaker_events)
         .add_attribute("action", "erishub/reinvest")
-        .add_attribute("exchange_rate", exchange_rate.to_string()))
+        .add_attributes(exchange_rate_attrs))
 }
 
 fn calc_current_exchange_rate(stake: AllianceStakeToken) -> Result<Decimal, ContractError> {
@@ -651,6 +698,15 @@ fn find_new_delegation(
         }
         | DelegationStrategy::Defined {
             ..
+        }
+        | DelegationStrategy::Performance {
+            ..
+        }
+        | DelegationStrategy::AllianceRewards {
+            ..
+        }
+        | DelegationStrategy::Composite {
+            ..
         } => {
             let current_delegations = query_all_delegations(
                 alliance_delegations,
@@ -706,12 +762,16 @@ pub fn queue_unbond(
     env: Env,
     receiver: Addr,
     ustake_to_burn: Uint128,
+    asset: String,
 ) -> ContractResult {
+    assert_not_paused(deps.storage, PausableAction::Unbond)?;
+    track_unbond_volume(deps.storage, &env, &asset, ustake_to_burn)?;
+
     let state = State::default();
 
-    let mut pending_batch = state.pending_batch.load(deps.storage)?;
+    let mut pending_batch = state.asset_pending_batches.load(deps.storage, &asset)?;
     pending_batch.ustake_to_burn += ustake_to_burn;
-    state.pending_batch.save(deps.storage, &pending_batch)?;
+    state.asset_pending_batches.save(deps.storage, &asset, &pending_batch)?;
 
     state.unbond_requests.update(
         deps.storage,
@@ -733,7 +793,10 @@ pub fn queue_unbond(
         start_time = "immediate".to_string();
         msgs.push(CosmosMsg::Wasm(WasmMsg::Execute {
             contract_addr: env.contract.address.into(),
-            msg: to_json_binary(&ExecuteMsg::SubmitBatch {})?,
+            msg: to_json_binary(&ExecuteMsg::SubmitBatch {
+                undelegations: None,
+                asset: Some(asset),
+            })?,
             funds: vec![],
         }));
     }
@@ -755,12 +818,15 @@ pub fn submit_batch(
     env: Env,
     sender: Addr,
     undelegations: Option<Vec<Undelegation>>,
+    asset: Option<String>,
 ) -> ContractResult {
     let state = State::default();
-    let mut stake = state.stake_token.load(deps.storage)?;
+    let asset = state.asset_key(deps.storage, asset)?;
+    let slashing_events = detect_slashing(deps.storage, &*deps.querier, &env, &asset)?;
+    let mut stake = state.assets.load(deps.storage, &asset)?;
     let unbond_period = state.unbond_period.load(deps.storage)?;
-    let pending_batch = state.pending_batch.load(deps.storage)?;
-    let alliance_delegations = state.alliance_delegations.load(deps.storage)?;
+    let pending_batch = state.asset_pending_batches.load(deps.storage, &asset)?;
+    let alliance_delegations = state.asset_delegations.load(deps.storage, &asset)?;
 
     let current_time = env.block.time.seconds();
     if current_time < pending_batch.est_unbond_start_time {
@@ -817,19 +883,22 @@ pub fn submit_batch(
             est_unbond_end_time: current_time + unbond_period,
         },
     )?;
+    state.batch_assets.save(deps.storage, pending_batch.id, &asset)?;
 
     let epoch_period = state.epoch_period.load(deps.storage)?;
-    state.pending_batch.save(
+    let next_id = state.next_batch_id(deps.storage)?;
+    state.asset_pending_batches.save(
         deps.storage,
+        &asset,
         &PendingBatch {
-            id: pending_batch.id + 1,
+            id: next_id,
             ustake_to_burn: Uint128::zero(),
             est_unbond_start_time: current_time + epoch_period,
         },
     )?;
 
     // validates that the amount is available and validator delegation exists
-    alliance_delegations.undelegate(&new_undelegations)?.save(&state, deps.storage)?;
+    alliance_delegations.undelegate(&new_undelegations)?.save(&state, deps.storage, &asset)?;
     let undelegate_msgs = new_undelegations
         .into_iter()
         .map(|d| d.to_cosmos_msg(env.contract.address.to_string(), stake.utoken.clone()))
@@ -838,7 +907,7 @@ pub fn submit_batch(
     // apply burn to the stored total supply and save state
     stake.total_utoken_bonded = stake.total_utoken_bonded.checked_sub(utoken_to_unbond)?;
     stake.total_supply = stake.total_supply.checked_sub(pending_batch.ustake_to_burn)?;
-    state.stake_token.save(deps.storage, &stake)?;
+    state.assets.save(deps.storage, &asset, &stake)?;
 
     let burn_msg: CosmosMsg<CustomMsgType> =
         chain(&env).create_burn_msg(stake.denom.clone(), pending_batch.ustake_to_burn);
@@ -852,13 +921,19 @@ pub fn submit_batch(
         .add_messages(undelegate_msgs)
         .add_message(burn_msg)
         .add_message(check_received_coin_msg(&deps, &env, stake, None)?)
+        .add_events(slashing_events)
         .add_event(event)
         .add_attribute("action", "erishub/unbond"))
 }
 
-pub fn reconcile(deps: DepsMut<CustomQueryType>, env: Env) -> ContractResult {
+pub fn reconcile(
+    deps: DepsMut<CustomQueryType>,
+    env: Env,
+    asset: Option<String>,
+) -> ContractResult {
     let state = State::default();
-    let stake = state.stake_token.load(deps.storage)?;
+    let asset = state.asset_key(deps.storage, asset)?;
+    let stake = state.assets.load(deps.storage, &asset)?;
     let current_time = env.block.time.seconds();
 
     // Load batches that have not been reconciled
@@ -874,10 +949,15 @@ pub fn reconcile(deps: DepsMut<CustomQueryType>, env: Env) -> ContractResult {
         })
         .collect::<StdResult<Vec<_>>>()?;
 
-    let mut batches = all_batches
-        .into_iter()
-        .filter(|b| current_time > b.est_unbond_end_time)
-        .collect::<Vec<_>>();
+    let mut batches = vec![];
+    for batch in all_batches {
+        // only batches of the same asset are reconciled against its balance
+        if current_time > batch.est_unbond_end_time
+            && state.batch_asset(deps.storage, batch.id)? == asset
+        {
+            batches.push(batch);
+        }
+    }
 
     let utoken_expected_received: Uint128 = batches.iter().map(|b| b.utoken_unclaimed).sum();
 
@@ -920,49 +1000,6 @@ pub fn reconcile(deps: DepsMut<CustomQueryType>, env: Env) -> ContractResult {
     Ok(Response::new().add_event(event).add_attribute("action", "erishub/reconcile"))
 }
 
-pub fn check_slashing(
-    deps: DepsMut<CustomQueryType>,
-    _env: Env,
-    sender: Addr,
-    current_delegations: Vec<(String, Uint128)>,
-    state_total_utoken_bonded: Uint128,
-) -> ContractResult {
-    let state = State::default();
-    state.assert_owner_or_operator(deps.storage, &sender)?;
-
-    let mut stake_token = state.stake_token.load(deps.storage)?;
-    let alliance_delegations = state.alliance_delegations.load(deps.storage)?;
-    let new_sum = Uint128::new(current_delegations.iter().map(|(_, amount)| amount.u128()).sum());
-
-    if stake_token.total_utoken_bonded != state_total_utoken_bonded {
-        return Err(ContractError::StateChanged("total_utoken_bonded".to_string()));
-    }
-
-    if alliance_delegations.delegations.len() != current_delegations.len() {
-        return Err(ContractError::StateChanged("delegations".to_string()));
-    }
-
-    if new_sum < state_total_utoken_bonded.multiply_ratio(95u128, 100u128) {
-        return Err(ContractError::StateChanged("big slash".to_string()));
-    }
-
-    let old = stake_token.total_utoken_bonded;
-    stake_token.total_utoken_bonded = new_sum;
-
-    let delegations = current_delegations.into_iter().collect::<HashMap<_, _>>();
-    state.alliance_delegations.save(
-        deps.storage,
-        &AllianceDelegations {
-            delegations,
-        },
-    )?;
-
-    Ok(Response::new()
-        .add_attribute("action", "erishub/check_slashing")
-        .add_attribute("old_utoken_bonded", old.to_string())
-        .add_attribute("new_utoken_bonded", new_sum.to_string()))
-}
-
 pub fn withdraw_unbonded(
     deps: DepsMut<CustomQueryType>,
     env: Env,
@@ -993,7 +1030,7 @@ pub fn withdraw_unbonded(
     // - has finished unbonding
     // If not sure whether the batches have been reconciled, the user should first invoke `ExecuteMsg::Reconcile`
     // before withdrawing.
-    let mut total_utoken_to_refund = Uint128::zero();
+    let mut utoken_to_refund_coins = Coins(vec![]);
     let mut ids: Vec<String> = vec![];
     for request in &requests {
         if let Ok(mut batch) = state.previous_batches.load(deps.storage, request.id) {
@@ -1003,12 +1040,16 @@ pub fn withdraw_unbonded(
 
                 ids.push(request.id.to_string());
 
-                total_utoken_to_refund += utoken_to_refund;
+                let utoken = state.batch_asset(deps.storage, request.id)?;
+                if !utoken_to_refund.is_zero() {
+                    utoken_to_refund_coins.add(&Coin::new(utoken_to_refund.u128(), utoken))?;
+                }
                 batch.total_shares -= request.shares;
                 batch.utoken_unclaimed -= utoken_to_refund;
 
                 if batch.total_shares.is_zero() {
                     state.previous_batches.remove(deps.storage, request.id)?;
+                    state.batch_assets.remove(deps.storage, request.id);
                 } else {
                     state.previous_batches.save(deps.storage, batch.id, &batch)?;
                 }
@@ -1018,21 +1059,21 @@ pub fn withdraw_unbonded(
         }
     }
 
-    if total_utoken_to_refund.is_zero() {
+    if utoken_to_refund_coins.0.is_empty() {
         return Err(ContractError::CantBeZero("withdrawable amount".into()));
     }
-    let stake = state.stake_token.load(deps.storage)?;
 
+    let utoken_refunded = utoken_to_refund_coins.0.iter().map(|c| c.to_string()).join(",");
     let refund_msg = CosmosMsg::Bank(BankMsg::Send {
         to_address: receiver.clone().into(),
-        amount: vec![Coin::new(total_utoken_to_refund.u128(), stake.utoken)],
+        amount: utoken_to_refund_coins.0,
     });
 
     let event = Event::new("erishub/unbonded_withdrawn")
         .add_attribute("ids", ids.join(","))
         .add_attribute("user", user)
         .add_attribute("receiver", receiver)
-        .add_attribute("utoken_refunded", total_utoken_to_refund);
+        .add_attribute("utoken_refunded", utoken_refunded);
 
     Ok(Response::new()
         .add_message(refund_msg)
@@ -1071,10 +1112,12 @@ pub fn rebalance(
     env: Env,
     sender: Addr,
     min_redelegation: Option<Uint128>,
+    asset: Option<String>,
 ) -> ContractResult {
     let state = State::default();
-    let stake = state.stake_token.load(deps.storage)?;
-    let alliance_delegations = state.alliance_delegations.load(deps.storage)?;
+    let asset = state.asset_key(deps.storage, asset)?;
+    let stake = state.assets.load(deps.storage, &asset)?;
+    let alliance_delegations = state.asset_delegations.load(deps.storage, &asset)?;
 
     state.assert_owner(deps.storage, &sender)?;
     let validators = state.get_validators(deps.storage, &deps.querier)?;
@@ -1098,7 +1141,7 @@ pub fn rebalance(
     .filter(|redelegation| redelegation.amount >= min_redelegation.u128())
     .collect::<Vec<_>>();
 
-    alliance_delegations.redelegate(&new_redelegations)?.save(&state, deps.storage)?;
+    alliance_delegations.redelegate(&new_redelegations)?.save(&state, deps.storage, &asset)?;
     let redelegate_msgs = new_redelegations
         .iter()
         .map(|rd| rd.to_cosmos_msg(env.contract.address.to_string()))
@@ -1122,6 +1165,58 @@ pub fn rebalance(
         .add_attribute("action", "erishub/rebalance"))
 }
 
+pub fn add_asset(
+    deps: DepsMut<CustomQueryType>,
+    env: Env,
+    sender: Addr,
+    utoken: String,
+    sub_denom: String,
+) -> ContractResult {
+    let state = State::default();
+    let chain = chain(&env);
+
+    state.assert_owner(deps.storage, &sender)?;
+
+    if state.assets.has(deps.storage, &utoken) {
+        return Err(ContractError::AssetAlreadyExists(utoken));
+    }
+
+    let full_denom = chain.get_token_denom(env.contract.address, sub_denom.clone());
+    let stake = AllianceStakeToken {
+        utoken: utoken.clone(),
+        denom: full_denom.clone(),
+        total_supply: Uint128::zero(),
+        total_utoken_bonded: Uint128::zero(),
+    };
+    validate_no_utoken_or_ustake_swap(&state.stages_preset.may_load(deps.storage)?, &stake)?;
+
+    let epoch_period = state.epoch_period.load(deps.storage)?;
+    let pending_batch = PendingBatch {
+        id: state.next_batch_id(deps.storage)?,
+        ustake_to_burn: Uint128::zero(),
+        est_unbond_start_time: env.block.time.seconds() + epoch_period,
+    };
+
+    state.assets.save(deps.storage, &utoken, &stake)?;
+    state.asset_pending_batches.save(deps.storage, &utoken, &pending_batch)?;
+    state.asset_delegations.save(
+        deps.storage,
+        &utoken,
+        &AllianceDelegations {
+            delegations: HashMap::new(),
+        },
+    )?;
+
+    let event = Event::new("erishub/asset_added")
+        .add_attribute("utoken", utoken)
+        .add_attribute("stake_token", full_denom.clone());
+
+    Ok(Response::new()
+        .add_message(chain.create_denom_msg(full_denom, sub_denom))
+        .add_event(event)
+        .add_attribute("action", "erishub/add_asset"))
+}
+
 pub fn transfer_ownership(
     deps: DepsMut<CustomQueryType>,
     sender: Addr,
@@ -1167,6 +1262,7 @@ pub fn accept_ownership(deps: DepsMut<CustomQueryType>, sender: Addr) -> Contrac
 #[allow(clippy::too_many_arguments)]
 pub fn update_config(
     deps: DepsMut<CustomQueryType>,
+    env: Env,
     sender: Addr,
     protocol_fee_contract: Option<String>,
     protocol_reward_fee: Option<Decimal>,
@@ -1179,6 +1275,9 @@ pub fn update_config(
     epoch_period: Option<u64>,
     unbond_period: Option<u64>,
     validator_proxy: Option<String>,
+    guardian: Option<String>,
+    remove_guardian: Option<bool>,
+    circuit_breaker: Option<CircuitBreakerConfig>,
 ) -> ContractResult {
     let state = State::default();
 
@@ -1226,12 +1325,14 @@ pub fn update_config(
     }
 
     if stages_preset.is_some() {
-        validate_no_utoken_or_ustake_swap(&stages_preset, &state.stake_token.load(deps.storage)?)?;
+        for stake_token in state.all_assets(deps.storage)? {
+            validate_no_utoken_or_ustake_swap(&stages_preset, &stake_token)?;
+        }
     }
 
     if let Some(stages_preset) = stages_preset {
-        // belief price is not allowed. We still store it with None, as otherwise a lot of additional logic is required to load it.
-        validate_no_belief_price(&stages_preset)?;
+        // belief price is only allowed for stages that can't be executed without it. We still store it with None, as otherwise a lot of additional logic is required to load it.
+        validate_no_belief_price(&env, &stages_preset)?;
         state.stages_preset.save(deps.storage, &stages_preset)?;
     }
 
@@ -1253,5 +1354,15 @@ pub fn update_config(
         state.default_max_spread.save(deps.storage, &default_max_spread)?;
     }
 
+    if let Some(guardian) = guardian {
+        state.guardian.save(deps.storage, &deps.api.addr_validate(&guardian)?)?;
+    } else if remove_guardian.unwrap_or(false) {
+        state.guardian.remove(deps.storage);
+    }
+
+    if let Some(circuit_breaker) = circuit_breaker {
+        state.circuit_breaker.save(deps.storage, &circuit_breaker.validate()?)?;
+    }
+
     Ok(Response::new().add_attribute("action", "erishub/update_config"))
 }
@@ -4,15 +4,20 @@ use cosmwasm_std::{Addr, Decimal, Env, QuerierWrapper, StdError, StdResult, Stor
 use eris::{
     alliance_lst::AllianceStakeToken,
     governance_helper::get_period,
+    helper::capped_shares,
     helpers::bps::BasicPoints,
     hub::{DelegationStrategy, WantedDelegationsShare},
+    uptime_oracle::get_uptimes,
 };
 use eris_chain_adapter::types::CustomQueryType;
 use itertools::Itertools;
 
 use crate::{
     state::State,
-    types::{alliance_delegations::AllianceDelegations, gauges::GaugeLoader, Delegation},
+    types::{
+        alliance_delegations::AllianceDelegations, alliance_querier::AllianceQuerier,
+        gauges::GaugeLoader, Delegation,
+    },
 };
 
 /// Query the amounts of Token a staker is delegating to a specific validator
@@ -111,6 +116,17 @@ pub(crate) fn get_wanted_delegations(
     let delegation_strategy =
         state.delegation_strategy.may_load(storage)?.unwrap_or(DelegationStrategy::Uniform {});
 
+    get_strategy_delegations(state, env, storage, querier, &loader, delegation_strategy)
+}
+
+fn get_strategy_delegations(
+    state: &State,
+    env: &Env,
+    storage: &dyn Storage,
+    querier: &QuerierWrapper<CustomQueryType>,
+    loader: &impl GaugeLoader,
+    delegation_strategy: DelegationStrategy<Addr>,
+) -> StdResult<(WantedDelegationsShare, bool)> {
     match delegation_strategy {
         DelegationStrategy::Uniform {} => {
             let validators = state.get_validators(storage, querier)?;
@@ -125,6 +141,7 @@ pub(crate) fn get_wanted_delegations(
                         .into_iter()
                         .map(|val| (val, share_per_validator))
                         .collect_vec(),
+                    parts: None,
                 },
                 // no need to store it
                 false,
@@ -140,6 +157,7 @@ pub(crate) fn get_wanted_delegations(
                     .into_iter()
                     .map(|(validator, bps)| Ok((validator, BasicPoints::try_from(bps)?.decimal())))
                     .collect::<StdResult<Vec<(String, Decimal)>>>()?,
+                parts: None,
             },
             // store it for get_utoken_per_validator
             true,
@@ -158,8 +176,8 @@ pub(crate) fn get_wanted_delegations(
             let vamp_factor = BasicPoints::try_from(amp_factor_bps)?.decimal();
             let emp_factor = Decimal::one().checked_sub(vamp_factor)?;
 
-            let vamp_context = Context::from_amps(&loader, querier, amp_gauges)?;
-            let emp_context = Context::from_emps(&loader, querier, emp_gauges)?;
+            let vamp_context = Context::from_amps(loader, querier, amp_gauges)?;
+            let emp_context = Context::from_emps(loader, querier, emp_gauges)?;
 
             let validators: Vec<_> = state
                 .get_validators(storage, querier)?
@@ -209,6 +227,149 @@ pub(crate) fn get_wanted_delegations(
                     shares: validators,
                     tune_time: env.block.time.seconds(),
                     tune_period: get_period(env.block.time.seconds())?,
+                    parts: None,
+                },
+                true,
+            ))
+        },
+        DelegationStrategy::Performance {
+            commission_cap_bps,
+            uptime_oracle,
+            min_score,
+            max_delegation_bps,
+            validator_count,
+        } => {
+            let commission_cap = BasicPoints::try_from(commission_cap_bps)?.decimal();
+            let max_delegation = BasicPoints::try_from(max_delegation_bps)?.decimal();
+
+            let validators = state.get_validators(storage, querier)?;
+            let uptimes = uptime_oracle
+                .map(|uptime_oracle| get_uptimes(querier, uptime_oracle, &validators))
+                .transpose()?;
+
+            let scores: Vec<_> = validators
+                .into_iter()
+                .map(|val| -> StdResult<Option<(String, Decimal)>> {
+                    // jailed or inactive validators are not returned by the staking module
+                    let validator = match querier.query_validator(&val)? {
+                        Some(validator) => validator,
+                        None => return Ok(None),
+                    };
+
+                    if validator.commission > commission_cap {
+                        return Ok(None);
+                    }
+
+                    // without an oracle every validator is expected to be fully online
+                    let uptime = match &uptimes {
+                        Some(uptimes) => uptimes.get(&val).copied().unwrap_or_default(),
+                        None => Decimal::one(),
+                    };
+
+                    let score =
+                        Decimal::one().checked_sub(validator.commission)?.checked_mul(uptime)?;
+                    Ok(Some((val, score)))
+                })
+                .collect::<StdResult<Vec<_>>>()?
+                .into_iter()
+                .flatten()
+                .filter(|(_, score)| !score.is_zero() && *score >= min_score)
+                .collect();
+
+            if scores.is_empty() {
+                return Err(StdError::generic_err(
+                    "No validator fulfills the performance criteria.",
+                ));
+            }
+
+            Ok((
+                WantedDelegationsShare {
+                    shares: shares_from_scores(scores, max_delegation, validator_count)?,
+                    tune_time: env.block.time.seconds(),
+                    tune_period: get_period(env.block.time.seconds())?,
+                    parts: None,
+                },
+                true,
+            ))
+        },
+        DelegationStrategy::AllianceRewards {
+            commission_cap_bps,
+            max_delegation_bps,
+            validator_count,
+        } => {
+            let alliance_querier = AllianceQuerier::new(&**querier);
+            let commission_cap = BasicPoints::try_from(commission_cap_bps)?.decimal();
+            let max_delegation = BasicPoints::try_from(max_delegation_bps)?.decimal();
+
+            // alliance rewards are distributed pro rata to the stake, so a delegated token earns the
+            // same rewards with every validator known to the alliance, apart from the commission
+            let mut scores = vec![];
+            for val in state.get_validators(storage, querier)? {
+                match alliance_querier.is_alliance_validator(val.clone())? {
+                    // without the alliance queries every validator receives the same delegation
+                    None => {
+                        return get_strategy_delegations(
+                            state,
+                            env,
+                            storage,
+                            querier,
+                            loader,
+                            DelegationStrategy::Uniform {},
+                        )
+                    },
+                    Some(false) => continue,
+                    Some(true) => (),
+                }
+
+                let validator = match querier.query_validator(&val)? {
+                    Some(validator) => validator,
+                    None => continue,
+                };
+
+                if validator.commission > commission_cap {
+                    continue;
+                }
+
+                let score = Decimal::one().saturating_sub(validator.commission);
+                if !score.is_zero() {
+                    scores.push((val, score));
+                }
+            }
+
+            if scores.is_empty() {
+                return Err(StdError::generic_err(
+                    "No validator fulfills the alliance reward criteria.",
+                ));
+            }
+
+            Ok((
+                WantedDelegationsShare {
+                    shares: shares_from_scores(scores, max_delegation, validator_count)?,
+                    tune_time: env.block.time.seconds(),
+                    tune_period: get_period(env.block.time.seconds())?,
+                    parts: None,
+                },
+                true,
+            ))
+        },
+        DelegationStrategy::Composite {
+            parts,
+        } => {
+            let parts = parts
+                .into_iter()
+                .map(|(strategy, weight_bps)| -> StdResult<(u16, Vec<(String, Decimal)>)> {
+                    let (share, _) =
+                        get_strategy_delegations(state, env, storage, querier, loader, strategy)?;
+                    Ok((weight_bps, share.shares))
+                })
+                .collect::<StdResult<Vec<_>>>()?;
+
+            Ok((
+                WantedDelegationsShare {
+                    shares: WantedDelegationsShare::merge_parts(&parts)?,
+                    tune_time: env.block.time.seconds(),
+                    tune_period: get_period(env.block.time.seconds())?,
+                    parts: Some(parts),
                 },
                 true,
             ))
@@ -216,6 +377,21 @@ pub(crate) fn get_wanted_delegations(
     }
 }
 
+/// Distributes the delegations by score, limited by the max delegation of a single validator
+fn shares_from_scores(
+    scores: Vec<(String, Decimal)>,
+    max_delegation: Decimal,
+    validator_count: u8,
+) -> StdResult<Vec<(String, Decimal)>> {
+    let scores = scores
+        .into_iter()
+        .sorted_by(|(_, a), (_, b)| b.cmp(a)) // Sort in descending order
+        .take(validator_count.into())
+        .collect();
+
+    capped_shares(scores, max_delegation)
+}
+
 struct Context {
     pub sum: Uint128,
     pub points: HashMap<String, Uint128>,
@@ -1,10 +1,12 @@
 #[cfg(not(feature = "library"))]
 pub mod contract;
 
+pub mod circuit_breaker;
 pub mod execute;
 pub mod helpers;
 pub mod math;
 pub mod queries;
+pub mod slashing;
 pub mod state;
 pub mod types;
 
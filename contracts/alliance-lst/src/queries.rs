@@ -1,5 +1,5 @@
 use crate::constants::DAY;
-use crate::helpers::{get_wanted_delegations, query_all_delegations};
+use crate::helpers::{get_wanted_delegations, query_all_delegations, query_all_delegations_amount};
 use crate::math::{
     compute_unbond_amount, compute_undelegations, get_utoken_per_validator_prepared,
 };
@@ -7,12 +7,13 @@ use crate::state::State;
 use crate::types::gauges::PeriodGaugeLoader;
 use cosmwasm_std::{Addr, Decimal, Deps, Env, Order, StdResult, Uint128};
 use cw_storage_plus::Bound;
-use eris::alliance_lst::{ConfigResponse, Undelegation};
+use eris::alliance_lst::{AllianceStakeToken, ConfigResponse, Undelegation};
 use eris::governance_helper::get_period;
 use eris::hub::{
     Batch, DelegationsResponse, ExchangeRatesResponse, PendingBatch, StateResponse,
     UnbondRequestsByBatchResponseItem, UnbondRequestsByUserResponseItem,
-    UnbondRequestsByUserResponseItemDetails, WantedDelegationsResponse,
+    UnbondRequestsByUserResponseItemDetails, WantedDelegationsPart, WantedDelegationsResponse,
+    WantedDelegationsShare,
 };
 use eris_chain_adapter::types::CustomQueryType;
 use itertools::Itertools;
@@ -24,7 +25,7 @@ const DEFAULT_LIMIT: u32 = 10;
 pub fn config(deps: Deps<CustomQueryType>) -> StdResult<ConfigResponse> {
     let state = State::default();
 
-    let stake = state.stake_token.load(deps.storage)?;
+    let stake = state.assets.load(deps.storage, &state.primary_asset.load(deps.storage)?)?;
 
     Ok(ConfigResponse {
         owner: state.owner.load(deps.storage)?.into(),
@@ -40,41 +41,27 @@ pub fn config(deps: Deps<CustomQueryType>) -> StdResult<ConfigResponse> {
         stages_preset: state.stages_preset.may_load(deps.storage)?.unwrap_or_default(),
         withdrawals_preset: state.withdrawals_preset.may_load(deps.storage)?.unwrap_or_default(),
         allow_donations: state.allow_donations.may_load(deps.storage)?.unwrap_or(false),
-        delegation_strategy: match state
+        delegation_strategy: state
             .delegation_strategy
             .may_load(deps.storage)?
             .unwrap_or(eris::hub::DelegationStrategy::Uniform)
-        {
-            eris::hub::DelegationStrategy::Uniform => eris::hub::DelegationStrategy::Uniform,
-            eris::hub::DelegationStrategy::Defined {
-                shares_bps,
-            } => eris::hub::DelegationStrategy::Defined {
-                shares_bps,
-            },
-            eris::hub::DelegationStrategy::Gauges {
-                amp_gauges,
-                emp_gauges,
-                amp_factor_bps,
-                min_delegation_bps,
-                max_delegation_bps,
-                validator_count,
-            } => eris::hub::DelegationStrategy::Gauges {
-                amp_gauges: amp_gauges.to_string(),
-                emp_gauges: emp_gauges.map(|a| a.to_string()),
-                amp_factor_bps,
-                min_delegation_bps,
-                max_delegation_bps,
-                validator_count,
-            },
-        },
-        validator_proxy: state.validator_proxy.load(deps.storage)?.to_string()
+            .into(),
+        validator_proxy: state.validator_proxy.load(deps.storage)?.to_string(),
+        guardian: state.guardian.may_load(deps.storage)?.map(|addr| addr.into()),
+        circuit_breaker: state.circuit_breaker.may_load(deps.storage)?,
+        paused: state.paused.may_load(deps.storage)?.unwrap_or_default(),
     })
 }
 
-pub fn state(deps: Deps<CustomQueryType>, env: Env) -> StdResult<StateResponse> {
+pub fn state(
+    deps: Deps<CustomQueryType>,
+    env: Env,
+    asset: Option<String>,
+) -> StdResult<StateResponse> {
     let state = State::default();
 
-    let stake_token = state.stake_token.load(deps.storage)?;
+    let asset = state.asset_key(deps.storage, asset)?;
+    let stake_token = state.assets.load(deps.storage, &asset)?;
     let total_ustake = stake_token.total_supply;
     let total_utoken = stake_token.total_utoken_bonded;
 
@@ -90,6 +77,7 @@ pub fn state(deps: Deps<CustomQueryType>, env: Env) -> StdResult<StateResponse>
             v
         })
         .filter(|item| item.est_unbond_end_time > env.block.time.seconds())
+        .filter(|item| state.batch_asset(deps.storage, item.id).unwrap() == asset)
         .map(|item| item.utoken_unclaimed.u128())
         .sum();
 
@@ -112,18 +100,27 @@ pub fn state(deps: Deps<CustomQueryType>, env: Env) -> StdResult<StateResponse>
     })
 }
 
+pub fn assets(deps: Deps<CustomQueryType>) -> StdResult<Vec<AllianceStakeToken>> {
+    let state = State::default();
+    state.all_assets(deps.storage)
+}
+
 pub fn wanted_delegations(
     deps: Deps<CustomQueryType>,
     _env: Env,
+    asset: Option<String>,
 ) -> StdResult<WantedDelegationsResponse> {
     let state = State::default();
-    let stake_token = state.stake_token.load(deps.storage)?;
+    let asset = state.asset_key(deps.storage, asset)?;
+    let stake_token = state.assets.load(deps.storage, &asset)?;
 
+    let utoken_staked = query_all_delegations_amount(&stake_token)?;
     let (delegations, _, _, share) =
         get_utoken_per_validator_prepared(&state, deps.storage, &stake_token, &deps.querier, None)?;
 
     Ok(WantedDelegationsResponse {
         delegations: sort_delegations(delegations),
+        parts: parts_delegations(&share, utoken_staked)?,
         tune_time_period: share.map(|s| (s.tune_time, s.tune_period)),
     })
 }
@@ -132,9 +129,11 @@ pub fn simulate_wanted_delegations(
     deps: Deps<CustomQueryType>,
     env: Env,
     period: Option<u64>,
+    asset: Option<String>,
 ) -> StdResult<WantedDelegationsResponse> {
     let state = State::default();
-    let stake_token = state.stake_token.load(deps.storage)?;
+    let asset = state.asset_key(deps.storage, asset)?;
+    let stake_token = state.assets.load(deps.storage, &asset)?;
 
     let period = period.unwrap_or(get_period(env.block.time.seconds())? + 1);
 
@@ -148,6 +147,7 @@ pub fn simulate_wanted_delegations(
         },
     )?;
 
+    let utoken_staked = query_all_delegations_amount(&stake_token)?;
     let (delegations, _, _, share) = get_utoken_per_validator_prepared(
         &state,
         deps.storage,
@@ -158,9 +158,21 @@ pub fn simulate_wanted_delegations(
 
     Ok(WantedDelegationsResponse {
         delegations: sort_delegations(delegations), // Sort in descending order
+        parts: parts_delegations(&share, utoken_staked)?,
         tune_time_period: share.map(|s| (s.tune_time, s.tune_period)),
     })
 }
+
+/// Breakdown of the wanted delegations for each part of a composite strategy
+fn parts_delegations(
+    share: &Option<WantedDelegationsShare>,
+    utoken_staked: u128,
+) -> StdResult<Option<Vec<WantedDelegationsPart>>> {
+    match share {
+        Some(share) => share.get_parts_delegations(Uint128::new(utoken_staked)),
+        None => Ok(None),
+    }
+}
 /// Sort delegations by amount descending and then by address ascending
 fn sort_delegations(
     delegations: std::collections::HashMap<String, Uint128>,
@@ -177,9 +189,13 @@ fn sort_delegations(
         .collect()
 }
 
-pub fn pending_batch(deps: Deps<CustomQueryType>) -> StdResult<PendingBatch> {
+pub fn pending_batch(
+    deps: Deps<CustomQueryType>,
+    asset: Option<String>,
+) -> StdResult<PendingBatch> {
     let state = State::default();
-    state.pending_batch.load(deps.storage)
+    let asset = state.asset_key(deps.storage, asset)?;
+    state.asset_pending_batches.load(deps.storage, &asset)
 }
 
 pub fn previous_batch(deps: Deps<CustomQueryType>, id: u64) -> StdResult<Batch> {
@@ -279,8 +295,6 @@ pub fn unbond_requests_by_user_details(
     let addr = deps.api.addr_validate(&user)?;
     let start = start_after.map(|id| Bound::exclusive((id, &addr)));
 
-    let pending = state.pending_batch.load(deps.storage)?;
-
     state
         .unbond_requests
         .idx
@@ -291,9 +305,10 @@ pub fn unbond_requests_by_user_details(
         .map(|item| {
             let (_, v) = item?;
 
+            let pending = state.find_pending_batch(deps.storage, v.id)?;
             let state_msg: String;
             let previous: Option<Batch>;
-            if pending.id == v.id {
+            if pending.is_some() {
                 state_msg = "PENDING".to_string();
                 previous = None;
             } else {
@@ -311,11 +326,7 @@ pub fn unbond_requests_by_user_details(
                 id: v.id,
                 shares: v.shares,
                 state: state_msg,
-                pending: if pending.id == v.id {
-                    Some(pending.clone())
-                } else {
-                    None
-                },
+                pending,
                 batch: previous,
             })
         })
@@ -327,13 +338,16 @@ pub fn query_exchange_rates(
     _env: Env,
     start_after: Option<u64>,
     limit: Option<u32>,
+    asset: Option<String>,
 ) -> StdResult<ExchangeRatesResponse> {
     let state = State::default();
     let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
     let end = start_after.map(Bound::exclusive);
 
+    let asset = state.asset_key(deps.storage, asset)?;
     let exchange_rates = state
-        .exchange_history
+        .asset_exchange_history
+        .prefix(&asset)
         .range(deps.storage, None, end, Order::Descending)
         .take(limit)
         .collect::<StdResult<Vec<(u64, Decimal)>>>()?;
@@ -356,9 +370,14 @@ pub fn query_exchange_rates(
     })
 }
 
-pub fn delegations(deps: Deps<CustomQueryType>, _env: Env) -> StdResult<DelegationsResponse> {
+pub fn delegations(
+    deps: Deps<CustomQueryType>,
+    _env: Env,
+    asset: Option<String>,
+) -> StdResult<DelegationsResponse> {
     let state = State::default();
-    let delegations = state.alliance_delegations.load(deps.storage)?;
+    let asset = state.asset_key(deps.storage, asset)?;
+    let delegations = state.asset_delegations.load(deps.storage, &asset)?;
 
     Ok(DelegationsResponse {
         delegations: delegations.delegations.into_iter().collect_vec(),
@@ -368,12 +387,14 @@ pub fn delegations(deps: Deps<CustomQueryType>, _env: Env) -> StdResult<Delegati
 pub fn simulate_undelegations(
     deps: Deps<CustomQueryType>,
     env: Env,
+    asset: Option<String>,
 ) -> StdResult<Vec<Undelegation>> {
     let state = State::default();
-    let stake = state.stake_token.load(deps.storage)?;
+    let asset = state.asset_key(deps.storage, asset)?;
+    let stake = state.assets.load(deps.storage, &asset)?;
     let validators = state.get_validators(deps.storage, &deps.querier)?;
-    let pending_batch = state.pending_batch.load(deps.storage)?;
-    let alliance_delegations = state.alliance_delegations.load(deps.storage)?;
+    let pending_batch = state.asset_pending_batches.load(deps.storage, &asset)?;
+    let alliance_delegations = state.asset_delegations.load(deps.storage, &asset)?;
 
     let delegations = query_all_delegations(
         &alliance_delegations,
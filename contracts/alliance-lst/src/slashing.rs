@@ -0,0 +1,212 @@
+use std::collections::HashMap;
+
+use cosmwasm_std::{
+    Addr, Decimal, Deps, DepsMut, Env, Event, Order, Querier, Response, StdError, StdResult,
+    Storage, Uint128,
+};
+use cw_storage_plus::Bound;
+use eris::alliance_lst::AllianceStakeToken;
+use eris::hub::{SlashingEvent, SlashingEventsResponse};
+use eris_chain_adapter::types::CustomQueryType;
+use itertools::Itertools;
+
+use crate::error::{ContractError, ContractResult};
+use crate::state::State;
+use crate::types::alliance_delegations::{AllianceDelegations, REDUCTION_AMOUNT};
+use crate::types::alliance_querier::AllianceQuerier;
+
+const MAX_LIMIT: u32 = 30;
+const DEFAULT_LIMIT: u32 = 10;
+
+/// Queries the delegation of every tracked validator from the alliance module and applies each
+/// decrease as a slashing. A delegation that is not found is fully slashed. Nothing happens if the
+/// alliance query is not available on the chain, in that case slashings need to be applied through
+/// `CheckSlashing`.
+pub fn detect_slashing(
+    storage: &mut dyn Storage,
+    querier: &dyn Querier,
+    env: &Env,
+    asset: &str,
+) -> StdResult<Vec<Event>> {
+    let state = State::default();
+    let mut stake = state.assets.load(storage, asset)?;
+    let mut alliance_delegations = state.asset_delegations.load(storage, asset)?;
+    let alliance_querier = AllianceQuerier::new(querier);
+
+    let mut slashed: Vec<(String, Uint128)> = vec![];
+    for (i, (validator, tracked)) in alliance_delegations.delegations.iter().sorted().enumerate() {
+        let current = match alliance_querier.query_delegation_amount(
+            stake.utoken.clone(),
+            env.contract.address.to_string(),
+            validator.clone(),
+        )? {
+            Some(current) => current,
+            // the support of the query is checked once, with the first validator
+            None if i == 0 => return Ok(vec![]),
+            None => return Err(StdError::generic_err("alliance delegation query not supported")),
+        };
+
+        // the alliance module sometimes rounds down delegations, which is not a slashing
+        if current.u128() + REDUCTION_AMOUNT < tracked.u128() {
+            slashed.push((validator.clone(), *tracked - current));
+        }
+    }
+
+    if slashed.is_empty() {
+        return Ok(vec![]);
+    }
+
+    let events = record_slashings(storage, env, &stake, &slashed)?;
+
+    for (validator, amount) in &slashed {
+        let remaining = alliance_delegations.delegations[validator] - *amount;
+        if remaining.is_zero() {
+            alliance_delegations.delegations.remove(validator);
+        } else {
+            alliance_delegations.delegations.insert(validator.clone(), remaining);
+        }
+        stake.total_utoken_bonded = stake.total_utoken_bonded.saturating_sub(*amount);
+    }
+    state.asset_delegations.save(storage, asset, &alliance_delegations)?;
+    state.assets.save(storage, asset, &stake)?;
+
+    Ok(events)
+}
+
+/// Applies the delegations provided by the owner or operator. Fallback for chains where the
+/// alliance query is not available.
+pub fn check_slashing(
+    deps: DepsMut<CustomQueryType>,
+    env: Env,
+    sender: Addr,
+    current_delegations: Vec<(String, Uint128)>,
+    state_total_utoken_bonded: Uint128,
+    asset: Option<String>,
+) -> ContractResult {
+    let state = State::default();
+    state.assert_owner_or_operator(deps.storage, &sender)?;
+
+    let asset = state.asset_key(deps.storage, asset)?;
+    let mut stake_token = state.assets.load(deps.storage, &asset)?;
+    let alliance_delegations = state.asset_delegations.load(deps.storage, &asset)?;
+    let new_sum = Uint128::new(current_delegations.iter().map(|(_, amount)| amount.u128()).sum());
+
+    if stake_token.total_utoken_bonded != state_total_utoken_bonded {
+        return Err(ContractError::StateChanged("total_utoken_bonded".to_string()));
+    }
+
+    if alliance_delegations.delegations.len() != current_delegations.len() {
+        return Err(ContractError::StateChanged("delegations".to_string()));
+    }
+
+    if new_sum < state_total_utoken_bonded.multiply_ratio(95u128, 100u128) {
+        return Err(ContractError::StateChanged("big slash".to_string()));
+    }
+
+    let delegations = current_delegations.into_iter().collect::<HashMap<_, _>>();
+    let slashed = alliance_delegations
+        .delegations
+        .iter()
+        .sorted()
+        .filter_map(|(validator, tracked)| {
+            let current = delegations.get(validator).copied().unwrap_or_default();
+            (current < *tracked).then(|| (validator.clone(), *tracked - current))
+        })
+        .collect_vec();
+    let events = record_slashings(deps.storage, &env, &stake_token, &slashed)?;
+
+    let old = stake_token.total_utoken_bonded;
+    stake_token.total_utoken_bonded = new_sum;
+    state.assets.save(deps.storage, &asset, &stake_token)?;
+
+    state.asset_delegations.save(
+        deps.storage,
+        &asset,
+        &AllianceDelegations {
+            delegations,
+        },
+    )?;
+
+    Ok(Response::new()
+        .add_events(events)
+        .add_attribute("action", "erishub/check_slashing")
+        .add_attribute("old_utoken_bonded", old.to_string())
+        .add_attribute("new_utoken_bonded", new_sum.to_string()))
+}
+
+/// Records the slashed amount of each validator in the slashing history
+fn record_slashings(
+    storage: &mut dyn Storage,
+    env: &Env,
+    stake: &AllianceStakeToken,
+    slashed: &[(String, Uint128)],
+) -> StdResult<Vec<Event>> {
+    let state = State::default();
+
+    let mut next_id = state
+        .slashing_events
+        .keys(storage, None, None, Order::Descending)
+        .next()
+        .transpose()?
+        .map_or(1, |id| id + 1);
+
+    let mut total_utoken = stake.total_utoken_bonded;
+    let mut events: Vec<Event> = vec![];
+    for (validator, amount) in slashed {
+        let rate_before = calc_exchange_rate(total_utoken, stake.total_supply);
+        total_utoken = total_utoken.saturating_sub(*amount);
+        let rate_after = calc_exchange_rate(total_utoken, stake.total_supply);
+
+        state.slashing_events.save(
+            storage,
+            next_id,
+            &SlashingEvent {
+                asset: stake.utoken.clone(),
+                validator: validator.clone(),
+                amount: *amount,
+                time: env.block.time.seconds(),
+                rate_before,
+                rate_after,
+            },
+        )?;
+        next_id += 1;
+
+        events.push(
+            Event::new("erishub/slashed")
+                .add_attribute("validator", validator)
+                .add_attribute("utoken_slashed", *amount)
+                .add_attribute("rate_before", rate_before.to_string())
+                .add_attribute("rate_after", rate_after.to_string()),
+        );
+    }
+
+    Ok(events)
+}
+
+fn calc_exchange_rate(total_utoken: Uint128, total_supply: Uint128) -> Decimal {
+    if total_supply.is_zero() {
+        Decimal::one()
+    } else {
+        Decimal::from_ratio(total_utoken, total_supply)
+    }
+}
+
+pub fn query_slashing_events(
+    deps: Deps<CustomQueryType>,
+    start_after: Option<u64>,
+    limit: Option<u32>,
+) -> StdResult<SlashingEventsResponse> {
+    let state = State::default();
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let end = start_after.map(Bound::exclusive);
+
+    let events = state
+        .slashing_events
+        .range(deps.storage, None, end, Order::Descending)
+        .take(limit)
+        .collect::<StdResult<Vec<_>>>()?;
+
+    Ok(SlashingEventsResponse {
+        events,
+    })
+}
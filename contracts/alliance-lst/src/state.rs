@@ -1,11 +1,12 @@
-use cosmwasm_std::{Addr, Coin, Decimal, QuerierWrapper, StdError, Storage};
+use cosmwasm_std::{Addr, Coin, Decimal, Order, QuerierWrapper, StdError, StdResult, Storage};
 use cw_storage_plus::{Index, IndexList, IndexedMap, Item, Map, MultiIndex};
 
 use eris::{
     alliance_lst::AllianceStakeToken,
+    circuit_breaker::{CircuitBreakerConfig, PauseState, VolumeTracker},
     hub::{
-        Batch, DelegationStrategy, FeeConfig, PendingBatch, SingleSwapConfig, UnbondRequest,
-        WantedDelegationsShare,
+        Batch, DelegationStrategy, FeeConfig, PendingBatch, SingleSwapConfig, SlashingEvent,
+        UnbondRequest, WantedDelegationsShare,
     },
 };
 use eris_chain_adapter::types::{CustomQueryType, DenomType, WithdrawType};
@@ -28,8 +29,8 @@ pub struct State<'a> {
 
     /// Pending ownership transfer, awaiting acceptance by the new owner
     pub new_owner: Item<'a, Addr>,
-    /// Denom and supply of the Liquid Staking token
-    pub stake_token: Item<'a, AllianceStakeToken>,
+    /// utoken of the primary asset, which is used if a message doesn't specify an asset
+    pub primary_asset: Item<'a, String>,
     /// How often the unbonding queue is to be executed
     pub epoch_period: Item<'a, u64>,
     /// The staking module's unbonding time, in seconds
@@ -38,13 +39,8 @@ pub struct State<'a> {
     pub validator_proxy: Item<'a, Addr>,
     pub validators_proxy_item: Item<'a, Vec<String>>,
 
-    /// stores all delegations
-    pub alliance_delegations: Item<'a, AllianceDelegations>,
-
     /// Coins that can be reinvested
     pub unlocked_coins: Item<'a, Vec<Coin>>,
-    /// The current batch of unbonding requests queded to be executed
-    pub pending_batch: Item<'a, PendingBatch>,
     /// Previous batches that have started unbonding but not yet finished
     pub previous_batches: IndexedMap<'a, u64, Batch, PreviousBatchesIndexes<'a>>,
     /// Users' shares in unbonding batches
@@ -58,10 +54,32 @@ pub struct State<'a> {
     /// Specifies wether the contract allows donations
     pub allow_donations: Item<'a, bool>,
 
-    // history of the exchange_rate
-    pub exchange_history: Map<'a, u64, Decimal>,
-
     pub default_max_spread: Item<'a, u64>,
+
+    /// History of detected slashings
+    pub slashing_events: Map<'a, u64, SlashingEvent>,
+
+    /// Denom and supply of the Liquid Staking token of every alliance asset by utoken, including the
+    /// primary asset
+    pub assets: Map<'a, &'a str, AllianceStakeToken>,
+    /// The current batch of unbonding requests of each asset queued to be executed
+    pub asset_pending_batches: Map<'a, &'a str, PendingBatch>,
+    /// Delegations of each asset
+    pub asset_delegations: Map<'a, &'a str, AllianceDelegations>,
+    /// History of the exchange rate of each asset
+    pub asset_exchange_history: Map<'a, (&'a str, u64), Decimal>,
+    /// utoken of the asset a previous batch belongs to. Batches submitted before multiple assets
+    /// were supported have no entry and belong to the primary asset.
+    pub batch_assets: Map<'a, u64, String>,
+
+    /// Account that can pause bond, unbond and harvest
+    pub guardian: Item<'a, Addr>,
+    /// Exchange rate and volume limits
+    pub circuit_breaker: Item<'a, CircuitBreakerConfig>,
+    /// Actions that are currently paused
+    pub paused: Item<'a, PauseState>,
+    /// Rolling volume of bonds and unbonds of every asset
+    pub volumes: Map<'a, &'a str, VolumeTracker>,
 }
 
 impl Default for State<'static> {
@@ -86,23 +104,30 @@ impl Default for State<'static> {
             operator: Item::new("operator"),
             stages_preset: Item::new("stages_preset"),
             withdrawals_preset: Item::new("withdrawals_preset"),
-            stake_token: Item::new("stake_token"),
+            primary_asset: Item::new("primary_asset"),
             epoch_period: Item::new("epoch_period"),
             unbond_period: Item::new("unbond_period"),
             validator_proxy: Item::new("validator_proxy"),
             validators_proxy_item: Item::new("validators"),
-            alliance_delegations: Item::new("alliance_delegations"),
 
             unlocked_coins: Item::new("unlocked_coins"),
-            pending_batch: Item::new("pending_batch"),
             previous_batches: IndexedMap::new("previous_batches", pb_indexes),
             unbond_requests: IndexedMap::new("unbond_requests", ubr_indexes),
             fee_config: Item::new("fee_config"),
             delegation_strategy: Item::new("delegation_strategy"),
             delegation_goal: Item::new("delegation_goal"),
             allow_donations: Item::new("allow_donations"),
-            exchange_history: Map::new("exchange_history"),
             default_max_spread: Item::new("default_max_spread"),
+            slashing_events: Map::new("slashing_events"),
+            assets: Map::new("assets"),
+            asset_pending_batches: Map::new("asset_pending_batches"),
+            asset_delegations: Map::new("asset_delegations"),
+            asset_exchange_history: Map::new("asset_exchange_history"),
+            batch_assets: Map::new("batch_assets"),
+            guardian: Item::new("guardian"),
+            circuit_breaker: Item::new("circuit_breaker"),
+            paused: Item::new("paused"),
+            volumes: Map::new("volumes"),
         }
     }
 }
@@ -117,6 +142,19 @@ impl<'a> State<'a> {
         }
     }
 
+    pub fn assert_guardian_or_owner(
+        &self,
+        storage: &dyn Storage,
+        sender: &Addr,
+    ) -> Result<(), ContractError> {
+        if self.guardian.may_load(storage)?.as_ref() == Some(sender) {
+            return Ok(());
+        }
+
+        self.assert_owner(storage, sender)
+            .map_err(|_| ContractError::UnauthorizedSenderNotGuardian {})
+    }
+
     pub fn assert_operator(
         &self,
         storage: &dyn Storage,
@@ -182,6 +220,115 @@ impl<'a> State<'a> {
         let validator_proxy = self.validator_proxy.load(storage)?;
         self.validators_proxy_item.query(querier, validator_proxy)
     }
+
+    /// Validates the asset parameter of a message and returns the utoken of the asset. Messages
+    /// without an asset use the primary asset.
+    pub fn asset_key(&self, storage: &dyn Storage, asset: Option<String>) -> StdResult<String> {
+        match asset {
+            None => self.primary_asset.load(storage),
+            Some(utoken) => {
+                if self.assets.has(storage, &utoken) {
+                    Ok(utoken)
+                } else {
+                    Err(StdError::generic_err(format!("asset {0} not found", utoken)))
+                }
+            },
+        }
+    }
+
+    /// Finds the asset by its stake token denom
+    pub fn asset_key_by_denom(
+        &self,
+        storage: &dyn Storage,
+        denom: &str,
+    ) -> Result<String, ContractError> {
+        for item in self.assets.range(storage, None, None, Order::Ascending) {
+            let (utoken, stake) = item?;
+            if stake.denom == denom {
+                return Ok(utoken);
+            }
+        }
+
+        Err(ContractError::ExpectingAllianceStakeToken(denom.to_string()))
+    }
+
+    /// Primary asset first, followed by the additional assets
+    pub fn all_assets(&self, storage: &dyn Storage) -> StdResult<Vec<AllianceStakeToken>> {
+        let primary_asset = self.primary_asset.load(storage)?;
+        let mut assets = vec![self.assets.load(storage, &primary_asset)?];
+        for item in self.assets.range(storage, None, None, Order::Ascending) {
+            let (utoken, stake) = item?;
+            if utoken != primary_asset {
+                assets.push(stake);
+            }
+        }
+        Ok(assets)
+    }
+
+    /// utoken of the asset a previous batch belongs to
+    pub fn batch_asset(&self, storage: &dyn Storage, id: u64) -> StdResult<String> {
+        match self.batch_assets.may_load(storage, id)? {
+            Some(utoken) => Ok(utoken),
+            None => self.primary_asset.load(storage),
+        }
+    }
+
+    /// Batch ids are shared by all assets, so that unbond requests and previous batches can stay
+    /// keyed by the id alone.
+    pub fn next_batch_id(&self, storage: &dyn Storage) -> StdResult<u64> {
+        let mut id = 0;
+        for item in self.asset_pending_batches.range(storage, None, None, Order::Ascending) {
+            id = id.max(item?.1.id);
+        }
+        Ok(id + 1)
+    }
+
+    pub fn find_pending_batch(
+        &self,
+        storage: &dyn Storage,
+        id: u64,
+    ) -> StdResult<Option<PendingBatch>> {
+        for item in self.asset_pending_batches.range(storage, None, None, Order::Ascending) {
+            let (_, pending) = item?;
+            if pending.id == id {
+                return Ok(Some(pending));
+            }
+        }
+        Ok(None)
+    }
+
+    /// Moves the primary asset from the items used before multiple assets were supported into the
+    /// asset maps. Does nothing if it has already been moved.
+    pub fn migrate_primary_asset(&self, storage: &mut dyn Storage) -> StdResult<()> {
+        let stake_token: Item<AllianceStakeToken> = Item::new("stake_token");
+        let pending_batch: Item<PendingBatch> = Item::new("pending_batch");
+        let alliance_delegations: Item<AllianceDelegations> = Item::new("alliance_delegations");
+        let exchange_history: Map<u64, Decimal> = Map::new("exchange_history");
+
+        let stake = match stake_token.may_load(storage)? {
+            Some(stake) => stake,
+            None => return Ok(()),
+        };
+        let utoken = stake.utoken.clone();
+
+        self.primary_asset.save(storage, &utoken)?;
+        self.assets.save(storage, &utoken, &stake)?;
+        self.asset_pending_batches.save(storage, &utoken, &pending_batch.load(storage)?)?;
+        self.asset_delegations.save(storage, &utoken, &alliance_delegations.load(storage)?)?;
+
+        let history = exchange_history
+            .range(storage, None, None, Order::Ascending)
+            .collect::<StdResult<Vec<_>>>()?;
+        for (time, exchange_rate) in history {
+            self.asset_exchange_history.save(storage, (&utoken, time), &exchange_rate)?;
+            exchange_history.remove(storage, time);
+        }
+
+        stake_token.remove(storage);
+        pending_batch.remove(storage);
+        alliance_delegations.remove(storage);
+        Ok(())
+    }
 }
 
 pub struct PreviousBatchesIndexes<'a> {
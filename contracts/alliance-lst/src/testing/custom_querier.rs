@@ -1,9 +1,14 @@
+use std::collections::HashMap;
+
 use cosmwasm_std::testing::{BankQuerier, StakingQuerier, MOCK_CONTRACT_ADDR};
 use cosmwasm_std::{
-    from_json, Addr, Coin, Empty, FullDelegation, Querier, QuerierResult, QueryRequest,
-    SystemError, WasmQuery,
+    from_json, to_json_binary, Addr, Coin, ContractResult, DecCoin, Decimal, Decimal256,
+    FullDelegation, Querier, QuerierResult, QueryRequest, SystemError, SystemResult, Uint128,
+    Validator, WasmQuery,
 };
+use eris_chain_shared::alliance_query::AllianceQuery;
 
+use crate::types::alliance_querier::{AllianceValidatorResponse, DelegationResponse};
 use crate::types::Delegation;
 
 use super::helpers::{err_unsupported_query, MOCK_UTOKEN};
@@ -12,8 +17,21 @@ use super::helpers::{err_unsupported_query, MOCK_UTOKEN};
 pub(super) struct CustomQuerier {
     pub bank_querier: BankQuerier,
     pub staking_querier: StakingQuerier,
+    pub validators: Vec<Validator>,
+    pub delegations: Vec<FullDelegation>,
+    /// Delegations returned by the alliance module, alliance queries are unsupported if not set.
+    /// Delegations that are not in the map are not found.
+    pub alliance_delegations: Option<HashMap<String, Uint128>>,
+    /// Validators for which the alliance delegation query fails
+    pub alliance_delegation_errors: Vec<String>,
+    /// Amount staked per alliance denom with each validator known to the alliance module, the
+    /// alliance validator query is unsupported if not set
+    pub alliance_validators: Option<HashMap<String, Vec<DecCoin>>>,
 }
 
+/// Validators returned by the validator proxy
+pub(super) const MOCK_VALIDATORS: [&str; 3] = ["alice", "bob", "charlie"];
+
 impl Querier for CustomQuerier {
     fn raw_query(&self, bin_request: &[u8]) -> QuerierResult {
         let request: QueryRequest<_> = match from_json(bin_request) {
@@ -47,10 +65,47 @@ impl CustomQuerier {
             })
             .collect::<Vec<_>>();
 
-        self.staking_querier = StakingQuerier::new(MOCK_UTOKEN, &[], &fds);
+        self.delegations = fds;
+        self.staking_querier =
+            StakingQuerier::new(MOCK_UTOKEN, &self.validators, &self.delegations);
+    }
+
+    /// Validators with their commission, validators not in the list are treated as jailed
+    pub fn set_staking_validators(&mut self, validators: &[(&str, Decimal)]) {
+        self.validators = validators
+            .iter()
+            .map(|(address, commission)| Validator {
+                address: address.to_string(),
+                commission: *commission,
+                max_commission: Decimal::one(),
+                max_change_rate: Decimal::one(),
+            })
+            .collect();
+
+        self.staking_querier =
+            StakingQuerier::new(MOCK_UTOKEN, &self.validators, &self.delegations);
+    }
+
+    pub fn set_alliance_total_staked(&mut self, validator: &str, total_staked: &[(&str, u128)]) {
+        self.alliance_validators.get_or_insert_with(HashMap::new).insert(
+            validator.to_string(),
+            total_staked
+                .iter()
+                .map(|(denom, amount)| DecCoin::new(Decimal256::from_ratio(*amount, 1u128), *denom))
+                .collect(),
+        );
     }
 
-    pub fn handle_query(&self, request: &QueryRequest<Empty>) -> QuerierResult {
+    pub fn set_alliance_delegations(&mut self, delegations: &[(&str, u128)]) {
+        self.alliance_delegations = Some(
+            delegations
+                .iter()
+                .map(|(validator, amount)| (validator.to_string(), Uint128::new(*amount)))
+                .collect(),
+        );
+    }
+
+    pub fn handle_query(&self, request: &QueryRequest<AllianceQuery>) -> QuerierResult {
         match request {
             QueryRequest::Wasm(WasmQuery::Smart {
                 msg,
@@ -63,10 +118,66 @@ impl CustomQuerier {
                 err_unsupported_query(msg)
             },
 
+            QueryRequest::Wasm(WasmQuery::Raw {
+                key,
+                ..
+            }) if key.as_slice() == b"validators" => {
+                SystemResult::Ok(ContractResult::Ok(to_json_binary(&MOCK_VALIDATORS).unwrap()))
+            },
+
             QueryRequest::Bank(query) => self.bank_querier.query(query),
 
             QueryRequest::Staking(query) => self.staking_querier.query(query),
 
+            QueryRequest::Custom(AllianceQuery::Delegation {
+                denom,
+                delegator,
+                validator,
+            }) => match &self.alliance_delegations {
+                Some(_) if self.alliance_delegation_errors.contains(validator) => {
+                    SystemResult::Ok(ContractResult::Err("[mock] query failed".to_string()))
+                },
+                Some(delegations) => match delegations.get(validator) {
+                    Some(amount) => SystemResult::Ok(ContractResult::Ok(
+                        to_json_binary(&DelegationResponse {
+                            denom: denom.clone(),
+                            delegator: delegator.clone(),
+                            validator: validator.clone(),
+                            amount: Coin::new(amount.u128(), denom.clone()),
+                        })
+                        .unwrap(),
+                    )),
+                    None => SystemResult::Ok(ContractResult::Err(
+                        "rpc error: code = NotFound desc = delegation not found".to_string(),
+                    )),
+                },
+                // the chain rejects custom queries of modules it doesn't have
+                None => SystemResult::Err(SystemError::UnsupportedRequest {
+                    kind: "alliance".to_string(),
+                }),
+            },
+
+            QueryRequest::Custom(AllianceQuery::Validator {
+                validator_addr,
+            }) => match &self.alliance_validators {
+                Some(validators) => match validators.get(validator_addr) {
+                    Some(total_staked) => SystemResult::Ok(ContractResult::Ok(
+                        to_json_binary(&AllianceValidatorResponse {
+                            validator_addr: validator_addr.clone(),
+                            total_staked: total_staked.clone(),
+                        })
+                        .unwrap(),
+                    )),
+                    None => SystemResult::Ok(ContractResult::Err(format!(
+                        "validator {} not found",
+                        validator_addr
+                    ))),
+                },
+                None => SystemResult::Err(SystemError::UnsupportedRequest {
+                    kind: "alliance".to_string(),
+                }),
+            },
+
             _ => err_unsupported_query(request),
         }
     }
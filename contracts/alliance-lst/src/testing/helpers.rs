@@ -73,9 +73,10 @@ pub(super) fn set_total_stake_supply(
     total_utoken_bonded: u128,
 ) {
     state
-        .stake_token
+        .assets
         .save(
             deps.as_mut().storage,
+            MOCK_UTOKEN,
             &AllianceStakeToken {
                 utoken: MOCK_UTOKEN.to_string(),
                 denom: get_stake_full_denom(),
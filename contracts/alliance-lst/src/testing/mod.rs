@@ -1,7 +1,11 @@
 mod custom_querier;
 mod helpers;
 pub mod test_defined_delegations;
+mod tests_alliance_rewards;
+mod tests_assets;
+mod tests_circuit_breaker;
 pub mod tests_claim;
 mod tests_default;
 pub mod tests_exchange_rates;
 pub mod tests_gauges;
+mod tests_slashing;
@@ -75,19 +75,25 @@ fn setup_test() -> OwnedDeps<MockStorage, MockApi, CustomQuerier, CustomQueryTyp
             shares: vec![
                 ("alice".into(), Decimal::from_str("0.6").unwrap()),
                 ("bob".into(), Decimal::from_str("0.4").unwrap())
-            ]
+            ],
+            parts: None,
         }
     );
 
-    let res: WantedDelegationsResponse =
-        query_helper(deps.as_ref(), QueryMsg::WantedDelegations {});
+    let res: WantedDelegationsResponse = query_helper(
+        deps.as_ref(),
+        QueryMsg::WantedDelegations {
+            asset: None,
+        },
+    );
 
     assert_eq!(
         res,
         WantedDelegationsResponse {
             tune_time_period: Some((EPOCH_START + WEEK, 1)),
             // nothing bonded yet
-            delegations: vec![("alice".into(), Uint128::zero()), ("bob".into(), Uint128::zero())]
+            delegations: vec![("alice".into(), Uint128::zero()), ("bob".into(), Uint128::zero())],
+            parts: None,
         },
     );
 
@@ -128,7 +134,12 @@ fn proper_instantiation() {
         }
     );
 
-    let res: StateResponse = query_helper(deps.as_ref(), QueryMsg::State {});
+    let res: StateResponse = query_helper(
+        deps.as_ref(),
+        QueryMsg::State {
+            asset: None,
+        },
+    );
     assert_eq!(
         res,
         StateResponse {
@@ -165,6 +176,9 @@ fn validate_update() {
             epoch_period: None,
             unbond_period: None,
             validator_proxy: None,
+            guardian: None,
+            remove_guardian: None,
+            circuit_breaker: None,
         },
     )
     .unwrap_err();
@@ -189,6 +203,9 @@ fn validate_update() {
             epoch_period: None,
             unbond_period: None,
             validator_proxy: None,
+            guardian: None,
+            remove_guardian: None,
+            circuit_breaker: None,
         },
     )
     .unwrap_err();
@@ -212,6 +229,9 @@ fn validate_update() {
             epoch_period: None,
             unbond_period: None,
             validator_proxy: None,
+            guardian: None,
+            remove_guardian: None,
+            circuit_breaker: None,
         },
     )
     .unwrap_err();
@@ -235,6 +255,9 @@ fn validate_update() {
             epoch_period: None,
             unbond_period: None,
             validator_proxy: None,
+            guardian: None,
+            remove_guardian: None,
+            circuit_breaker: None,
         },
     )
     .unwrap();
@@ -283,7 +306,7 @@ fn bonding() {
     deps.querier.set_bank_balances(&[coin(12345 + 222, MOCK_UTOKEN)]);
 
     assert_eq!(
-        State::default().stake_token.load(deps.as_ref().storage).unwrap(),
+        State::default().assets.load(deps.as_ref().storage, MOCK_UTOKEN).unwrap(),
         AllianceStakeToken {
             utoken: MOCK_UTOKEN.to_string(),
             denom: STAKE_DENOM.to_string(),
@@ -343,7 +366,12 @@ fn bonding() {
         Delegation::new("charlie", 354011, MOCK_UTOKEN),
     ]);
 
-    let res: StateResponse = query_helper(deps.as_ref(), QueryMsg::State {});
+    let res: StateResponse = query_helper(
+        deps.as_ref(),
+        QueryMsg::State {
+            asset: None,
+        },
+    );
     assert_eq!(
         res,
         StateResponse {
@@ -357,8 +385,12 @@ fn bonding() {
         }
     );
 
-    let res: WantedDelegationsResponse =
-        query_helper(deps.as_ref(), QueryMsg::WantedDelegations {});
+    let res: WantedDelegationsResponse = query_helper(
+        deps.as_ref(),
+        QueryMsg::WantedDelegations {
+            asset: None,
+        },
+    );
     assert_eq!(
         res,
         WantedDelegationsResponse {
@@ -370,7 +402,8 @@ fn bonding() {
             delegations: vec![
                 ("alice".into(), Uint128::new(622407)),
                 ("bob".into(), Uint128::new(414938))
-            ]
+            ],
+            parts: None,
         },
     );
 
@@ -380,6 +413,7 @@ fn bonding() {
         mock_info("alice", &[Coin::new(12345, MOCK_UTOKEN)]),
         ExecuteMsg::Rebalance {
             min_redelegation: None,
+            asset: None,
         },
     )
     .unwrap_err();
@@ -391,6 +425,7 @@ fn bonding() {
         mock_info("owner", &[Coin::new(12345, MOCK_UTOKEN)]),
         ExecuteMsg::Rebalance {
             min_redelegation: None,
+            asset: None,
         },
     )
     .unwrap();
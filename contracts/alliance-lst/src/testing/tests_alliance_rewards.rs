@@ -0,0 +1,144 @@
+use cosmwasm_std::testing::{mock_info, MockApi, MockStorage};
+use cosmwasm_std::{Decimal, OwnedDeps, StdError};
+
+use eris::alliance_lst::ExecuteMsg;
+use eris::hub::DelegationStrategy;
+use eris_chain_adapter::types::CustomQueryType;
+
+use crate::contract::execute;
+use crate::error::ContractError;
+use crate::state::State;
+use crate::testing::helpers::{setup_test, MOCK_UTOKEN};
+
+use super::custom_querier::CustomQuerier;
+use super::helpers::mock_env_at_timestamp;
+
+fn setup_alliance_rewards() -> OwnedDeps<MockStorage, MockApi, CustomQuerier, CustomQueryType> {
+    let mut deps = setup_test();
+
+    deps.querier.set_staking_validators(&[
+        ("alice", Decimal::percent(5)),
+        ("bob", Decimal::percent(5)),
+        ("charlie", Decimal::percent(20)),
+    ]);
+
+    execute(
+        deps.as_mut(),
+        mock_env_at_timestamp(10000),
+        mock_info("owner", &[]),
+        ExecuteMsg::UpdateConfig {
+            protocol_fee_contract: None,
+            protocol_reward_fee: None,
+            operator: None,
+            stages_preset: None,
+            withdrawals_preset: None,
+            allow_donations: None,
+            delegation_strategy: Some(DelegationStrategy::AllianceRewards {
+                commission_cap_bps: 1000,
+                max_delegation_bps: 10000,
+                validator_count: 2,
+            }),
+            default_max_spread: None,
+            epoch_period: None,
+            unbond_period: None,
+            validator_proxy: None,
+            guardian: None,
+            remove_guardian: None,
+            circuit_breaker: None,
+        },
+    )
+    .unwrap();
+
+    deps
+}
+
+fn tune(
+    deps: &mut OwnedDeps<MockStorage, MockApi, CustomQuerier, CustomQueryType>,
+) -> Result<(), ContractError> {
+    execute(
+        deps.as_mut(),
+        mock_env_at_timestamp(10000),
+        mock_info("owner", &[]),
+        ExecuteMsg::TuneDelegations {},
+    )
+    .map(|_| ())
+}
+
+//--------------------------------------------------------------------------------------------------
+// Execution
+//--------------------------------------------------------------------------------------------------
+
+#[test]
+fn tuning_by_alliance_rewards() {
+    let mut deps = setup_alliance_rewards();
+    let state = State::default();
+
+    deps.querier.set_alliance_total_staked("alice", &[(MOCK_UTOKEN, 300)]);
+    deps.querier.set_alliance_total_staked("bob", &[(MOCK_UTOKEN, 100)]);
+    deps.querier.set_alliance_total_staked("charlie", &[(MOCK_UTOKEN, 1000)]);
+    tune(&mut deps).unwrap();
+
+    // charlie is above the commission cap, the alliance stake doesn't change the rewards per token
+    assert_eq!(
+        state.delegation_goal.load(deps.as_ref().storage).unwrap().shares,
+        vec![
+            ("alice".to_string(), Decimal::percent(50)),
+            ("bob".to_string(), Decimal::percent(50))
+        ]
+    );
+
+    // the validators with the lowest commission are preferred
+    deps.querier.set_staking_validators(&[
+        ("alice", Decimal::percent(5)),
+        ("bob", Decimal::percent(2)),
+        ("charlie", Decimal::percent(1)),
+    ]);
+    tune(&mut deps).unwrap();
+
+    let shares = state.delegation_goal.load(deps.as_ref().storage).unwrap().shares;
+    assert_eq!(
+        shares.iter().map(|(validator, _)| validator.as_str()).collect::<Vec<_>>(),
+        vec!["charlie", "bob"]
+    );
+    assert!(shares[0].1 > shares[1].1);
+
+    deps.querier.set_staking_validators(&[
+        ("alice", Decimal::percent(20)),
+        ("bob", Decimal::percent(20)),
+        ("charlie", Decimal::percent(20)),
+    ]);
+    let err = tune(&mut deps).unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::Std(StdError::generic_err(
+            "No validator fulfills the alliance reward criteria."
+        ))
+    );
+}
+
+#[test]
+fn tuning_by_alliance_rewards_of_known_validators() {
+    let mut deps = setup_alliance_rewards();
+    let state = State::default();
+
+    // bob is not known to the alliance module and doesn't earn alliance rewards
+    deps.querier.set_alliance_total_staked("alice", &[(MOCK_UTOKEN, 100)]);
+    deps.querier.set_alliance_total_staked("charlie", &[(MOCK_UTOKEN, 300)]);
+    tune(&mut deps).unwrap();
+
+    assert_eq!(
+        state.delegation_goal.load(deps.as_ref().storage).unwrap().shares,
+        vec![("alice".to_string(), Decimal::one())]
+    );
+}
+
+#[test]
+fn degrading_to_uniform_without_alliance_query() {
+    let mut deps = setup_alliance_rewards();
+    let state = State::default();
+
+    tune(&mut deps).unwrap();
+
+    // uniform shares are not stored
+    assert_eq!(state.delegation_goal.may_load(deps.as_ref().storage).unwrap(), None);
+}
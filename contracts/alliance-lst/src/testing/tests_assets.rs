@@ -0,0 +1,313 @@
+use std::collections::HashMap;
+
+use cosmwasm_std::testing::{mock_info, MockApi, MockStorage, MOCK_CONTRACT_ADDR};
+use cosmwasm_std::{coin, BankMsg, CosmosMsg, Decimal, OwnedDeps, StdError, Uint128};
+use cw_storage_plus::{Item, Map};
+
+use eris::alliance_lst::{AllianceStakeToken, ExecuteMsg, QueryMsg, Undelegation};
+use eris::hub::{DelegationsResponse, PendingBatch};
+use eris_chain_adapter::types::{CustomQueryType, StageType};
+use eris_chain_shared::chain_trait::ChainInterface;
+
+use crate::contract::{execute, query};
+use crate::error::ContractError;
+use crate::state::State;
+use crate::testing::helpers::{
+    chain_test, get_stake_full_denom, mock_dependencies, query_helper, setup_test, MOCK_UTOKEN,
+};
+use crate::types::alliance_delegations::AllianceDelegations;
+
+use super::custom_querier::CustomQuerier;
+use super::helpers::mock_env_at_timestamp;
+
+const OTHER_UTOKEN: &str = "uother";
+
+fn other_stake_denom() -> String {
+    chain_test().get_token_denom(MOCK_CONTRACT_ADDR, "other".into())
+}
+
+fn add_asset_msg(utoken: &str) -> ExecuteMsg {
+    ExecuteMsg::AddAsset {
+        utoken: utoken.to_string(),
+        denom: "other".to_string(),
+    }
+}
+
+fn setup_assets() -> OwnedDeps<MockStorage, MockApi, CustomQuerier, CustomQueryType> {
+    let mut deps = setup_test();
+    let state = State::default();
+
+    execute(
+        deps.as_mut(),
+        mock_env_at_timestamp(10000),
+        mock_info("owner", &[]),
+        add_asset_msg(OTHER_UTOKEN),
+    )
+    .unwrap();
+
+    state
+        .assets
+        .save(
+            deps.as_mut().storage,
+            OTHER_UTOKEN,
+            &AllianceStakeToken {
+                utoken: OTHER_UTOKEN.to_string(),
+                denom: other_stake_denom(),
+                total_supply: Uint128::new(100000),
+                total_utoken_bonded: Uint128::new(100000),
+            },
+        )
+        .unwrap();
+    state
+        .asset_delegations
+        .save(
+            deps.as_mut().storage,
+            OTHER_UTOKEN,
+            &AllianceDelegations {
+                delegations: HashMap::from([("alice".to_string(), Uint128::new(100000))]),
+            },
+        )
+        .unwrap();
+
+    deps
+}
+
+//--------------------------------------------------------------------------------------------------
+// Execution
+//--------------------------------------------------------------------------------------------------
+
+#[test]
+fn adding_assets() {
+    let mut deps = setup_test();
+
+    let err = execute(
+        deps.as_mut(),
+        mock_env_at_timestamp(10000),
+        mock_info("user", &[]),
+        add_asset_msg(OTHER_UTOKEN),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+
+    let res = execute(
+        deps.as_mut(),
+        mock_env_at_timestamp(10000),
+        mock_info("owner", &[]),
+        add_asset_msg(OTHER_UTOKEN),
+    )
+    .unwrap();
+    assert_eq!(
+        res.messages[0].msg,
+        chain_test().create_denom_msg(other_stake_denom(), "other".to_string())
+    );
+
+    for utoken in &[OTHER_UTOKEN, MOCK_UTOKEN] {
+        let err = execute(
+            deps.as_mut(),
+            mock_env_at_timestamp(10000),
+            mock_info("owner", &[]),
+            add_asset_msg(utoken),
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::AssetAlreadyExists(utoken.to_string()));
+    }
+
+    let res: Vec<AllianceStakeToken> = query_helper(deps.as_ref(), QueryMsg::Assets {});
+    assert_eq!(
+        res.iter().map(|stake| stake.denom.clone()).collect::<Vec<_>>(),
+        vec![get_stake_full_denom(), other_stake_denom()]
+    );
+
+    // batch ids are shared by all assets
+    let res: PendingBatch = query_helper(
+        deps.as_ref(),
+        QueryMsg::PendingBatch {
+            asset: Some(OTHER_UTOKEN.to_string()),
+        },
+    );
+    assert_eq!(
+        res,
+        PendingBatch {
+            id: 2,
+            ustake_to_burn: Uint128::zero(),
+            est_unbond_start_time: 10000 + 259200,
+        }
+    );
+
+    let err = query(
+        deps.as_ref(),
+        mock_env_at_timestamp(10000),
+        QueryMsg::PendingBatch {
+            asset: Some("unknown".to_string()),
+        },
+    )
+    .unwrap_err();
+    assert_eq!(err, StdError::generic_err("asset unknown not found"));
+}
+
+#[test]
+fn unbonding_assets() {
+    let mut deps = setup_assets();
+    let state = State::default();
+
+    let err = execute(
+        deps.as_mut(),
+        mock_env_at_timestamp(20000),
+        mock_info("user", &[coin(1000, "unknown")]),
+        ExecuteMsg::QueueUnbond {
+            receiver: None,
+        },
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::ExpectingAllianceStakeToken("unknown".to_string()));
+
+    execute(
+        deps.as_mut(),
+        mock_env_at_timestamp(20000),
+        mock_info("user", &[coin(1000, other_stake_denom())]),
+        ExecuteMsg::QueueUnbond {
+            receiver: None,
+        },
+    )
+    .unwrap();
+
+    assert_eq!(
+        state
+            .asset_pending_batches
+            .load(deps.as_ref().storage, MOCK_UTOKEN)
+            .unwrap()
+            .ustake_to_burn,
+        Uint128::zero()
+    );
+    assert_eq!(
+        state
+            .asset_pending_batches
+            .load(deps.as_ref().storage, OTHER_UTOKEN)
+            .unwrap()
+            .ustake_to_burn,
+        Uint128::new(1000)
+    );
+
+    execute(
+        deps.as_mut(),
+        mock_env_at_timestamp(10000 + 259200),
+        mock_info("operator", &[]),
+        ExecuteMsg::SubmitBatch {
+            undelegations: Some(vec![Undelegation {
+                validator: "alice".to_string(),
+                amount: Uint128::new(1000),
+            }]),
+            asset: Some(OTHER_UTOKEN.to_string()),
+        },
+    )
+    .unwrap();
+
+    assert_eq!(state.batch_assets.load(deps.as_ref().storage, 2).unwrap(), OTHER_UTOKEN);
+    assert_eq!(
+        state.asset_pending_batches.load(deps.as_ref().storage, OTHER_UTOKEN).unwrap().id,
+        3
+    );
+    assert_eq!(state.asset_pending_batches.load(deps.as_ref().storage, MOCK_UTOKEN).unwrap().id, 1);
+
+    let res: DelegationsResponse = query_helper(
+        deps.as_ref(),
+        QueryMsg::Delegations {
+            asset: Some(OTHER_UTOKEN.to_string()),
+        },
+    );
+    assert_eq!(res.delegations, vec![("alice".to_string(), Uint128::new(99000))]);
+
+    // the batch is refunded in the utoken of its asset
+    let mut batch = state.previous_batches.load(deps.as_ref().storage, 2).unwrap();
+    batch.reconciled = true;
+    state.previous_batches.save(deps.as_mut().storage, 2, &batch).unwrap();
+
+    let res = execute(
+        deps.as_mut(),
+        mock_env_at_timestamp(10000 + 259200 + 1814401),
+        mock_info("user", &[]),
+        ExecuteMsg::WithdrawUnbonded {
+            receiver: None,
+        },
+    )
+    .unwrap();
+    assert_eq!(
+        res.messages[0].msg,
+        CosmosMsg::Bank(BankMsg::Send {
+            to_address: "user".to_string(),
+            amount: vec![coin(1000, OTHER_UTOKEN)],
+        })
+    );
+    assert!(!state.batch_assets.has(deps.as_ref().storage, 2));
+}
+
+#[test]
+fn harvesting_assets() {
+    let mut deps = setup_assets();
+
+    let harvest_msg = |asset: Option<String>| ExecuteMsg::Harvest {
+        validators: None,
+        withdrawals: None,
+        stages: Some(vec![vec![(StageType::fin("fin"), OTHER_UTOKEN.into(), None, None, None)]]),
+        asset,
+    };
+
+    // only the stages of the harvested asset are validated
+    execute(
+        deps.as_mut(),
+        mock_env_at_timestamp(20000),
+        mock_info("operator", &[]),
+        harvest_msg(None),
+    )
+    .unwrap();
+
+    let err = execute(
+        deps.as_mut(),
+        mock_env_at_timestamp(20000),
+        mock_info("operator", &[]),
+        harvest_msg(Some(OTHER_UTOKEN.to_string())),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::SwapFromNotAllowed(OTHER_UTOKEN.to_string()));
+}
+
+#[test]
+fn migrating_primary_asset() {
+    let mut deps = mock_dependencies();
+    let state = State::default();
+
+    let stake = AllianceStakeToken {
+        utoken: MOCK_UTOKEN.to_string(),
+        denom: get_stake_full_denom(),
+        total_supply: Uint128::new(1000),
+        total_utoken_bonded: Uint128::new(1100),
+    };
+    let pending_batch = PendingBatch {
+        id: 3,
+        ustake_to_burn: Uint128::new(100),
+        est_unbond_start_time: 20000,
+    };
+    let delegations = AllianceDelegations {
+        delegations: HashMap::from([("alice".to_string(), Uint128::new(1100))]),
+    };
+
+    let storage = deps.as_mut().storage;
+    Item::new("stake_token").save(storage, &stake).unwrap();
+    Item::new("pending_batch").save(storage, &pending_batch).unwrap();
+    Item::new("alliance_delegations").save(storage, &delegations).unwrap();
+    Map::new("exchange_history").save(storage, 10000u64, &Decimal::one()).unwrap();
+
+    state.migrate_primary_asset(storage).unwrap();
+    // migrating again does nothing
+    state.migrate_primary_asset(storage).unwrap();
+
+    assert_eq!(state.primary_asset.load(storage).unwrap(), MOCK_UTOKEN);
+    assert_eq!(state.assets.load(storage, MOCK_UTOKEN).unwrap(), stake);
+    assert_eq!(state.asset_pending_batches.load(storage, MOCK_UTOKEN).unwrap(), pending_batch);
+    assert_eq!(state.asset_delegations.load(storage, MOCK_UTOKEN).unwrap(), delegations);
+    assert_eq!(
+        state.asset_exchange_history.load(storage, (MOCK_UTOKEN, 10000)).unwrap(),
+        Decimal::one()
+    );
+    assert_eq!(Item::<AllianceStakeToken>::new("stake_token").may_load(storage).unwrap(), None);
+}
@@ -0,0 +1,181 @@
+use cosmwasm_std::testing::{mock_info, MockApi, MockStorage, MOCK_CONTRACT_ADDR};
+use cosmwasm_std::{coin, Coin, Decimal, OwnedDeps, Uint128};
+
+use eris::alliance_lst::{ConfigResponse, ExecuteMsg, QueryMsg};
+use eris::circuit_breaker::{CircuitBreakerConfig, PausableAction, PauseState};
+use eris::hub::CallbackMsg;
+use eris_chain_adapter::types::CustomQueryType;
+
+use crate::contract::execute;
+use crate::error::ContractError;
+use crate::state::State;
+use crate::testing::helpers::{
+    get_stake_full_denom, query_helper, set_total_stake_supply, setup_test, MOCK_UTOKEN,
+};
+
+use super::custom_querier::CustomQuerier;
+use super::helpers::mock_env_at_timestamp;
+
+fn setup_circuit_breaker() -> OwnedDeps<MockStorage, MockApi, CustomQuerier, CustomQueryType> {
+    let mut deps = setup_test();
+
+    execute(
+        deps.as_mut(),
+        mock_env_at_timestamp(10000),
+        mock_info("owner", &[]),
+        ExecuteMsg::UpdateConfig {
+            protocol_fee_contract: None,
+            protocol_reward_fee: None,
+            operator: None,
+            stages_preset: None,
+            withdrawals_preset: None,
+            allow_donations: None,
+            delegation_strategy: None,
+            default_max_spread: None,
+            epoch_period: None,
+            unbond_period: None,
+            validator_proxy: None,
+            guardian: Some("guardian".to_string()),
+            remove_guardian: None,
+            circuit_breaker: Some(CircuitBreakerConfig {
+                max_exchange_rate_change: Some(Decimal::percent(5)),
+                volume_window: 1000,
+                max_bond_volume: Some(Uint128::new(1000)),
+                max_unbond_volume: Some(Uint128::new(500)),
+            }),
+        },
+    )
+    .unwrap();
+
+    deps
+}
+
+fn queue_unbond(
+    deps: &mut OwnedDeps<MockStorage, MockApi, CustomQuerier, CustomQueryType>,
+    timestamp: u64,
+    amount: u128,
+) -> Result<(), ContractError> {
+    execute(
+        deps.as_mut(),
+        mock_env_at_timestamp(timestamp),
+        mock_info("user_1", &[Coin::new(amount, get_stake_full_denom())]),
+        ExecuteMsg::QueueUnbond {
+            receiver: None,
+        },
+    )
+    .map(|_| ())
+}
+
+//--------------------------------------------------------------------------------------------------
+// Execution
+//--------------------------------------------------------------------------------------------------
+
+#[test]
+fn pausing_actions() {
+    let mut deps = setup_circuit_breaker();
+
+    let err = execute(
+        deps.as_mut(),
+        mock_env_at_timestamp(10000),
+        mock_info("user_1", &[]),
+        ExecuteMsg::SetPaused {
+            action: PausableAction::Unbond,
+            paused: true,
+        },
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::UnauthorizedSenderNotGuardian {});
+
+    execute(
+        deps.as_mut(),
+        mock_env_at_timestamp(10000),
+        mock_info("guardian", &[]),
+        ExecuteMsg::SetPaused {
+            action: PausableAction::Unbond,
+            paused: true,
+        },
+    )
+    .unwrap();
+    assert_eq!(
+        queue_unbond(&mut deps, 10000, 100).unwrap_err(),
+        ContractError::Paused("unbond".into())
+    );
+
+    // only the owner can resume
+    let err = execute(
+        deps.as_mut(),
+        mock_env_at_timestamp(10000),
+        mock_info("guardian", &[]),
+        ExecuteMsg::SetPaused {
+            action: PausableAction::Unbond,
+            paused: false,
+        },
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+
+    execute(
+        deps.as_mut(),
+        mock_env_at_timestamp(10000),
+        mock_info("owner", &[]),
+        ExecuteMsg::SetPaused {
+            action: PausableAction::Unbond,
+            paused: false,
+        },
+    )
+    .unwrap();
+    queue_unbond(&mut deps, 10000, 100).unwrap();
+}
+
+#[test]
+fn limiting_volumes() {
+    let mut deps = setup_circuit_breaker();
+
+    queue_unbond(&mut deps, 10000, 500).unwrap();
+    assert_eq!(
+        queue_unbond(&mut deps, 10999, 1).unwrap_err(),
+        ContractError::VolumeLimitExceeded("unbond".into())
+    );
+
+    // the bucket of the first unbond left the rolling window
+    queue_unbond(&mut deps, 11050, 500).unwrap();
+}
+
+#[test]
+fn tripping_on_exchange_rate_change() {
+    let mut deps = setup_circuit_breaker();
+    let state = State::default();
+
+    set_total_stake_supply(&state, &mut deps, 1000, 1000);
+    state
+        .asset_exchange_history
+        .save(deps.as_mut().storage, (MOCK_UTOKEN, 5000), &Decimal::one())
+        .unwrap();
+
+    // burning ustake raised the exchange rate by more than 5%
+    state
+        .unlocked_coins
+        .save(deps.as_mut().storage, &vec![coin(100, get_stake_full_denom())])
+        .unwrap();
+
+    let res = execute(
+        deps.as_mut(),
+        mock_env_at_timestamp(10000),
+        mock_info(MOCK_CONTRACT_ADDR, &[]),
+        ExecuteMsg::Callback(CallbackMsg::Reinvest {
+            skip_fee: false,
+        }),
+    )
+    .unwrap();
+    assert_eq!(res.events[1].ty, "erishub/circuit_breaker_tripped");
+
+    let res: ConfigResponse = query_helper(deps.as_ref(), QueryMsg::Config {});
+    assert_eq!(
+        res.paused,
+        PauseState {
+            bond: true,
+            unbond: true,
+            harvest: true,
+        }
+    );
+}
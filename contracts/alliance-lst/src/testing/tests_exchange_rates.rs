@@ -60,7 +60,7 @@ fn reinvesting_check_exchange_rates() {
         vec![attr("action", "erishub/reinvest"), attr("exchange_rate", "10.013334668134948443")]
     );
     assert_eq!(
-        state.stake_token.load(deps.as_mut().storage).unwrap(),
+        state.assets.load(deps.as_mut().storage, MOCK_UTOKEN).unwrap(),
         AllianceStakeToken {
             utoken: MOCK_UTOKEN.to_string(),
             denom: STAKE_DENOM.to_string(),
@@ -103,6 +103,7 @@ fn reinvesting_check_exchange_rates() {
         QueryMsg::ExchangeRates {
             start_after: None,
             limit: None,
+            asset: None,
         },
         2083600,
     );
@@ -0,0 +1,220 @@
+use std::collections::HashMap;
+
+use cosmwasm_std::testing::{mock_info, MockApi, MockStorage};
+use cosmwasm_std::{Decimal, Event, OwnedDeps, StdError, Uint128};
+
+use eris::alliance_lst::{ExecuteMsg, QueryMsg};
+use eris::hub::{SlashingEvent, SlashingEventsResponse};
+use eris_chain_adapter::types::CustomQueryType;
+
+use crate::contract::execute;
+use crate::error::ContractError;
+use crate::state::State;
+use crate::testing::helpers::{query_helper, set_total_stake_supply, setup_test, MOCK_UTOKEN};
+use crate::types::alliance_delegations::AllianceDelegations;
+
+use super::custom_querier::CustomQuerier;
+use super::helpers::mock_env_at_timestamp;
+
+fn setup_slashing() -> OwnedDeps<MockStorage, MockApi, CustomQuerier, CustomQueryType> {
+    let mut deps = setup_test();
+    let state = State::default();
+
+    set_total_stake_supply(&state, &mut deps, 1000000, 1000000);
+    state
+        .asset_delegations
+        .save(
+            deps.as_mut().storage,
+            MOCK_UTOKEN,
+            &AllianceDelegations {
+                delegations: HashMap::from([
+                    ("alice".to_string(), Uint128::new(500000)),
+                    ("bob".to_string(), Uint128::new(500000)),
+                ]),
+            },
+        )
+        .unwrap();
+
+    deps
+}
+
+fn harvest(
+    deps: &mut OwnedDeps<MockStorage, MockApi, CustomQuerier, CustomQueryType>,
+) -> Vec<Event> {
+    execute(
+        deps.as_mut(),
+        mock_env_at_timestamp(20000),
+        mock_info("worker", &[]),
+        ExecuteMsg::Harvest {
+            validators: None,
+            withdrawals: None,
+            stages: None,
+            asset: None,
+        },
+    )
+    .unwrap()
+    .events
+}
+
+//--------------------------------------------------------------------------------------------------
+// Execution
+//--------------------------------------------------------------------------------------------------
+
+#[test]
+fn detecting_slashing_on_harvest() {
+    let mut deps = setup_slashing();
+    let state = State::default();
+
+    // alice is only affected by the rounding of the alliance module
+    deps.querier.set_alliance_delegations(&[("alice", 499995), ("bob", 489995)]);
+
+    let events = harvest(&mut deps);
+    assert_eq!(
+        events,
+        vec![Event::new("erishub/slashed")
+            .add_attribute("validator", "bob")
+            .add_attribute("utoken_slashed", "10005")
+            .add_attribute("rate_before", "1")
+            .add_attribute("rate_after", "0.989995")]
+    );
+
+    assert_eq!(
+        state.asset_delegations.load(deps.as_ref().storage, MOCK_UTOKEN).unwrap().delegations,
+        HashMap::from([
+            ("alice".to_string(), Uint128::new(500000)),
+            ("bob".to_string(), Uint128::new(489995)),
+        ])
+    );
+    assert_eq!(
+        state.assets.load(deps.as_ref().storage, MOCK_UTOKEN).unwrap().total_utoken_bonded,
+        Uint128::new(989995)
+    );
+
+    let res: SlashingEventsResponse = query_helper(
+        deps.as_ref(),
+        QueryMsg::SlashingEvents {
+            start_after: None,
+            limit: None,
+        },
+    );
+    assert_eq!(
+        res.events,
+        vec![(
+            1,
+            SlashingEvent {
+                asset: MOCK_UTOKEN.to_string(),
+                validator: "bob".to_string(),
+                amount: Uint128::new(10005),
+                time: 20000,
+                rate_before: Decimal::one(),
+                rate_after: Decimal::from_ratio(989995u128, 1000000u128),
+            }
+        )]
+    );
+
+    // the slashing is only applied once
+    assert_eq!(harvest(&mut deps), vec![]);
+}
+
+#[test]
+fn detecting_removed_delegations() {
+    let mut deps = setup_slashing();
+    let state = State::default();
+
+    // the delegation to bob is fully slashed and no longer found
+    deps.querier.set_alliance_delegations(&[("alice", 500000)]);
+
+    let events = harvest(&mut deps);
+    assert_eq!(
+        events,
+        vec![Event::new("erishub/slashed")
+            .add_attribute("validator", "bob")
+            .add_attribute("utoken_slashed", "500000")
+            .add_attribute("rate_before", "1")
+            .add_attribute("rate_after", "0.5")]
+    );
+    assert_eq!(
+        state.asset_delegations.load(deps.as_ref().storage, MOCK_UTOKEN).unwrap().delegations,
+        HashMap::from([("alice".to_string(), Uint128::new(500000))])
+    );
+    assert_eq!(
+        state.assets.load(deps.as_ref().storage, MOCK_UTOKEN).unwrap().total_utoken_bonded,
+        Uint128::new(500000)
+    );
+}
+
+#[test]
+fn failing_detection_on_query_errors() {
+    let mut deps = setup_slashing();
+
+    deps.querier.set_alliance_delegations(&[("alice", 500000), ("bob", 490000)]);
+    deps.querier.alliance_delegation_errors = vec!["alice".to_string()];
+
+    let err = execute(
+        deps.as_mut(),
+        mock_env_at_timestamp(20000),
+        mock_info("worker", &[]),
+        ExecuteMsg::Harvest {
+            validators: None,
+            withdrawals: None,
+            stages: None,
+            asset: None,
+        },
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::Std(StdError::generic_err("Querier contract error: [mock] query failed"))
+    );
+}
+
+#[test]
+fn skipping_detection_without_alliance_query() {
+    let mut deps = setup_slashing();
+    let state = State::default();
+
+    assert_eq!(harvest(&mut deps), vec![]);
+    assert_eq!(
+        state.assets.load(deps.as_ref().storage, MOCK_UTOKEN).unwrap().total_utoken_bonded,
+        Uint128::new(1000000)
+    );
+}
+
+#[test]
+fn checking_slashing_manually() {
+    let mut deps = setup_slashing();
+    let state = State::default();
+
+    let msg = ExecuteMsg::CheckSlashing {
+        state_total_utoken_bonded: Uint128::new(1000000),
+        delegations: vec![
+            ("alice".to_string(), Uint128::new(500000)),
+            ("bob".to_string(), Uint128::new(480000)),
+        ],
+        asset: None,
+    };
+
+    let err =
+        execute(deps.as_mut(), mock_env_at_timestamp(20000), mock_info("user", &[]), msg.clone())
+            .unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+
+    let res = execute(deps.as_mut(), mock_env_at_timestamp(20000), mock_info("operator", &[]), msg)
+        .unwrap();
+    assert_eq!(res.events.len(), 1);
+    assert_eq!(res.events[0].attributes[1].value, "20000");
+
+    assert_eq!(
+        state.assets.load(deps.as_ref().storage, MOCK_UTOKEN).unwrap().total_utoken_bonded,
+        Uint128::new(980000)
+    );
+
+    let res: SlashingEventsResponse = query_helper(
+        deps.as_ref(),
+        QueryMsg::SlashingEvents {
+            start_after: None,
+            limit: None,
+        },
+    );
+    assert_eq!(res.events[0].1.validator, "bob");
+}
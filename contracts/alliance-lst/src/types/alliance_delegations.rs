@@ -14,7 +14,7 @@ pub struct AllianceDelegations {
     pub delegations: HashMap<String, Uint128>,
 }
 
-const REDUCTION_AMOUNT: u128 = 10u128;
+pub(crate) const REDUCTION_AMOUNT: u128 = 10u128;
 
 impl AllianceDelegations {
     pub fn query_all_delegations(&self, denom: &str) -> Vec<Delegation> {
@@ -117,8 +117,13 @@ impl AllianceDelegations {
         Ok(self)
     }
 
-    pub fn save(self, state: &State, storage: &mut dyn Storage) -> StdResult<AllianceDelegations> {
-        state.alliance_delegations.save(storage, &self)?;
+    pub fn save(
+        self,
+        state: &State,
+        storage: &mut dyn Storage,
+        asset: &str,
+    ) -> StdResult<AllianceDelegations> {
+        state.asset_delegations.save(storage, asset, &self)?;
         Ok(self)
     }
 }
@@ -1,6 +1,9 @@
-use cosmwasm_std::{Coin, QuerierWrapper, StdResult};
-use eris_chain_shared::alliance_query::{AllianceQuery, AllianceQueryWrapper};
-use serde::{Deserialize, Serialize};
+use cosmwasm_std::{
+    from_json, to_json_vec, Binary, Coin, ContractResult, DecCoin, Querier, QueryRequest, StdError,
+    StdResult, SystemError, SystemResult, Uint128,
+};
+use eris_chain_shared::alliance_query::AllianceQuery;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
 #[serde(rename_all = "snake_case")]
@@ -11,30 +14,121 @@ pub struct DelegationResponse {
     pub amount: Coin,
 }
 
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub struct AllianceValidatorResponse {
+    pub validator_addr: String,
+    /// amount staked with the validator per alliance denom
+    pub total_staked: Vec<DecCoin>,
+}
+
+/// Sends alliance queries through any querier, so that chains without the alliance module return
+/// an error instead of failing to compile.
 pub struct AllianceQuerier<'a> {
-    querier: &'a QuerierWrapper<'a, AllianceQueryWrapper>,
+    querier: &'a dyn Querier,
 }
 
 impl<'a> AllianceQuerier<'a> {
-    pub fn new(querier: &'a QuerierWrapper<AllianceQueryWrapper>) -> Self {
+    pub fn new(querier: &'a dyn Querier) -> Self {
         AllianceQuerier {
             querier,
         }
     }
 
+    /// Queries whether the validator is known to the alliance module. Returns `None` if the chain
+    /// doesn't support alliance queries.
+    pub fn is_alliance_validator(&self, validator_addr: String) -> StdResult<Option<bool>> {
+        let request: QueryRequest<AllianceQuery> = AllianceQuery::Validator {
+            validator_addr,
+        }
+        .into();
+
+        match self.raw_query(&request)? {
+            SystemResult::Err(SystemError::UnsupportedRequest {
+                ..
+            }) => Ok(None),
+            SystemResult::Ok(ContractResult::Err(contract_err))
+                if contract_err.contains("not found") =>
+            {
+                Ok(Some(false))
+            },
+            result => {
+                let _: AllianceValidatorResponse = Self::parse(result)?;
+                Ok(Some(true))
+            },
+        }
+    }
+
     pub fn query_delegation(
         &self,
         denom: String,
         delegator: String,
         validator: String,
     ) -> StdResult<DelegationResponse> {
-        let request = AllianceQuery::Delegation {
+        let request: QueryRequest<AllianceQuery> = AllianceQuery::Delegation {
             denom,
             delegator,
             validator,
         }
         .into();
 
-        self.querier.query(&request)
+        self.query(&request)
+    }
+
+    /// Queries the delegated amount, a delegation that doesn't exist (e.g. fully slashed) is
+    /// returned as zero. Returns `None` if the chain doesn't support alliance queries.
+    pub fn query_delegation_amount(
+        &self,
+        denom: String,
+        delegator: String,
+        validator: String,
+    ) -> StdResult<Option<Uint128>> {
+        let request: QueryRequest<AllianceQuery> = AllianceQuery::Delegation {
+            denom,
+            delegator,
+            validator,
+        }
+        .into();
+
+        match self.raw_query(&request)? {
+            SystemResult::Err(SystemError::UnsupportedRequest {
+                ..
+            }) => Ok(None),
+            SystemResult::Ok(ContractResult::Err(contract_err))
+                if contract_err.contains("not found") =>
+            {
+                Ok(Some(Uint128::zero()))
+            },
+            result => {
+                let delegation: DelegationResponse = Self::parse(result)?;
+                Ok(Some(delegation.amount.amount))
+            },
+        }
+    }
+
+    fn query<T: DeserializeOwned>(&self, request: &QueryRequest<AllianceQuery>) -> StdResult<T> {
+        Self::parse(self.raw_query(request)?)
+    }
+
+    fn raw_query(
+        &self,
+        request: &QueryRequest<AllianceQuery>,
+    ) -> StdResult<SystemResult<ContractResult<Binary>>> {
+        let raw = to_json_vec(request).map_err(|serialize_err| {
+            StdError::generic_err(format!("Serializing QueryRequest: {}", serialize_err))
+        })?;
+        Ok(self.querier.raw_query(&raw))
+    }
+
+    fn parse<T: DeserializeOwned>(result: SystemResult<ContractResult<Binary>>) -> StdResult<T> {
+        match result {
+            SystemResult::Err(system_err) => {
+                Err(StdError::generic_err(format!("Querier system error: {}", system_err)))
+            },
+            SystemResult::Ok(ContractResult::Err(contract_err)) => {
+                Err(StdError::generic_err(format!("Querier contract error: {}", contract_err)))
+            },
+            SystemResult::Ok(ContractResult::Ok(value)) => from_json(&value),
+        }
     }
 }
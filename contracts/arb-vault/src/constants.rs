@@ -1,5 +1,6 @@
 pub const CONTRACT_NAME: &str = "eris-arb-vault-whitewhale";
 pub const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
 pub const INSTANTIATE_TOKEN_REPLY_ID: u64 = 1;
+pub const IBC_TRANSFER_REPLY_ID: u64 = 2;
 
 pub const MAX_UNBOND_TIME_S: u64 = 100 * 24 * 60 * 60;
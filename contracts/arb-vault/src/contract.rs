@@ -1,4 +1,4 @@
-use crate::constants::{CONTRACT_NAME, CONTRACT_VERSION};
+use crate::constants::{CONTRACT_NAME, CONTRACT_VERSION, IBC_TRANSFER_REPLY_ID};
 use crate::domain;
 use crate::domain::callback::handle_callback;
 use crate::domain::config::execute_update_config;
@@ -6,19 +6,28 @@ use crate::domain::execute::{
     execute_arbitrage, execute_deposit, execute_unbond_liquidity, execute_unbond_user,
     execute_withdraw_liquidity, execute_withdraw_unbonded, execute_withdraw_unbonding_immediate,
 };
+use crate::domain::ibc::{
+    execute_complete_ibc_redemptions, execute_expire_ibc_redemptions,
+    execute_set_ibc_exchange_rate, execute_settle_ibc_redemptions, handle_sudo,
+    handle_transfer_reply,
+};
 use crate::domain::ownership::{claim_ownership, drop_ownership_proposal, propose_new_owner};
+use crate::domain::swap::execute_swap;
 use crate::error::{ContractError, ContractResult, CustomResult};
+use crate::extensions::ConfigEx;
 use crate::query::{
-    query_config, query_exchange_rates, query_state, query_takeable, query_unbond_requests,
-    query_user_info,
+    query_config, query_exchange_rates, query_ibc_redemptions, query_simulate_swap, query_state,
+    query_takeable, query_unbond_queue, query_unbond_requests, query_user_info,
 };
+use crate::state::{State, UnbondHistory, UnbondQueueItem, UnbondQueueTotals};
 use cosmwasm_std::{
-    entry_point, to_json_binary, Binary, Deps, DepsMut, Env, MessageInfo, Response,
+    entry_point, to_json_binary, Addr, Binary, Deps, DepsMut, Env, MessageInfo, Order, Reply,
+    Response, StdResult, Uint128,
 };
 use cw2::{get_contract_version, set_contract_version};
 
 use eris::arb_vault::InstantiateMsg;
-use eris::arb_vault::{ExecuteMsg, MigrateMsg, QueryMsg};
+use eris::arb_vault::{ExecuteMsg, MigrateMsg, QueryMsg, SudoMsg};
 
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn instantiate(
@@ -38,7 +47,8 @@ pub fn execute(deps: DepsMut, env: Env, info: MessageInfo, msg: ExecuteMsg) -> C
             msg,
             result_token,
             wanted_profit,
-        } => execute_arbitrage(deps, env, info, msg, result_token, wanted_profit),
+            utoken,
+        } => execute_arbitrage(deps, env, info, msg, result_token, wanted_profit, utoken),
 
         ExecuteMsg::WithdrawFromLiquidStaking {
             names,
@@ -48,6 +58,16 @@ pub fn execute(deps: DepsMut, env: Env, info: MessageInfo, msg: ExecuteMsg) -> C
             names,
         } => execute_unbond_liquidity(deps, env, info, names),
 
+        ExecuteMsg::CompleteIbcRedemptions {
+            ids,
+        } => execute_complete_ibc_redemptions(deps, info, ids),
+        ExecuteMsg::ExpireIbcRedemptions {
+            ids,
+        } => execute_expire_ibc_redemptions(deps, env, info, ids),
+        ExecuteMsg::SettleIbcRedemptions {
+            ids,
+        } => execute_settle_ibc_redemptions(deps, env, info, ids),
+
         // User actions
         ExecuteMsg::Unbond {
             immediate,
@@ -63,27 +83,21 @@ pub fn execute(deps: DepsMut, env: Env, info: MessageInfo, msg: ExecuteMsg) -> C
             id,
         } => execute_withdraw_unbonding_immediate(deps, env, info, id),
 
-        // ExecuteMsg::Swap {
-        //     offer_asset,
-        //     ask_asset_info,
-        //     belief_price,
-        //     max_spread,
-        //     to,
-        // } => domain::swap::execute_swap_native(
-        //     deps,
-        //     env,
-        //     info,
-        //     offer_asset,
-        //     ask_asset_info,
-        //     belief_price,
-        //     max_spread,
-        //     to,
-        // ),
+        ExecuteMsg::Swap {
+            min_return,
+            receiver,
+        } => execute_swap(deps, env, info, min_return, receiver),
+
         // Allowed by Owner
         ExecuteMsg::UpdateConfig {
             ..
         } => execute_update_config(deps, env, info, msg),
 
+        ExecuteMsg::SetIbcExchangeRate {
+            name,
+            exchange_rate,
+        } => execute_set_ibc_exchange_rate(deps, info, name, exchange_rate),
+
         ExecuteMsg::ProposeNewOwner {
             owner,
             expires_in,
@@ -94,6 +108,19 @@ pub fn execute(deps: DepsMut, env: Env, info: MessageInfo, msg: ExecuteMsg) -> C
     }
 }
 
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn sudo(deps: DepsMut, env: Env, msg: SudoMsg) -> ContractResult {
+    handle_sudo(deps, env, msg)
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn reply(deps: DepsMut, _env: Env, reply: Reply) -> ContractResult {
+    match reply.id {
+        IBC_TRANSFER_REPLY_ID => handle_transfer_reply(deps, reply),
+        id => Err(ContractError::InvalidReplyId(id)),
+    }
+}
+
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> CustomResult<Binary> {
     let res = match msg {
@@ -106,7 +133,8 @@ pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> CustomResult<Binary> {
         } => to_json_binary(&query_user_info(deps, env, address)?)?,
         QueryMsg::Takeable {
             wanted_profit,
-        } => to_json_binary(&query_takeable(deps, env, wanted_profit)?)?,
+            utoken,
+        } => to_json_binary(&query_takeable(deps, env, wanted_profit, utoken)?)?,
 
         QueryMsg::UnbondRequests {
             address,
@@ -114,6 +142,21 @@ pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> CustomResult<Binary> {
             start_after,
         } => to_json_binary(&query_unbond_requests(deps, env, address, start_after, limit)?)?,
 
+        QueryMsg::UnbondQueue {
+            address,
+            start_after,
+            limit,
+        } => to_json_binary(&query_unbond_queue(deps, env, address, start_after, limit)?)?,
+
+        QueryMsg::SimulateSwap {
+            offer_asset,
+        } => to_json_binary(&query_simulate_swap(deps, env, offer_asset)?)?,
+
+        QueryMsg::IbcRedemptions {
+            start_after,
+            limit,
+        } => to_json_binary(&query_ibc_redemptions(deps, start_after, limit)?)?,
+
         QueryMsg::ExchangeRates {
             start_after_d,
             limit,
@@ -123,9 +166,42 @@ pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> CustomResult<Binary> {
 }
 
 #[cfg_attr(not(feature = "library"), entry_point)]
-pub fn migrate(deps: DepsMut, _env: Env, _msg: MigrateMsg) -> Result<Response, ContractError> {
+pub fn migrate(deps: DepsMut, env: Env, _msg: MigrateMsg) -> Result<Response, ContractError> {
     let contract_version = get_contract_version(deps.storage)?;
 
+    // unbond items created before the queue existed are added in order of their ids
+    let state = State::default();
+    if state.unbond_queue_totals.may_load(deps.storage)?.is_none() {
+        let mut items = state
+            .unbond_history
+            .range(deps.storage, None, None, Order::Ascending)
+            .collect::<StdResult<Vec<((Addr, u64), UnbondHistory)>>>()?;
+        items.sort_by_key(|((_, id), _)| *id);
+
+        let mut totals = UnbondQueueTotals::default();
+        for ((owner, id), item) in items {
+            let item = UnbondQueueItem {
+                owner,
+                start: totals.requested,
+                amount: item.amount_asset,
+            };
+            totals.requested += item.amount;
+            state.unbond_queue.save(deps.storage, id, &item)?;
+        }
+        state.unbond_queue_totals.save(deps.storage, &totals)?;
+    }
+
+    // balances locked before the split was stored are split by the current available funds
+    let mut balance_locked = state.balance_locked.load(deps.storage)?;
+    if balance_locked.assets.is_empty() && !balance_locked.balance.is_zero() {
+        let config = state.config.load(deps.storage)?;
+        let available = config.query_available(&deps.querier, &env)?;
+        let balance = balance_locked.balance;
+        balance_locked.balance = Uint128::zero();
+        balance_locked.lock(&available, balance);
+        state.balance_locked.save(deps.storage, &balance_locked)?;
+    }
+
     set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
 
     Ok(Response::new()
@@ -40,12 +40,16 @@ pub fn execute_assert_result(
     let state = State::default();
     let config = state.config.load(deps.storage)?;
     let lp_token = state.lp_token.load(deps.storage)?;
-    let mut lsds = config.lsd_group(&env);
 
     let old_balance = state.assert_is_nested(deps.storage)?;
-    let new_balances = lsds.get_total_assets_err(deps.as_ref(), &env, &state, &config)?;
+    let vault = config.get_vault_balances(deps.as_ref(), &env, &state)?;
     let total_lp_supply = lp_token.total_supply;
 
+    // the profit is checked in the base asset that has been borrowed
+    let asset = vault.get_asset_by_lsd(&env, &result_token)?;
+    let new_balances = &asset.balances;
+    let mut lsds = asset.config.lsd_group(&env);
+
     let result_token_string = result_token.to_string();
     let active_lsd_adapter = lsds.get_adapter_by_asset(result_token)?;
     let active_lsd_balance = new_balances.get_by_name(&active_lsd_adapter.name)?;
@@ -102,7 +106,7 @@ pub fn execute_assert_result(
         (None, vec![])
     } else if new_balances.vault_takeable >= fee_amount {
         // send fees in utoken if takeable allows it.
-        let utoken = native_asset(config.utoken, fee_amount);
+        let utoken = native_asset(asset.config.utoken.clone(), fee_amount);
         let fee_msg = utoken.into_msg(fee_config.protocol_fee_contract)?.to_specific()?;
 
         (Some(fee_msg), vec![])
@@ -129,7 +133,7 @@ pub fn execute_assert_result(
     state.balance_checkpoint.remove(deps.storage);
 
     // we store the exchange rate daily to not create too much data.
-    let new_vault_total = new_balances.vault_total - fee_amount;
+    let new_vault_total = vault.total.vault_total - fee_amount * asset.price;
 
     let exchange_rate = Decimal::from_ratio(new_vault_total, total_lp_supply);
     state.exchange_history.save(
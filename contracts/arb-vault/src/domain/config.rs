@@ -1,6 +1,5 @@
 use cosmwasm_std::{DepsMut, Env, MessageInfo, Response, StdError};
-use eris::arb_vault::ExecuteMsg;
-use itertools::Itertools;
+use eris::arb_vault::{ExecuteMsg, ValidatedConfig};
 
 use crate::{
     constants::MAX_UNBOND_TIME_S,
@@ -23,6 +22,8 @@ pub fn execute_update_config(
             disable_lsd,
             remove_lsd,
             force_remove_lsd,
+            insert_asset,
+            remove_asset,
             fee_config,
             remove_whitelist,
             set_whitelist,
@@ -58,7 +59,7 @@ pub fn execute_update_config(
                     return Err(ContractError::AdapterNameDuplicate(insert_lsd.name));
                 }
             } else if let Some(disable_lsd) = disable_lsd {
-                let lsd = config.lsds.iter_mut().find(|a| a.name == disable_lsd);
+                let lsd = config.all_lsds_mut().into_iter().find(|a| a.name == disable_lsd);
                 if let Some(lsd) = lsd {
                     lsd.disabled = true;
                     config_changed = true;
@@ -78,24 +79,67 @@ pub fn execute_update_config(
                     Err(ContractError::CannotRemoveAdapterThatHasFunds {})?;
                 }
 
-                config.lsds =
-                    config.lsds.into_iter().filter(|lsd| lsd.name != remove_lsd).collect_vec();
-
+                remove_lsd_by_name(&mut config, &remove_lsd);
                 config_changed = true;
             } else if let Some(force_remove_lsd) = force_remove_lsd {
-                config.lsds = config
-                    .lsds
+                remove_lsd_by_name(&mut config, &force_remove_lsd);
+                config_changed = true;
+            }
+
+            if let Some(insert_asset) = insert_asset {
+                let insert_asset = insert_asset.validate(deps.api)?;
+                insert_asset.utilization_method.validate()?;
+
+                if insert_asset.utoken == config.utoken {
+                    return Err(ContractError::BaseAssetDuplicate(insert_asset.utoken));
+                }
+
+                // replaces the asset with the same utoken
+                config.assets.retain(|asset| asset.utoken != insert_asset.utoken);
+
+                for lsd in insert_asset.lsds.iter() {
+                    if config.all_lsds().iter().any(|existing| existing.name == lsd.name) {
+                        return Err(ContractError::AdapterNameDuplicate(lsd.name.clone()));
+                    }
+                }
+
+                config.assets.push(insert_asset);
+                config_changed = true;
+            }
+
+            if let Some(remove_asset) = remove_asset {
+                let (asset_config, _) = config
+                    .asset_configs()
                     .into_iter()
-                    .filter(|lsd| lsd.name != force_remove_lsd)
-                    .collect_vec();
+                    .skip(1)
+                    .find(|(asset, _)| asset.utoken == remove_asset)
+                    .ok_or_else(|| ContractError::BaseAssetNotFound(remove_asset.clone()))?;
+
+                // cannot remove asset if any funds are still held in it
+                let available = asset_config.query_utoken_amount(&deps.querier, &env)?;
+                let balances = asset_config
+                    .lsd_group(&env)
+                    .get_balances(&deps.as_ref(), &env.contract.address)?;
+                if !available.is_zero()
+                    || balances.iter().any(|balance| {
+                        !balance.unbonding.is_zero()
+                            || !balance.withdrawable.is_zero()
+                            || !balance.xbalance.is_zero()
+                    })
+                {
+                    Err(ContractError::CannotRemoveAssetThatHasFunds {})?;
+                }
 
+                config.assets.retain(|asset| asset.utoken != remove_asset);
                 config_changed = true;
             }
 
             if config_changed {
+                // an LSD listed under two base assets would be valued in the first one only
+                config.lsd_group(&env).assert_unique_assets()?;
+
                 // after the config change, it still needs to be able to query all assets.
-                let mut lsds = config.lsd_group(&env);
-                lsds.get_total_assets_err(deps.as_ref(), &env, &state, &config)?;
+                config.get_vault_balances(deps.as_ref(), &env, &state)?;
 
                 state.config.save(deps.storage, &config)?;
             }
@@ -123,3 +167,10 @@ pub fn execute_update_config(
         _ => Err(StdError::generic_err("not supported").into()),
     }
 }
+
+fn remove_lsd_by_name(config: &mut ValidatedConfig, name: &str) {
+    config.lsds.retain(|lsd| lsd.name != name);
+    for asset in config.assets.iter_mut() {
+        asset.lsds.retain(|lsd| lsd.name != name);
+    }
+}
@@ -1,15 +1,18 @@
 use crate::asserts::{assert_has_funds, assert_max_amount, assert_min_profit};
 use crate::error::{ContractError, ContractResult};
+use crate::extensions::balances_ex::{
+    available_value, available_values, split_value_coins, AssetAvailable,
+};
 use crate::extensions::{BalancesEx, ConfigEx};
 use crate::helpers::{calc_fees, get_share_from_deposit};
-use crate::state::{BalanceCheckpoint, BalanceLocked, State, UnbondHistory};
-use astroport::asset::{native_asset, native_asset_info, Asset, AssetInfo};
+use crate::state::{BalanceCheckpoint, State, UnbondHistory};
+use astroport::asset::{Asset, AssetInfo};
 use cosmwasm_std::{
-    attr, coin, Addr, Coin, CosmosMsg, Decimal, DepsMut, Env, MessageInfo, Order, QuerierWrapper,
-    Response, StdResult, Storage, Uint128, WasmMsg,
+    attr, coin, Addr, BankMsg, Coin, CosmosMsg, Decimal, DepsMut, Env, MessageInfo, Response,
+    Storage, Uint128, WasmMsg,
 };
-use eris::arb_vault::{CallbackMsg, ExecuteSubMsg, LpToken, ValidatedConfig};
-use eris::{CustomMsgExt, CustomResponse};
+use eris::arb_vault::{CallbackMsg, ExecuteSubMsg, LpToken, ValidatedFeeConfig};
+use eris::CustomResponse;
 use eris_chain_adapter::types::{chain, CustomMsgType};
 use eris_chain_shared::chain_trait::ChainInterface;
 use itertools::Itertools;
@@ -26,11 +29,16 @@ pub fn execute_arbitrage(
     message: ExecuteSubMsg,
     result_token: AssetInfo,
     wanted_profit: Decimal,
+    utoken: Option<String>,
 ) -> ContractResult {
     let state = State::default();
     let config = state.config.load(deps.storage)?;
-    let mut lsds = config.lsd_group(&env);
-    let balances = lsds.get_total_assets_err(deps.as_ref(), &env, &state, &config)?;
+    let vault = config.get_vault_balances(deps.as_ref(), &env, &state)?;
+
+    // the borrowed base asset and the result token need to belong together
+    let asset = vault.get_asset(utoken.as_deref().unwrap_or(&config.utoken))?;
+    let balances = &asset.balances;
+    let mut lsds = asset.config.lsd_group(&env);
 
     let lsd = lsds.get_adapter_by_asset(result_token.clone())?;
     lsd.assert_not_disabled()?;
@@ -38,7 +46,7 @@ pub fn execute_arbitrage(
     state.assert_not_nested(deps.storage)?;
     assert_has_funds(&message.funds_amount)?;
     assert_min_profit(&wanted_profit)?;
-    assert_max_amount(&config, &balances, &wanted_profit, &message.funds_amount)?;
+    assert_max_amount(&asset.config, balances, &wanted_profit, &message.funds_amount)?;
 
     // setup contract to call, by default the sender is called with the funds requested
     let contract_addr = if let Some(contract_addr) = message.contract_addr {
@@ -47,7 +55,7 @@ pub fn execute_arbitrage(
         info.sender
     };
     let active_balance = balances.get_by_name(&lsd.name)?.clone();
-    lsds.assert_not_lsd_contract(&contract_addr)?;
+    config.lsd_group(&env).assert_not_lsd_contract(&contract_addr)?;
 
     // create balance checkpoint with total value, as it needs to be higher after full execution.
     state.balance_checkpoint.save(
@@ -63,7 +71,7 @@ pub fn execute_arbitrage(
         contract_addr: contract_addr.to_string(),
         msg: message.msg.clone(),
         funds: vec![Coin {
-            denom: config.utoken,
+            denom: asset.config.utoken.clone(),
             amount: message.funds_amount,
         }],
     });
@@ -103,7 +111,7 @@ pub fn execute_withdraw_liquidity(
 }
 
 pub fn execute_unbond_liquidity(
-    deps: DepsMut,
+    mut deps: DepsMut,
     env: Env,
     info: MessageInfo,
     names: Option<Vec<String>>,
@@ -115,13 +123,13 @@ pub fn execute_unbond_liquidity(
     state.assert_not_nested(deps.storage)?;
     state.assert_sender_whitelisted(deps.storage, &info.sender)?;
 
-    let (messages, attributes) = lsds.get_unbond_msgs(&deps)?;
+    let (messages, attributes) = lsds.get_unbond_msgs(&mut deps)?;
 
     if messages.is_empty() {
         return Err(ContractError::NothingToUnbond {});
     }
 
-    Ok(Response::new().add_messages(messages).add_attributes(attributes))
+    Ok(Response::new().add_submessages(messages).add_attributes(attributes))
 }
 
 pub fn execute_deposit(
@@ -134,7 +142,6 @@ pub fn execute_deposit(
     let state = State::default();
     let config = state.config.load(deps.storage)?;
     let mut lp_token = state.lp_token.load(deps.storage)?;
-    let mut lsds = config.lsd_group(&env);
 
     state.assert_not_nested(deps.storage)?;
     deposit.info.check(deps.api)?;
@@ -144,17 +151,24 @@ pub fn execute_deposit(
         return Err(ContractError::InvalidZeroAmount {});
     }
 
-    if deposit.info != native_asset_info(config.utoken.clone()) {
-        return Err(ContractError::AssetMismatch {});
-    }
+    let utoken = match &deposit.info {
+        AssetInfo::NativeToken {
+            denom,
+        } if config.asset_configs().iter().any(|(asset, _)| asset.utoken == *denom) => denom,
+        _ => return Err(ContractError::AssetMismatch {}),
+    };
 
     let deposit_amount = deposit.amount;
 
-    let assets = lsds.get_total_assets_err(deps.as_ref(), &env, &state, &config)?;
+    let vault = config.get_vault_balances(deps.as_ref(), &env, &state)?;
+    let assets = &vault.total;
+
+    // the share is based on the value of the deposit in the primary utoken
+    let deposit_value = deposit_amount * vault.get_asset(utoken)?.price;
 
-    // removing the deposit amount for correct share calculation
-    let vault_utoken = assets.vault_total.checked_sub(deposit_amount)?;
-    let share = get_share_from_deposit(lp_token.total_supply, vault_utoken, deposit_amount)?;
+    // removing the deposit value for correct share calculation
+    let vault_utoken = assets.vault_total.checked_sub(deposit_value)?;
+    let share = get_share_from_deposit(lp_token.total_supply, vault_utoken, deposit_value)?;
 
     // Mint LP tokens for the sender or for the receiver (if set)
     let recipient = if let Some(recipient) = recipient {
@@ -191,7 +205,6 @@ pub fn execute_unbond_user(
     let state = State::default();
     let config = state.config.load(deps.storage)?;
     let mut lp_token = state.lp_token.load(deps.storage)?;
-    let mut lsds = config.lsd_group(&env);
 
     if info.funds.len() != 1 {
         return Err(ContractError::InvalidFunds {});
@@ -209,26 +222,27 @@ pub fn execute_unbond_user(
     state.assert_not_nested(deps.storage)?;
 
     let total_lp_supply = lp_token.total_supply;
-    let assets = lsds.get_total_assets_err(deps.as_ref(), &env, &state, &config)?;
+    let fee_config = state.fee_config.load(deps.storage)?;
+    let vault = config.get_vault_balances(deps.as_ref(), &env, &state)?;
+    let assets = &vault.total;
     let withdraw_amount = assets.vault_total.multiply_ratio(lp_amount, total_lp_supply);
 
     let mut response = if let Some(true) = immediate {
         // use full fee, zero unlocked
+        let (_, withdraw_pool_fee) = calc_fees(&fee_config, withdraw_amount, Decimal::one())?;
         create_withdraw_msgs(
-            &deps.querier,
             deps.storage,
             &env,
             &state,
-            &config,
+            &fee_config,
+            &vault.available(),
             sender,
             withdraw_amount,
-            Decimal::one(),
+            withdraw_pool_fee,
             Uint128::zero(),
             true,
         )?
     } else {
-        let fee_config = state.fee_config.load(deps.storage)?;
-
         state.add_to_unbond_history(
             deps.storage,
             sender.clone(),
@@ -237,6 +251,7 @@ pub fn execute_unbond_user(
                 start_time: env.block.time.seconds(),
                 release_time: env.block.time.seconds() + config.unbond_time_s,
             },
+            &vault.available(),
         )?;
 
         let withdraw_protocol_fee = withdraw_amount * fee_config.protocol_withdraw_fee;
@@ -270,28 +285,41 @@ pub fn execute_withdraw_unbonding_immediate(
 ) -> ContractResult {
     let state = State::default();
     let config = state.config.load(deps.storage)?;
+    let fee_config = state.fee_config.load(deps.storage)?;
     state.assert_not_nested(deps.storage)?;
 
-    let key = (info.sender.clone(), id);
-    let unbond_history = state.unbond_history.load(deps.storage, key.clone())?;
+    let unbond_history = state.unbond_history.load(deps.storage, (info.sender.clone(), id))?;
 
     let withdraw_amount = unbond_history.amount_asset;
 
     let withdraw_pool_fee_factor = unbond_history.pool_fee_factor(env.block.time.seconds());
+    let (_, withdraw_pool_fee) = calc_fees(&fee_config, withdraw_amount, withdraw_pool_fee_factor)?;
+    let available = config.query_available(&deps.querier, &env)?;
     let response = create_withdraw_msgs(
-        &deps.querier,
         deps.storage,
         &env,
         &state,
-        &config,
-        info.sender,
+        &fee_config,
+        &available,
+        info.sender.clone(),
         withdraw_amount,
-        withdraw_pool_fee_factor,
+        withdraw_pool_fee,
         withdraw_amount,
         true,
     )?;
 
-    state.unbond_history.remove(deps.storage, key);
+    // immediate withdrawals need all locked funds to be available, so the queue is filled
+    let mut totals = state.unbond_queue_totals.may_load(deps.storage)?.unwrap_or_default();
+    totals.fill(available_value(&available));
+    state.unbond_queue_totals.save(deps.storage, &totals)?;
+
+    state.take_from_unbond_history(
+        deps.storage,
+        info.sender,
+        id,
+        unbond_history,
+        withdraw_amount,
+    )?;
 
     Ok(response)
 }
@@ -299,53 +327,66 @@ pub fn execute_withdraw_unbonding_immediate(
 pub fn execute_withdraw_unbonded(deps: DepsMut, env: Env, info: MessageInfo) -> ContractResult {
     let state = State::default();
     let config = state.config.load(deps.storage)?;
+    let fee_config = state.fee_config.load(deps.storage)?;
     state.assert_not_nested(deps.storage)?;
 
     let current_time = env.block.time.seconds();
+    let available = config.query_available(&deps.querier, &env)?;
 
-    let unbond_history = state
-        .unbond_history
-        .prefix(info.sender.clone())
-        .range(deps.storage, None, None, Order::Ascending)
-        .filter_ok(|element| element.1.release_time <= current_time)
-        .take(30)
-        .collect::<StdResult<Vec<(u64, UnbondHistory)>>>()?;
+    // unbond items are filled in FIFO order by the available funds, also before their release time
+    let (totals, entries) = state.load_unbond_queue_entries(
+        deps.storage,
+        &info.sender,
+        available_value(&available),
+        None,
+        30,
+    )?;
+    state.unbond_queue_totals.save(deps.storage, &totals)?;
+    let fills = entries.into_iter().filter(|entry| !entry.fillable.is_zero()).collect_vec();
 
-    // check that something can be withdrawn
-    let withdraw_amount: Uint128 =
-        unbond_history.iter().map(|element| element.1.amount_asset).sum();
+    let mut withdraw_amount = Uint128::zero();
+    let mut withdraw_pool_fee = Uint128::zero();
+    for entry in fills {
+        // the pool fee decays with the time waited and is zero after the release time
+        let (_, pool_fee) =
+            calc_fees(&fee_config, entry.fillable, entry.history.pool_fee_factor(current_time))?;
 
-    let response = create_withdraw_msgs(
-        &deps.querier,
+        withdraw_amount += entry.fillable;
+        withdraw_pool_fee += pool_fee;
+
+        state.take_from_unbond_history(
+            deps.storage,
+            info.sender.clone(),
+            entry.id,
+            entry.history,
+            entry.fillable,
+        )?;
+    }
+
+    create_withdraw_msgs(
         deps.storage,
         &env,
         &state,
-        &config,
-        info.sender.clone(),
+        &fee_config,
+        &available,
+        info.sender,
         withdraw_amount,
-        Decimal::zero(),
+        withdraw_pool_fee,
         withdraw_amount,
         false,
-    )?;
-
-    // remove elements
-    for (id, _) in unbond_history {
-        state.unbond_history.remove(deps.storage, (info.sender.clone(), id));
-    }
-
-    Ok(response)
+    )
 }
 
 #[allow(clippy::too_many_arguments)]
 fn create_withdraw_msgs(
-    querier: &QuerierWrapper,
     storage: &mut dyn Storage,
     env: &Env,
     state: &State,
-    config: &ValidatedConfig,
+    fee_config: &ValidatedFeeConfig,
+    available: &[AssetAvailable],
     receiver: Addr,
     withdraw_amount: Uint128,
-    withdraw_pool_fee_factor: Decimal,
+    withdraw_pool_fee: Uint128,
     take_from_locked: Uint128,
     immediate: bool,
 ) -> ContractResult {
@@ -354,9 +395,10 @@ fn create_withdraw_msgs(
     }
 
     // check that enough assets are in the pool
-    let balance_locked = state.balance_locked.load(storage)?;
-    let locked_after = balance_locked.balance.checked_sub(take_from_locked).unwrap_or_default();
-    let available_amount = config.query_utoken_amount(querier, env)?;
+    let mut balance_locked = state.balance_locked.load(storage)?;
+    let unlocked = balance_locked.unlock(take_from_locked);
+    let locked_after = balance_locked.balance;
+    let available_amount = available_value(available);
 
     if immediate {
         // can only take immediate from not locked amount
@@ -367,34 +409,43 @@ fn create_withdraw_msgs(
         }
     }
 
-    state.balance_locked.save(
-        storage,
-        &BalanceLocked {
-            balance: locked_after,
-        },
-    )?;
-
-    let fee_config = state.fee_config.load(storage)?;
+    state.balance_locked.save(storage, &balance_locked)?;
 
-    let (withdraw_protocol_fee, withdraw_pool_fee) =
-        calc_fees(&fee_config, withdraw_amount, withdraw_pool_fee_factor)?;
+    let withdraw_protocol_fee = withdraw_amount * fee_config.protocol_withdraw_fee;
 
     let receive_amount =
         withdraw_amount.checked_sub(withdraw_protocol_fee)?.checked_sub(withdraw_pool_fee)?;
 
+    // locked withdrawals are paid out by the split frozen when they were locked, others pro rata to
+    // the available funds of all base assets
+    let weights = if unlocked.is_empty() {
+        available_values(available)
+    } else {
+        available
+            .iter()
+            .map(|asset| {
+                unlocked
+                    .iter()
+                    .find(|(utoken, _)| *utoken == asset.utoken)
+                    .map(|(_, value)| *value)
+                    .unwrap_or_default()
+            })
+            .collect_vec()
+    };
+
     let protocol_fee_msg = if !withdraw_protocol_fee.is_zero() {
-        Some(
-            native_asset(config.utoken.clone(), withdraw_protocol_fee)
-                .into_msg(fee_config.protocol_fee_contract)?
-                .to_specific()?,
-        )
+        Some(CosmosMsg::Bank(BankMsg::Send {
+            to_address: fee_config.protocol_fee_contract.to_string(),
+            amount: split_value_coins(available, &weights, withdraw_protocol_fee)?,
+        }))
     } else {
         None
     };
 
-    let withdraw_msg = native_asset(config.utoken.clone(), receive_amount)
-        .into_msg(receiver.clone())?
-        .to_specific()?;
+    let withdraw_msg: CosmosMsg<CustomMsgType> = CosmosMsg::Bank(BankMsg::Send {
+        to_address: receiver.to_string(),
+        amount: split_value_coins(available, &weights, receive_amount)?,
+    });
 
     Ok(Response::<CustomMsgType>::new()
         // send assets to the sender
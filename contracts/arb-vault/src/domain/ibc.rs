@@ -0,0 +1,250 @@
+use std::collections::hash_map::Entry;
+use std::collections::HashMap;
+
+use cosmwasm_std::{attr, Decimal, DepsMut, Env, MessageInfo, Reply, Response, StdError, Uint128};
+use eris::arb_vault::{IbcLifecycleComplete, LsdType, SudoMsg};
+
+use crate::error::{ContractError, ContractResult};
+use crate::extensions::ConfigEx;
+use crate::lsds::ibc::decode_transfer_sequence;
+use crate::state::State;
+
+//----------------------------------------------------------------------------------------
+//  IBC REDEMPTIONS
+//----------------------------------------------------------------------------------------
+
+/// Stores the sequence of a sent redemption transfer, replies arrive in the order of the transfers.
+pub fn handle_transfer_reply(deps: DepsMut, reply: Reply) -> ContractResult {
+    let state = State::default();
+    let id = state
+        .pop_ibc_pending_transfer(deps.storage)?
+        .ok_or(ContractError::InvalidReplyId(reply.id))?;
+    let mut redemption = state.ibc_redemptions.load(deps.storage, id)?;
+
+    let sequence = reply
+        .result
+        .into_result()
+        .map_err(StdError::generic_err)?
+        .data
+        .and_then(|data| decode_transfer_sequence(&data))
+        .ok_or_else(|| StdError::generic_err("transfer sequence not found"))?;
+
+    redemption.sequence = Some(sequence);
+    state.ibc_redemptions.save(deps.storage, redemption.id, &redemption)?;
+    state.ibc_transfers.save(
+        deps.storage,
+        (redemption.channel_id.as_str(), sequence),
+        &redemption.id,
+    )?;
+
+    Ok(Response::new().add_attributes(vec![
+        attr("action", "arb/ibc_transfer_sent"),
+        attr("id", redemption.id.to_string()),
+        attr("sequence", sequence.to_string()),
+    ]))
+}
+
+pub fn handle_sudo(deps: DepsMut, _env: Env, msg: SudoMsg) -> ContractResult {
+    let state = State::default();
+
+    let (channel, sequence, success) = match msg {
+        SudoMsg::IbcLifecycleComplete(IbcLifecycleComplete::IbcAck {
+            channel,
+            sequence,
+            success,
+            ..
+        }) => (channel, sequence, success),
+        SudoMsg::IbcLifecycleComplete(IbcLifecycleComplete::IbcTimeout {
+            channel,
+            sequence,
+        }) => (channel, sequence, false),
+    };
+
+    let id = state.ibc_transfers.may_load(deps.storage, (channel.as_str(), sequence))?;
+    let mut redemption = if let Some(id) = id {
+        state.ibc_redemptions.load(deps.storage, id)?
+    } else {
+        // not a redemption transfer
+        return Ok(Response::new());
+    };
+
+    if success {
+        // an expired redemption is counted again
+        redemption.acknowledged = true;
+        redemption.expired = false;
+        state.ibc_redemptions.save(deps.storage, redemption.id, &redemption)?;
+    } else {
+        // the LSD tokens are refunded to the vault
+        state.remove_ibc_redemption(deps.storage, &redemption);
+    }
+
+    Ok(Response::new().add_attributes(vec![
+        attr("action", "arb/ibc_lifecycle_complete"),
+        attr("id", redemption.id.to_string()),
+        attr("success", success.to_string()),
+    ]))
+}
+
+pub fn execute_complete_ibc_redemptions(
+    deps: DepsMut,
+    info: MessageInfo,
+    ids: Vec<u64>,
+) -> ContractResult {
+    let state = State::default();
+    state.assert_not_nested(deps.storage)?;
+
+    let asset_configs = state.config.load(deps.storage)?.asset_configs();
+    let mut received: HashMap<String, Uint128> =
+        info.funds.into_iter().map(|coin| (coin.denom, coin.amount)).collect();
+
+    let mut attributes = vec![attr("action", "arb/execute_complete_ibc_redemptions")];
+    for id in ids {
+        let mut redemption = state.ibc_redemptions.load(deps.storage, id)?;
+        if !redemption.acknowledged {
+            return Err(ContractError::IbcRedemptionNotAcknowledged(id));
+        }
+
+        let utoken = asset_configs
+            .iter()
+            .find(|(config, _)| config.lsds.iter().any(|lsd| lsd.name == redemption.name))
+            .map(|(config, _)| config.utoken.clone())
+            .ok_or_else(|| ContractError::AdapterNotFound(redemption.name.clone()))?;
+
+        // the funds are part of the vault balance, from now on instead of the expected amount
+        let available = received
+            .get_mut(&utoken)
+            .filter(|available| !available.is_zero())
+            .ok_or(ContractError::IbcRedemptionNotReceived(id))?;
+        let amount = redemption.expected_amount.min(*available);
+        *available -= amount;
+        redemption.expected_amount -= amount;
+
+        if redemption.expected_amount.is_zero() {
+            state.remove_ibc_redemption(deps.storage, &redemption);
+        } else {
+            state.ibc_redemptions.save(deps.storage, id, &redemption)?;
+        }
+
+        attributes.push(attr("id", id.to_string()));
+        attributes.push(attr("received_amount", amount));
+        attributes.push(attr("remaining_amount", redemption.expected_amount));
+    }
+
+    Ok(Response::new().add_attributes(attributes))
+}
+
+/// Settles acknowledged redemptions whose utoken was sent back by a plain transfer, which is already
+/// part of the vault balance and would otherwise be counted twice.
+pub fn execute_settle_ibc_redemptions(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    ids: Vec<u64>,
+) -> ContractResult {
+    let state = State::default();
+    state.assert_not_nested(deps.storage)?;
+    state.assert_sender_whitelisted(deps.storage, &info.sender)?;
+
+    let asset_configs = state.config.load(deps.storage)?.asset_configs();
+    let mut balances: HashMap<String, Uint128> = HashMap::new();
+
+    let mut attributes = vec![attr("action", "arb/execute_settle_ibc_redemptions")];
+    for id in ids {
+        let redemption = state.ibc_redemptions.load(deps.storage, id)?;
+        if !redemption.acknowledged {
+            return Err(ContractError::IbcRedemptionNotAcknowledged(id));
+        }
+
+        let config = asset_configs
+            .iter()
+            .find(|(config, _)| config.lsds.iter().any(|lsd| lsd.name == redemption.name))
+            .map(|(config, _)| config)
+            .ok_or_else(|| ContractError::AdapterNotFound(redemption.name.clone()))?;
+
+        // the returned utoken can only be settled once
+        let balance = match balances.entry(config.utoken.clone()) {
+            Entry::Occupied(entry) => entry.into_mut(),
+            Entry::Vacant(entry) => entry.insert(config.query_utoken_amount(&deps.querier, &env)?),
+        };
+        *balance = balance
+            .checked_sub(redemption.expected_amount)
+            .map_err(|_| ContractError::IbcRedemptionNotReceived(id))?;
+
+        state.remove_ibc_redemption(deps.storage, &redemption);
+
+        attributes.push(attr("id", id.to_string()));
+        attributes.push(attr("settled_amount", redemption.expected_amount));
+    }
+
+    Ok(Response::new().add_attributes(attributes))
+}
+
+pub fn execute_expire_ibc_redemptions(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    ids: Vec<u64>,
+) -> ContractResult {
+    let state = State::default();
+    state.assert_not_nested(deps.storage)?;
+    state.assert_sender_whitelisted(deps.storage, &info.sender)?;
+
+    let mut attributes = vec![attr("action", "arb/execute_expire_ibc_redemptions")];
+    for id in ids {
+        let mut redemption = state.ibc_redemptions.load(deps.storage, id)?;
+        if redemption.acknowledged
+            || redemption.expired
+            || env.block.time.seconds() <= redemption.timeout
+        {
+            return Err(ContractError::IbcRedemptionNotExpirable(id));
+        }
+
+        // kept to be restored by a late acknowledgement, a refund of the LSD tokens is part of the
+        // vault balance again
+        redemption.expired = true;
+        state.ibc_redemptions.save(deps.storage, id, &redemption)?;
+        attributes.push(attr("id", id.to_string()));
+    }
+
+    Ok(Response::new().add_attributes(attributes))
+}
+
+pub fn execute_set_ibc_exchange_rate(
+    deps: DepsMut,
+    info: MessageInfo,
+    name: String,
+    exchange_rate: Decimal,
+) -> ContractResult {
+    let state = State::default();
+    state.assert_owner(deps.storage, &info.sender)?;
+
+    if exchange_rate.is_zero() {
+        return Err(ContractError::ConfigToLow("exchange_rate".into()));
+    }
+
+    let mut config = state.config.load(deps.storage)?;
+    let lsd_type = config
+        .all_lsds_mut()
+        .into_iter()
+        .find(|lsd| lsd.name == name)
+        .map(|lsd| &mut lsd.lsd_type)
+        .ok_or_else(|| ContractError::AdapterNotFound(name.clone()))?;
+
+    if let LsdType::Ibc {
+        exchange_rate: current,
+        ..
+    } = lsd_type
+    {
+        *current = exchange_rate;
+    } else {
+        return Err(ContractError::AdapterNotFound(format!("ibc - {0}", name)));
+    }
+
+    state.config.save(deps.storage, &config)?;
+
+    Ok(Response::new().add_attributes(vec![
+        attr("action", "arb/execute_set_ibc_exchange_rate"),
+        attr("name", name),
+        attr("exchange_rate", exchange_rate.to_string()),
+    ]))
+}
@@ -1,8 +1,8 @@
 use crate::{
     constants::{CONTRACT_NAME, CONTRACT_VERSION},
     error::ContractResult,
-    extensions::UtilizationMethodEx,
-    state::{BalanceLocked, State},
+    extensions::{ConfigEx, UtilizationMethodEx},
+    state::{BalanceLocked, State, UnbondQueueTotals},
 };
 use cosmwasm_std::{Addr, DepsMut, Env, Response, StdResult, Uint128};
 use cw2::set_contract_version;
@@ -33,10 +33,13 @@ pub fn instantiate(deps: DepsMut, env: Env, msg: InstantiateMsg) -> ContractResu
         lsds,
         utoken: msg.utoken,
         utilization_method: msg.utilization_method,
+        assets: vec![],
     };
+    config.lsd_group(&env).assert_unique_assets()?;
     state.config.save(deps.storage, &config)?;
     state.owner.save(deps.storage, &deps.api.addr_validate(&msg.owner)?)?;
     state.unbond_id.save(deps.storage, &0)?;
+    state.unbond_queue_totals.save(deps.storage, &UnbondQueueTotals::default())?;
     state.fee_config.save(deps.storage, &msg.fee_config.validate(deps.api)?)?;
 
     state.update_whitelist(deps.storage, deps.api, msg.whitelist)?;
@@ -45,6 +48,7 @@ pub fn instantiate(deps: DepsMut, env: Env, msg: InstantiateMsg) -> ContractResu
         deps.storage,
         &BalanceLocked {
             balance: Uint128::zero(),
+            assets: vec![],
         },
     )?;
 
@@ -1,6 +1,7 @@
 pub mod callback;
 pub mod config;
 pub mod execute;
+pub mod ibc;
 pub mod instantiate;
 pub mod ownership;
 pub mod swap;
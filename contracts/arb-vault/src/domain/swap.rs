@@ -1,68 +1,132 @@
-// use crate::extensions::ConfigEx;
-// use astroport::asset::{token_asset, Asset, AssetInfo};
-// use cosmwasm_std::{Addr, Decimal, DepsMut, Env, MessageInfo, Response};
-// use eris::helper::addr_opt_validate;
-
-// use crate::{
-//     error::{ContractError, ContractResult},
-//     state::State,
-// };
-
-// #[allow(clippy::too_many_arguments)]
-// pub(crate) fn execute_swap_native(
-//     deps: DepsMut,
-//     env: Env,
-//     info: MessageInfo,
-//     offer_asset: Asset,
-//     ask_asset_info: Option<AssetInfo>,
-//     belief_price: Option<Decimal>,
-//     max_spread: Option<Decimal>,
-//     to: Option<String>,
-// ) -> ContractResult {
-//     let receiver_addr = addr_opt_validate(deps.api, &to)?;
-//     let to_addr = receiver_addr.unwrap_or_else(|| info.sender.clone());
-
-//     offer_asset.info.check(deps.api)?;
-//     if !offer_asset.is_native_token() {
-//         return Err(ContractError::Cw20DirectSwap {});
-//     }
-//     offer_asset.assert_sent_native_token_balance(&info)?;
-
-//     execute_swap(deps, env, offer_asset, ask_asset_info, belief_price, max_spread, to_addr)
-// }
-
-// #[allow(clippy::too_many_arguments)]
-// pub(crate) fn execute_swap_cw20(
-//     deps: DepsMut,
-//     env: Env,
-//     cw20_sender: String,
-//     offer_asset: Asset,
-//     ask_asset_info: Option<AssetInfo>,
-//     belief_price: Option<Decimal>,
-//     max_spread: Option<Decimal>,
-//     to: Option<String>,
-// ) -> ContractResult {
-//     let receiver_addr = addr_opt_validate(deps.api, &to)?;
-//     let cw20_addr = deps.api.addr_validate(&cw20_sender)?;
-//     let to_addr = receiver_addr.unwrap_or(cw20_addr);
-
-//     execute_swap(deps, env, offer_asset, ask_asset_info, belief_price, max_spread, to_addr)
-// }
-
-// fn execute_swap(
-//     deps: DepsMut,
-//     env: Env,
-//     offer_asset: Asset,
-//     ask_asset_info: Option<AssetInfo>,
-//     belief_price: Option<Decimal>,
-//     max_spread: Option<Decimal>,
-//     to_addr: cosmwasm_std::Addr,
-// ) -> ContractResult {
-//     let state = State::default();
-//     let config = state.config.load(deps.storage)?;
-//     let lsds = config.lsd_group(&env);
-
-//     let mut allowed_assets = lsds.;
-
-//     Ok(Response::new().add_attribute("action", "arb/execute_swap"))
-// }
+use std::ops::Div;
+
+use astroport::asset::{native_asset, native_asset_info};
+use cosmwasm_std::{attr, Decimal, DepsMut, Env, MessageInfo, Response, Uint128};
+use eris::arb_vault::{BalancesDetails, ExchangeHistory, ValidatedConfig, ValidatedFeeConfig};
+use eris::constants::DAY;
+use eris::helper::addr_opt_validate;
+use eris::{CustomMsgExt, CustomResponse};
+
+use crate::error::{ContractError, ContractResult, CustomResult};
+use crate::extensions::{BalancesEx, ConfigEx};
+use crate::state::State;
+
+pub(crate) struct SwapResult {
+    pub discount: Decimal,
+    pub return_amount: Uint128,
+    pub fee_amount: Uint128,
+}
+
+pub fn execute_swap(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    min_return: Option<Uint128>,
+    receiver: Option<String>,
+) -> ContractResult {
+    let state = State::default();
+    let config = state.config.load(deps.storage)?;
+    let fee_config = state.fee_config.load(deps.storage)?;
+    let lp_token = state.lp_token.load(deps.storage)?;
+
+    state.assert_not_nested(deps.storage)?;
+
+    if info.funds.len() != 1 {
+        return Err(ContractError::InvalidFunds {});
+    }
+
+    let offer = info.funds[0].clone();
+    if offer.amount.is_zero() {
+        return Err(ContractError::InvalidZeroAmount {});
+    }
+
+    let receiver = addr_opt_validate(deps.api, &receiver)?.unwrap_or(info.sender);
+
+    // the LSD is swapped for the base asset it belongs to
+    let offer_asset = native_asset_info(offer.denom.clone());
+    let vault = config.get_vault_balances(deps.as_ref(), &env, &state)?;
+    let asset = vault.get_asset_by_lsd(&env, &offer_asset)?;
+    let mut lsds = asset.config.lsd_group(&env);
+    let mut balances = asset.balances.clone();
+
+    let lsd = lsds.get_adapter_by_asset(offer_asset)?;
+    lsd.assert_not_disabled()?;
+
+    let xfactor = balances.get_by_name(&lsd.name)?.xfactor;
+    let value = offer.amount * xfactor;
+
+    // removing the offered amount, as it is already part of the contract balance
+    balances.vault_total = balances.vault_total.checked_sub(value)?;
+
+    let swap = calc_swap(&asset.config, &fee_config, &balances, value)?;
+
+    if let Some(min_return) = min_return {
+        if swap.return_amount < min_return {
+            return Err(ContractError::SwapReturnTooLow {
+                return_amount: swap.return_amount,
+                min_return,
+            });
+        }
+    }
+
+    let return_msg = native_asset(asset.config.utoken.clone(), swap.return_amount)
+        .into_msg(receiver.clone())?
+        .to_specific()?;
+
+    let fee_msg = if !swap.fee_amount.is_zero() {
+        Some(
+            native_asset(asset.config.utoken.clone(), swap.fee_amount)
+                .into_msg(fee_config.protocol_fee_contract)?
+                .to_specific()?,
+        )
+    } else {
+        None
+    };
+
+    // the discount minus the protocol fee is the profit of the vault
+    let profit = value.checked_sub(swap.return_amount)?.checked_sub(swap.fee_amount)?;
+    let new_vault_total = vault
+        .total
+        .vault_total
+        .checked_sub(value * asset.price)?
+        .checked_add(profit * asset.price)?;
+
+    if !lp_token.total_supply.is_zero() {
+        state.exchange_history.save(
+            deps.storage,
+            env.block.time.seconds().div(DAY),
+            &ExchangeHistory {
+                exchange_rate: Decimal::from_ratio(new_vault_total, lp_token.total_supply),
+                time_s: env.block.time.seconds(),
+            },
+        )?;
+    }
+
+    Ok(Response::new().add_message(return_msg).add_optional_message(fee_msg).add_attributes(vec![
+        attr("action", "arb/execute_swap"),
+        attr("type", lsd.name.clone()),
+        attr("receiver", receiver),
+        attr("offer_amount", offer.amount),
+        attr("value", value),
+        attr("discount", swap.discount.to_string()),
+        attr("return_amount", swap.return_amount),
+        attr("fee_amount", swap.fee_amount),
+    ]))
+}
+
+/// Prices an LSD value in utoken, using the discount of the lowest utilization step that can provide it.
+pub(crate) fn calc_swap(
+    config: &ValidatedConfig,
+    fee_config: &ValidatedFeeConfig,
+    balances: &BalancesDetails,
+    value: Uint128,
+) -> CustomResult<SwapResult> {
+    let discount = balances.calc_discount_for_amount(config, &value)?;
+    let discount_amount = value * discount;
+
+    Ok(SwapResult {
+        discount,
+        return_amount: value.checked_sub(discount_amount)?,
+        fee_amount: discount_amount * fee_config.protocol_performance_fee,
+    })
+}
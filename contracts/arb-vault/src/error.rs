@@ -70,6 +70,9 @@ pub enum ContractError {
     #[error("Adapter duplicated: {0}")]
     AdapterNameDuplicate(String),
 
+    #[error("Adapter token duplicated: {0}")]
+    AdapterAssetDuplicate(String),
+
     #[error("cannot find `instantiate` event")]
     CannotFindInstantiateEvent {},
 
@@ -142,6 +145,36 @@ pub enum ContractError {
 
     #[error("Invalid funds deposited")]
     InvalidFunds {},
+
+    #[error("IBC redemption {0} has not been acknowledged")]
+    IbcRedemptionNotAcknowledged(u64),
+
+    #[error("No utoken received for IBC redemption {0}")]
+    IbcRedemptionNotReceived(u64),
+
+    #[error("IBC redemption {0} cannot expire")]
+    IbcRedemptionNotExpirable(u64),
+
+    #[error("Base asset not found: {0}")]
+    BaseAssetNotFound(String),
+
+    #[error("Base asset duplicated: {0}")]
+    BaseAssetDuplicate(String),
+
+    #[error("Cannot remove a base asset that has funds")]
+    CannotRemoveAssetThatHasFunds {},
+
+    #[error("Could not query price of {0}: {1}")]
+    PriceError(String, String),
+
+    #[error("Price of {0} is stale")]
+    PriceStale(String),
+
+    #[error("Swap return {return_amount} is lower than the minimum return {min_return}")]
+    SwapReturnTooLow {
+        return_amount: Uint128,
+        min_return: Uint128,
+    },
 }
 
 pub fn adapter_error(adapter: &str, msg: &str, orig: StdError) -> ContractError {
@@ -1,6 +1,133 @@
 use crate::error::{ContractError, CustomResult};
-use cosmwasm_std::{Decimal, Uint128};
+use crate::extensions::ConfigEx;
+use astroport::asset::AssetInfo;
+use cosmwasm_std::{coin, Coin, Decimal, Env, Uint128};
 use eris::arb_vault::{BalancesDetails, ClaimBalance, UtilizationMethod, ValidatedConfig};
+use itertools::Itertools;
+
+/// Funds of a base asset held by the contract
+pub(crate) struct AssetAvailable {
+    pub utoken: String,
+    // value of one unit in the primary utoken
+    pub price: Decimal,
+    pub available: Uint128,
+}
+
+/// Balances of a single base asset, denominated in the base asset
+pub(crate) struct AssetBalances {
+    // config containing only the base asset and its LSDs
+    pub config: ValidatedConfig,
+    pub price: Decimal,
+    pub balances: BalancesDetails,
+}
+
+pub(crate) struct VaultBalances {
+    // aggregated over all base assets, valued in the primary utoken
+    pub total: BalancesDetails,
+    pub assets: Vec<AssetBalances>,
+}
+
+impl VaultBalances {
+    pub fn get_asset(&self, utoken: &str) -> CustomResult<&AssetBalances> {
+        self.assets
+            .iter()
+            .find(|asset| asset.config.utoken == utoken)
+            .ok_or_else(|| ContractError::BaseAssetNotFound(utoken.to_string()))
+    }
+
+    pub fn available(&self) -> Vec<AssetAvailable> {
+        self.assets
+            .iter()
+            .map(|asset| AssetAvailable {
+                utoken: asset.config.utoken.clone(),
+                price: asset.price,
+                available: asset.balances.vault_available,
+            })
+            .collect_vec()
+    }
+
+    pub fn get_asset_by_lsd(
+        &self,
+        env: &Env,
+        asset_info: &AssetInfo,
+    ) -> CustomResult<&AssetBalances> {
+        self.assets
+            .iter()
+            .find(|asset| {
+                asset.config.lsd_group(env).get_adapter_by_asset(asset_info.clone()).is_ok()
+            })
+            .ok_or_else(|| ContractError::AdapterNotFound(format!("token - {0}", asset_info)))
+    }
+}
+
+/// Total value of the available funds in the primary utoken.
+pub(crate) fn available_value(available: &[AssetAvailable]) -> Uint128 {
+    available.iter().map(|asset| asset.available * asset.price).sum()
+}
+
+/// Value of the available funds of each base asset in the primary utoken.
+pub(crate) fn available_values(available: &[AssetAvailable]) -> Vec<Uint128> {
+    available.iter().map(|asset| asset.available * asset.price).collect_vec()
+}
+
+/// Splits a value in the primary utoken over the base assets, pro rata to their available funds.
+pub(crate) fn split_value(
+    available: &[AssetAvailable],
+    value: Uint128,
+) -> CustomResult<Vec<Uint128>> {
+    split_value_by(available, &available_values(available), value)
+}
+
+/// Splits a value in the primary utoken over the base assets, pro rata to the weight of each asset.
+/// No amount exceeds the available funds of its asset. The remainder goes to the first assets with
+/// funds left, a remainder below one unit of an asset stays in the vault.
+pub(crate) fn split_value_by(
+    available: &[AssetAvailable],
+    weights: &[Uint128],
+    value: Uint128,
+) -> CustomResult<Vec<Uint128>> {
+    let total: Uint128 = weights.iter().sum();
+    let to_amount = |value: Uint128, price: Decimal| {
+        value
+            .checked_div_floor(price)
+            .map_err(|e| ContractError::CalculationError("split value".into(), e.to_string()))
+    };
+
+    let mut amounts = vec![Uint128::zero(); available.len()];
+    let mut remaining = value;
+    if !total.is_zero() {
+        for ((amount, asset), weight) in amounts.iter_mut().zip(available.iter()).zip(weights) {
+            let asset_value = value.multiply_ratio(*weight, total);
+            *amount = to_amount(asset_value, asset.price)?.min(asset.available);
+            remaining = remaining.checked_sub(*amount * asset.price)?;
+        }
+    }
+
+    for (amount, asset) in amounts.iter_mut().zip(available.iter()) {
+        if remaining.is_zero() {
+            break;
+        }
+        let extra = to_amount(remaining, asset.price)?.min(asset.available - *amount);
+        *amount += extra;
+        remaining = remaining.checked_sub(extra * asset.price)?;
+    }
+
+    Ok(amounts)
+}
+
+/// Coins paying out a value in the primary utoken, pro rata to the weight of each base asset.
+pub(crate) fn split_value_coins(
+    available: &[AssetAvailable],
+    weights: &[Uint128],
+    value: Uint128,
+) -> CustomResult<Vec<Coin>> {
+    Ok(split_value_by(available, weights, value)?
+        .into_iter()
+        .zip(available.iter())
+        .filter(|(amount, _)| !amount.is_zero())
+        .map(|(amount, asset)| coin(amount.u128(), asset.utoken.clone()))
+        .collect_vec())
+}
 
 pub trait BalancesEx {
     fn get_max_utilization_for_profit(
@@ -20,6 +147,12 @@ pub trait BalancesEx {
         profit: &Decimal,
     ) -> CustomResult<Uint128>;
 
+    fn calc_discount_for_amount(
+        &self,
+        config: &ValidatedConfig,
+        amount: &Uint128,
+    ) -> CustomResult<Decimal>;
+
     fn get_by_name(&self, name: &str) -> CustomResult<&ClaimBalance>;
 }
 
@@ -75,6 +208,20 @@ impl BalancesEx for BalancesDetails {
         Ok(vault_takeable)
     }
 
+    fn calc_discount_for_amount(
+        &self,
+        config: &ValidatedConfig,
+        amount: &Uint128,
+    ) -> CustomResult<Decimal> {
+        // the lowest profit step that can provide the full amount is used as discount
+        self.calc_all_takeable_steps(config)?
+            .into_iter()
+            .sorted_by(|a, b| a.0.cmp(&b.0))
+            .find(|(_, takeable)| takeable >= amount)
+            .map(|(profit, _)| profit)
+            .ok_or(ContractError::NotEnoughFundsTakeable {})
+    }
+
     fn get_by_name(&self, name: &str) -> CustomResult<&ClaimBalance> {
         if let Some(claim) = self.details.iter().find(|detail| detail.name == *name) {
             Ok(claim)
@@ -1,13 +1,39 @@
+use std::str::FromStr;
+
+use crate::error::{ContractError, CustomResult};
+use crate::extensions::balances_ex::{
+    available_value, AssetAvailable, AssetBalances, VaultBalances,
+};
+use crate::lsds::generic::query_raw_json;
 use crate::lsds::lsdgroup::LsdGroup;
+use crate::state::State;
 use astroport::asset::native_asset_info;
-use cosmwasm_std::{Addr, Env, QuerierWrapper, StdResult, Uint128};
-use eris::arb_vault::Config;
+use cosmwasm_std::{Addr, Binary, Decimal, Deps, Env, QuerierWrapper, StdResult, Uint128};
+use eris::arb_vault::{
+    AssetBalance, AssetPrice, BalancesDetails, Config, LsdConfig, ValidatedConfig,
+};
+use eris::helpers::json::JsonValue;
 use itertools::Itertools;
 
 pub trait ConfigEx {
     fn lsd_group(&self, env: &Env) -> LsdGroup;
     fn lsd_group_by_names(&self, env: &Env, names: Option<Vec<String>>) -> LsdGroup;
     fn query_utoken_amount(&self, querier: &QuerierWrapper, env: &Env) -> StdResult<Uint128>;
+
+    fn all_lsds(&self) -> Vec<&LsdConfig<Addr>>;
+    fn all_lsds_mut(&mut self) -> Vec<&mut LsdConfig<Addr>>;
+    fn asset_configs(&self) -> Vec<(ValidatedConfig, AssetPrice<Addr>)>;
+    fn query_available(
+        &self,
+        querier: &QuerierWrapper,
+        env: &Env,
+    ) -> CustomResult<Vec<AssetAvailable>>;
+    fn get_vault_balances(
+        &self,
+        deps: Deps,
+        env: &Env,
+        state: &State,
+    ) -> CustomResult<VaultBalances>;
 }
 
 impl ConfigEx for Config<Addr> {
@@ -17,15 +43,185 @@ impl ConfigEx for Config<Addr> {
 
     fn lsd_group_by_names(&self, env: &Env, names: Option<Vec<String>>) -> LsdGroup {
         let lsds = if let Some(names) = names {
-            self.lsds.iter().filter(|lsd| names.contains(&lsd.name)).collect_vec()
+            self.all_lsds().into_iter().filter(|lsd| names.contains(&lsd.name)).collect_vec()
         } else {
-            self.lsds.iter().collect_vec()
+            self.all_lsds()
         };
 
-        LsdGroup::new(&lsds, env.contract.address.clone())
+        LsdGroup::new(&lsds, env)
     }
 
     fn query_utoken_amount(&self, querier: &QuerierWrapper, env: &Env) -> StdResult<Uint128> {
         native_asset_info(self.utoken.clone()).query_pool(querier, env.contract.address.clone())
     }
+
+    fn all_lsds(&self) -> Vec<&LsdConfig<Addr>> {
+        self.lsds.iter().chain(self.assets.iter().flat_map(|asset| asset.lsds.iter())).collect_vec()
+    }
+
+    fn all_lsds_mut(&mut self) -> Vec<&mut LsdConfig<Addr>> {
+        self.lsds
+            .iter_mut()
+            .chain(self.assets.iter_mut().flat_map(|asset| asset.lsds.iter_mut()))
+            .collect_vec()
+    }
+
+    /// Splits the config into one config per base asset, the primary utoken being the first.
+    fn asset_configs(&self) -> Vec<(ValidatedConfig, AssetPrice<Addr>)> {
+        let primary = ValidatedConfig {
+            utoken: self.utoken.clone(),
+            utilization_method: self.utilization_method.clone(),
+            unbond_time_s: self.unbond_time_s,
+            lsds: self.lsds.clone(),
+            assets: vec![],
+        };
+
+        let mut configs = vec![(primary, AssetPrice::Ratio(Decimal::one()))];
+        for asset in self.assets.iter() {
+            configs.push((
+                ValidatedConfig {
+                    utoken: asset.utoken.clone(),
+                    utilization_method: asset.utilization_method.clone(),
+                    unbond_time_s: self.unbond_time_s,
+                    lsds: asset.lsds.clone(),
+                    assets: vec![],
+                },
+                asset.price.clone(),
+            ));
+        }
+        configs
+    }
+
+    fn query_available(
+        &self,
+        querier: &QuerierWrapper,
+        env: &Env,
+    ) -> CustomResult<Vec<AssetAvailable>> {
+        self.asset_configs()
+            .into_iter()
+            .map(|(config, price)| {
+                Ok(AssetAvailable {
+                    price: query_price(querier, env, &config.utoken, &price)?,
+                    available: config.query_utoken_amount(querier, env)?,
+                    utoken: config.utoken,
+                })
+            })
+            .collect()
+    }
+
+    fn get_vault_balances(
+        &self,
+        deps: Deps,
+        env: &Env,
+        state: &State,
+    ) -> CustomResult<VaultBalances> {
+        let available = self.query_available(&deps.querier, env)?;
+        let balance_locked = state.balance_locked.load(deps.storage)?;
+        let locked_user_withdrawls = balance_locked.balance;
+
+        // the locked user withdrawals keep the split over the base assets from when they were locked
+        let locked = balance_locked.asset_amounts(&available)?;
+
+        let mut total = BalancesDetails {
+            tvl_utoken: Uint128::zero(),
+            vault_total: Uint128::zero(),
+            vault_available: available_value(&available),
+            vault_takeable: Uint128::zero(),
+            locked_user_withdrawls,
+            lsd_unbonding: Uint128::zero(),
+            lsd_withdrawable: Uint128::zero(),
+            lsd_xvalue: Uint128::zero(),
+            assets: vec![],
+            details: vec![],
+        };
+
+        let mut assets = vec![];
+        for (((config, _), asset), locked) in
+            self.asset_configs().into_iter().zip(available.into_iter()).zip(locked.into_iter())
+        {
+            let balances =
+                config.lsd_group(env).get_total_assets_err(deps, env, asset.available, locked)?;
+            let price = asset.price;
+
+            total.tvl_utoken += balances.tvl_utoken * price;
+            total.lsd_unbonding += balances.lsd_unbonding * price;
+            total.lsd_withdrawable += balances.lsd_withdrawable * price;
+            total.lsd_xvalue += balances.lsd_xvalue * price;
+            // details stay denominated in the base asset of the LSD
+            total.details.extend(balances.details.iter().cloned());
+            total.assets.push(AssetBalance {
+                utoken: config.utoken.clone(),
+                price,
+                tvl_utoken: balances.tvl_utoken,
+                vault_total: balances.vault_total,
+                vault_available: balances.vault_available,
+                vault_takeable: balances.vault_takeable,
+                locked_user_withdrawls: balances.locked_user_withdrawls,
+            });
+
+            assets.push(AssetBalances {
+                config,
+                price,
+                balances,
+            });
+        }
+
+        total.vault_total =
+            total.tvl_utoken.checked_sub(locked_user_withdrawls).unwrap_or_default();
+        total.vault_takeable =
+            total.vault_available.checked_sub(locked_user_withdrawls).unwrap_or_default();
+
+        Ok(VaultBalances {
+            total,
+            assets,
+        })
+    }
+}
+
+fn query_price(
+    querier: &QuerierWrapper,
+    env: &Env,
+    utoken: &str,
+    price: &AssetPrice<Addr>,
+) -> CustomResult<Decimal> {
+    match price {
+        AssetPrice::Ratio(ratio) => Ok(*ratio),
+        AssetPrice::Oracle {
+            contract,
+            query,
+            path,
+            updated_at_path,
+            max_age,
+        } => {
+            let msg = Binary::from(query.replace("{utoken}", utoken).into_bytes());
+            let response = query_raw_json(querier, contract, msg)
+                .map_err(|e| ContractError::PriceError(utoken.to_string(), e.to_string()))?;
+
+            let response = JsonValue::parse(&response)
+                .map_err(|e| ContractError::PriceError(utoken.to_string(), e.to_string()))?;
+
+            let value = response.at(path).and_then(|value| value.as_text()).ok_or_else(|| {
+                ContractError::PriceError(utoken.to_string(), "price not found".into())
+            })?;
+            let price = Decimal::from_str(value)
+                .map_err(|e| ContractError::PriceError(utoken.to_string(), e.to_string()))?;
+
+            if price.is_zero() {
+                return Err(ContractError::PriceError(utoken.to_string(), "zero price".into()));
+            }
+
+            let updated_at = response
+                .at(updated_at_path)
+                .and_then(|value| value.as_text())
+                .and_then(|value| value.parse::<u64>().ok())
+                .ok_or_else(|| {
+                    ContractError::PriceError(utoken.to_string(), "update time not found".into())
+                })?;
+            if updated_at.saturating_add(*max_age) < env.block.time.seconds() {
+                return Err(ContractError::PriceStale(utoken.to_string()));
+            }
+
+            Ok(price)
+        },
+    }
 }
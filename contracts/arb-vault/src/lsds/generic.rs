@@ -0,0 +1,215 @@
+use std::str::FromStr;
+
+use astroport::asset::{native_asset_info, AssetInfo};
+use cosmwasm_std::{
+    coin, to_json_vec, Addr, Binary, ContractResult, CosmosMsg, Decimal, Deps, Empty,
+    QuerierWrapper, QueryRequest, StdError, StdResult, SystemResult, Uint128, WasmMsg, WasmQuery,
+};
+use eris::{arb_vault::GenericLsdSpec, helpers::json::JsonValue};
+use eris_chain_adapter::types::CustomMsgType;
+
+use crate::error::{adapter_error, adapter_error_empty, CustomResult};
+
+use super::lsdadapter::LsdAdapter;
+
+pub struct GenericLsdAdapter {
+    pub exchange_rate_cache: Option<Decimal>,
+    pub unbonding_requests_cache: Option<Vec<UnbondingRequestCacheItem>>,
+
+    pub wallet: Addr,
+    pub addr: Addr,
+    pub denom: String,
+    pub spec: GenericLsdSpec,
+}
+
+pub struct UnbondingRequestCacheItem {
+    pub amount: Uint128,
+    pub withdrawable: bool,
+}
+
+impl GenericLsdAdapter {
+    fn render(&self, template: &str, amount: Uint128, page: Option<(&str, u32)>) -> Binary {
+        Binary::from(
+            GenericLsdSpec::render(template, self.wallet.as_str(), amount, page).into_bytes(),
+        )
+    }
+
+    fn query(
+        &self,
+        deps: &Deps,
+        template: &str,
+        page: Option<(&str, u32)>,
+        msg: &str,
+    ) -> CustomResult<JsonValue> {
+        query_raw_json(&deps.querier, &self.addr, self.render(template, Uint128::zero(), page))
+            .and_then(|response| JsonValue::parse(&response))
+            .map_err(|a| adapter_error("generic", msg, a))
+    }
+
+    fn cached_exchange_rate(&mut self, deps: &Deps) -> CustomResult<Decimal> {
+        if self.exchange_rate_cache.is_none() {
+            let response =
+                self.query(deps, &self.spec.exchange_rate_query, None, "query_exchange_rate")?;
+            let value = response
+                .at(&self.spec.exchange_rate_path)
+                .and_then(|value| value.as_text())
+                .ok_or_else(|| adapter_error_empty("generic", "exchange rate not found"))?;
+
+            self.exchange_rate_cache = Some(
+                Decimal::from_str(value)
+                    .map_err(|a| adapter_error("generic", "exchange rate", a))?,
+            );
+        }
+
+        Ok(self.exchange_rate_cache.unwrap())
+    }
+
+    fn cached_unbonding_requests(
+        &mut self,
+        deps: &Deps,
+    ) -> CustomResult<&Vec<UnbondingRequestCacheItem>> {
+        if self.unbonding_requests_cache.is_none() {
+            self.unbonding_requests_cache = Some(self.query_unbonding_requests(deps)?);
+        }
+
+        Ok(self.unbonding_requests_cache.as_ref().unwrap())
+    }
+
+    /// Queries all unbonding requests, page by page if the hub paginates them.
+    fn query_unbonding_requests(
+        &self,
+        deps: &Deps,
+    ) -> CustomResult<Vec<UnbondingRequestCacheItem>> {
+        let mut result = vec![];
+        let mut start_after = "null".to_string();
+
+        loop {
+            let pagination = self.spec.unbonding_requests_pagination.as_ref();
+            let response = self.query(
+                deps,
+                &self.spec.unbonding_requests_query,
+                pagination.map(|pagination| (start_after.as_str(), pagination.limit)),
+                "query_unbonding_requests",
+            )?;
+            let requests = response
+                .at(&self.spec.unbonding_requests_path)
+                .and_then(|value| value.as_array())
+                .ok_or_else(|| adapter_error_empty("generic", "unbonding requests not found"))?;
+
+            for request in requests {
+                result.push(self.parse_unbonding_request(request)?);
+            }
+
+            // a page that is not full is the last one
+            let pagination = match pagination {
+                Some(pagination) if requests.len() >= pagination.limit as usize => pagination,
+                _ => return Ok(result),
+            };
+
+            let cursor = requests
+                .last()
+                .and_then(|request| request.at(&pagination.cursor_path))
+                .ok_or_else(|| adapter_error_empty("generic", "cursor not found"))?
+                .to_json_string()
+                .map_err(|a| adapter_error("generic", "cursor", a))?;
+
+            if cursor == start_after {
+                return Err(adapter_error_empty("generic", "cursor did not advance"));
+            }
+            start_after = cursor;
+        }
+    }
+
+    fn parse_unbonding_request(
+        &self,
+        request: &JsonValue,
+    ) -> CustomResult<UnbondingRequestCacheItem> {
+        let amount = request
+            .at(&self.spec.amount_path)
+            .and_then(|value| value.as_text())
+            .ok_or_else(|| adapter_error_empty("generic", "amount not found"))?;
+        let withdrawable = request
+            .at(&self.spec.withdrawable_path)
+            .and_then(|value| value.as_bool())
+            .ok_or_else(|| adapter_error_empty("generic", "withdrawable not found"))?;
+
+        Ok(UnbondingRequestCacheItem {
+            amount: Uint128::from_str(amount).map_err(|a| adapter_error("generic", "amount", a))?,
+            withdrawable,
+        })
+    }
+}
+
+impl LsdAdapter for GenericLsdAdapter {
+    fn used_contracts(&self) -> Vec<Addr> {
+        vec![self.addr.clone()]
+    }
+
+    fn asset(&self) -> AssetInfo {
+        native_asset_info(self.denom.clone())
+    }
+
+    fn unbond(&self, _deps: &Deps, amount: Uint128) -> CustomResult<Vec<CosmosMsg<CustomMsgType>>> {
+        Ok(vec![CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: self.addr.to_string(),
+            funds: vec![coin(amount.u128(), self.denom.to_string())],
+            msg: self.render(&self.spec.unbond_msg, amount, None),
+        })])
+    }
+
+    fn query_unbonding(&mut self, deps: &Deps) -> CustomResult<Uint128> {
+        Ok(self
+            .cached_unbonding_requests(deps)?
+            .iter()
+            .filter(|a| !a.withdrawable)
+            .map(|a| a.amount)
+            .sum())
+    }
+
+    fn withdraw(
+        &mut self,
+        _deps: &Deps,
+        amount: Uint128,
+    ) -> CustomResult<Vec<CosmosMsg<CustomMsgType>>> {
+        Ok(vec![CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: self.addr.to_string(),
+            funds: vec![],
+            msg: self.render(&self.spec.withdraw_msg, amount, None),
+        })])
+    }
+
+    fn query_withdrawable(&mut self, deps: &Deps) -> CustomResult<Uint128> {
+        Ok(self
+            .cached_unbonding_requests(deps)?
+            .iter()
+            .filter(|a| a.withdrawable)
+            .map(|a| a.amount)
+            .sum())
+    }
+
+    fn query_factor_x_to_normal(&mut self, deps: &Deps) -> CustomResult<Decimal> {
+        self.cached_exchange_rate(deps)
+    }
+}
+
+//----------------------------------------------------------------------------------------
+//  RAW JSON
+//----------------------------------------------------------------------------------------
+
+/// Executes a smart query and returns the raw JSON response.
+pub(crate) fn query_raw_json(
+    querier: &QuerierWrapper,
+    contract_addr: &Addr,
+    msg: Binary,
+) -> StdResult<Binary> {
+    let request: QueryRequest<Empty> = QueryRequest::Wasm(WasmQuery::Smart {
+        contract_addr: contract_addr.to_string(),
+        msg,
+    });
+
+    match querier.raw_query(&to_json_vec(&request)?) {
+        SystemResult::Ok(ContractResult::Ok(value)) => Ok(value),
+        SystemResult::Ok(ContractResult::Err(err)) => Err(StdError::generic_err(err)),
+        SystemResult::Err(err) => Err(StdError::generic_err(err.to_string())),
+    }
+}
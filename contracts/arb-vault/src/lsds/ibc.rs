@@ -0,0 +1,173 @@
+use astroport::asset::{native_asset_info, AssetInfo};
+use cosmwasm_std::{Addr, CosmosMsg, Decimal, Deps, Order, StdResult, Timestamp, Uint128};
+use eris::arb_vault::IbcRedemption;
+use eris_chain_adapter::types::CustomMsgType;
+
+use crate::error::CustomResult;
+use crate::state::State;
+
+use super::lsdadapter::LsdAdapter;
+
+const TRANSFER_TYPE_URL: &str = "/ibc.applications.transfer.v1.MsgTransfer";
+
+pub struct IbcLsd {
+    pub name: String,
+    pub wallet: Addr,
+    pub time: Timestamp,
+
+    pub denom: String,
+    pub channel_id: String,
+    pub receiver: String,
+    pub memo: String,
+    pub timeout_s: u64,
+    pub exchange_rate: Decimal,
+}
+
+impl IbcLsd {
+    fn query_redemptions(&self, deps: &Deps) -> StdResult<Vec<IbcRedemption>> {
+        State::default()
+            .ibc_redemptions
+            .range(deps.storage, None, None, Order::Ascending)
+            .map(|item| item.map(|(_, redemption)| redemption))
+            .filter(|item| item.as_ref().map_or(true, |redemption| redemption.name == self.name))
+            .collect()
+    }
+
+    fn get_transfer_msg(&self, amount: Uint128) -> CosmosMsg<CustomMsgType> {
+        let memo = self
+            .memo
+            .replace("{wallet}", self.wallet.as_str())
+            .replace("{amount}", &amount.to_string());
+
+        CosmosMsg::Stargate {
+            type_url: TRANSFER_TYPE_URL.to_string(),
+            value: encode_transfer(
+                &self.channel_id,
+                &self.denom,
+                amount,
+                self.wallet.as_str(),
+                &self.receiver,
+                self.time.plus_seconds(self.timeout_s).nanos(),
+                &memo,
+            )
+            .into(),
+        }
+    }
+}
+
+impl LsdAdapter for IbcLsd {
+    fn used_contracts(&self) -> Vec<Addr> {
+        vec![]
+    }
+
+    fn asset(&self) -> AssetInfo {
+        native_asset_info(self.denom.clone())
+    }
+
+    fn unbond(&self, _deps: &Deps, amount: Uint128) -> CustomResult<Vec<CosmosMsg<CustomMsgType>>> {
+        Ok(vec![self.get_transfer_msg(amount)])
+    }
+
+    fn query_unbonding(&mut self, deps: &Deps) -> CustomResult<Uint128> {
+        // redemptions are counted from sending the transfer until the utoken returned
+        Ok(self
+            .query_redemptions(deps)?
+            .iter()
+            .filter(|redemption| !redemption.expired)
+            .map(|redemption| redemption.expected_amount)
+            .sum())
+    }
+
+    fn withdraw(
+        &mut self,
+        _deps: &Deps,
+        _amount: Uint128,
+    ) -> CustomResult<Vec<CosmosMsg<CustomMsgType>>> {
+        // the utoken is sent back by the remote chain
+        Ok(vec![])
+    }
+
+    fn query_withdrawable(&mut self, _deps: &Deps) -> CustomResult<Uint128> {
+        Ok(Uint128::zero())
+    }
+
+    fn query_factor_x_to_normal(&mut self, _deps: &Deps) -> CustomResult<Decimal> {
+        Ok(self.exchange_rate)
+    }
+
+    fn ibc_redemption(&self, amount: Uint128) -> Option<IbcRedemption> {
+        Some(IbcRedemption {
+            id: 0,
+            name: self.name.clone(),
+            channel_id: self.channel_id.clone(),
+            sequence: None,
+            amount,
+            expected_amount: amount * self.exchange_rate,
+            timeout: self.time.plus_seconds(self.timeout_s).seconds(),
+            acknowledged: false,
+            expired: false,
+        })
+    }
+}
+
+//----------------------------------------------------------------------------------------
+//  PROTOBUF
+//----------------------------------------------------------------------------------------
+
+// IbcMsg::Transfer does not support a memo, so the MsgTransfer is encoded directly.
+fn encode_transfer(
+    channel_id: &str,
+    denom: &str,
+    amount: Uint128,
+    sender: &str,
+    receiver: &str,
+    timeout_nanos: u64,
+    memo: &str,
+) -> Vec<u8> {
+    let mut token = vec![];
+    encode_bytes(&mut token, 1, denom.as_bytes());
+    encode_bytes(&mut token, 2, amount.to_string().as_bytes());
+
+    let mut msg = vec![];
+    encode_bytes(&mut msg, 1, b"transfer");
+    encode_bytes(&mut msg, 2, channel_id.as_bytes());
+    encode_bytes(&mut msg, 3, &token);
+    encode_bytes(&mut msg, 4, sender.as_bytes());
+    encode_bytes(&mut msg, 5, receiver.as_bytes());
+    encode_varint(&mut msg, 7 << 3);
+    encode_varint(&mut msg, timeout_nanos);
+    if !memo.is_empty() {
+        encode_bytes(&mut msg, 8, memo.as_bytes());
+    }
+    msg
+}
+
+/// Reads the sequence of a MsgTransferResponse.
+pub(crate) fn decode_transfer_sequence(data: &[u8]) -> Option<u64> {
+    if *data.first()? != 1 << 3 {
+        return None;
+    }
+
+    let mut value = 0u64;
+    for (i, byte) in data[1..].iter().take(10).enumerate() {
+        value |= u64::from(byte & 0x7f) << (7 * i);
+        if byte & 0x80 == 0 {
+            return Some(value);
+        }
+    }
+    None
+}
+
+fn encode_bytes(buf: &mut Vec<u8>, field: u64, value: &[u8]) {
+    encode_varint(buf, (field << 3) | 2);
+    encode_varint(buf, value.len() as u64);
+    buf.extend_from_slice(value);
+}
+
+fn encode_varint(buf: &mut Vec<u8>, mut value: u64) {
+    while value >= 0x80 {
+        buf.push((value as u8 & 0x7f) | 0x80);
+        value >>= 7;
+    }
+    buf.push(value as u8);
+}
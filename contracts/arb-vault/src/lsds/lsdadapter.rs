@@ -1,5 +1,6 @@
 use astroport::asset::AssetInfo;
 use cosmwasm_std::{Addr, CosmosMsg, Decimal, Deps, Uint128};
+use eris::arb_vault::IbcRedemption;
 use eris_chain_adapter::types::CustomMsgType;
 
 use crate::error::CustomResult;
@@ -22,4 +23,9 @@ pub trait LsdAdapter {
     fn query_withdrawable(&mut self, deps: &Deps) -> CustomResult<Uint128>;
 
     fn query_factor_x_to_normal(&mut self, deps: &Deps) -> CustomResult<Decimal>;
+
+    /// Redemption on a remote chain started by unbonding, which is tracked until the utoken returned.
+    fn ibc_redemption(&self, _amount: Uint128) -> Option<IbcRedemption> {
+        None
+    }
 }
@@ -1,23 +1,27 @@
 use astroport::asset::AssetInfo;
-use cosmwasm_std::{attr, Addr, Attribute, CosmosMsg, Deps, DepsMut, Env, Uint128};
-use eris::arb_vault::{BalancesDetails, ClaimBalance, LsdConfig, LsdType, ValidatedConfig};
+use cosmwasm_std::{attr, Addr, Attribute, CosmosMsg, Deps, DepsMut, Env, SubMsg, Uint128};
+use eris::arb_vault::{BalancesDetails, ClaimBalance, LsdConfig, LsdType};
 use eris_chain_adapter::types::CustomMsgType;
 use itertools::Itertools;
 
 use crate::{
+    constants::IBC_TRANSFER_REPLY_ID,
     error::{ContractError, CustomResult},
-    extensions::config_ex::ConfigEx,
     state::State,
 };
 
-use super::{eris_tf::ErisTf, lsdwrapper::LsdWrapper, steak_tf::SteakTf};
+use super::{
+    eris_tf::ErisTf, generic::GenericLsdAdapter, ibc::IbcLsd, lsdwrapper::LsdWrapper,
+    steak_tf::SteakTf,
+};
 
 pub struct LsdGroup {
     lsds: Vec<LsdWrapper>,
 }
 
 impl LsdGroup {
-    pub fn new(lsd_configs: &[&LsdConfig<Addr>], wallet_address: Addr) -> LsdGroup {
+    pub fn new(lsd_configs: &[&LsdConfig<Addr>], env: &Env) -> LsdGroup {
+        let wallet_address = env.contract.address.clone();
         let lsds = lsd_configs
             .iter()
             .map(|config| -> LsdWrapper {
@@ -46,6 +50,36 @@ impl LsdGroup {
                             denom,
                             wallet: wallet_address.clone(),
                         }),
+                        LsdType::Generic {
+                            addr,
+                            denom,
+                            spec,
+                        } => Box::new(GenericLsdAdapter {
+                            exchange_rate_cache: None,
+                            unbonding_requests_cache: None,
+                            addr,
+                            denom,
+                            spec,
+                            wallet: wallet_address.clone(),
+                        }),
+                        LsdType::Ibc {
+                            denom,
+                            channel_id,
+                            receiver,
+                            memo,
+                            timeout_s,
+                            exchange_rate,
+                        } => Box::new(IbcLsd {
+                            name: config.name.clone(),
+                            wallet: wallet_address.clone(),
+                            time: env.block.time,
+                            denom,
+                            channel_id,
+                            receiver,
+                            memo,
+                            timeout_s,
+                            exchange_rate,
+                        }),
                     },
                 }
             })
@@ -61,6 +95,17 @@ impl LsdGroup {
         result.ok_or_else(|| ContractError::AdapterNotFound(format!("token - {0}", asset_info)))
     }
 
+    /// Each LSD token can only belong to one adapter, as swaps find the adapter by the token.
+    pub fn assert_unique_assets(&self) -> CustomResult<()> {
+        let assets = self.lsds.iter().map(|lsd| lsd.adapter.asset()).collect_vec();
+        for (i, asset) in assets.iter().enumerate() {
+            if assets[..i].contains(asset) {
+                return Err(ContractError::AdapterAssetDuplicate(asset.to_string()));
+            }
+        }
+        Ok(())
+    }
+
     pub fn get_adapter_by_name(&mut self, name: &String) -> CustomResult<&mut LsdWrapper> {
         let result = self.lsds.iter_mut().find(|t| t.name == *name);
         result.ok_or_else(|| ContractError::AdapterNotFound(name.clone()))
@@ -103,17 +148,30 @@ impl LsdGroup {
 
     pub fn get_unbond_msgs(
         &mut self,
-        deps: &DepsMut,
-    ) -> CustomResult<(Vec<CosmosMsg<CustomMsgType>>, Vec<Attribute>)> {
-        let mut messages: Vec<CosmosMsg<CustomMsgType>> = vec![];
+        deps: &mut DepsMut,
+    ) -> CustomResult<(Vec<SubMsg<CustomMsgType>>, Vec<Attribute>)> {
+        let state = State::default();
+        let mut messages: Vec<SubMsg<CustomMsgType>> = vec![];
         let mut attributes: Vec<Attribute> = vec![attr("action", "arb/execute_unbond_liquidity")];
 
         for lsd in self.lsds.iter_mut() {
             let unbondable_amount = lsd.adapter.asset().query_pool(&deps.querier, &lsd.wallet)?;
 
             if !unbondable_amount.is_zero() {
-                let mut msgs = lsd.adapter.unbond(&deps.as_ref(), unbondable_amount)?;
-                messages.append(&mut msgs);
+                let msgs = lsd.adapter.unbond(&deps.as_ref(), unbondable_amount)?;
+
+                if let Some(redemption) = lsd.adapter.ibc_redemption(unbondable_amount) {
+                    // the reply provides the sequence of the transfer to track its acknowledgement
+                    let id = state.add_ibc_redemption(deps.storage, redemption)?;
+                    messages.extend(
+                        msgs.into_iter()
+                            .map(|msg| SubMsg::reply_on_success(msg, IBC_TRANSFER_REPLY_ID)),
+                    );
+                    attributes.push(attr("ibc_redemption_id", id.to_string()));
+                } else {
+                    messages.extend(msgs.into_iter().map(SubMsg::new));
+                }
+
                 attributes.push(attr("type", lsd.name.clone()));
                 attributes.push(attr("unbond_amount", unbondable_amount))
             }
@@ -121,14 +179,15 @@ impl LsdGroup {
         Ok((messages, attributes))
     }
 
+    /// Balances of a single base asset, with the share of the locked user withdrawals assigned to it.
     pub(crate) fn get_total_assets_err(
         &mut self,
         deps: Deps,
         env: &Env,
-        state: &State,
-        config: &ValidatedConfig,
+        vault_available: Uint128,
+        locked_user_withdrawls: Uint128,
     ) -> CustomResult<BalancesDetails> {
-        self.get_total_assets(deps, env, state, config)
+        self.get_total_assets(deps, env, vault_available, locked_user_withdrawls)
             .map_err(|e| ContractError::CouldNotLoadTotalAssets(e.to_string()))
     }
 
@@ -136,12 +195,9 @@ impl LsdGroup {
         &mut self,
         deps: Deps,
         env: &Env,
-        state: &State,
-        config: &ValidatedConfig,
+        vault_available: Uint128,
+        locked_user_withdrawls: Uint128,
     ) -> CustomResult<BalancesDetails> {
-        let vault_available = config.query_utoken_amount(&deps.querier, env)?;
-
-        let locked_user_withdrawls = state.balance_locked.load(deps.storage)?.balance;
         let balances = self.get_balances(&deps, &env.contract.address)?;
 
         let mut lsd_unbonding = Uint128::zero();
@@ -169,6 +225,7 @@ impl LsdGroup {
             vault_available,
             vault_takeable: vault_available.checked_sub(locked_user_withdrawls).unwrap_or_default(),
             locked_user_withdrawls,
+            assets: vec![],
             details: balances,
         })
     }
@@ -1,4 +1,6 @@
 pub mod eris_tf;
+pub mod generic;
+pub mod ibc;
 pub mod lsdadapter;
 pub mod lsdgroup;
 pub mod lsdwrapper;
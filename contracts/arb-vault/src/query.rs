@@ -1,13 +1,16 @@
+use crate::domain::swap::calc_swap;
 use crate::error::{ContractError, CustomResult};
+use crate::extensions::balances_ex::available_value;
 use crate::extensions::{BalancesEx, ConfigEx};
 use crate::helpers::calc_fees;
 use crate::state::{State, UnbondHistory};
-use astroport::asset::native_asset_info;
+use astroport::asset::{native_asset_info, Asset};
 use cosmwasm_std::{Decimal, Deps, Env, Order, StdResult, Uint128};
 use cw_storage_plus::Bound;
 use eris::arb_vault::{
-    BalancesOptionalDetails, ConfigResponse, ExchangeHistory, ExchangeRatesResponse, StateDetails,
-    StateResponse, TakeableResponse, UnbondItem, UnbondRequestsResponse, UserInfoResponse,
+    BalancesOptionalDetails, ConfigResponse, ExchangeHistory, ExchangeRatesResponse, IbcRedemption,
+    IbcRedemptionsResponse, SimulateSwapResponse, StateDetails, StateResponse, TakeableResponse,
+    UnbondItem, UnbondQueuePosition, UnbondQueueResponse, UnbondRequestsResponse, UserInfoResponse,
 };
 use eris::constants::DAY;
 use eris::voting_escrow::{DEFAULT_LIMIT, MAX_LIMIT};
@@ -34,23 +37,51 @@ pub fn query_takeable(
     deps: Deps,
     env: Env,
     wanted_profit: Option<Decimal>,
+    utoken: Option<String>,
 ) -> CustomResult<TakeableResponse> {
     let state = State::default();
     let config = state.config.load(deps.storage)?;
-    let mut lsds = config.lsd_group(&env);
-    let balances = lsds.get_total_assets_err(deps, &env, &state, &config)?;
+    let vault = config.get_vault_balances(deps, &env, &state)?;
+    let asset = vault.get_asset(&utoken.unwrap_or_else(|| config.utoken.clone()))?;
+    let balances = &asset.balances;
 
     Ok(TakeableResponse {
         takeable: match wanted_profit {
-            Some(wanted) => Some(balances.calc_takeable_for_profit(&config, &wanted)?),
+            Some(wanted) => Some(balances.calc_takeable_for_profit(&asset.config, &wanted)?),
             _ => None,
         },
-        steps: balances.calc_all_takeable_steps(&config).map_err(|e| {
+        steps: balances.calc_all_takeable_steps(&asset.config).map_err(|e| {
             ContractError::CalculationError("takeable for steps".into(), e.to_string())
         })?,
     })
 }
 
+pub fn query_simulate_swap(
+    deps: Deps,
+    env: Env,
+    offer_asset: Asset,
+) -> CustomResult<SimulateSwapResponse> {
+    let state = State::default();
+    let config = state.config.load(deps.storage)?;
+    let fee_config = state.fee_config.load(deps.storage)?;
+    let vault = config.get_vault_balances(deps, &env, &state)?;
+    let asset = vault.get_asset_by_lsd(&env, &offer_asset.info)?;
+    let balances = &asset.balances;
+
+    let mut lsds = asset.config.lsd_group(&env);
+    let lsd = lsds.get_adapter_by_asset(offer_asset.info)?;
+    lsd.assert_not_disabled()?;
+
+    let value = offer_asset.amount * balances.get_by_name(&lsd.name)?.xfactor;
+    let swap = calc_swap(&asset.config, &fee_config, balances, value)?;
+
+    Ok(SimulateSwapResponse {
+        value,
+        discount: swap.discount,
+        return_amount: swap.return_amount,
+    })
+}
+
 pub fn query_unbond_requests(
     deps: Deps,
     env: Env,
@@ -99,6 +130,42 @@ pub fn query_unbond_requests(
     })
 }
 
+pub fn query_unbond_queue(
+    deps: Deps,
+    env: Env,
+    address: String,
+    start_after: Option<u64>,
+    limit: Option<u32>,
+) -> CustomResult<UnbondQueueResponse> {
+    let address = deps.api.addr_validate(&address)?;
+    let state = State::default();
+    let config = state.config.load(deps.storage)?;
+
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let vault_available = available_value(&config.query_available(&deps.querier, &env)?);
+    let (totals, entries) = state.load_unbond_queue_entries(
+        deps.storage,
+        &address,
+        vault_available,
+        start_after,
+        limit,
+    )?;
+
+    Ok(UnbondQueueResponse {
+        vault_available,
+        queue_total: totals.queued(),
+        positions: entries
+            .into_iter()
+            .map(|entry| UnbondQueuePosition {
+                id: entry.id,
+                amount_ahead: entry.amount_ahead,
+                amount_asset: entry.history.amount_asset,
+                fillable: entry.fillable,
+            })
+            .collect(),
+    })
+}
+
 pub fn query_state(
     deps: Deps,
     env: Env,
@@ -107,20 +174,21 @@ pub fn query_state(
     let state = State::default();
     let config = state.config.load(deps.storage)?;
     let lp_token = state.lp_token.load(deps.storage)?;
-    let mut lsds = config.lsd_group(&env);
 
     let total_lp_supply: Uint128 = lp_token.total_supply;
-    let balances = lsds.get_total_assets_err(deps, &env, &state, &config)?;
+    let vault = config.get_vault_balances(deps, &env, &state)?;
     let details = if include_details.unwrap_or_default() {
+        let primary = vault.get_asset(&config.utoken)?;
         Some(StateDetails {
-            takeable_steps: balances.calc_all_takeable_steps(&config).map_err(|e| {
-                ContractError::CalculationError("takeable for steps".into(), e.to_string())
-            })?,
+            takeable_steps: primary.balances.calc_all_takeable_steps(&primary.config).map_err(
+                |e| ContractError::CalculationError("takeable for steps".into(), e.to_string()),
+            )?,
         })
     } else {
         None
     };
 
+    let balances = vault.total;
     let resp = StateResponse {
         exchange_rate: if total_lp_supply.is_zero() {
             Decimal::one()
@@ -137,6 +205,7 @@ pub fn query_state(
             lsd_unbonding: balances.lsd_unbonding,
             lsd_withdrawable: balances.lsd_withdrawable,
             lsd_xvalue: balances.lsd_xvalue,
+            assets: balances.assets,
             details: if include_details.unwrap_or_default() {
                 Some(balances.details)
             } else {
@@ -154,11 +223,10 @@ pub fn query_user_info(deps: Deps, env: Env, address: String) -> CustomResult<Us
     let config = state.config.load(deps.storage)?;
     let lp_token = state.lp_token.load(deps.storage)?;
 
-    let mut lsds = config.lsd_group(&env);
     let address = deps.api.addr_validate(&address)?;
 
     let total_lp_supply = lp_token.total_supply;
-    let balances = lsds.get_total_assets_err(deps, &env, &state, &config)?;
+    let balances = config.get_vault_balances(deps, &env, &state)?.total;
 
     let lp_amount = native_asset_info(lp_token.denom).query_pool(&deps.querier, address)?;
 
@@ -174,6 +242,26 @@ pub fn query_user_info(deps: Deps, env: Env, address: String) -> CustomResult<Us
     })
 }
 
+pub fn query_ibc_redemptions(
+    deps: Deps,
+    start_after: Option<u64>,
+    limit: Option<u32>,
+) -> StdResult<IbcRedemptionsResponse> {
+    let state = State::default();
+
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after.map(Bound::exclusive);
+
+    Ok(IbcRedemptionsResponse {
+        redemptions: state
+            .ibc_redemptions
+            .range(deps.storage, start, None, Order::Ascending)
+            .take(limit)
+            .map(|item| item.map(|(_, redemption)| redemption))
+            .collect::<StdResult<Vec<IbcRedemption>>>()?,
+    })
+}
+
 pub fn query_exchange_rates(
     deps: Deps,
     _env: Env,
@@ -1,9 +1,13 @@
-use crate::{domain::ownership::OwnershipProposal, error::ContractError};
+use crate::{
+    domain::ownership::OwnershipProposal,
+    error::{ContractError, CustomResult},
+    extensions::balances_ex::{available_value, AssetAvailable},
+};
 use cosmwasm_schema::cw_serde;
-use cosmwasm_std::{Addr, Api, Decimal, StdResult, Storage, Uint128};
-use cw_storage_plus::{Item, Map};
+use cosmwasm_std::{Addr, Api, Decimal, Order, StdResult, Storage, Uint128};
+use cw_storage_plus::{Bound, Item, Map};
 use eris::arb_vault::{
-    ClaimBalance, ExchangeHistory, LpToken, ValidatedConfig, ValidatedFeeConfig,
+    ClaimBalance, ExchangeHistory, IbcRedemption, LpToken, ValidatedConfig, ValidatedFeeConfig,
 };
 
 #[cw_serde]
@@ -16,6 +20,98 @@ pub struct BalanceCheckpoint {
 #[cw_serde]
 pub struct BalanceLocked {
     pub balance: Uint128,
+    // utoken -> locked value in the primary utoken, split when the balance is locked
+    #[serde(default)]
+    pub assets: Vec<(String, Uint128)>,
+}
+
+impl BalanceLocked {
+    /// Locks a value, assigned to the base assets pro rata to their available funds.
+    /// The rounding remainder goes to the asset with the most funds, the primary utoken without funds.
+    pub(crate) fn lock(&mut self, available: &[AssetAvailable], value: Uint128) {
+        let total = available_value(available);
+        let mut remaining = value;
+        for asset in available.iter() {
+            let share = if total.is_zero() {
+                Uint128::zero()
+            } else {
+                value.multiply_ratio(asset.available * asset.price, total)
+            };
+            remaining -= share;
+            self.add_asset_value(&asset.utoken, share);
+        }
+
+        // max_by_key returns the last maximum, reversed this is the first one
+        if let Some(asset) =
+            available.iter().rev().max_by_key(|asset| asset.available * asset.price)
+        {
+            self.add_asset_value(&asset.utoken, remaining);
+        }
+
+        self.balance += value;
+    }
+
+    /// Unlocks a value, taken from the base assets pro rata to their locked value. Returns the value
+    /// taken from each base asset.
+    pub(crate) fn unlock(&mut self, value: Uint128) -> Vec<(String, Uint128)> {
+        self.balance = self.balance.checked_sub(value).unwrap_or_default();
+
+        let total: Uint128 = self.assets.iter().map(|(_, locked)| *locked).sum();
+        if value >= total {
+            return std::mem::take(&mut self.assets);
+        }
+
+        let mut taken = vec![];
+        let mut remaining = value;
+        for (utoken, locked) in self.assets.iter_mut() {
+            let share = value.multiply_ratio(*locked, total);
+            *locked -= share;
+            remaining -= share;
+            taken.push((utoken.clone(), share));
+        }
+
+        // the rounding remainder is taken from the assets with value left
+        for ((_, locked), (_, taken)) in self.assets.iter_mut().zip(taken.iter_mut()) {
+            let share = remaining.min(*locked);
+            *locked -= share;
+            *taken += share;
+            remaining -= share;
+        }
+
+        self.assets.retain(|(_, locked)| !locked.is_zero());
+        taken.retain(|(_, taken)| !taken.is_zero());
+        taken
+    }
+
+    /// Locked amount of each available base asset, in units of the asset.
+    pub(crate) fn asset_amounts(&self, available: &[AssetAvailable]) -> CustomResult<Vec<Uint128>> {
+        available
+            .iter()
+            .map(|asset| {
+                let value = self
+                    .assets
+                    .iter()
+                    .find(|(utoken, _)| *utoken == asset.utoken)
+                    .map(|(_, locked)| *locked)
+                    .unwrap_or_default();
+
+                value.checked_div_floor(asset.price).map_err(|e| {
+                    ContractError::CalculationError("locked amount".into(), e.to_string())
+                })
+            })
+            .collect()
+    }
+
+    fn add_asset_value(&mut self, utoken: &str, value: Uint128) {
+        if value.is_zero() {
+            return;
+        }
+
+        match self.assets.iter_mut().find(|(existing, _)| existing == utoken) {
+            Some((_, locked)) => *locked += value,
+            None => self.assets.push((utoken.to_string(), value)),
+        }
+    }
 }
 
 #[cw_serde]
@@ -41,6 +137,61 @@ impl UnbondHistory {
     }
 }
 
+pub(crate) struct UnbondQueueEntry {
+    pub id: u64,
+    pub history: UnbondHistory,
+    // amount requested ahead in the queue that is not filled yet
+    pub amount_ahead: Uint128,
+    // amount covered by the available funds
+    pub fillable: Uint128,
+}
+
+#[cw_serde]
+pub struct UnbondQueueItem {
+    pub owner: Addr,
+    // amount requested by all unbond items queued before this one
+    pub start: Uint128,
+    // amount requested by this unbond item
+    pub amount: Uint128,
+}
+
+/// The queue is a sequence of requested amounts, which is filled in FIFO order up to a cursor.
+/// Filled amounts stay locked for their unbond items, so a fill check only compares the cursor
+/// with the position of an item.
+#[cw_serde]
+#[derive(Default)]
+pub struct UnbondQueueTotals {
+    // amount requested by all unbond items ever queued
+    pub requested: Uint128,
+    // amount taken out of the queue by withdrawals
+    pub claimed: Uint128,
+    // the requested amounts are filled up to this amount
+    pub filled_to: Uint128,
+}
+
+impl UnbondQueueTotals {
+    /// Moves the cursor forward by the available funds, which can't be more than requested.
+    pub fn fill(&mut self, available: Uint128) {
+        self.filled_to = self.filled_to.max(self.claimed + available).min(self.requested);
+    }
+
+    /// Amount of an unbond item that is filled and not withdrawn yet.
+    pub fn fillable(&self, item: &UnbondQueueItem, remaining: Uint128) -> Uint128 {
+        let claimed = item.amount.saturating_sub(remaining);
+        self.filled_to.saturating_sub(item.start).min(item.amount).saturating_sub(claimed)
+    }
+
+    /// Amount requested ahead of an unbond item that is not filled yet.
+    pub fn amount_ahead(&self, item: &UnbondQueueItem) -> Uint128 {
+        item.start.saturating_sub(self.filled_to)
+    }
+
+    /// Amount requested by the unbond items in the queue.
+    pub fn queued(&self) -> Uint128 {
+        self.requested.saturating_sub(self.claimed)
+    }
+}
+
 pub(crate) struct State<'a> {
     pub config: Item<'a, ValidatedConfig>,
     pub lp_token: Item<'a, LpToken>,
@@ -50,9 +201,18 @@ pub(crate) struct State<'a> {
     pub exchange_history: Map<'a, u64, ExchangeHistory>,
     pub unbond_history: Map<'a, (Addr, u64), UnbondHistory>,
     pub unbond_id: Item<'a, u64>,
+    // unbond id -> position in the queue, the ids define the FIFO order of the queue
+    pub unbond_queue: Map<'a, u64, UnbondQueueItem>,
+    pub unbond_queue_totals: Item<'a, UnbondQueueTotals>,
     pub balance_checkpoint: Item<'a, BalanceCheckpoint>,
     pub balance_locked: Item<'a, BalanceLocked>,
     pub whitelisted_addrs: Item<'a, Vec<Addr>>,
+    pub ibc_redemptions: Map<'a, u64, IbcRedemption>,
+    pub ibc_redemption_id: Item<'a, u64>,
+    // (channel, sequence) -> redemption id
+    pub ibc_transfers: Map<'a, (&'a str, u64), u64>,
+    // redemptions waiting for the reply of their transfer, in the order of the transfers
+    pub ibc_pending_transfers: Item<'a, Vec<u64>>,
 }
 
 impl Default for State<'static> {
@@ -66,9 +226,15 @@ impl Default for State<'static> {
             exchange_history: Map::new("exchange_history"),
             unbond_history: Map::new("unbond_history"),
             unbond_id: Item::new("unbond_id"),
+            unbond_queue: Map::new("unbond_queue"),
+            unbond_queue_totals: Item::new("unbond_queue_totals"),
             balance_checkpoint: Item::new("balance_checkpoint"),
             balance_locked: Item::new("balance_locked"),
             whitelisted_addrs: Item::new("whitelisted_addrs"),
+            ibc_redemptions: Map::new("ibc_redemptions"),
+            ibc_redemption_id: Item::new("ibc_redemption_id"),
+            ibc_transfers: Map::new("ibc_transfers"),
+            ibc_pending_transfers: Item::new("ibc_pending_transfers"),
         }
     }
 }
@@ -135,19 +301,123 @@ impl<'a> State<'a> {
         store: &mut dyn Storage,
         sender_addr: Addr,
         element: UnbondHistory,
+        available: &[AssetAvailable],
     ) -> Result<(), ContractError> {
         self.balance_locked.update(store, |mut existing| -> StdResult<_> {
-            existing.balance += element.amount_asset;
+            existing.lock(available, element.amount_asset);
             Ok(existing)
         })?;
 
+        let mut totals = self.unbond_queue_totals.may_load(store)?.unwrap_or_default();
+        let item = UnbondQueueItem {
+            owner: sender_addr.clone(),
+            start: totals.requested,
+            amount: element.amount_asset,
+        };
+        totals.requested += element.amount_asset;
+
         let id = self.unbond_id.load(store)?;
         self.unbond_history.save(store, (sender_addr, id), &element)?;
+        self.unbond_queue.save(store, id, &item)?;
+        self.unbond_queue_totals.save(store, &totals)?;
         self.unbond_id.save(store, &(id + 1))?;
 
         Ok(())
     }
 
+    /// Reduces the unbond item by the withdrawn amount. The locked balance is updated by the withdraw itself.
+    pub fn take_from_unbond_history(
+        &self,
+        store: &mut dyn Storage,
+        sender_addr: Addr,
+        id: u64,
+        mut element: UnbondHistory,
+        amount: Uint128,
+    ) -> Result<(), ContractError> {
+        element.amount_asset = element.amount_asset.checked_sub(amount)?;
+
+        let mut totals = self.unbond_queue_totals.may_load(store)?.unwrap_or_default();
+        totals.claimed += amount;
+        self.unbond_queue_totals.save(store, &totals)?;
+
+        if element.amount_asset.is_zero() {
+            self.unbond_history.remove(store, (sender_addr, id));
+            self.unbond_queue.remove(store, id);
+        } else {
+            self.unbond_history.save(store, (sender_addr, id), &element)?;
+        }
+
+        Ok(())
+    }
+
+    /// Loads the unbond items of an owner with the amount that is filled by the available funds.
+    pub fn load_unbond_queue_entries(
+        &self,
+        store: &dyn Storage,
+        owner: &Addr,
+        available: Uint128,
+        start_after: Option<u64>,
+        limit: usize,
+    ) -> Result<(UnbondQueueTotals, Vec<UnbondQueueEntry>), ContractError> {
+        let mut totals = self.unbond_queue_totals.may_load(store)?.unwrap_or_default();
+        totals.fill(available);
+
+        let entries = self
+            .unbond_history
+            .prefix(owner.clone())
+            .range(store, start_after.map(Bound::exclusive), None, Order::Ascending)
+            .take(limit)
+            .map(|item| {
+                let (id, history) = item?;
+                let item = self.unbond_queue.load(store, id)?;
+                Ok(UnbondQueueEntry {
+                    id,
+                    amount_ahead: totals.amount_ahead(&item),
+                    fillable: totals.fillable(&item, history.amount_asset),
+                    history,
+                })
+            })
+            .collect::<StdResult<Vec<_>>>()?;
+
+        Ok((totals, entries))
+    }
+
+    pub fn add_ibc_redemption(
+        &self,
+        store: &mut dyn Storage,
+        mut redemption: IbcRedemption,
+    ) -> Result<u64, ContractError> {
+        let id = self.ibc_redemption_id.may_load(store)?.unwrap_or(1);
+        redemption.id = id;
+        self.ibc_redemptions.save(store, id, &redemption)?;
+        self.ibc_redemption_id.save(store, &(id + 1))?;
+
+        let mut pending = self.ibc_pending_transfers.may_load(store)?.unwrap_or_default();
+        pending.push(id);
+        self.ibc_pending_transfers.save(store, &pending)?;
+
+        Ok(id)
+    }
+
+    /// Takes the redemption of the next transfer reply.
+    pub fn pop_ibc_pending_transfer(&self, store: &mut dyn Storage) -> StdResult<Option<u64>> {
+        let mut pending = self.ibc_pending_transfers.may_load(store)?.unwrap_or_default();
+        if pending.is_empty() {
+            return Ok(None);
+        }
+
+        let id = pending.remove(0);
+        self.ibc_pending_transfers.save(store, &pending)?;
+        Ok(Some(id))
+    }
+
+    pub fn remove_ibc_redemption(&self, store: &mut dyn Storage, redemption: &IbcRedemption) {
+        if let Some(sequence) = redemption.sequence {
+            self.ibc_transfers.remove(store, (redemption.channel_id.as_str(), sequence));
+        }
+        self.ibc_redemptions.remove(store, redemption.id);
+    }
+
     pub(crate) fn update_whitelist(
         &self,
         store: &mut dyn Storage,
@@ -2,8 +2,8 @@ use super::cw20_querier::Cw20Querier;
 use super::helpers::err_unsupported_query;
 use cosmwasm_std::testing::{BankQuerier, StakingQuerier, MOCK_CONTRACT_ADDR};
 use cosmwasm_std::{
-    coin, from_json, to_json_binary, Coin, Decimal, Empty, Querier, QuerierResult, QueryRequest,
-    SystemError, SystemResult, Uint128, WasmQuery,
+    coin, from_json, to_json_binary, Binary, Coin, ContractResult, Decimal, Empty, Querier,
+    QuerierResult, QueryRequest, SystemError, SystemResult, Uint128, WasmQuery,
 };
 use cw20::Cw20QueryMsg;
 use std::str::FromStr;
@@ -17,6 +17,8 @@ pub(super) struct CustomQuerier {
     pub unbonding_amount: Uint128,
     pub unbonding_amount_eris: Option<Uint128>,
     pub withdrawable_amount: Uint128,
+    /// Update time of the price returned by the oracle
+    pub oracle_updated_at: u64,
 }
 
 impl Querier for CustomQuerier {
@@ -190,6 +192,55 @@ impl CustomQuerier {
                         ),
                         _ => err_unsupported_query(msg),
                     };
+                } else if contract_addr == "oracle" {
+                    let query = String::from_utf8(msg.to_vec()).unwrap();
+                    if query != r#"{"price":{"denom":"uother"}}"# {
+                        return err_unsupported_query(msg);
+                    }
+
+                    let response =
+                        format!(r#"{{"price":"2","updated_at":{0}}}"#, self.oracle_updated_at);
+                    return SystemResult::Ok(ContractResult::Ok(Binary::from(
+                        response.into_bytes(),
+                    )));
+                } else if contract_addr == "generic" {
+                    let query = String::from_utf8(msg.to_vec()).unwrap();
+
+                    let response = if query == r#"{"rates":{}}"# {
+                        r#"{"total":"1200", "rates": {"redemption":"1.2","nested":["}",{}]}}"#
+                            .to_string()
+                    } else if query
+                        == format!(r#"{{"requests":{{"owner":"{0}"}}}}"#, MOCK_CONTRACT_ADDR)
+                    {
+                        format!(
+                            r#"{{"owner":"{0}","requests":[{{"amount":"{1}","status":{{"claimable":true}}}},{{"amount":"{2}","status":{{"claimable":false}}}}]}}"#,
+                            MOCK_CONTRACT_ADDR, self.withdrawable_amount, self.unbonding_amount
+                        )
+                    } else if let Some(page) = query.strip_prefix(&format!(
+                        r#"{{"requests":{{"owner":"{0}","#,
+                        MOCK_CONTRACT_ADDR
+                    )) {
+                        // one request per page, ordered by id
+                        let requests = match page {
+                            r#""start_after":null,"limit":1}}"# => format!(
+                                r#"[{{"id":1,"amount":"{0}","status":{{"claimable":true}}}}]"#,
+                                self.withdrawable_amount
+                            ),
+                            r#""start_after":1,"limit":1}}"# => format!(
+                                r#"[{{"id":2,"amount":"{0}","status":{{"claimable":false}}}}]"#,
+                                self.unbonding_amount
+                            ),
+                            r#""start_after":2,"limit":1}}"# => "[]".to_string(),
+                            _ => return err_unsupported_query(msg),
+                        };
+                        format!(r#"{{"requests":{0}}}"#, requests)
+                    } else {
+                        return err_unsupported_query(msg);
+                    };
+
+                    return SystemResult::Ok(ContractResult::Ok(Binary::from(
+                        response.into_bytes(),
+                    )));
                 }
 
                 err_unsupported_query(msg)
@@ -2,3 +2,4 @@ pub mod custom_querier;
 pub mod cw20_querier;
 pub mod helpers;
 pub mod tests;
+pub mod tests_ibc;
@@ -3,7 +3,10 @@ use std::str::FromStr;
 use crate::{
     contract::execute,
     error::ContractError,
-    query::{query_state, query_takeable, query_unbond_requests},
+    extensions::balances_ex::{split_value, AssetAvailable},
+    query::{
+        query_simulate_swap, query_state, query_takeable, query_unbond_queue, query_unbond_requests,
+    },
     testing::helpers::{
         _mock_env_at_timestamp, chain_test, create_default_lsd_configs, mock_env, setup_test,
     },
@@ -15,13 +18,15 @@ use astroport::asset::{native_asset, native_asset_info, token_asset_info};
 use cosmwasm_schema::cw_serde;
 use cosmwasm_std::testing::{mock_info, MockApi, MockStorage, MOCK_CONTRACT_ADDR};
 use cosmwasm_std::{
-    attr, coin, from_json, to_json_binary, Addr, BankMsg, Coin, CosmosMsg, Decimal, Deps,
-    OwnedDeps, Response, Uint128, WasmMsg,
+    attr, coin, from_json, to_json_binary, Addr, BankMsg, Binary, Coin, CosmosMsg, Decimal, Deps,
+    OwnedDeps, Response, StdError, Uint128, WasmMsg,
 };
 use eris::{
     arb_vault::{
-        Balances, ClaimBalance, Config, ConfigResponse, ExecuteMsg, ExecuteSubMsg, FeeConfig,
-        LpToken, StateDetails, StateResponse, TakeableResponse, UnbondItem, UnbondRequestsResponse,
+        AssetBalance, AssetPrice, Balances, BaseAssetConfig, ClaimBalance, Config, ConfigResponse,
+        ExecuteMsg, ExecuteSubMsg, FeeConfig, GenericLsdPagination, GenericLsdSpec, LpToken,
+        LsdConfig, LsdType, SimulateSwapResponse, StateDetails, StateResponse, TakeableResponse,
+        UnbondItem, UnbondQueuePosition, UnbondQueueResponse, UnbondRequestsResponse,
         UserInfoResponse, UtilizationMethod,
     },
     CustomMsgExt,
@@ -57,7 +62,8 @@ fn proper_initialization() {
                 lsds: create_default_lsd_configs()
                     .into_iter()
                     .map(|a| a.validate(deps.as_ref().api).unwrap())
-                    .collect_vec()
+                    .collect_vec(),
+                assets: vec![],
             },
             fee_config: eris::arb_vault::FeeConfig {
                 protocol_fee_contract: Addr::unchecked("fee"),
@@ -86,6 +92,8 @@ fn update_config() {
         insert_lsd: None,
         remove_lsd: None,
         force_remove_lsd: None,
+        insert_asset: None,
+        remove_asset: None,
         fee_config: None,
         set_whitelist: None,
         remove_whitelist: None,
@@ -114,7 +122,8 @@ fn update_config() {
                 lsds: create_default_lsd_configs()
                     .into_iter()
                     .map(|a| a.validate(deps.as_ref().api).unwrap())
-                    .collect_vec()
+                    .collect_vec(),
+                assets: vec![],
             },
             fee_config: FeeConfig {
                 protocol_fee_contract: Addr::unchecked("fee"),
@@ -138,6 +147,8 @@ fn update_config() {
         insert_lsd: None,
         remove_lsd: None,
         force_remove_lsd: None,
+        insert_asset: None,
+        remove_asset: None,
         fee_config: None,
         remove_whitelist: None,
         set_whitelist: None,
@@ -157,7 +168,8 @@ fn update_config() {
                 lsds: create_default_lsd_configs()
                     .into_iter()
                     .map(|a| a.validate(deps.as_ref().api).unwrap())
-                    .collect_vec()
+                    .collect_vec(),
+                assets: vec![],
             },
             fee_config: FeeConfig {
                 protocol_fee_contract: Addr::unchecked("fee"),
@@ -411,6 +423,7 @@ fn throws_if_provided_profit_not_found() {
         },
         result_token: native_asset_info("eriscw".into()),
         wanted_profit: Decimal::from_ratio(10u128, 100u128),
+        utoken: None,
     };
 
     let result = execute(deps.as_mut(), mock_env(), whitelist_info, exec_msg).unwrap_err();
@@ -433,6 +446,7 @@ fn throws_if_not_whitelisted_executor() {
         },
         result_token: native_asset_info("eriscw".into()),
         wanted_profit: Decimal::from_ratio(1u128, 100u128),
+        utoken: None,
     };
 
     let withdraw_msg = ExecuteMsg::WithdrawFromLiquidStaking {
@@ -1207,10 +1221,13 @@ fn withdraw_liquidity_unbonded_all_success() {
     //
     let withdraw_unbonded = ExecuteMsg::WithdrawUnbonded {};
 
+    // no funds available to fill the queue
+    deps.querier.set_bank_balance(0);
     let res =
         execute(deps.as_mut(), mid_time, user.clone(), withdraw_unbonded.clone()).unwrap_err();
 
     assert_eq!(res, ContractError::NoWithdrawableAsset {});
+    deps.querier.set_bank_balance(220_000000u128);
 
     //
     // WITHDRAW UNBONDED
@@ -1364,6 +1381,8 @@ fn withdraw_liquidity_unbonded_half_success() {
     //
     // WITHDRAW UNBONDED
     //
+    // only the first unbond item is filled by the available funds
+    deps.querier.set_bank_balance(120_000000u128);
     let withdraw_unbonded = ExecuteMsg::WithdrawUnbonded {};
     let res = execute(deps.as_mut(), before_end_time.clone(), user.clone(), withdraw_unbonded)
         .expect("expect response");
@@ -1458,11 +1477,147 @@ fn withdraw_liquidity_unbonded_half_success() {
     //
     let withdraw_unbonded = ExecuteMsg::WithdrawUnbonded {};
 
+    // no funds available to fill the queue
+    deps.querier.set_bank_balance(0);
     let res = execute(deps.as_mut(), before_end_time, user, withdraw_unbonded).unwrap_err();
 
     assert_eq!(res, ContractError::NoWithdrawableAsset {});
 }
 
+#[test]
+fn withdraw_unbonded_queue_partial_fill() {
+    let (mut deps, _res) = _unbonding_slow_120();
+
+    let mid_time = _mock_env_at_timestamp(51);
+    let end_time = _mock_env_at_timestamp(200);
+
+    let user002 = mock_info("user002", &[coin(10_000000u128, TEST_LP_TOKEN)]);
+    let withdraw = ExecuteMsg::Unbond {
+        immediate: Some(false),
+    };
+    execute(deps.as_mut(), mid_time.clone(), user002, withdraw).unwrap();
+
+    // part of the vault is still unbonding from the LSDs
+    deps.querier.set_bank_balance(100_000000u128);
+
+    let queue =
+        query_unbond_queue(deps.as_ref(), mid_time.clone(), "user001".into(), None, None).unwrap();
+    assert_eq!(
+        queue,
+        UnbondQueueResponse {
+            vault_available: Uint128::new(100_000000u128),
+            queue_total: Uint128::new(130_000000u128),
+            positions: vec![UnbondQueuePosition {
+                id: 0,
+                amount_ahead: Uint128::zero(),
+                amount_asset: Uint128::new(120_000000u128),
+                fillable: Uint128::new(100_000000u128),
+            }]
+        }
+    );
+
+    let queue =
+        query_unbond_queue(deps.as_ref(), mid_time.clone(), "user002".into(), None, None).unwrap();
+    assert_eq!(
+        queue.positions,
+        vec![UnbondQueuePosition {
+            id: 1,
+            amount_ahead: Uint128::new(20_000000u128),
+            amount_asset: Uint128::new(10_000000u128),
+            fillable: Uint128::zero(),
+        }]
+    );
+
+    let withdraw_unbonded = ExecuteMsg::WithdrawUnbonded {};
+    let err = execute(
+        deps.as_mut(),
+        mid_time.clone(),
+        mock_info("user002", &[]),
+        withdraw_unbonded.clone(),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::NoWithdrawableAsset {});
+
+    //
+    // PARTIAL FILL BEFORE RELEASE
+    //
+    let res = execute(
+        deps.as_mut(),
+        mid_time.clone(),
+        mock_info("user001", &[]),
+        withdraw_unbonded.clone(),
+    )
+    .unwrap();
+
+    // waited half of the unbond time: 100 * 0.05 * 0.5
+    let withdraw_amount = Uint128::new(100_000000u128);
+    let pool_fee = Uint128::new(2_500000u128);
+    let protocol_fee = Uint128::new(2_000000u128);
+    let receive_amount = withdraw_amount - pool_fee - protocol_fee;
+
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "arb/execute_withdraw"),
+            attr("from", "cosmos2contract"),
+            attr("receiver", "user001"),
+            attr("withdraw_amount", withdraw_amount),
+            attr("receive_amount", receive_amount),
+            attr("protocol_fee", protocol_fee),
+            attr("pool_fee", pool_fee),
+            attr("immediate", true.to_string()),
+        ]
+    );
+
+    // the pool fee stays in the vault
+    deps.querier.set_bank_balance(2_500000u128);
+
+    let state = query_state(deps.as_ref(), mid_time.clone(), None).unwrap();
+    assert_eq!(state.balances.locked_user_withdrawls, Uint128::new(30_000000u128));
+
+    let queue =
+        query_unbond_queue(deps.as_ref(), mid_time.clone(), "user001".into(), None, None).unwrap();
+    assert_eq!(
+        queue.positions,
+        vec![UnbondQueuePosition {
+            id: 0,
+            amount_ahead: Uint128::zero(),
+            amount_asset: Uint128::new(20_000000u128),
+            fillable: Uint128::new(2_500000u128),
+        }]
+    );
+
+    //
+    // FILL AFTER RELEASE
+    //
+    // the LSDs have been withdrawn
+    deps.querier.set_bank_balance(32_500000u128);
+
+    let res =
+        execute(deps.as_mut(), end_time.clone(), mock_info("user002", &[]), withdraw_unbonded)
+            .unwrap();
+
+    let withdraw_amount = Uint128::new(10_000000u128);
+    let protocol_fee = Uint128::new(200000u128);
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "arb/execute_withdraw"),
+            attr("from", "cosmos2contract"),
+            attr("receiver", "user002"),
+            attr("withdraw_amount", withdraw_amount),
+            attr("receive_amount", withdraw_amount - protocol_fee),
+            attr("protocol_fee", protocol_fee),
+            attr("pool_fee", Uint128::zero()),
+            attr("immediate", false.to_string()),
+        ]
+    );
+
+    let queue = query_unbond_queue(deps.as_ref(), end_time, "user002".into(), None, None).unwrap();
+    assert_eq!(queue.queue_total, Uint128::new(20_000000u128));
+    assert_eq!(queue.positions, vec![]);
+}
+
 #[test]
 fn query_check_balances() {
     let (mut deps, _res) = _unbonding_slow_120();
@@ -1496,6 +1651,15 @@ fn query_check_balances() {
                 lsd_unbonding: unbonding,
                 lsd_withdrawable: withdrawable,
                 lsd_xvalue: Uint128::zero(),
+                assets: vec![AssetBalance {
+                    utoken: "utoken".into(),
+                    price: Decimal::one(),
+                    tvl_utoken: total_value + locked,
+                    vault_total: total_value,
+                    vault_available: pool_available,
+                    vault_takeable: pool_takeable,
+                    locked_user_withdrawls: locked,
+                }],
                 details: None,
             },
             exchange_rate: Decimal::from_str("1.714").unwrap(),
@@ -1517,6 +1681,15 @@ fn query_check_balances() {
                 lsd_unbonding: unbonding,
                 lsd_withdrawable: withdrawable,
                 lsd_xvalue: Uint128::zero(),
+                assets: vec![AssetBalance {
+                    utoken: "utoken".into(),
+                    price: Decimal::one(),
+                    tvl_utoken: total_value + locked,
+                    vault_total: total_value,
+                    vault_available: pool_available,
+                    vault_takeable: pool_takeable,
+                    locked_user_withdrawls: locked,
+                }],
                 details: Some(vec![
                     ClaimBalance {
                         name: "eris".to_string(),
@@ -1562,7 +1735,7 @@ fn query_check_available() {
 
     let total_value = pool_available + unbonding + withdrawable - locked;
 
-    let available = query_takeable(deps.as_ref(), mock_env(), None).unwrap();
+    let available = query_takeable(deps.as_ref(), mock_env(), None, None).unwrap();
 
     assert_eq!(
         available,
@@ -1593,7 +1766,7 @@ fn query_check_available() {
     );
 
     let available =
-        query_takeable(deps.as_ref(), mock_env(), Some(Decimal::from_str("0.01").unwrap()))
+        query_takeable(deps.as_ref(), mock_env(), Some(Decimal::from_str("0.01").unwrap()), None)
             .unwrap();
 
     assert_eq!(
@@ -1625,7 +1798,7 @@ fn query_check_available() {
     );
 
     let available =
-        query_takeable(deps.as_ref(), mock_env(), Some(Decimal::from_str("0.6").unwrap()))
+        query_takeable(deps.as_ref(), mock_env(), Some(Decimal::from_str("0.6").unwrap()), None)
             .unwrap_err();
 
     // currently no interpolation possible
@@ -1654,6 +1827,7 @@ fn execute_arb_throws() {
         },
         result_token: native_asset_info("eriscw".into()),
         wanted_profit: Decimal::from_str("0.025").unwrap(),
+        utoken: None,
     };
     let res = execute(deps.as_mut(), mock_env(), whitelist_info.clone(), exec_msg)
         .expect_err("expects error");
@@ -1667,6 +1841,7 @@ fn execute_arb_throws() {
         },
         result_token: token_asset_info(Addr::unchecked("xxx")),
         wanted_profit: Decimal::from_str("0.025").unwrap(),
+        utoken: None,
     };
     let res = execute(deps.as_mut(), mock_env(), whitelist_info.clone(), exec_msg)
         .expect_err("expects error");
@@ -1680,6 +1855,7 @@ fn execute_arb_throws() {
         },
         result_token: native_asset_info("eriscw".into()),
         wanted_profit: Decimal::from_str("0.025").unwrap(),
+        utoken: None,
     };
     let res = execute(deps.as_mut(), mock_env(), whitelist_info.clone(), exec_msg)
         .expect_err("expects error");
@@ -1698,7 +1874,7 @@ fn execute_arb_throws() {
     assert_eq!(res, ContractError::NotExecuting {});
 
     let wanted_profit = Decimal::from_str("0.015").unwrap();
-    let takeable = query_takeable(deps.as_ref(), mock_env(), Some(wanted_profit))
+    let takeable = query_takeable(deps.as_ref(), mock_env(), Some(wanted_profit), None)
         .unwrap()
         .takeable
         .expect("expects takeable");
@@ -1711,6 +1887,7 @@ fn execute_arb_throws() {
         },
         result_token: native_asset_info("eriscw".into()),
         wanted_profit,
+        utoken: None,
     };
     let res = execute(deps.as_mut(), mock_env(), whitelist_info, exec_msg).unwrap_err();
     assert_eq!(res, ContractError::CannotCallLsdContract {});
@@ -1742,7 +1919,7 @@ fn execute_arb() {
     let contract_info = mock_info(MOCK_CONTRACT_ADDR, &[]);
 
     let wanted_profit = Decimal::from_str("0.015").unwrap();
-    let takeable = query_takeable(deps.as_ref(), mock_env(), Some(wanted_profit))
+    let takeable = query_takeable(deps.as_ref(), mock_env(), Some(wanted_profit), None)
         .unwrap()
         .takeable
         .expect("expects takeable");
@@ -1755,6 +1932,7 @@ fn execute_arb() {
         },
         result_token: native_asset_info("eriscw".into()),
         wanted_profit,
+        utoken: None,
     };
     let res = execute(deps.as_mut(), mock_env(), whitelist_info.clone(), exec_msg).unwrap();
 
@@ -1832,6 +2010,7 @@ fn execute_arb() {
             },
             result_token: native_asset_info("eriscw".into()),
             wanted_profit,
+            utoken: None,
         },
     )
     .unwrap_err();
@@ -1896,6 +2075,15 @@ fn execute_arb() {
                 lsd_unbonding: Uint128::new(50400000),
                 lsd_withdrawable: Uint128::new(0),
                 lsd_xvalue: Uint128::new(0),
+                assets: vec![AssetBalance {
+                    utoken: "utoken".into(),
+                    price: Decimal::one(),
+                    tvl_utoken: old_tvl,
+                    vault_total: Uint128::new(150400000),
+                    vault_available: Uint128::new(220000000),
+                    vault_takeable: Uint128::new(100000000),
+                    locked_user_withdrawls: Uint128::new(120000000),
+                }],
                 details: None
             },
             details: None
@@ -1951,6 +2139,15 @@ fn execute_arb() {
                 lsd_unbonding: Uint128::new(50400000 + (eris_amount * eris_exchange_rate).u128()),
                 lsd_withdrawable: Uint128::new(0),
                 lsd_xvalue: Uint128::new(0),
+                assets: vec![AssetBalance {
+                    utoken: "utoken".into(),
+                    price: Decimal::one(),
+                    tvl_utoken: new_tvl,
+                    vault_total: Uint128::new(151223199),
+                    vault_available: Uint128::new(165120000),
+                    vault_takeable: Uint128::new(45120000),
+                    locked_user_withdrawls: Uint128::new(120000000),
+                }],
                 details: None
             },
             details: None
@@ -1988,12 +2185,598 @@ fn execute_arb() {
     assert_eq!(new_share, Uint128::new(75611599));
 
     // expect takeable to be 0 afterwards
-    let takeable =
-        query_takeable(deps.as_ref(), mock_env(), Some(wanted_profit)).unwrap().takeable.unwrap();
+    let takeable = query_takeable(deps.as_ref(), mock_env(), Some(wanted_profit), None)
+        .unwrap()
+        .takeable
+        .unwrap();
 
     assert_eq!(takeable, Uint128::zero());
 }
 
+#[test]
+fn swap_lsd_for_utoken() {
+    let (mut deps, _res) = _provide_liquidity();
+
+    // value 66 -> 50% step allows 50, 70% step allows 70
+    let simulated = query_simulate_swap(
+        deps.as_ref(),
+        mock_env(),
+        native_asset("eriscw".into(), Uint128::new(60_000000u128)),
+    )
+    .unwrap();
+    assert_eq!(
+        simulated,
+        SimulateSwapResponse {
+            value: Uint128::new(66_000000u128),
+            discount: Decimal::from_str("0.015").unwrap(),
+            return_amount: Uint128::new(65_010000u128),
+        }
+    );
+
+    let err = query_simulate_swap(
+        deps.as_ref(),
+        mock_env(),
+        native_asset("eriscw".into(), Uint128::new(100_000000u128)),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::NotEnoughFundsTakeable {});
+
+    let swap = ExecuteMsg::Swap {
+        min_return: Some(Uint128::new(10_900000u128)),
+        receiver: None,
+    };
+
+    let err = execute(deps.as_mut(), mock_env(), mock_info("user", &[]), swap.clone()).unwrap_err();
+    assert_eq!(err, ContractError::InvalidFunds {});
+
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("user", &[coin(10_000000u128, "utoken")]),
+        swap.clone(),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::AdapterNotFound("token - utoken".into()));
+
+    // the offered tokens are already in the contract during execution
+    deps.querier
+        .set_bank_balances(&[coin(100_000000u128, "utoken"), coin(10_000000u128, "eriscw")]);
+    let user = mock_info("user", &[coin(10_000000u128, "eriscw")]);
+
+    let err = execute(deps.as_mut(), mock_env(), user.clone(), swap).unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::SwapReturnTooLow {
+            return_amount: Uint128::new(10_890000u128),
+            min_return: Uint128::new(10_900000u128),
+        }
+    );
+
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        user,
+        ExecuteMsg::Swap {
+            min_return: Some(Uint128::new(10_890000u128)),
+            receiver: Some("receiver".into()),
+        },
+    )
+    .unwrap();
+
+    // 1% discount of 11, of which 1% is the protocol fee
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "arb/execute_swap"),
+            attr("type", "eris"),
+            attr("receiver", "receiver"),
+            attr("offer_amount", "10000000"),
+            attr("value", "11000000"),
+            attr("discount", "0.01"),
+            attr("return_amount", "10890000"),
+            attr("fee_amount", "1100"),
+        ]
+    );
+    assert_eq!(
+        res.messages.into_iter().map(|m| m.msg).collect_vec(),
+        vec![
+            CosmosMsg::Bank(BankMsg::Send {
+                to_address: "receiver".to_string(),
+                amount: vec![coin(10_890000u128, "utoken")],
+            }),
+            CosmosMsg::Bank(BankMsg::Send {
+                to_address: "fee".to_string(),
+                amount: vec![coin(1100u128, "utoken")],
+            }),
+        ]
+    );
+}
+
+fn json_path(keys: &[&str]) -> Vec<String> {
+    keys.iter().map(|key| key.to_string()).collect_vec()
+}
+
+fn generic_spec() -> GenericLsdSpec {
+    GenericLsdSpec {
+        unbond_msg: r#"{"unstake":{"receiver":"{wallet}","amount":"{amount}"}}"#.into(),
+        withdraw_msg: r#"{"claim":{}}"#.into(),
+        exchange_rate_query: r#"{"rates":{}}"#.into(),
+        exchange_rate_path: json_path(&["rates", "redemption"]),
+        unbonding_requests_query: r#"{"requests":{"owner":"{wallet}"}}"#.into(),
+        unbonding_requests_path: json_path(&["requests"]),
+        amount_path: json_path(&["amount"]),
+        withdrawable_path: json_path(&["status", "claimable"]),
+        unbonding_requests_pagination: None,
+    }
+}
+
+fn insert_generic_msg(spec: GenericLsdSpec) -> ExecuteMsg {
+    ExecuteMsg::UpdateConfig {
+        utilization_method: None,
+        unbond_time_s: None,
+        disable_lsd: None,
+        insert_lsd: Some(LsdConfig {
+            disabled: false,
+            name: "generic".into(),
+            lsd_type: LsdType::Generic {
+                addr: "generic".into(),
+                denom: "genericcw".into(),
+                spec,
+            },
+        }),
+        remove_lsd: None,
+        force_remove_lsd: None,
+        insert_asset: None,
+        remove_asset: None,
+        fee_config: None,
+        set_whitelist: None,
+        remove_whitelist: None,
+    }
+}
+
+#[test]
+fn generic_lsd_adapter() {
+    let mut deps = setup_test();
+
+    execute(deps.as_mut(), mock_env(), mock_info("owner", &[]), insert_generic_msg(generic_spec()))
+        .unwrap();
+
+    deps.querier.with_unbonding(Uint128::new(24_000000u128));
+    deps.querier.with_withdrawable(Uint128::new(10_000000u128));
+    deps.querier
+        .set_bank_balances(&[coin(100_000000u128, "utoken"), coin(5_000000u128, "genericcw")]);
+
+    let state = query_state(deps.as_ref(), mock_env(), Some(true)).unwrap();
+    assert_eq!(
+        state.balances.details.unwrap()[2],
+        ClaimBalance {
+            name: "generic".to_string(),
+            withdrawable: Uint128::new(10_000000u128),
+            unbonding: Uint128::new(24_000000u128),
+            xbalance: Uint128::new(5_000000u128),
+            xfactor: Decimal::from_str("1.2").unwrap(),
+        }
+    );
+
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("whitelisted_exec", &[]),
+        ExecuteMsg::UnbondFromLiquidStaking {
+            names: Some(vec!["generic".to_string()]),
+        },
+    )
+    .unwrap();
+    assert_eq!(
+        res.messages[0].msg,
+        CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: "generic".to_string(),
+            msg: Binary::from(
+                format!(
+                    r#"{{"unstake":{{"receiver":"{0}","amount":"5000000"}}}}"#,
+                    MOCK_CONTRACT_ADDR
+                )
+                .into_bytes()
+            ),
+            funds: vec![coin(5_000000u128, "genericcw")],
+        })
+    );
+
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("whitelisted_exec", &[]),
+        ExecuteMsg::WithdrawFromLiquidStaking {
+            names: Some(vec!["generic".to_string()]),
+        },
+    )
+    .unwrap();
+    assert_eq!(
+        res.messages[0].msg,
+        CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: "generic".to_string(),
+            msg: Binary::from(br#"{"claim":{}}"#.to_vec()),
+            funds: vec![],
+        })
+    );
+}
+
+#[test]
+fn generic_lsd_adapter_pagination() {
+    let mut deps = setup_test();
+
+    let spec = GenericLsdSpec {
+        unbonding_requests_query:
+            r#"{"requests":{"owner":"{wallet}","start_after":{start_after},"limit":{limit}}}"#
+                .into(),
+        unbonding_requests_pagination: Some(GenericLsdPagination {
+            limit: 1,
+            cursor_path: json_path(&["id"]),
+        }),
+        ..generic_spec()
+    };
+    execute(deps.as_mut(), mock_env(), mock_info("owner", &[]), insert_generic_msg(spec)).unwrap();
+
+    deps.querier.with_unbonding(Uint128::new(24_000000u128));
+    deps.querier.with_withdrawable(Uint128::new(10_000000u128));
+    deps.querier
+        .set_bank_balances(&[coin(100_000000u128, "utoken"), coin(5_000000u128, "genericcw")]);
+
+    // every page is queried until a page is not full
+    let state = query_state(deps.as_ref(), mock_env(), Some(true)).unwrap();
+    assert_eq!(
+        state.balances.details.unwrap()[2],
+        ClaimBalance {
+            name: "generic".to_string(),
+            withdrawable: Uint128::new(10_000000u128),
+            unbonding: Uint128::new(24_000000u128),
+            xbalance: Uint128::new(5_000000u128),
+            xfactor: Decimal::from_str("1.2").unwrap(),
+        }
+    );
+}
+
+#[test]
+fn generic_lsd_adapter_invalid_templates() {
+    let mut deps = setup_test();
+
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("owner", &[]),
+        insert_generic_msg(GenericLsdSpec {
+            withdraw_msg: r#"{"claim":{}"#.into(),
+            ..generic_spec()
+        }),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::Std(StdError::generic_err("withdraw_msg is not a JSON object")));
+
+    // the page placeholders are only filled when the query is paginated
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("owner", &[]),
+        insert_generic_msg(GenericLsdSpec {
+            unbonding_requests_query: r#"{"requests":{"start_after":{start_after}}}"#.into(),
+            ..generic_spec()
+        }),
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::Std(StdError::generic_err("unbonding_requests_query is not a JSON object"))
+    );
+
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("owner", &[]),
+        insert_generic_msg(GenericLsdSpec {
+            unbonding_requests_pagination: Some(GenericLsdPagination {
+                limit: 0,
+                cursor_path: json_path(&["id"]),
+            }),
+            ..generic_spec()
+        }),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::Std(StdError::generic_err("pagination limit cannot be zero")));
+}
+
+#[test]
+fn multi_asset_deposit_and_withdraw() {
+    let (mut deps, _res) = _provide_liquidity();
+
+    let insert_asset = |utoken: &str, lsds: Vec<LsdConfig<String>>| ExecuteMsg::UpdateConfig {
+        utilization_method: None,
+        unbond_time_s: None,
+        disable_lsd: None,
+        insert_lsd: None,
+        remove_lsd: None,
+        force_remove_lsd: None,
+        insert_asset: Some(BaseAssetConfig {
+            utoken: utoken.to_string(),
+            price: AssetPrice::Ratio(Decimal::from_str("2").unwrap()),
+            utilization_method: UtilizationMethod::Steps(vec![(
+                Decimal::from_ratio(10u128, 1000u128),
+                Decimal::from_ratio(50u128, 100u128),
+            )]),
+            lsds,
+        }),
+        remove_asset: None,
+        fee_config: None,
+        set_whitelist: None,
+        remove_whitelist: None,
+    };
+
+    let res =
+        execute(deps.as_mut(), mock_env(), mock_info("owner", &[]), insert_asset("utoken", vec![]))
+            .unwrap_err();
+    assert_eq!(res, ContractError::BaseAssetDuplicate("utoken".to_string()));
+
+    // an LSD token can only be listed under one base asset
+    let duplicate_lsd = LsdConfig {
+        disabled: false,
+        name: "eris_other".into(),
+        lsd_type: LsdType::Eris {
+            addr: "eris".into(),
+            denom: "eriscw".into(),
+        },
+    };
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("owner", &[]),
+        insert_asset("uother", vec![duplicate_lsd]),
+    )
+    .unwrap_err();
+    assert_eq!(res, ContractError::AdapterAssetDuplicate("eriscw".to_string()));
+
+    execute(deps.as_mut(), mock_env(), mock_info("owner", &[]), insert_asset("uother", vec![]))
+        .unwrap();
+
+    // deposit 50 uother valued at 100 utoken
+    deps.querier.set_bank_balances(&[coin(100_000000, "utoken"), coin(50_000000, "uother")]);
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("user", &[coin(50_000000, "uother")]),
+        ExecuteMsg::Deposit {
+            asset: native_asset("uother".to_string(), Uint128::new(50_000000)),
+            receiver: None,
+        },
+    )
+    .unwrap();
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "arb/execute_deposit"),
+            attr("sender", "user"),
+            attr("recipient", "user"),
+            attr("deposit_amount", "50000000"),
+            attr("share", "100000000"),
+            attr("vault_utoken_new", "200000000"),
+        ]
+    );
+
+    let state = query_state(deps.as_ref(), mock_env(), None).unwrap();
+    assert_eq!(state.balances.vault_total, Uint128::new(200_000000));
+    assert_eq!(state.balances.vault_available, Uint128::new(200_000000));
+    assert_eq!(
+        state.balances.assets[1],
+        AssetBalance {
+            utoken: "uother".into(),
+            price: Decimal::from_str("2").unwrap(),
+            tvl_utoken: Uint128::new(50_000000),
+            vault_total: Uint128::new(50_000000),
+            vault_available: Uint128::new(50_000000),
+            vault_takeable: Uint128::new(50_000000),
+            locked_user_withdrawls: Uint128::zero(),
+        }
+    );
+
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("owner", &[]),
+        ExecuteMsg::UpdateConfig {
+            utilization_method: None,
+            unbond_time_s: None,
+            disable_lsd: None,
+            insert_lsd: None,
+            remove_lsd: None,
+            force_remove_lsd: None,
+            insert_asset: None,
+            remove_asset: Some("uother".to_string()),
+            fee_config: None,
+            set_whitelist: None,
+            remove_whitelist: None,
+        },
+    )
+    .unwrap_err();
+    assert_eq!(res, ContractError::CannotRemoveAssetThatHasFunds {});
+
+    // half of the shares are paid out pro rata in both assets
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("user", &[coin(100_000000u128, TEST_LP_TOKEN)]),
+        ExecuteMsg::Unbond {
+            immediate: Some(true),
+        },
+    )
+    .unwrap();
+
+    // withdraw 100, pool fee 5, protocol fee 2
+    assert_eq!(
+        res.messages[0].msg,
+        CosmosMsg::Bank(BankMsg::Send {
+            to_address: "user".to_string(),
+            amount: vec![coin(46_500000, "utoken"), coin(23_250000, "uother")],
+        })
+    );
+    assert_eq!(
+        res.messages[1].msg,
+        CosmosMsg::Bank(BankMsg::Send {
+            to_address: "fee".to_string(),
+            amount: vec![coin(1_000000, "utoken"), coin(500000, "uother")],
+        })
+    );
+
+    // the locked withdrawal is split by the available funds when it is locked
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("user", &[coin(50_000000u128, TEST_LP_TOKEN)]),
+        ExecuteMsg::Unbond {
+            immediate: Some(false),
+        },
+    )
+    .unwrap();
+
+    let state = query_state(deps.as_ref(), mock_env(), None).unwrap();
+    let locked_utoken = state.balances.assets[0].locked_user_withdrawls;
+    let locked_uother = state.balances.assets[1].locked_user_withdrawls;
+    assert!(!locked_utoken.is_zero() && !locked_uother.is_zero());
+
+    // and keeps its split when the available funds change
+    deps.querier.set_bank_balances(&[coin(200_000000, "utoken"), coin(50_000000, "uother")]);
+    let state = query_state(deps.as_ref(), mock_env(), None).unwrap();
+    assert_eq!(state.balances.assets[0].locked_user_withdrawls, locked_utoken);
+    assert_eq!(state.balances.assets[1].locked_user_withdrawls, locked_uother);
+
+    // the locked withdrawal of 100 is paid out by its split instead of the available funds
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("user", &[]),
+        ExecuteMsg::WithdrawImmediate {
+            id: 0,
+        },
+    )
+    .unwrap();
+    assert_eq!(
+        res.messages[0].msg,
+        CosmosMsg::Bank(BankMsg::Send {
+            to_address: "user".to_string(),
+            amount: vec![coin(46_500000, "utoken"), coin(23_250000, "uother")],
+        })
+    );
+    assert_eq!(
+        res.messages[1].msg,
+        CosmosMsg::Bank(BankMsg::Send {
+            to_address: "fee".to_string(),
+            amount: vec![coin(1_000000, "utoken"), coin(500000, "uother")],
+        })
+    );
+
+    let state = query_state(deps.as_ref(), mock_env(), None).unwrap();
+    assert_eq!(state.balances.assets[0].locked_user_withdrawls, Uint128::zero());
+    assert_eq!(state.balances.assets[1].locked_user_withdrawls, Uint128::zero());
+}
+
+#[test]
+fn oracle_price_max_age() {
+    let (mut deps, _res) = _provide_liquidity();
+
+    let insert_asset = |max_age: u64| ExecuteMsg::UpdateConfig {
+        utilization_method: None,
+        unbond_time_s: None,
+        disable_lsd: None,
+        insert_lsd: None,
+        remove_lsd: None,
+        force_remove_lsd: None,
+        insert_asset: Some(BaseAssetConfig {
+            utoken: "uother".to_string(),
+            price: AssetPrice::Oracle {
+                contract: "oracle".to_string(),
+                query: r#"{"price":{"denom":"{utoken}"}}"#.to_string(),
+                path: vec!["price".to_string()],
+                updated_at_path: vec!["updated_at".to_string()],
+                max_age,
+            },
+            utilization_method: UtilizationMethod::Steps(vec![(
+                Decimal::from_ratio(10u128, 1000u128),
+                Decimal::from_ratio(50u128, 100u128),
+            )]),
+            lsds: vec![],
+        }),
+        remove_asset: None,
+        fee_config: None,
+        set_whitelist: None,
+        remove_whitelist: None,
+    };
+
+    let err =
+        execute(deps.as_mut(), mock_env(), mock_info("owner", &[]), insert_asset(0)).unwrap_err();
+    assert_eq!(err, ContractError::Std(StdError::generic_err("max_age can't be zero")));
+
+    let mut env = mock_env();
+    env.block.time = env.block.time.plus_seconds(10000);
+    deps.querier.oracle_updated_at = env.block.time.seconds() - 3600;
+    execute(deps.as_mut(), env.clone(), mock_info("owner", &[]), insert_asset(3600)).unwrap();
+
+    deps.querier.set_bank_balances(&[coin(100_000000, "utoken"), coin(50_000000, "uother")]);
+    let state = query_state(deps.as_ref(), env.clone(), None).unwrap();
+    assert_eq!(state.balances.assets[1].price, Decimal::from_str("2").unwrap());
+    assert_eq!(state.balances.vault_total, Uint128::new(200_000000));
+
+    // deposits are not priced with a stale price
+    deps.querier.oracle_updated_at = env.block.time.seconds() - 3601;
+    let err = execute(
+        deps.as_mut(),
+        env,
+        mock_info("user", &[coin(10_000000, "uother")]),
+        ExecuteMsg::Deposit {
+            asset: native_asset("uother".to_string(), Uint128::new(10_000000)),
+            receiver: None,
+        },
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::PriceStale("uother".to_string()));
+}
+
+#[test]
+fn split_value_without_primary_funds() {
+    let available = vec![
+        AssetAvailable {
+            utoken: "utoken".into(),
+            price: Decimal::one(),
+            available: Uint128::zero(),
+        },
+        AssetAvailable {
+            utoken: "uother".into(),
+            price: Decimal::from_str("1.5").unwrap(),
+            available: Uint128::new(1000),
+        },
+    ];
+
+    // the remainder below one unit of uother stays in the vault
+    let amounts = split_value(&available, Uint128::new(100)).unwrap();
+    assert_eq!(amounts, vec![Uint128::zero(), Uint128::new(66)]);
+
+    // amounts never exceed the available funds
+    let amounts = split_value(&available, Uint128::new(2000)).unwrap();
+    assert_eq!(amounts, vec![Uint128::zero(), Uint128::new(1000)]);
+
+    // with primary funds the remainder is paid in the primary utoken
+    let available = vec![
+        AssetAvailable {
+            utoken: "utoken".into(),
+            price: Decimal::one(),
+            available: Uint128::new(10),
+        },
+        AssetAvailable {
+            utoken: "uother".into(),
+            price: Decimal::from_str("1.5").unwrap(),
+            available: Uint128::new(1000),
+        },
+    ];
+    let amounts = split_value(&available, Uint128::new(100)).unwrap();
+    assert_eq!(amounts, vec![Uint128::new(1), Uint128::new(66)]);
+}
+
 fn calc_takeable(total_value: Uint128, pool_takeable: Uint128, share: &str) -> Uint128 {
     // total value * share = total pool that can be used for that share
     // + takeable - total value
@@ -0,0 +1,419 @@
+use std::str::FromStr;
+
+use cosmwasm_std::testing::{mock_info, MockApi, MockStorage, MOCK_CONTRACT_ADDR};
+use cosmwasm_std::{
+    coin, CosmosMsg, Decimal, OwnedDeps, Reply, ReplyOn, StdError, SubMsgResponse, SubMsgResult,
+    Uint128,
+};
+use eris::arb_vault::{
+    ClaimBalance, ExecuteMsg, IbcLifecycleComplete, IbcRedemption, LsdConfig, LsdType, SudoMsg,
+};
+
+use crate::constants::IBC_TRANSFER_REPLY_ID;
+use crate::contract::{execute, reply, sudo};
+use crate::error::ContractError;
+use crate::query::{query_ibc_redemptions, query_state};
+use crate::state::State;
+
+use super::custom_querier::CustomQuerier;
+use super::helpers::{mock_env, setup_test};
+
+const IBC_DENOM: &str = "ibc/lsd";
+const CHANNEL: &str = "channel-1";
+
+fn setup_ibc() -> OwnedDeps<MockStorage, MockApi, CustomQuerier> {
+    let mut deps = setup_test();
+
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("owner", &[]),
+        ExecuteMsg::UpdateConfig {
+            utilization_method: None,
+            unbond_time_s: None,
+            disable_lsd: None,
+            insert_lsd: Some(LsdConfig {
+                disabled: false,
+                name: "ibc".into(),
+                lsd_type: LsdType::Ibc {
+                    denom: IBC_DENOM.into(),
+                    channel_id: CHANNEL.into(),
+                    receiver: "redemption".into(),
+                    memo: r#"{"ibc_callback":"{wallet}"}"#.into(),
+                    timeout_s: 600,
+                    exchange_rate: Decimal::from_str("1.2").unwrap(),
+                },
+            }),
+            remove_lsd: None,
+            force_remove_lsd: None,
+            insert_asset: None,
+            remove_asset: None,
+            fee_config: None,
+            set_whitelist: None,
+            remove_whitelist: None,
+        },
+    )
+    .unwrap();
+
+    deps
+}
+
+fn unbond_ibc(deps: &mut OwnedDeps<MockStorage, MockApi, CustomQuerier>, amount: u128) -> u64 {
+    deps.querier.set_bank_balances(&[coin(100_000000u128, "utoken"), coin(amount, IBC_DENOM)]);
+
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("whitelisted_exec", &[]),
+        ExecuteMsg::UnbondFromLiquidStaking {
+            names: Some(vec!["ibc".to_string()]),
+        },
+    )
+    .unwrap();
+
+    // the LSD tokens left the vault
+    deps.querier.set_bank_balances(&[coin(100_000000u128, "utoken")]);
+
+    assert_eq!(res.messages.len(), 1);
+    assert_eq!(res.messages[0].reply_on, ReplyOn::Success);
+    assert_eq!(res.messages[0].id, IBC_TRANSFER_REPLY_ID);
+    match &res.messages[0].msg {
+        CosmosMsg::Stargate {
+            type_url,
+            value,
+        } => {
+            assert_eq!(type_url, "/ibc.applications.transfer.v1.MsgTransfer");
+            let memo = format!(r#"{{"ibc_callback":"{0}"}}"#, MOCK_CONTRACT_ADDR);
+            assert!(value.ends_with(memo.as_bytes()));
+        },
+        _ => panic!("DO NOT ENTER HERE"),
+    }
+
+    res.attributes
+        .iter()
+        .find(|attr| attr.key == "ibc_redemption_id")
+        .map(|attr| attr.value.parse().unwrap())
+        .unwrap()
+}
+
+//--------------------------------------------------------------------------------------------------
+// Mocked IBC layer
+//--------------------------------------------------------------------------------------------------
+
+fn transfer_reply(sequence: u64) -> Reply {
+    // MsgTransferResponse { sequence }
+    let mut data = vec![1 << 3];
+    let mut value = sequence;
+    while value >= 0x80 {
+        data.push((value as u8 & 0x7f) | 0x80);
+        value >>= 7;
+    }
+    data.push(value as u8);
+
+    Reply {
+        id: IBC_TRANSFER_REPLY_ID,
+        result: SubMsgResult::Ok(SubMsgResponse {
+            events: vec![],
+            data: Some(data.into()),
+        }),
+    }
+}
+
+fn ibc_ack(sequence: u64, success: bool) -> SudoMsg {
+    SudoMsg::IbcLifecycleComplete(IbcLifecycleComplete::IbcAck {
+        channel: CHANNEL.into(),
+        sequence,
+        ack: "".into(),
+        success,
+    })
+}
+
+fn ibc_timeout(sequence: u64) -> SudoMsg {
+    SudoMsg::IbcLifecycleComplete(IbcLifecycleComplete::IbcTimeout {
+        channel: CHANNEL.into(),
+        sequence,
+    })
+}
+
+//--------------------------------------------------------------------------------------------------
+// Execution
+//--------------------------------------------------------------------------------------------------
+
+#[test]
+fn ibc_redemption_acknowledged() {
+    let mut deps = setup_ibc();
+
+    let id = unbond_ibc(&mut deps, 10_000000);
+    reply(deps.as_mut(), mock_env(), transfer_reply(300)).unwrap();
+
+    assert_eq!(
+        query_ibc_redemptions(deps.as_ref(), None, None).unwrap().redemptions,
+        vec![IbcRedemption {
+            id: 1,
+            name: "ibc".into(),
+            channel_id: CHANNEL.into(),
+            sequence: Some(300),
+            amount: Uint128::new(10_000000u128),
+            expected_amount: Uint128::new(12_000000u128),
+            timeout: 601,
+            acknowledged: false,
+            expired: false,
+        }]
+    );
+
+    // counted as unbonding until the utoken returned
+    let state = query_state(deps.as_ref(), mock_env(), Some(true)).unwrap();
+    assert_eq!(
+        state.balances.details.unwrap()[2],
+        ClaimBalance {
+            name: "ibc".into(),
+            withdrawable: Uint128::zero(),
+            unbonding: Uint128::new(12_000000u128),
+            xbalance: Uint128::zero(),
+            xfactor: Decimal::from_str("1.2").unwrap(),
+        }
+    );
+
+    let complete = ExecuteMsg::CompleteIbcRedemptions {
+        ids: vec![id],
+    };
+    let err =
+        execute(deps.as_mut(), mock_env(), mock_info("whitelisted_exec", &[]), complete.clone())
+            .unwrap_err();
+    assert_eq!(err, ContractError::IbcRedemptionNotAcknowledged(id));
+
+    // unknown transfers are ignored
+    let res = sudo(deps.as_mut(), mock_env(), ibc_ack(299, true)).unwrap();
+    assert!(res.attributes.is_empty());
+
+    sudo(deps.as_mut(), mock_env(), ibc_ack(300, true)).unwrap();
+    assert!(State::default().ibc_redemptions.load(deps.as_ref().storage, id).unwrap().acknowledged);
+
+    // the redemption is only completed by the returned utoken
+    let err =
+        execute(deps.as_mut(), mock_env(), mock_info("whitelisted_exec", &[]), complete.clone())
+            .unwrap_err();
+    assert_eq!(err, ContractError::IbcRedemptionNotReceived(id));
+
+    // the utoken partially returned to the vault
+    deps.querier.set_bank_balances(&[coin(105_000000u128, "utoken")]);
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("hook", &[coin(5_000000u128, "utoken")]),
+        complete.clone(),
+    )
+    .unwrap();
+
+    let state = query_state(deps.as_ref(), mock_env(), None).unwrap();
+    assert_eq!(state.balances.lsd_unbonding, Uint128::new(7_000000u128));
+    assert_eq!(state.balances.tvl_utoken, Uint128::new(112_000000u128));
+
+    deps.querier.set_bank_balances(&[coin(112_000000u128, "utoken")]);
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("hook", &[coin(7_000000u128, "utoken")]),
+        complete,
+    )
+    .unwrap();
+
+    assert_eq!(query_ibc_redemptions(deps.as_ref(), None, None).unwrap().redemptions, vec![]);
+    assert!(!State::default().ibc_transfers.has(deps.as_ref().storage, (CHANNEL, 300)));
+    let state = query_state(deps.as_ref(), mock_env(), None).unwrap();
+    assert_eq!(state.balances.lsd_unbonding, Uint128::zero());
+    assert_eq!(state.balances.tvl_utoken, Uint128::new(112_000000u128));
+}
+
+#[test]
+fn ibc_redemption_settled_without_hook() {
+    let mut deps = setup_ibc();
+
+    let id = unbond_ibc(&mut deps, 10_000000);
+    reply(deps.as_mut(), mock_env(), transfer_reply(1)).unwrap();
+
+    let settle = ExecuteMsg::SettleIbcRedemptions {
+        ids: vec![id],
+    };
+    let err =
+        execute(deps.as_mut(), mock_env(), mock_info("whitelisted_exec", &[]), settle.clone())
+            .unwrap_err();
+    assert_eq!(err, ContractError::IbcRedemptionNotAcknowledged(id));
+
+    sudo(deps.as_mut(), mock_env(), ibc_ack(1, true)).unwrap();
+
+    // the utoken returned by a plain transfer is counted twice
+    deps.querier.set_bank_balances(&[coin(112_000000u128, "utoken")]);
+    let state = query_state(deps.as_ref(), mock_env(), None).unwrap();
+    assert_eq!(state.balances.lsd_unbonding, Uint128::new(12_000000u128));
+    assert_eq!(state.balances.tvl_utoken, Uint128::new(124_000000u128));
+
+    let err =
+        execute(deps.as_mut(), mock_env(), mock_info("user", &[]), settle.clone()).unwrap_err();
+    assert_eq!(err, ContractError::UnauthorizedNotWhitelisted {});
+
+    execute(deps.as_mut(), mock_env(), mock_info("whitelisted_exec", &[]), settle.clone()).unwrap();
+
+    assert_eq!(query_ibc_redemptions(deps.as_ref(), None, None).unwrap().redemptions, vec![]);
+    assert!(!State::default().ibc_transfers.has(deps.as_ref().storage, (CHANNEL, 1)));
+    let state = query_state(deps.as_ref(), mock_env(), None).unwrap();
+    assert_eq!(state.balances.lsd_unbonding, Uint128::zero());
+    assert_eq!(state.balances.tvl_utoken, Uint128::new(112_000000u128));
+
+    // the vault balance needs to cover the settled redemptions
+    let first = unbond_ibc(&mut deps, 5_000000);
+    reply(deps.as_mut(), mock_env(), transfer_reply(2)).unwrap();
+    let second = unbond_ibc(&mut deps, 5_000000);
+    reply(deps.as_mut(), mock_env(), transfer_reply(3)).unwrap();
+    sudo(deps.as_mut(), mock_env(), ibc_ack(2, true)).unwrap();
+    sudo(deps.as_mut(), mock_env(), ibc_ack(3, true)).unwrap();
+
+    deps.querier.set_bank_balances(&[coin(10_000000u128, "utoken")]);
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("whitelisted_exec", &[]),
+        ExecuteMsg::SettleIbcRedemptions {
+            ids: vec![first, second],
+        },
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::IbcRedemptionNotReceived(second));
+}
+
+#[test]
+fn ibc_redemption_failed() {
+    let mut deps = setup_ibc();
+
+    unbond_ibc(&mut deps, 10_000000);
+    reply(deps.as_mut(), mock_env(), transfer_reply(1)).unwrap();
+    unbond_ibc(&mut deps, 5_000000);
+    reply(deps.as_mut(), mock_env(), transfer_reply(2)).unwrap();
+
+    // no transfer is waiting for its reply
+    let err = reply(deps.as_mut(), mock_env(), transfer_reply(3)).unwrap_err();
+    assert_eq!(err, ContractError::InvalidReplyId(IBC_TRANSFER_REPLY_ID));
+
+    let err = reply(
+        deps.as_mut(),
+        mock_env(),
+        Reply {
+            id: 1,
+            ..transfer_reply(3)
+        },
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::InvalidReplyId(1));
+
+    // refunded LSD tokens are no longer unbonding
+    sudo(deps.as_mut(), mock_env(), ibc_timeout(1)).unwrap();
+    sudo(deps.as_mut(), mock_env(), ibc_ack(2, false)).unwrap();
+
+    assert_eq!(query_ibc_redemptions(deps.as_ref(), None, None).unwrap().redemptions, vec![]);
+    let state = query_state(deps.as_ref(), mock_env(), None).unwrap();
+    assert_eq!(state.balances.lsd_unbonding, Uint128::zero());
+}
+
+#[test]
+fn ibc_redemption_expired() {
+    let mut deps = setup_ibc();
+
+    let id = unbond_ibc(&mut deps, 10_000000);
+    reply(deps.as_mut(), mock_env(), transfer_reply(1)).unwrap();
+
+    let expire = ExecuteMsg::ExpireIbcRedemptions {
+        ids: vec![id],
+    };
+    let err =
+        execute(deps.as_mut(), mock_env(), mock_info("user", &[]), expire.clone()).unwrap_err();
+    assert_eq!(err, ContractError::UnauthorizedNotWhitelisted {});
+
+    // the transfer has not timed out yet
+    let err =
+        execute(deps.as_mut(), mock_env(), mock_info("whitelisted_exec", &[]), expire.clone())
+            .unwrap_err();
+    assert_eq!(err, ContractError::IbcRedemptionNotExpirable(id));
+
+    let mut env = mock_env();
+    env.block.time = env.block.time.plus_seconds(601);
+    execute(deps.as_mut(), env.clone(), mock_info("whitelisted_exec", &[]), expire.clone())
+        .unwrap();
+
+    // the refunded LSD tokens are counted instead
+    let state = query_state(deps.as_ref(), env.clone(), None).unwrap();
+    assert_eq!(state.balances.lsd_unbonding, Uint128::zero());
+
+    let err = execute(deps.as_mut(), env.clone(), mock_info("whitelisted_exec", &[]), expire)
+        .unwrap_err();
+    assert_eq!(err, ContractError::IbcRedemptionNotExpirable(id));
+
+    // a late acknowledgement restores the redemption
+    sudo(deps.as_mut(), env.clone(), ibc_ack(1, true)).unwrap();
+    let redemption = State::default().ibc_redemptions.load(deps.as_ref().storage, id).unwrap();
+    assert!(redemption.acknowledged && !redemption.expired);
+    let state = query_state(deps.as_ref(), env, None).unwrap();
+    assert_eq!(state.balances.lsd_unbonding, Uint128::new(12_000000u128));
+}
+
+#[test]
+fn ibc_memo_without_callback() {
+    let mut deps = setup_test();
+
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("owner", &[]),
+        ExecuteMsg::UpdateConfig {
+            utilization_method: None,
+            unbond_time_s: None,
+            disable_lsd: None,
+            insert_lsd: Some(LsdConfig {
+                disabled: false,
+                name: "ibc".into(),
+                lsd_type: LsdType::Ibc {
+                    denom: IBC_DENOM.into(),
+                    channel_id: CHANNEL.into(),
+                    receiver: "redemption".into(),
+                    memo: r#"{"wasm":{"contract":"redemption"}}"#.into(),
+                    timeout_s: 600,
+                    exchange_rate: Decimal::from_str("1.2").unwrap(),
+                },
+            }),
+            remove_lsd: None,
+            force_remove_lsd: None,
+            insert_asset: None,
+            remove_asset: None,
+            fee_config: None,
+            set_whitelist: None,
+            remove_whitelist: None,
+        },
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::Std(StdError::generic_err("memo needs to set ibc_callback to {wallet}"))
+    );
+}
+
+#[test]
+fn setting_ibc_exchange_rate() {
+    let mut deps = setup_ibc();
+
+    let set_rate = |name: &str| ExecuteMsg::SetIbcExchangeRate {
+        name: name.into(),
+        exchange_rate: Decimal::from_str("1.25").unwrap(),
+    };
+
+    let err =
+        execute(deps.as_mut(), mock_env(), mock_info("user", &[]), set_rate("ibc")).unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+
+    let err =
+        execute(deps.as_mut(), mock_env(), mock_info("owner", &[]), set_rate("eris")).unwrap_err();
+    assert_eq!(err, ContractError::AdapterNotFound("ibc - eris".into()));
+
+    execute(deps.as_mut(), mock_env(), mock_info("owner", &[]), set_rate("ibc")).unwrap();
+
+    let state = query_state(deps.as_ref(), mock_env(), Some(true)).unwrap();
+    assert_eq!(state.balances.details.unwrap()[2].xfactor, Decimal::from_str("1.25").unwrap());
+}
@@ -0,0 +1,128 @@
+use cosmwasm_std::{
+    Addr, Decimal, DepsMut, Env, Event, Order, Response, StdResult, Storage, Uint128,
+};
+use eris::circuit_breaker::{self, PausableAction};
+use eris::DecimalCheckedOps;
+use eris_chain_adapter::types::CustomQueryType;
+
+use crate::error::{ContractError, ContractResult};
+use crate::state::State;
+
+/// Pauses or resumes an action. The guardian can only pause, resuming requires the owner.
+pub fn set_paused(
+    deps: DepsMut<CustomQueryType>,
+    sender: Addr,
+    action: PausableAction,
+    paused: bool,
+) -> ContractResult {
+    let state = State::default();
+
+    if paused {
+        state.assert_guardian_or_owner(deps.storage, &sender)?;
+    } else {
+        state.assert_owner(deps.storage, &sender)?;
+    }
+
+    circuit_breaker::set_paused(deps.storage, &state.paused, action, paused)?;
+
+    Ok(Response::new()
+        .add_event(
+            Event::new("erishub/paused_updated")
+                .add_attribute("pausable_action", action.as_str())
+                .add_attribute("paused", paused.to_string()),
+        )
+        .add_attribute("action", "erishub/set_paused"))
+}
+
+pub fn assert_not_paused(
+    storage: &dyn Storage,
+    action: PausableAction,
+) -> Result<(), ContractError> {
+    Ok(circuit_breaker::assert_not_paused(storage, &State::default().paused, action)?)
+}
+
+/// Adds the amount to the rolling volume of the action and verifies it stays within its limit.
+pub fn track_volume(
+    storage: &mut dyn Storage,
+    env: &Env,
+    action: PausableAction,
+    amount: Uint128,
+) -> Result<(), ContractError> {
+    let state = State::default();
+    Ok(circuit_breaker::track_volume(
+        storage,
+        &state.circuit_breaker,
+        &state.volumes,
+        action.as_str(),
+        env.block.time.seconds(),
+        action,
+        amount,
+    )?)
+}
+
+/// Adds the unbonded ustake to the rolling unbond volume, valued in utoken at the last recorded
+/// exchange rate like the bond volume.
+pub fn track_unbond_volume(
+    storage: &mut dyn Storage,
+    env: &Env,
+    ustake_amount: Uint128,
+) -> Result<(), ContractError> {
+    let utoken_amount = unbond_volume(storage, ustake_amount)?;
+    track_volume(storage, env, PausableAction::Unbond, utoken_amount)
+}
+
+/// Removes the ustake of a canceled unbond from the rolling unbond volume again.
+pub fn untrack_unbond_volume(
+    storage: &mut dyn Storage,
+    env: &Env,
+    ustake_amount: Uint128,
+) -> StdResult<()> {
+    let state = State::default();
+    let utoken_amount = unbond_volume(storage, ustake_amount)?;
+    circuit_breaker::untrack_volume(
+        storage,
+        &state.circuit_breaker,
+        &state.volumes,
+        PausableAction::Unbond.as_str(),
+        env.block.time.seconds(),
+        utoken_amount,
+    )
+}
+
+fn unbond_volume(storage: &dyn Storage, ustake_amount: Uint128) -> StdResult<Uint128> {
+    let exchange_rate = last_exchange_rate(storage)?.unwrap_or_else(Decimal::one);
+    exchange_rate.checked_mul_uint(ustake_amount)
+}
+
+fn last_exchange_rate(storage: &dyn Storage) -> StdResult<Option<Decimal>> {
+    Ok(State::default()
+        .exchange_history
+        .range(storage, None, None, Order::Descending)
+        .next()
+        .transpose()?
+        .map(|(_, exchange_rate)| exchange_rate))
+}
+
+/// Compares the exchange rate with the last recorded one. If it moved by more than the allowed
+/// change, all actions are paused until the owner resumes them.
+pub fn check_exchange_rate(
+    storage: &mut dyn Storage,
+    exchange_rate: Decimal,
+) -> StdResult<Option<Event>> {
+    let state = State::default();
+
+    let previous = last_exchange_rate(storage)?;
+    let tripped = circuit_breaker::check_exchange_rate(
+        storage,
+        &state.circuit_breaker,
+        &state.paused,
+        previous,
+        exchange_rate,
+    )?;
+
+    Ok(previous.filter(|_| tripped).map(|previous| {
+        Event::new("erishub/circuit_breaker_tripped")
+            .add_attribute("previous_exchange_rate", previous.to_string())
+            .add_attribute("exchange_rate", exchange_rate.to_string())
+    }))
+}
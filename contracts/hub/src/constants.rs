@@ -3,6 +3,12 @@ use cosmwasm_std::Decimal;
 pub const CONTRACT_NAME: &str = "eris-staking-hub-tokenfactory";
 pub const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
 pub const DAY: u64 = 24 * 60 * 60;
+/// Default of the staking module's `max_entries` param
+pub const DEFAULT_MAX_UNBONDING_ENTRIES: u32 = 7;
+/// Delegation shortfalls up to this share (in bps) are treated as rounding and not as slashing
+pub const SLASHING_THRESHOLD_BPS: u128 = 1;
+/// Delegation shortfalls up to this amount are treated as rounding and not as slashing
+pub const SLASHING_THRESHOLD_AMOUNT: u128 = 10;
 
 pub fn get_reward_fee_cap() -> Decimal {
     // 10% max reward fee
@@ -1,16 +1,17 @@
 use cosmwasm_std::{
     entry_point, to_json_binary, Binary, Deps, DepsMut, Env, MessageInfo, Response, StdResult,
+    Uint128,
 };
 use cw2::set_contract_version;
 
-use eris::hub::{CallbackMsg, ExecuteMsg, InstantiateMsg, MigrateMsg, QueryMsg};
+use eris::hub::{CallbackMsg, ExecuteMsg, InstantiateMsg, MigrateMsg, QueryMsg, SudoMsg};
 use eris_chain_adapter::types::CustomQueryType;
 
 use crate::claim::exec_claim;
 use crate::constants::{CONTRACT_NAME, CONTRACT_VERSION};
 use crate::error::{ContractError, ContractResult};
 use crate::state::State;
-use crate::{execute, gov, queries};
+use crate::{circuit_breaker, execute, gov, prices, queries, referral, slashing};
 
 #[entry_point]
 pub fn instantiate(
@@ -33,14 +34,17 @@ pub fn execute(
     match msg {
         ExecuteMsg::Bond {
             receiver,
+            referrer,
         } => execute::bond(
             deps,
             env,
             receiver.map(|s| api.addr_validate(&s)).transpose()?.unwrap_or(info.sender),
+            referrer.map(|s| api.addr_validate(&s)).transpose()?,
             &info.funds,
             false,
         ),
-        ExecuteMsg::Donate {} => execute::bond(deps, env, info.sender, &info.funds, true),
+        ExecuteMsg::Donate {} => execute::bond(deps, env, info.sender, None, &info.funds, true),
+        ExecuteMsg::DonateReserve {} => execute::donate_reserve(deps, &info.funds),
         ExecuteMsg::WithdrawUnbonded {
             receiver,
         } => execute::withdraw_unbonded(
@@ -49,6 +53,12 @@ pub fn execute(
             info.sender.clone(),
             receiver.map(|s| api.addr_validate(&s)).transpose()?.unwrap_or(info.sender),
         ),
+        ExecuteMsg::WithdrawUnbondedFor {
+            users,
+        } => execute::withdraw_unbonded_for(deps, env, users),
+        ExecuteMsg::SetAutoClaim {
+            enabled,
+        } => execute::set_auto_claim(deps, info.sender, enabled),
         ExecuteMsg::AddValidator {
             validator,
         } => execute::add_validator(deps, info.sender, validator),
@@ -79,11 +89,28 @@ pub fn execute(
             proposal_id,
             votes,
         } => gov::vote_weighted(deps, env, info, proposal_id, votes),
+        ExecuteMsg::UpdateHolderVoting {
+            enabled,
+        } => gov::update_holder_voting(deps, env, info.sender, enabled),
+        ExecuteMsg::OpenHolderVote {
+            proposal_id,
+            start_time,
+            end_time,
+        } => gov::open_holder_vote(deps, env, info.sender, proposal_id, start_time, end_time),
+        ExecuteMsg::CastHolderVote {
+            proposal_id,
+            votes,
+        } => gov::cast_holder_vote(deps, env, info.sender, proposal_id, votes),
+        ExecuteMsg::SubmitHolderVote {
+            proposal_id,
+        } => gov::submit_holder_vote(deps, env, proposal_id),
+        ExecuteMsg::ClaimReferralRewards {} => referral::claim_referral_rewards(deps, info.sender),
         ExecuteMsg::Callback(callback_msg) => callback(deps, env, info, callback_msg),
 
         ExecuteMsg::UpdateConfig {
             protocol_fee_contract,
             protocol_reward_fee,
+            fee_recipients,
             operator,
             stages_preset,
             allow_donations,
@@ -94,12 +121,22 @@ pub fn execute(
             default_max_spread,
             epoch_period,
             unbond_period,
-            ..
+            instant_unbond_config,
+            remove_instant_unbond_config,
+            remove_slashed_validators,
+            max_unbonding_entries,
+            guardian,
+            remove_guardian,
+            circuit_breaker,
+            referral_share,
+            rebalance_config,
         } => execute::update_config(
             deps,
+            env,
             info.sender,
             protocol_fee_contract,
             protocol_reward_fee,
+            fee_recipients,
             operator,
             stages_preset,
             withdrawals_preset,
@@ -110,34 +147,98 @@ pub fn execute(
             default_max_spread,
             epoch_period,
             unbond_period,
+            instant_unbond_config,
+            remove_instant_unbond_config,
+            remove_slashed_validators,
+            max_unbonding_entries,
+            guardian,
+            remove_guardian,
+            circuit_breaker,
+            referral_share,
+            rebalance_config,
         ),
+        ExecuteMsg::SetPaused {
+            action,
+            paused,
+        } => circuit_breaker::set_paused(deps, info.sender, action, paused),
         ExecuteMsg::QueueUnbond {
             receiver,
         } => {
-            let state = State::default();
-            let stake_token = state.stake_token.load(deps.storage)?;
-
-            if info.funds.len() != 1 {
-                return Err(ContractError::ExpectingSingleCoin {});
-            }
-
-            if info.funds[0].denom != stake_token.denom {
-                return Err(ContractError::ExpectingStakeToken(info.funds[0].denom.to_string()));
-            }
+            let ustake_to_burn = validate_stake_token_received(&deps, &info)?;
+            referral::remove_referred_shares(deps.storage, &info.sender, ustake_to_burn)?;
 
             execute::queue_unbond(
                 deps,
                 env,
                 api.addr_validate(&receiver.unwrap_or_else(|| info.sender.to_string()))?,
-                info.funds[0].amount,
+                ustake_to_burn,
             )
         },
+        ExecuteMsg::InstantUnbond {
+            min_received,
+            receiver,
+        } => {
+            let ustake_to_burn = validate_stake_token_received(&deps, &info)?;
+            referral::remove_referred_shares(deps.storage, &info.sender, ustake_to_burn)?;
+
+            execute::instant_unbond(
+                deps,
+                env,
+                api.addr_validate(&receiver.unwrap_or_else(|| info.sender.to_string()))?,
+                ustake_to_burn,
+                min_received,
+            )
+        },
+        ExecuteMsg::TransferUnbondRequest {
+            id,
+            recipient,
+            shares,
+        } => execute::transfer_unbond_request(
+            deps,
+            info.sender,
+            id,
+            api.addr_validate(&recipient)?,
+            shares,
+        ),
+        ExecuteMsg::CancelUnbond {
+            amount,
+        } => execute::cancel_unbond(deps, env, info.sender, amount),
+        ExecuteMsg::Rebond {
+            id,
+        } => execute::rebond(deps, env, info.sender, id),
+        ExecuteMsg::CheckSlashing {} => slashing::check_slashing(deps, env, info),
+        ExecuteMsg::UpdatePriceFeed {
+            denom,
+            feed,
+        } => prices::update_price_feed(deps, info.sender, denom, feed),
+        ExecuteMsg::PushPrice {
+            denom,
+            price,
+        } => prices::push_price(deps, env, info.sender, denom, price),
         ExecuteMsg::Claim {
             claims,
         } => exec_claim(deps, env, info, claims),
     }
 }
 
+fn validate_stake_token_received(
+    deps: &DepsMut<CustomQueryType>,
+    info: &MessageInfo,
+) -> Result<Uint128, ContractError> {
+    let state = State::default();
+    let stake_token = state.stake_token.load(deps.storage)?;
+
+    if info.funds.len() != 1 {
+        return Err(ContractError::ExpectingSingleCoin {});
+    }
+
+    if info.funds[0].denom != stake_token.denom {
+        return Err(ContractError::ExpectingStakeToken(info.funds[0].denom.to_string()));
+    }
+
+    Ok(info.funds[0].amount)
+}
+
 fn callback(
     deps: DepsMut<CustomQueryType>,
     env: Env,
@@ -166,6 +267,21 @@ fn callback(
     }
 }
 
+#[entry_point]
+pub fn sudo(deps: DepsMut<CustomQueryType>, env: Env, msg: SudoMsg) -> ContractResult {
+    match msg {
+        SudoMsg::TrackBeforeSend {
+            from,
+            to,
+            amount,
+        } => gov::track_before_send(deps, env, from, to, amount),
+        // transfers are never blocked, the balances are tracked in TrackBeforeSend
+        SudoMsg::BlockBeforeSend {
+            ..
+        } => Ok(Response::new()),
+    }
+}
+
 #[entry_point]
 pub fn query(deps: Deps<CustomQueryType>, env: Env, msg: QueryMsg) -> StdResult<Binary> {
     match msg {
@@ -199,6 +315,10 @@ pub fn query(deps: Deps<CustomQueryType>, env: Env, msg: QueryMsg) -> StdResult<
             limit,
             env,
         )?),
+        QueryMsg::ClaimableUsers {
+            start_after,
+            limit,
+        } => to_json_binary(&queries::claimable_users(deps, env, start_after, limit)?),
         QueryMsg::WantedDelegations {} => to_json_binary(&queries::wanted_delegations(deps, env)?),
         QueryMsg::SimulateWantedDelegations {
             period,
@@ -208,6 +328,28 @@ pub fn query(deps: Deps<CustomQueryType>, env: Env, msg: QueryMsg) -> StdResult<
             start_after,
             limit,
         } => to_json_binary(&queries::query_exchange_rates(deps, env, start_after, limit)?),
+        QueryMsg::InstantUnbondQuote {
+            ustake_amount,
+        } => to_json_binary(&queries::instant_unbond_quote(deps, env, ustake_amount)?),
+        QueryMsg::UnbondingEntries {} => to_json_binary(&queries::unbonding_entries(deps, env)?),
+        QueryMsg::SlashingEvents {
+            start_after,
+            limit,
+        } => to_json_binary(&slashing::query_slashing_events(deps, start_after, limit)?),
+        QueryMsg::SimulateHarvest {
+            validators,
+            withdrawals,
+            stages,
+        } => {
+            to_json_binary(&queries::simulate_harvest(deps, env, validators, withdrawals, stages)?)
+        },
+        QueryMsg::HolderVote {
+            proposal_id,
+        } => to_json_binary(&gov::query_holder_vote(deps, proposal_id)?),
+        QueryMsg::PriceFeeds {} => to_json_binary(&prices::query_price_feeds(deps)?),
+        QueryMsg::ReferralInfo {
+            referrer,
+        } => to_json_binary(&referral::referral_info(deps, referrer)?),
     }
 }
 
@@ -1,4 +1,5 @@
-use cosmwasm_std::{OverflowError, Response, StdError};
+use cosmwasm_std::{OverflowError, Response, StdError, Uint128};
+use eris::circuit_breaker::CircuitBreakerError;
 use eris_chain_adapter::types::CustomMsgType;
 use thiserror::Error;
 
@@ -25,6 +26,9 @@ pub enum ContractError {
     #[error("Unauthorized: sender is not operator")]
     UnauthorizedSenderNotOperator {},
 
+    #[error("Unauthorized: sender is not guardian")]
+    UnauthorizedSenderNotGuardian {},
+
     #[error("Expecting only single coin")]
     ExpectingSingleCoin {},
 
@@ -84,4 +88,79 @@ pub enum ContractError {
 
     #[error("No validators configured. There needs to be at least one validator available.")]
     NoValidatorsConfigured,
+
+    #[error("Instant unbond is disabled")]
+    InstantUnbondDisabled {},
+
+    #[error("Instant unbond reserve is too low, available: {0}")]
+    InstantUnbondReserveTooLow(Uint128),
+
+    #[error("Received amount is less than the minimum: {0}")]
+    MinReceivedNotReached(Uint128),
+
+    #[error("Not enough shares in unbond request, available: {0}")]
+    NotEnoughShares(Uint128),
+
+    #[error("Can't transfer an unbond request to the sender")]
+    CantTransferToSender {},
+
+    #[error("Batch {0} has already finished unbonding")]
+    BatchAlreadyUnbonded(u64),
+
+    #[error("Holder vote for proposal {0} already exists")]
+    HolderVoteExists(u64),
+
+    #[error("Holder vote for proposal {0} not found")]
+    HolderVoteNotFound(u64),
+
+    #[error("Holder vote for proposal {0} has ended")]
+    HolderVoteEnded(u64),
+
+    #[error("Holder vote for proposal {0} starts before the holder balances are tracked")]
+    HolderVoteNotTracked(u64),
+
+    #[error("Holder voting is not enabled")]
+    HolderVotingDisabled {},
+
+    #[error("No voting power at the snapshot")]
+    NoVotingPower {},
+
+    #[error("Price of {0} is stale")]
+    PriceStale(String),
+
+    #[error("No price available for {0}")]
+    PriceNotAvailable(String),
+
+    #[error("Price feed of {0} does not accept pushed prices")]
+    PriceNotPushable(String),
+
+    #[error("{0} is paused")]
+    Paused(String),
+
+    #[error("{0} volume limit exceeded")]
+    VolumeLimitExceeded(String),
+
+    #[error("Can't refer yourself")]
+    SelfReferral {},
+
+    #[error("Delegation goal has not been tuned")]
+    DelegationGoalNotTuned {},
+
+    #[error("Nothing to rebalance")]
+    NothingToRebalance {},
+
+    #[error("Delegation strategy {0} is not supported")]
+    DelegationStrategyNotSupported(String),
+}
+
+impl From<CircuitBreakerError> for ContractError {
+    fn from(err: CircuitBreakerError) -> Self {
+        match err {
+            CircuitBreakerError::Std(err) => ContractError::Std(err),
+            CircuitBreakerError::Paused(action) => ContractError::Paused(action.as_str().into()),
+            CircuitBreakerError::VolumeLimitExceeded(action) => {
+                ContractError::VolumeLimitExceeded(action.as_str().into())
+            },
+        }
+    }
 }
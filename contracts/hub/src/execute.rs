@@ -2,14 +2,16 @@ use std::cmp;
 
 use cosmwasm_std::{
     attr, to_json_binary, Addr, Attribute, BankMsg, Coin, CosmosMsg, Decimal, DepsMut,
-    DistributionMsg, Env, Event, Order, Response, StdResult, Uint128, WasmMsg,
+    DistributionMsg, Env, Event, Order, Response, StdError, StdResult, Storage, Uint128, WasmMsg,
 };
 use cw2::set_contract_version;
+use eris::circuit_breaker::{CircuitBreakerConfig, PausableAction};
 use eris::helper::validate_received_funds;
 use eris::{CustomEvent, CustomMsgExt, CustomResponse, DecimalCheckedOps};
 
 use eris::hub::{
-    Batch, CallbackMsg, DelegationStrategy, ExecuteMsg, FeeConfig, InstantiateMsg, PendingBatch,
+    validate_fee_recipients, Batch, CallbackMsg, DelegationStrategy, ExecuteMsg, FeeConfig,
+    FeeRecipient, InstantUnbondConfig, InstantiateMsg, PendingBatch, RebalanceConfig,
     SingleSwapConfig, StakeToken, UnbondRequest,
 };
 use eris_chain_adapter::types::{
@@ -18,17 +20,25 @@ use eris_chain_adapter::types::{
 };
 use itertools::Itertools;
 
+use crate::circuit_breaker::{
+    assert_not_paused, check_exchange_rate, track_unbond_volume, track_volume,
+    untrack_unbond_volume,
+};
 use crate::constants::get_reward_fee_cap;
 use crate::error::{ContractError, ContractResult};
 use crate::helpers::{
-    assert_validator_exists, assert_validators_exists, dedupe, get_wanted_delegations,
-    query_all_delegations, query_all_delegations_amount, query_delegation, query_delegations,
+    assert_strategy_supported, assert_validator_exists, assert_validators_exists, dedupe,
+    get_wanted_delegations, query_all_delegations, query_all_delegations_amount, query_delegation,
+    query_delegations,
 };
 use crate::math::{
-    compute_mint_amount, compute_redelegations_for_rebalancing, compute_redelegations_for_removal,
-    compute_unbond_amount, compute_undelegations, get_utoken_per_validator,
-    mark_reconciled_batches, reconcile_batches,
+    compute_instant_unbond, compute_mint_amount, compute_redelegations_for_rebalancing,
+    compute_redelegations_for_removal, compute_unbond_amount, compute_undelegations,
+    compute_validators_per_batch, get_utoken_per_validator, mark_reconciled_batches,
+    reconcile_batches,
 };
+use crate::prices::get_belief_price;
+use crate::referral::{accrue_referral_rewards, add_referred_shares, pending_referral_rewards};
 use crate::state::State;
 use crate::types::gauges::TuneInfoGaugeLoader;
 // use crate::types::gauges::TuneInfoGaugeLoader;
@@ -89,6 +99,7 @@ pub fn instantiate(
         &FeeConfig {
             protocol_fee_contract: deps.api.addr_validate(&msg.protocol_fee_contract)?,
             protocol_reward_fee: msg.protocol_reward_fee,
+            fee_recipients: vec![],
         },
     )?;
 
@@ -102,6 +113,7 @@ pub fn instantiate(
     )?;
 
     let delegation_strategy = msg.delegation_strategy.unwrap_or(DelegationStrategy::Uniform);
+    assert_strategy_supported(&delegation_strategy)?;
     state
         .delegation_strategy
         .save(deps.storage, &delegation_strategy.validate(deps.api, &validators)?)?;
@@ -140,16 +152,23 @@ pub fn bond(
     deps: DepsMut<CustomQueryType>,
     env: Env,
     receiver: Addr,
+    referrer: Option<Addr>,
     funds: &[Coin],
     donate: bool,
 ) -> ContractResult {
     let state = State::default();
     let mut stake = state.stake_token.load(deps.storage)?;
 
+    assert_not_paused(deps.storage, PausableAction::Bond)?;
+
     let token_to_bond = validate_received_funds(funds, &stake.utoken)?;
+    if !donate {
+        track_volume(deps.storage, &env, PausableAction::Bond, token_to_bond)?;
+    }
 
     let (new_delegation, delegations) =
         find_new_delegation(&state, &deps, &env, token_to_bond, &stake.utoken)?;
+    state.track_delegation(deps.storage, &new_delegation.validator, new_delegation.amount)?;
 
     // Query the current supply of Staking Token and compute the amount to mint
     let ustake_supply = stake.total_supply;
@@ -165,8 +184,13 @@ pub fn bond(
         compute_mint_amount(ustake_supply, token_to_bond, &delegations)
     };
 
+    if let Some(referrer) = &referrer {
+        add_referred_shares(deps.storage, &receiver, referrer.clone(), ustake_to_mint)?;
+    }
+
     let event = Event::new("erishub/bonded")
         .add_attribute("receiver", receiver.clone())
+        .add_optional_attribute(referrer.map(|referrer| attr("referrer", referrer)))
         .add_attribute("token_bonded", token_to_bond)
         .add_attribute("ustake_minted", ustake_to_mint);
 
@@ -188,6 +212,23 @@ pub fn bond(
         .add_attribute("action", "erishub/bond"))
 }
 
+pub fn donate_reserve(deps: DepsMut<CustomQueryType>, funds: &[Coin]) -> ContractResult {
+    let state = State::default();
+    let stake = state.stake_token.load(deps.storage)?;
+
+    let utoken_donated = validate_received_funds(funds, &stake.utoken)?;
+
+    let reserve = state.instant_unbond_reserve.may_load(deps.storage)?.unwrap_or_default();
+    let reserve = reserve.checked_add(utoken_donated)?;
+    state.instant_unbond_reserve.save(deps.storage, &reserve)?;
+
+    let event = Event::new("erishub/reserve_donated")
+        .add_attribute("utoken_donated", utoken_donated)
+        .add_attribute("reserve", reserve);
+
+    Ok(Response::new().add_event(event).add_attribute("action", "erishub/donate_reserve"))
+}
+
 pub fn harvest(
     deps: DepsMut<CustomQueryType>,
     env: Env,
@@ -196,6 +237,8 @@ pub fn harvest(
     stages: Option<Vec<Vec<SingleSwapConfig>>>,
     sender: Addr,
 ) -> ContractResult {
+    assert_not_paused(deps.storage, PausableAction::Harvest)?;
+
     let state = State::default();
     let stake = state.stake_token.load(deps.storage)?;
 
@@ -281,7 +324,7 @@ pub fn withdraw_lps(
     let mut withdraw_msgs: Vec<CosmosMsg<CustomMsgType>> = vec![];
     let chain = chain(&env);
     let get_denoms = || withdrawals.iter().map(|a| a.1.clone()).collect_vec();
-    let balances = get_balances_hashmap(&deps, env, get_denoms)?;
+    let balances = get_balances_hashmap(&deps.querier, env, get_denoms)?;
     let get_chain_config = || State::default().chain_config.load(deps.storage);
 
     for (withdraw_type, denom) in withdrawals {
@@ -313,7 +356,7 @@ pub fn single_stage_swap(
     let default_max_spread = state.get_default_max_spread(deps.storage);
     let get_chain_config = || state.chain_config.load(deps.storage);
     let get_denoms = || stage.iter().map(|a| a.1.clone()).collect_vec();
-    let balances = get_balances_hashmap(&deps, env, get_denoms)?;
+    let balances = get_balances_hashmap(&deps.querier, env.clone(), get_denoms)?;
 
     let mut response = Response::new().add_attribute("action", "erishub/single_stage_swap");
     // iterate all specified swaps of the stage
@@ -332,11 +375,13 @@ pub fn single_stage_swap(
                         fee_config.protocol_reward_fee.checked_mul_uint(available)?;
                     available = available.saturating_sub(protocol_fee);
 
-                    let send_fee = denom
-                        .with_balance(protocol_fee)
-                        .into_msg(&fee_config.protocol_fee_contract)?
-                        .to_specific()?;
-                    response = response.add_message(send_fee)
+                    for (recipient, amount) in fee_config.split_fee(protocol_fee)? {
+                        let send_fee = denom
+                            .with_balance(amount)
+                            .into_msg(&recipient.address)?
+                            .to_specific()?;
+                        response = response.add_message(send_fee)
+                    }
                 }
 
                 let used_amount = match max_amount {
@@ -350,6 +395,12 @@ pub fn single_stage_swap(
                     None => available,
                 };
 
+                // swaps without belief price use the price feed of the denom if configured
+                let belief_price = match belief_price {
+                    Some(belief_price) => Some(belief_price),
+                    None => get_belief_price(&deps, &env, &denom.to_string())?,
+                };
+
                 // create a single swap message add add to submsgs
                 let msg = chain.create_single_stage_swap_msgs(
                     get_chain_config,
@@ -385,10 +436,14 @@ fn validate_no_utoken_or_ustake_swap(
     Ok(())
 }
 
-fn validate_no_belief_price(stages: &Vec<Vec<SingleSwapConfig>>) -> Result<(), ContractError> {
+fn validate_no_belief_price(
+    env: &Env,
+    stages: &Vec<Vec<SingleSwapConfig>>,
+) -> Result<(), ContractError> {
+    let chain = chain(env);
     for stage in stages {
-        for (_, _, belief_price, _, _) in stage {
-            if belief_price.is_some() {
+        for (stage_type, denom, belief_price, _, _) in stage {
+            if belief_price.is_some() && !chain.requires_belief_price(stage_type, denom) {
                 return Err(ContractError::BeliefPriceNotAllowed {});
             }
         }
@@ -397,7 +452,7 @@ fn validate_no_belief_price(stages: &Vec<Vec<SingleSwapConfig>>) -> Result<(), C
 }
 
 /// This callback is used to take a current snapshot of the balance and add the received balance to the unlocked_coins state after the execution
-fn check_received_coin_msg(
+pub(crate) fn check_received_coin_msg(
     deps: &DepsMut<CustomQueryType>,
     env: &Env,
     stake: StakeToken,
@@ -451,6 +506,8 @@ pub fn reinvest(deps: DepsMut<CustomQueryType>, env: Env, skip_fee: bool) -> Con
     let mut msgs: Vec<CosmosMsg<CustomMsgType>> = vec![];
 
     let mut total_utoken: Option<u128> = None;
+    let instant_unbond_config = state.instant_unbond_config.may_load(deps.storage)?;
+    let mut reserve = state.instant_unbond_reserve.may_load(deps.storage)?.unwrap_or_default();
     let protocol_reward_fee = if skip_fee {
         Decimal::zero()
     } else {
@@ -459,23 +516,91 @@ pub fn reinvest(deps: DepsMut<CustomQueryType>, env: Env, skip_fee: bool) -> Con
 
     for coin in unlocked_coins.iter() {
         let available = coin.amount;
-        let protocol_fee = protocol_reward_fee.checked_mul_uint(available)?;
+        let mut protocol_fee = protocol_reward_fee.checked_mul_uint(available)?;
         let remaining = available.saturating_sub(protocol_fee);
 
+        // the referral share of the utoken fee stays in the contract until claimed
+        if coin.denom == stake.utoken {
+            let referral_rewards =
+                accrue_referral_rewards(deps.storage, protocol_fee, stake.total_supply)?;
+            if !referral_rewards.is_zero() {
+                protocol_fee = protocol_fee.checked_sub(referral_rewards)?;
+                event = event.add_attribute("utoken_referral_rewards", referral_rewards);
+            }
+
+            let bounties =
+                pay_rebalance_bounties(deps.storage, protocol_fee, &stake.utoken, &mut msgs)?;
+            if !bounties.is_zero() {
+                protocol_fee = protocol_fee.checked_sub(bounties)?;
+                event = event.add_attribute("utoken_rebalance_bounties", bounties);
+            }
+        }
+        let fee_shares = fee_config.split_fee(protocol_fee)?;
+
         let send_fee = if coin.denom == stake.utoken {
-            let to_bond = remaining;
+            // refill the instant unbond reserve up to its target
+            let to_reserve = match &instant_unbond_config {
+                Some(config) => config
+                    .reserve_share
+                    .checked_mul_uint(remaining)?
+                    .min(config.reserve_target.saturating_sub(reserve)),
+                None => Uint128::zero(),
+            };
+            reserve += to_reserve;
+
+            let to_bond = remaining.checked_sub(to_reserve)?;
+            // fees of recipients that receive stake token are delegated as well
+            let staked_fees =
+                fee_shares.iter().filter(|(recipient, _)| recipient.is_staked()).collect_vec();
+            let staked_fee: Uint128 = staked_fees.iter().map(|(_, amount)| *amount).sum();
+            let to_delegate = to_bond.checked_add(staked_fee)?;
+
             // if receiving normal utoken -> restake
             let (new_delegation, delegations) =
-                find_new_delegation(&state, &deps, &env, to_bond, &stake.utoken)?;
+                find_new_delegation(&state, &deps, &env, to_delegate, &stake.utoken)?;
 
             let utoken_staked: u128 = delegations.iter().map(|d| d.amount).sum();
-            total_utoken = Some(utoken_staked + to_bond.u128());
+            total_utoken = Some(utoken_staked + to_delegate.u128());
 
             event = event
                 .add_attribute("utoken_bonded", to_bond)
                 .add_attribute("utoken_protocol_fee", protocol_fee);
 
-            msgs.push(new_delegation.to_cosmos_msg());
+            if !to_reserve.is_zero() {
+                event = event.add_attribute("utoken_reserved", to_reserve);
+            }
+
+            if !to_delegate.is_zero() {
+                state.track_delegation(
+                    deps.storage,
+                    &new_delegation.validator,
+                    new_delegation.amount,
+                )?;
+                msgs.push(new_delegation.to_cosmos_msg());
+            }
+
+            // all fees are minted at the exchange rate after the rewards are bonded, so that the
+            // recipients don't take a share of the rewards
+            let ustake_supply = stake.total_supply;
+            let mut delegations = delegations;
+            delegations.push(Delegation::new(
+                &new_delegation.validator,
+                to_bond.u128(),
+                &stake.utoken,
+            ));
+            for (recipient, amount) in staked_fees {
+                let ustake_to_mint = compute_mint_amount(ustake_supply, *amount, &delegations);
+                stake.total_supply = stake.total_supply.checked_add(ustake_to_mint)?;
+                msgs.extend(chain(&env).create_mint_msgs(
+                    stake.denom.clone(),
+                    ustake_to_mint,
+                    recipient.address.clone(),
+                ));
+                event = event.add_attribute("ustake_fee_minted", ustake_to_mint);
+            }
+            if !staked_fee.is_zero() {
+                state.stake_token.save(deps.storage, &stake)?;
+            }
             true
         } else if coin.denom == stake.denom {
             // if receiving ustake (staked utoken) -> burn
@@ -492,13 +617,15 @@ pub fn reinvest(deps: DepsMut<CustomQueryType>, env: Env, skip_fee: bool) -> Con
             false
         };
 
-        if send_fee && !protocol_fee.is_zero() {
-            let send_fee = SendFee::new(
-                fee_config.protocol_fee_contract.clone(),
-                protocol_fee.u128(),
-                coin.denom.clone(),
-            );
-            msgs.push(send_fee.to_cosmos_msg());
+        if send_fee {
+            for (recipient, amount) in fee_shares {
+                // staked utoken fees have already been minted as stake token
+                if coin.denom == stake.utoken && recipient.is_staked() {
+                    continue;
+                }
+                let send_fee = SendFee::new(recipient.address, amount.u128(), coin.denom.clone());
+                msgs.push(send_fee.to_cosmos_msg());
+            }
         }
     }
 
@@ -506,13 +633,27 @@ pub fn reinvest(deps: DepsMut<CustomQueryType>, env: Env, skip_fee: bool) -> Con
     unlocked_coins.retain(|coin| coin.denom != stake.utoken && coin.denom != stake.denom);
     state.unlocked_coins.save(deps.storage, &unlocked_coins)?;
 
+    // unbonded positions taken over by instant unbonds flow back into the reserve
+    let (utoken_claimed, _) =
+        claim_unbonded(&state, deps.storage, &env.contract.address, env.block.time.seconds())?;
+    if !utoken_claimed.is_zero() {
+        reserve += utoken_claimed;
+        event = event.add_attribute("utoken_reserve_claimed", utoken_claimed);
+    }
+
+    if instant_unbond_config.is_some() || !reserve.is_zero() {
+        state.instant_unbond_reserve.save(deps.storage, &reserve)?;
+    }
+
     // update exchange_rate history
     let exchange_rate = calc_current_exchange_rate(total_utoken, &deps, &env, stake)?;
+    let circuit_breaker_event = check_exchange_rate(deps.storage, exchange_rate)?;
     state.exchange_history.save(deps.storage, env.block.time.seconds(), &exchange_rate)?;
 
     Ok(Response::new()
         .add_messages(msgs)
         .add_event(event)
+        .add_events(circuit_breaker_event)
         .add_attribute("action", "erishub/reinvest")
         .add_attribute("exchange_rate", exchange_rate.to_string()))
 }
@@ -629,11 +770,20 @@ fn find_new_delegation(
 
             Ok((new_delegation, delegations))
         },
+        DelegationStrategy::AllianceRewards {
+            ..
+        } => Err(ContractError::DelegationStrategyNotSupported("alliance_rewards".to_string())),
         DelegationStrategy::Gauges {
             ..
         }
         | DelegationStrategy::Defined {
             ..
+        }
+        | DelegationStrategy::Performance {
+            ..
+        }
+        | DelegationStrategy::Composite {
+            ..
         } => {
             let current_delegations =
                 query_all_delegations(&deps.querier, &env.contract.address, utoken)?;
@@ -686,47 +836,308 @@ pub fn queue_unbond(
     receiver: Addr,
     ustake_to_burn: Uint128,
 ) -> ContractResult {
+    assert_not_paused(deps.storage, PausableAction::Unbond)?;
+    track_unbond_volume(deps.storage, &env, ustake_to_burn)?;
+
     let state = State::default();
 
+    let pending_batch = add_to_pending_batch(&state, deps.storage, &receiver, ustake_to_burn)?;
+
+    let (start_time, msgs) = submit_batch_if_ready(&env, &pending_batch)?;
+
+    let event = Event::new("erishub/unbond_queued")
+        .add_attribute("est_unbond_start_time", start_time)
+        .add_attribute("id", pending_batch.id.to_string())
+        .add_attribute("receiver", receiver)
+        .add_attribute("ustake_to_burn", ustake_to_burn);
+
+    Ok(Response::new()
+        .add_messages(msgs)
+        .add_event(event)
+        .add_attribute("action", "erishub/queue_unbond"))
+}
+
+pub fn instant_unbond(
+    deps: DepsMut<CustomQueryType>,
+    env: Env,
+    receiver: Addr,
+    ustake_to_burn: Uint128,
+    min_received: Option<Uint128>,
+) -> ContractResult {
+    assert_not_paused(deps.storage, PausableAction::Unbond)?;
+
+    let state = State::default();
+    let config = state
+        .instant_unbond_config
+        .may_load(deps.storage)?
+        .ok_or(ContractError::InstantUnbondDisabled {})?;
+    let stake = state.stake_token.load(deps.storage)?;
+    let reserve = state.instant_unbond_reserve.may_load(deps.storage)?.unwrap_or_default();
+
+    let delegations = query_all_delegations(&deps.querier, &env.contract.address, &stake.utoken)?;
+    let quote =
+        compute_instant_unbond(&config, stake.total_supply, ustake_to_burn, &delegations, reserve)?;
+
+    if quote.receive_amount.is_zero() {
+        return Err(ContractError::CantBeZero("received amount".into()));
+    }
+
+    if quote.receive_amount > reserve {
+        return Err(ContractError::InstantUnbondReserveTooLow(reserve));
+    }
+
+    if let Some(min_received) = min_received {
+        if quote.receive_amount < min_received {
+            return Err(ContractError::MinReceivedNotReached(min_received));
+        }
+    }
+
+    track_volume(deps.storage, &env, PausableAction::Unbond, quote.utoken_amount)?;
+
+    state.instant_unbond_reserve.save(deps.storage, &(reserve - quote.receive_amount))?;
+
+    // the contract takes over the unbonding position, it refills the reserve after unbonding
+    let pending_batch =
+        add_to_pending_batch(&state, deps.storage, &env.contract.address, ustake_to_burn)?;
+
+    let (_, msgs) = submit_batch_if_ready(&env, &pending_batch)?;
+
+    let send_msg = CosmosMsg::Bank(BankMsg::Send {
+        to_address: receiver.to_string(),
+        amount: vec![Coin::new(quote.receive_amount.u128(), stake.utoken)],
+    });
+
+    let event = Event::new("erishub/instant_unbonded")
+        .add_attribute("id", pending_batch.id.to_string())
+        .add_attribute("receiver", receiver)
+        .add_attribute("ustake_to_burn", ustake_to_burn)
+        .add_attribute("utoken_received", quote.receive_amount)
+        .add_attribute("utoken_fee", quote.fee_amount);
+
+    Ok(Response::new()
+        .add_message(send_msg)
+        .add_messages(msgs)
+        .add_event(event)
+        .add_attribute("action", "erishub/instant_unbond"))
+}
+
+pub fn transfer_unbond_request(
+    deps: DepsMut<CustomQueryType>,
+    sender: Addr,
+    id: u64,
+    recipient: Addr,
+    shares: Option<Uint128>,
+) -> ContractResult {
+    let state = State::default();
+
+    if sender == recipient {
+        return Err(ContractError::CantTransferToSender {});
+    }
+
+    let mut request = state.unbond_requests.load(deps.storage, (id, &sender))?;
+    let shares = shares.unwrap_or(request.shares);
+
+    if shares.is_zero() {
+        return Err(ContractError::CantBeZero("shares".into()));
+    }
+
+    if shares > request.shares {
+        return Err(ContractError::NotEnoughShares(request.shares));
+    }
+
+    request.shares -= shares;
+    if request.shares.is_zero() {
+        state.unbond_requests.remove(deps.storage, (id, &sender))?;
+    } else {
+        state.unbond_requests.save(deps.storage, (id, &sender), &request)?;
+    }
+
+    // merge with an existing request of the recipient in the same batch
+    state.unbond_requests.update(deps.storage, (id, &recipient), |x| -> StdResult<_> {
+        let mut request = x.unwrap_or_else(|| UnbondRequest {
+            id,
+            user: recipient.clone(),
+            shares: Uint128::zero(),
+        });
+        request.shares += shares;
+        Ok(request)
+    })?;
+
+    let event = Event::new("erishub/unbond_request_transferred")
+        .add_attribute("id", id.to_string())
+        .add_attribute("sender", sender)
+        .add_attribute("recipient", recipient)
+        .add_attribute("shares", shares);
+
+    Ok(Response::new().add_event(event).add_attribute("action", "erishub/transfer_unbond_request"))
+}
+
+pub fn cancel_unbond(
+    deps: DepsMut<CustomQueryType>,
+    env: Env,
+    sender: Addr,
+    amount: Option<Uint128>,
+) -> ContractResult {
+    let state = State::default();
+    let stake = state.stake_token.load(deps.storage)?;
     let mut pending_batch = state.pending_batch.load(deps.storage)?;
-    pending_batch.ustake_to_burn += ustake_to_burn;
+
+    let mut request = state.unbond_requests.load(deps.storage, (pending_batch.id, &sender))?;
+    let ustake_to_return = amount.unwrap_or(request.shares);
+
+    if ustake_to_return.is_zero() {
+        return Err(ContractError::CantBeZero("amount".into()));
+    }
+
+    if ustake_to_return > request.shares {
+        return Err(ContractError::NotEnoughShares(request.shares));
+    }
+
+    request.shares -= ustake_to_return;
+    if request.shares.is_zero() {
+        state.unbond_requests.remove(deps.storage, (pending_batch.id, &sender))?;
+    } else {
+        state.unbond_requests.save(deps.storage, (pending_batch.id, &sender), &request)?;
+    }
+
+    // the ustake of the pending batch is still held by the contract
+    pending_batch.ustake_to_burn = pending_batch.ustake_to_burn.checked_sub(ustake_to_return)?;
     state.pending_batch.save(deps.storage, &pending_batch)?;
 
+    // the canceled unbond doesn't count towards the unbond volume limit
+    untrack_unbond_volume(deps.storage, &env, ustake_to_return)?;
+
+    let return_msg = CosmosMsg::Bank(BankMsg::Send {
+        to_address: sender.to_string(),
+        amount: vec![Coin::new(ustake_to_return.u128(), stake.denom)],
+    });
+
+    let event = Event::new("erishub/unbond_canceled")
+        .add_attribute("id", pending_batch.id.to_string())
+        .add_attribute("user", sender)
+        .add_attribute("ustake_returned", ustake_to_return);
+
+    Ok(Response::new()
+        .add_message(return_msg)
+        .add_event(event)
+        .add_attribute("action", "erishub/cancel_unbond"))
+}
+
+pub fn rebond(deps: DepsMut<CustomQueryType>, env: Env, sender: Addr, id: u64) -> ContractResult {
+    assert_not_paused(deps.storage, PausableAction::Bond)?;
+
+    let state = State::default();
+    let mut stake = state.stake_token.load(deps.storage)?;
+
+    let request = state.unbond_requests.load(deps.storage, (id, &sender))?;
+    let batch = state.previous_batches.load(deps.storage, id)?;
+
+    if batch.est_unbond_end_time <= env.block.time.seconds() {
+        return Err(ContractError::BatchAlreadyUnbonded(id));
+    }
+
+    let utoken_unbonding =
+        batch.utoken_unclaimed.multiply_ratio(request.shares, batch.total_shares);
+    if utoken_unbonding.is_zero() {
+        return Err(ContractError::CantBeZero("rebond amount".into()));
+    }
+
+    // the reserve pays for the rebond and takes over the unbonding position including any slashing
+    // of the batch, so the same fee as for an instant unbond of the position is charged
+    let config = state
+        .instant_unbond_config
+        .may_load(deps.storage)?
+        .ok_or(ContractError::InstantUnbondDisabled {})?;
+    let reserve = state.instant_unbond_reserve.may_load(deps.storage)?.unwrap_or_default();
+    let utoken_fee =
+        config.get_fee(reserve, utoken_unbonding).checked_mul_uint(utoken_unbonding)?;
+    let utoken_to_rebond = utoken_unbonding.checked_sub(utoken_fee)?;
+    if utoken_to_rebond > reserve {
+        return Err(ContractError::InstantUnbondReserveTooLow(reserve));
+    }
+    state.instant_unbond_reserve.save(deps.storage, &(reserve - utoken_to_rebond))?;
+
+    // the contract takes over the unbonding position, so the batch itself stays untouched
+    state.unbond_requests.remove(deps.storage, (id, &sender))?;
     state.unbond_requests.update(
         deps.storage,
-        (pending_batch.id, &receiver),
+        (id, &env.contract.address),
         |x| -> StdResult<_> {
-            let mut request = x.unwrap_or_else(|| UnbondRequest {
-                id: pending_batch.id,
-                user: receiver.clone(),
+            let mut contract_request = x.unwrap_or_else(|| UnbondRequest {
+                id,
+                user: env.contract.address.clone(),
                 shares: Uint128::zero(),
             });
-            request.shares += ustake_to_burn;
-            Ok(request)
+            contract_request.shares += request.shares;
+            Ok(contract_request)
         },
     )?;
 
+    let (new_delegation, delegations) =
+        find_new_delegation(&state, &deps, &env, utoken_to_rebond, &stake.utoken)?;
+    state.track_delegation(deps.storage, &new_delegation.validator, new_delegation.amount)?;
+
+    let ustake_to_mint = compute_mint_amount(stake.total_supply, utoken_to_rebond, &delegations);
+    stake.total_supply = stake.total_supply.checked_add(ustake_to_mint)?;
+    state.stake_token.save(deps.storage, &stake)?;
+
+    let mint_msgs =
+        chain(&env).create_mint_msgs(stake.denom.clone(), ustake_to_mint, sender.clone());
+
+    let event = Event::new("erishub/rebonded")
+        .add_attribute("id", id.to_string())
+        .add_attribute("user", sender)
+        .add_attribute("shares", request.shares)
+        .add_attribute("utoken_bonded", utoken_to_rebond)
+        .add_attribute("utoken_fee", utoken_fee)
+        .add_attribute("ustake_minted", ustake_to_mint);
+
+    Ok(Response::new()
+        .add_message(new_delegation.to_cosmos_msg())
+        .add_messages(mint_msgs)
+        .add_message(check_received_coin_msg(&deps, &env, stake, Some(utoken_to_rebond))?)
+        .add_event(event)
+        .add_attribute("action", "erishub/rebond"))
+}
+
+fn add_to_pending_batch(
+    state: &State,
+    storage: &mut dyn Storage,
+    receiver: &Addr,
+    ustake_to_burn: Uint128,
+) -> StdResult<PendingBatch> {
+    let mut pending_batch = state.pending_batch.load(storage)?;
+    pending_batch.ustake_to_burn += ustake_to_burn;
+    state.pending_batch.save(storage, &pending_batch)?;
+
+    state.unbond_requests.update(storage, (pending_batch.id, receiver), |x| -> StdResult<_> {
+        let mut request = x.unwrap_or_else(|| UnbondRequest {
+            id: pending_batch.id,
+            user: receiver.clone(),
+            shares: Uint128::zero(),
+        });
+        request.shares += ustake_to_burn;
+        Ok(request)
+    })?;
+
+    Ok(pending_batch)
+}
+
+fn submit_batch_if_ready(
+    env: &Env,
+    pending_batch: &PendingBatch,
+) -> StdResult<(String, Vec<CosmosMsg<CustomMsgType>>)> {
     let mut msgs: Vec<CosmosMsg<CustomMsgType>> = vec![];
     let mut start_time = pending_batch.est_unbond_start_time.to_string();
     if env.block.time.seconds() > pending_batch.est_unbond_start_time {
         start_time = "immediate".to_string();
         msgs.push(CosmosMsg::Wasm(WasmMsg::Execute {
-            contract_addr: env.contract.address.into(),
+            contract_addr: env.contract.address.to_string(),
             msg: to_json_binary(&ExecuteMsg::SubmitBatch {})?,
             funds: vec![],
         }));
     }
-
-    let event = Event::new("erishub/unbond_queued")
-        .add_attribute("est_unbond_start_time", start_time)
-        .add_attribute("id", pending_batch.id.to_string())
-        .add_attribute("receiver", receiver)
-        .add_attribute("ustake_to_burn", ustake_to_burn);
-
-    Ok(Response::new()
-        .add_messages(msgs)
-        .add_event(event)
-        .add_attribute("action", "erishub/queue_unbond"))
+    Ok((start_time, msgs))
 }
 
 pub fn submit_batch(deps: DepsMut<CustomQueryType>, env: Env) -> ContractResult {
@@ -744,6 +1155,15 @@ pub fn submit_batch(deps: DepsMut<CustomQueryType>, env: Env) -> ContractResult
     let delegations = query_all_delegations(&deps.querier, &env.contract.address, &stake.utoken)?;
     let ustake_supply = stake.total_supply;
 
+    let epoch_period = state.epoch_period.load(deps.storage)?;
+    let max_unbonding_entries = state.get_max_unbonding_entries(deps.storage)?;
+    let max_validators = compute_validators_per_batch(
+        validators.len(),
+        max_unbonding_entries,
+        epoch_period,
+        unbond_period,
+    );
+
     let utoken_to_unbond =
         compute_unbond_amount(ustake_supply, pending_batch.ustake_to_burn, &delegations);
     let new_undelegations = compute_undelegations(
@@ -753,6 +1173,9 @@ pub fn submit_batch(deps: DepsMut<CustomQueryType>, env: Env) -> ContractResult
         &delegations,
         validators,
         &stake.utoken,
+        &state.get_active_unbonding_entries(deps.storage, current_time)?,
+        max_unbonding_entries,
+        max_validators,
     )?;
 
     state.previous_batches.save(
@@ -767,7 +1190,6 @@ pub fn submit_batch(deps: DepsMut<CustomQueryType>, env: Env) -> ContractResult
         },
     )?;
 
-    let epoch_period = state.epoch_period.load(deps.storage)?;
     state.pending_batch.save(
         deps.storage,
         &PendingBatch {
@@ -777,6 +1199,16 @@ pub fn submit_batch(deps: DepsMut<CustomQueryType>, env: Env) -> ContractResult
         },
     )?;
 
+    for undelegation in &new_undelegations {
+        state.track_undelegation(deps.storage, &undelegation.validator, undelegation.amount)?;
+        state.track_unbonding_entry(
+            deps.storage,
+            &undelegation.validator,
+            current_time,
+            current_time + unbond_period,
+        )?;
+    }
+
     let undelegate_msgs =
         new_undelegations.into_iter().map(|d| d.to_cosmos_msg()).collect::<Vec<_>>();
 
@@ -831,7 +1263,15 @@ pub fn reconcile(deps: DepsMut<CustomQueryType>, env: Env) -> ContractResult {
     let utoken_expected_unlocked = Coins(unlocked_coins).find(&stake.utoken).amount;
 
     let utoken_expected = utoken_expected_received + utoken_expected_unlocked;
-    let utoken_actual = deps.querier.query_balance(&env.contract.address, stake.utoken)?.amount;
+    // the instant unbond reserve and unclaimed referral rewards are not part of the unbonded funds
+    let reserve = state.instant_unbond_reserve.may_load(deps.storage)?.unwrap_or_default();
+    let referral_rewards = pending_referral_rewards(deps.storage)?;
+    let utoken_actual = deps
+        .querier
+        .query_balance(&env.contract.address, stake.utoken)?
+        .amount
+        .saturating_sub(reserve)
+        .saturating_sub(referral_rewards);
 
     if utoken_actual >= utoken_expected {
         mark_reconciled_batches(&mut batches);
@@ -870,8 +1310,108 @@ pub fn withdraw_unbonded(
     receiver: Addr,
 ) -> ContractResult {
     let state = State::default();
+
+    let (total_utoken_to_refund, ids) =
+        claim_unbonded(&state, deps.storage, &user, env.block.time.seconds())?;
+
+    if total_utoken_to_refund.is_zero() {
+        return Err(ContractError::CantBeZero("withdrawable amount".into()));
+    }
+    let stake = state.stake_token.load(deps.storage)?;
+
+    let refund_msg = CosmosMsg::Bank(BankMsg::Send {
+        to_address: receiver.clone().into(),
+        amount: vec![Coin::new(total_utoken_to_refund.u128(), stake.utoken)],
+    });
+
+    let event = Event::new("erishub/unbonded_withdrawn")
+        .add_attribute("ids", ids.join(","))
+        .add_attribute("user", user)
+        .add_attribute("receiver", receiver)
+        .add_attribute("utoken_refunded", total_utoken_to_refund);
+
+    Ok(Response::new()
+        .add_message(refund_msg)
+        .add_event(event)
+        .add_attribute("action", "erishub/withdraw_unbonded"))
+}
+
+/// Withdraws the finished unbondings of users that enabled auto claim to the users. Invalid
+/// addresses, users without auto claim or without anything to withdraw are skipped, so that a
+/// single user doesn't block the others.
+pub fn withdraw_unbonded_for(
+    deps: DepsMut<CustomQueryType>,
+    env: Env,
+    users: Vec<String>,
+) -> ContractResult {
+    let state = State::default();
+    let stake = state.stake_token.load(deps.storage)?;
     let current_time = env.block.time.seconds();
 
+    let mut refund_msgs: Vec<CosmosMsg<CustomMsgType>> = vec![];
+    let mut events: Vec<Event> = vec![];
+    for user in users {
+        let user = match deps.api.addr_validate(&user) {
+            Ok(user) => user,
+            Err(_) => continue,
+        };
+        if !state.auto_claim.may_load(deps.storage, &user)?.unwrap_or(false) {
+            continue;
+        }
+
+        let (total_utoken_to_refund, ids) =
+            claim_unbonded(&state, deps.storage, &user, current_time)?;
+        if total_utoken_to_refund.is_zero() {
+            continue;
+        }
+
+        refund_msgs.push(CosmosMsg::Bank(BankMsg::Send {
+            to_address: user.to_string(),
+            amount: vec![Coin::new(total_utoken_to_refund.u128(), stake.utoken.clone())],
+        }));
+        events.push(
+            Event::new("erishub/unbonded_withdrawn")
+                .add_attribute("ids", ids.join(","))
+                .add_attribute("user", user.clone())
+                .add_attribute("receiver", user)
+                .add_attribute("utoken_refunded", total_utoken_to_refund),
+        );
+    }
+
+    if refund_msgs.is_empty() {
+        return Err(ContractError::CantBeZero("withdrawable amount".into()));
+    }
+
+    Ok(Response::new()
+        .add_messages(refund_msgs)
+        .add_events(events)
+        .add_attribute("action", "erishub/withdraw_unbonded_for"))
+}
+
+pub fn set_auto_claim(deps: DepsMut<CustomQueryType>, user: Addr, enabled: bool) -> ContractResult {
+    let state = State::default();
+
+    if enabled {
+        state.auto_claim.save(deps.storage, &user, &true)?;
+    } else {
+        state.auto_claim.remove(deps.storage, &user);
+    }
+
+    let event = Event::new("erishub/auto_claim_updated")
+        .add_attribute("user", user)
+        .add_attribute("enabled", enabled.to_string());
+
+    Ok(Response::new().add_event(event).add_attribute("action", "erishub/set_auto_claim"))
+}
+
+/// Removes all finished unbonding requests of the user and returns the claimable utoken amount
+/// together with the claimed batch ids.
+fn claim_unbonded(
+    state: &State,
+    storage: &mut dyn Storage,
+    user: &Addr,
+    current_time: u64,
+) -> StdResult<(Uint128, Vec<String>)> {
     // NOTE: If the user has too many unclaimed requests, this may not fit in the WASM memory...
     // However, this is practically never going to happen. Who would create hundreds of unbonding
     // requests and never claim them?
@@ -880,7 +1420,7 @@ pub fn withdraw_unbonded(
         .idx
         .user
         .prefix(user.to_string())
-        .range(deps.storage, None, None, Order::Ascending)
+        .range(storage, None, None, Order::Ascending)
         .map(|item| {
             let (_, v) = item?;
             Ok(v)
@@ -896,7 +1436,7 @@ pub fn withdraw_unbonded(
     let mut total_utoken_to_refund = Uint128::zero();
     let mut ids: Vec<String> = vec![];
     for request in &requests {
-        if let Ok(mut batch) = state.previous_batches.load(deps.storage, request.id) {
+        if let Ok(mut batch) = state.previous_batches.load(storage, request.id) {
             if batch.reconciled && batch.est_unbond_end_time < current_time {
                 let utoken_to_refund =
                     batch.utoken_unclaimed.multiply_ratio(request.shares, batch.total_shares);
@@ -908,36 +1448,17 @@ pub fn withdraw_unbonded(
                 batch.utoken_unclaimed -= utoken_to_refund;
 
                 if batch.total_shares.is_zero() {
-                    state.previous_batches.remove(deps.storage, request.id)?;
+                    state.previous_batches.remove(storage, request.id)?;
                 } else {
-                    state.previous_batches.save(deps.storage, batch.id, &batch)?;
+                    state.previous_batches.save(storage, batch.id, &batch)?;
                 }
 
-                state.unbond_requests.remove(deps.storage, (request.id, &user))?;
+                state.unbond_requests.remove(storage, (request.id, user))?;
             }
         }
     }
 
-    if total_utoken_to_refund.is_zero() {
-        return Err(ContractError::CantBeZero("withdrawable amount".into()));
-    }
-    let stake = state.stake_token.load(deps.storage)?;
-
-    let refund_msg = CosmosMsg::Bank(BankMsg::Send {
-        to_address: receiver.clone().into(),
-        amount: vec![Coin::new(total_utoken_to_refund.u128(), stake.utoken)],
-    });
-
-    let event = Event::new("erishub/unbonded_withdrawn")
-        .add_attribute("ids", ids.join(","))
-        .add_attribute("user", user)
-        .add_attribute("receiver", receiver)
-        .add_attribute("utoken_refunded", total_utoken_to_refund);
-
-    Ok(Response::new()
-        .add_message(refund_msg)
-        .add_event(event)
-        .add_attribute("action", "erishub/withdraw_unbonded"))
+    Ok((total_utoken_to_refund, ids))
 }
 
 pub fn tune_delegations(deps: DepsMut<CustomQueryType>, env: Env, sender: Addr) -> ContractResult {
@@ -966,6 +1487,9 @@ pub fn tune_delegations(deps: DepsMut<CustomQueryType>, env: Env, sender: Addr)
 // Ownership and management logics
 //--------------------------------------------------------------------------------------------------
 
+/// The owner can always rebalance. If a rebalance config is set, anyone else can rebalance towards
+/// the tuned delegation goal, limited by the budget of the current epoch. The caller receives a
+/// bounty, which is paid out of the next utoken protocol fee.
 pub fn rebalance(
     deps: DepsMut<CustomQueryType>,
     env: Env,
@@ -974,13 +1498,33 @@ pub fn rebalance(
 ) -> ContractResult {
     let state = State::default();
     let stake = state.stake_token.load(deps.storage)?;
-    state.assert_owner(deps.storage, &sender)?;
+
+    let rebalance_config = if state.assert_owner(deps.storage, &sender).is_ok() {
+        None
+    } else {
+        let rebalance_config =
+            state.rebalance_config.may_load(deps.storage)?.ok_or(ContractError::Unauthorized {})?;
+        if state.delegation_goal.may_load(deps.storage)?.is_none() {
+            return Err(ContractError::DelegationGoalNotTuned {});
+        }
+        Some(rebalance_config)
+    };
+
     let validators = state.validators.load(deps.storage)?;
     let delegations = query_all_delegations(&deps.querier, &env.contract.address, &stake.utoken)?;
 
-    let min_redelegation = min_redelegation.unwrap_or_default();
+    let mut min_redelegation = min_redelegation.unwrap_or_default();
+    if let Some(rebalance_config) = &rebalance_config {
+        min_redelegation = min_redelegation.max(rebalance_config.min_redelegation);
+    }
+
+    // redelegating from a validator that still receives a redelegation is not allowed, the
+    // permissionless rebalance skips them
+    let time = env.block.time.seconds();
+    let pending_redelegations = state.prune_redelegation_entries(deps.storage, time)?;
+    let permissionless = rebalance_config.is_some();
 
-    let new_redelegations = compute_redelegations_for_rebalancing(
+    let mut new_redelegations = compute_redelegations_for_rebalancing(
         &state,
         deps.storage,
         &delegations,
@@ -989,13 +1533,59 @@ pub fn rebalance(
     )?
     .into_iter()
     .filter(|redelegation| redelegation.amount >= min_redelegation.u128())
+    .filter(|redelegation| !permissionless || !pending_redelegations.contains(&redelegation.src))
     .collect::<Vec<_>>();
 
+    let mut event = Event::new("erishub/rebalanced");
+
+    if let Some(rebalance_config) = rebalance_config {
+        let epoch = time / state.epoch_period.load(deps.storage)?;
+        let redelegated = match state.rebalance_volume.may_load(deps.storage)? {
+            Some((last_epoch, redelegated)) if last_epoch == epoch => redelegated,
+            _ => Uint128::zero(),
+        };
+        let mut budget =
+            rebalance_config.max_redelegation_per_epoch.saturating_sub(redelegated).u128();
+
+        new_redelegations = new_redelegations
+            .into_iter()
+            .filter_map(|mut redelegation| {
+                redelegation.amount = redelegation.amount.min(budget);
+                if redelegation.amount == 0 || redelegation.amount < min_redelegation.u128() {
+                    return None;
+                }
+                budget -= redelegation.amount;
+                Some(redelegation)
+            })
+            .collect();
+
+        if new_redelegations.is_empty() {
+            return Err(ContractError::NothingToRebalance {});
+        }
+
+        let amount: u128 = new_redelegations.iter().map(|rd| rd.amount).sum();
+        state.rebalance_volume.save(deps.storage, &(epoch, redelegated + Uint128::new(amount)))?;
+
+        if !rebalance_config.bounty.is_zero() {
+            state.pending_bounties.update(deps.storage, &sender, |bounty| -> StdResult<_> {
+                Ok(bounty.unwrap_or_default().checked_add(rebalance_config.bounty)?)
+            })?;
+
+            event = event
+                .add_attribute("caller", sender)
+                .add_attribute("utoken_bounty", rebalance_config.bounty);
+        }
+    }
+
+    for rd in &new_redelegations {
+        state.track_redelegation(deps.storage, &rd.src, &rd.dst, rd.amount, time)?;
+    }
+
     let redelegate_msgs = new_redelegations.iter().map(|rd| rd.to_cosmos_msg()).collect::<Vec<_>>();
 
     let amount: u128 = new_redelegations.iter().map(|rd| rd.amount).sum();
 
-    let event = Event::new("erishub/rebalanced").add_attribute("utoken_moved", amount.to_string());
+    let event = event.add_attribute("utoken_moved", amount.to_string());
 
     let check_msg = if !redelegate_msgs.is_empty() {
         // only check coins if a redelegation is happening
@@ -1011,6 +1601,68 @@ pub fn rebalance(
         .add_attribute("action", "erishub/rebalance"))
 }
 
+/// Pays the pending rebalance bounties out of the protocol fee, in the order of the caller
+/// addresses. Bounties that are not covered stay pending for the next harvest. Returns the paid
+/// amount.
+fn pay_rebalance_bounties(
+    storage: &mut dyn Storage,
+    protocol_fee: Uint128,
+    utoken: &str,
+    msgs: &mut Vec<CosmosMsg<CustomMsgType>>,
+) -> StdResult<Uint128> {
+    let state = State::default();
+    let mut total_paid = Uint128::zero();
+
+    for (caller, bounty, paid) in covered_rebalance_bounties(storage, protocol_fee)? {
+        total_paid += paid;
+        if bounty > paid {
+            state.pending_bounties.save(storage, &caller, &(bounty - paid))?;
+        } else {
+            state.pending_bounties.remove(storage, &caller);
+        }
+        msgs.push(CosmosMsg::Bank(BankMsg::Send {
+            to_address: caller.to_string(),
+            amount: vec![Coin::new(paid.u128(), utoken)],
+        }));
+    }
+
+    Ok(total_paid)
+}
+
+/// Returns the utoken amount of the protocol fee that `pay_rebalance_bounties` would pay out,
+/// without paying it.
+pub(crate) fn simulate_rebalance_bounties(
+    storage: &dyn Storage,
+    protocol_fee: Uint128,
+) -> StdResult<Uint128> {
+    Ok(covered_rebalance_bounties(storage, protocol_fee)?
+        .into_iter()
+        .map(|(_, _, paid)| paid)
+        .sum())
+}
+
+/// Returns the pending bounties that are covered by the protocol fee with the paid amounts, in the
+/// order of the caller addresses.
+fn covered_rebalance_bounties(
+    storage: &dyn Storage,
+    protocol_fee: Uint128,
+) -> StdResult<Vec<(Addr, Uint128, Uint128)>> {
+    let state = State::default();
+    // only the bounties that are covered by the fee are loaded
+    let mut available = protocol_fee;
+    let mut paid_bounties = vec![];
+    for item in state.pending_bounties.range(storage, None, None, Order::Ascending) {
+        if available.is_zero() {
+            break;
+        }
+        let (caller, bounty) = item?;
+        let paid = bounty.min(available);
+        available -= paid;
+        paid_bounties.push((caller, bounty, paid));
+    }
+    Ok(paid_bounties)
+}
+
 pub fn add_validator(
     deps: DepsMut<CustomQueryType>,
     sender: Addr,
@@ -1035,74 +1687,152 @@ pub fn add_validator(
 }
 
 pub fn remove_validator(
-    deps: DepsMut<CustomQueryType>,
+    mut deps: DepsMut<CustomQueryType>,
     env: Env,
     sender: Addr,
     validator: String,
 ) -> ContractResult {
     let state = State::default();
-    let stake_token = state.stake_token.load(deps.storage)?;
 
     state.assert_owner(deps.storage, &sender)?;
 
-    let validators = state.validators.update(deps.storage, |mut validators| {
-        if !validators.contains(&validator) {
-            return Err(ContractError::ValidatorNotWhitelisted(validator.clone()));
-        }
-        validators.retain(|v| *v != validator);
+    let redelegate_msgs = remove_validator_from_whitelist(&state, &mut deps, &env, &validator)?;
 
-        if validators.is_empty() {
-            return Err(ContractError::NoValidatorsConfigured);
-        }
+    let event = Event::new("erishub/validator_removed").add_attribute("validator", validator);
 
-        Ok(validators)
-    })?;
+    let check_msg = if !redelegate_msgs.is_empty() {
+        // only check coins if a redelegation is happening
+        Some(check_received_coin_msg(&deps, &env, state.stake_token.load(deps.storage)?, None)?)
+    } else {
+        None
+    };
+
+    Ok(Response::new()
+        .add_messages(redelegate_msgs)
+        .add_optional_message(check_msg)
+        .add_event(event)
+        .add_attribute("action", "erishub/remove_validator"))
+}
+
+/// Removes the validator from the whitelist and returns the redelegations away from it.
+pub(crate) fn remove_validator_from_whitelist(
+    state: &State,
+    deps: &mut DepsMut<CustomQueryType>,
+    env: &Env,
+    validator: &str,
+) -> Result<Vec<CosmosMsg<CustomMsgType>>, ContractError> {
+    let validators = remove_from_whitelist(state, deps.storage, validator)?;
 
     let delegation_strategy =
         state.delegation_strategy.may_load(deps.storage)?.unwrap_or(DelegationStrategy::Uniform);
 
-    let redelegate_msgs = match delegation_strategy {
+    match delegation_strategy {
+        // only redelegate when old strategy
         DelegationStrategy::Uniform => {
-            // only redelegate when old strategy
-            let delegations = query_delegations(&deps.querier, &validators, &env.contract.address)?;
-            let delegation_to_remove =
-                query_delegation(&deps.querier, &validator, &env.contract.address)?;
-            let new_redelegations = compute_redelegations_for_removal(
-                &state,
-                deps.storage,
-                &delegation_to_remove,
-                &delegations,
-                validators,
-                &stake_token.utoken,
-            )?;
-
-            new_redelegations.iter().map(|d| d.to_cosmos_msg()).collect::<Vec<_>>()
+            redelegate_removed_validator(state, deps, env, validator, validators)
         },
+        DelegationStrategy::AllianceRewards {
+            ..
+        } => Err(ContractError::DelegationStrategyNotSupported("alliance_rewards".to_string())),
         DelegationStrategy::Gauges {
             ..
         }
         | DelegationStrategy::Defined {
             ..
+        }
+        | DelegationStrategy::Performance {
+            ..
+        }
+        | DelegationStrategy::Composite {
+            ..
         } => {
             // removed validators can have a delegation until the next tune, to keep undelegations in sync.
-            vec![]
+            Ok(vec![])
         },
-    };
+    }
+}
 
-    let event = Event::new("erishub/validator_removed").add_attribute("validator", validator);
+/// Removes a slashed validator, its delegation is redelegated right away for every strategy.
+pub(crate) fn remove_slashed_validator(
+    state: &State,
+    deps: &mut DepsMut<CustomQueryType>,
+    env: &Env,
+    validator: &str,
+) -> Result<Vec<CosmosMsg<CustomMsgType>>, ContractError> {
+    let validators = remove_from_whitelist(state, deps.storage, validator)?;
+    redelegate_removed_validator(state, deps, env, validator, validators)
+}
 
-    let check_msg = if !redelegate_msgs.is_empty() {
-        // only check coins if a redelegation is happening
-        Some(check_received_coin_msg(&deps, &env, state.stake_token.load(deps.storage)?, None)?)
+fn remove_from_whitelist(
+    state: &State,
+    storage: &mut dyn Storage,
+    validator: &str,
+) -> Result<Vec<String>, ContractError> {
+    state.validators.update(storage, |mut validators| {
+        if !validators.iter().any(|v| v == validator) {
+            return Err(ContractError::ValidatorNotWhitelisted(validator.to_string()));
+        }
+        validators.retain(|v| v != validator);
+
+        if validators.is_empty() {
+            return Err(ContractError::NoValidatorsConfigured);
+        }
+
+        Ok(validators)
+    })
+}
+
+/// Redelegates the delegation of a removed validator to the remaining validators, following the
+/// wanted delegations of the delegation strategy.
+fn redelegate_removed_validator(
+    state: &State,
+    deps: &mut DepsMut<CustomQueryType>,
+    env: &Env,
+    validator: &str,
+    validators: Vec<String>,
+) -> Result<Vec<CosmosMsg<CustomMsgType>>, ContractError> {
+    let stake_token = state.stake_token.load(deps.storage)?;
+
+    let (mut goal, store_goal) =
+        get_wanted_delegations(state, env, deps.storage, &deps.querier, TuneInfoGaugeLoader {})?;
+    let goal = if store_goal {
+        // the share of the removed validator is spread pro rata over the remaining validators
+        goal.shares.retain(|(v, _)| validators.contains(v));
+        let total = goal.shares.iter().fold(Decimal::zero(), |total, (_, share)| total + *share);
+        if !total.is_zero() {
+            for (_, share) in goal.shares.iter_mut() {
+                *share = *share / total;
+            }
+        }
+        state.delegation_goal.save(deps.storage, &goal)?;
+        Some(goal)
     } else {
         None
     };
 
-    Ok(Response::new()
-        .add_messages(redelegate_msgs)
-        .add_optional_message(check_msg)
-        .add_event(event)
-        .add_attribute("action", "erishub/remove_validator"))
+    let delegations = query_delegations(&deps.querier, &validators, &env.contract.address)?;
+    let delegation_to_remove = query_delegation(&deps.querier, validator, &env.contract.address)?;
+    let new_redelegations = compute_redelegations_for_removal(
+        state,
+        deps.storage,
+        &delegation_to_remove,
+        &delegations,
+        validators,
+        &stake_token.utoken,
+        goal,
+    )?;
+
+    for rd in &new_redelegations {
+        state.track_redelegation(
+            deps.storage,
+            &rd.src,
+            &rd.dst,
+            rd.amount,
+            env.block.time.seconds(),
+        )?;
+    }
+
+    Ok(new_redelegations.iter().map(|d| d.to_cosmos_msg()).collect())
 }
 
 pub fn transfer_ownership(
@@ -1150,9 +1880,11 @@ pub fn accept_ownership(deps: DepsMut<CustomQueryType>, sender: Addr) -> Contrac
 #[allow(clippy::too_many_arguments)]
 pub fn update_config(
     deps: DepsMut<CustomQueryType>,
+    env: Env,
     sender: Addr,
     protocol_fee_contract: Option<String>,
     protocol_reward_fee: Option<Decimal>,
+    fee_recipients: Option<Vec<FeeRecipient>>,
     operator: Option<String>,
     stages_preset: Option<Vec<Vec<SingleSwapConfig>>>,
     withdrawals_preset: Option<Vec<(WithdrawType, DenomType)>>,
@@ -1163,16 +1895,32 @@ pub fn update_config(
     default_max_spread: Option<u64>,
     epoch_period: Option<u64>,
     unbond_period: Option<u64>,
+    instant_unbond_config: Option<InstantUnbondConfig>,
+    remove_instant_unbond_config: Option<bool>,
+    remove_slashed_validators: Option<bool>,
+    max_unbonding_entries: Option<u32>,
+    guardian: Option<String>,
+    remove_guardian: Option<bool>,
+    circuit_breaker: Option<CircuitBreakerConfig>,
+    referral_share: Option<Decimal>,
+    rebalance_config: Option<RebalanceConfig>,
 ) -> ContractResult {
     let state = State::default();
 
     state.assert_owner(deps.storage, &sender)?;
 
-    if protocol_fee_contract.is_some() || protocol_reward_fee.is_some() {
+    if protocol_fee_contract.is_some() || protocol_reward_fee.is_some() || fee_recipients.is_some()
+    {
         let mut fee_config = state.fee_config.load(deps.storage)?;
 
         if let Some(protocol_fee_contract) = protocol_fee_contract {
             fee_config.protocol_fee_contract = deps.api.addr_validate(&protocol_fee_contract)?;
+            // the single address form replaces the recipients
+            fee_config.fee_recipients = vec![];
+        }
+
+        if let Some(fee_recipients) = fee_recipients {
+            fee_config.fee_recipients = validate_fee_recipients(deps.api, fee_recipients)?;
         }
 
         if let Some(protocol_reward_fee) = protocol_reward_fee {
@@ -1212,8 +1960,8 @@ pub fn update_config(
     }
 
     if let Some(stages_preset) = stages_preset {
-        // belief price is not allowed. We still store it with None, as otherwise a lot of additional logic is required to load it.
-        validate_no_belief_price(&stages_preset)?;
+        // belief price is only allowed for stages that can't be executed without it. We still store it with None, as otherwise a lot of additional logic is required to load it.
+        validate_no_belief_price(&env, &stages_preset)?;
         state.stages_preset.save(deps.storage, &stages_preset)?;
     }
 
@@ -1222,6 +1970,7 @@ pub fn update_config(
     }
 
     if let Some(delegation_strategy) = delegation_strategy {
+        assert_strategy_supported(&delegation_strategy)?;
         let validators = state.validators.load(deps.storage)?;
         state
             .delegation_strategy
@@ -1239,5 +1988,43 @@ pub fn update_config(
         state.vote_operator.save(deps.storage, &deps.api.addr_validate(&vote_operator)?)?;
     }
 
+    if let Some(instant_unbond_config) = instant_unbond_config {
+        state.instant_unbond_config.save(deps.storage, &instant_unbond_config.validate()?)?;
+    } else if remove_instant_unbond_config.unwrap_or(false) {
+        state.instant_unbond_config.remove(deps.storage);
+    }
+
+    if let Some(remove_slashed_validators) = remove_slashed_validators {
+        state.remove_slashed_validators.save(deps.storage, &remove_slashed_validators)?;
+    }
+
+    if let Some(max_unbonding_entries) = max_unbonding_entries {
+        if max_unbonding_entries == 0 {
+            return Err(ContractError::CantBeZero("max_unbonding_entries".into()));
+        }
+        state.max_unbonding_entries.save(deps.storage, &max_unbonding_entries)?;
+    }
+
+    if let Some(guardian) = guardian {
+        state.guardian.save(deps.storage, &deps.api.addr_validate(&guardian)?)?;
+    } else if remove_guardian.unwrap_or(false) {
+        state.guardian.remove(deps.storage);
+    }
+
+    if let Some(circuit_breaker) = circuit_breaker {
+        state.circuit_breaker.save(deps.storage, &circuit_breaker.validate()?)?;
+    }
+
+    if let Some(referral_share) = referral_share {
+        if referral_share > Decimal::one() {
+            return Err(StdError::generic_err("referral_share greater than 100%").into());
+        }
+        state.referral_share.save(deps.storage, &referral_share)?;
+    }
+
+    if let Some(rebalance_config) = rebalance_config {
+        state.rebalance_config.save(deps.storage, &rebalance_config.validate()?)?;
+    }
+
     Ok(Response::new().add_attribute("action", "erishub/update_config"))
 }
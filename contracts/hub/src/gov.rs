@@ -1,13 +1,18 @@
 use cosmwasm_std::{
-    CosmosMsg, Decimal, DepsMut, Env, Event, Fraction, GovMsg, MessageInfo, Response,
+    Addr, Coin, CosmosMsg, Decimal, Deps, DepsMut, Env, Event, Fraction, GovMsg, MessageInfo,
+    OverflowError, Response, StdError, StdResult, Storage, Uint128,
 };
-use eris_chain_adapter::types::{CustomMsgType, CustomQueryType};
+use eris::hub::{HolderBalance, HolderProposal, HolderVote, HolderVoteResponse};
+use eris::DecimalCheckedOps;
+use eris_chain_adapter::types::{chain, CustomMsgType, CustomQueryType};
+use eris_chain_shared::chain_trait::ChainInterface;
 use itertools::Itertools;
 use protobuf::SpecialFields;
 
 use crate::{
-    error::ContractResult,
+    error::{ContractError, ContractResult},
     protos::proto::{MsgVoteWeighted, VoteOption, WeightedVoteOption},
+    referral::remove_referred_shares,
     state::State,
 };
 
@@ -33,7 +38,7 @@ pub fn vote(
 
 pub fn vote_weighted(
     deps: DepsMut<CustomQueryType>,
-    _env: Env,
+    env: Env,
     info: MessageInfo,
     proposal_id: u64,
     votes: Vec<(Decimal, cosmwasm_std::VoteOption)>,
@@ -43,9 +48,20 @@ pub fn vote_weighted(
 
     let event = Event::new("erishub/voted_weighted").add_attribute("prop", proposal_id.to_string());
 
+    Ok(Response::<CustomMsgType>::new()
+        .add_message(create_vote_weighted_msg(&env, proposal_id, votes))
+        .add_event(event)
+        .add_attribute("action", "erishub/vote_weighted"))
+}
+
+fn create_vote_weighted_msg(
+    env: &Env,
+    proposal_id: u64,
+    votes: Vec<(Decimal, cosmwasm_std::VoteOption)>,
+) -> CosmosMsg<CustomMsgType> {
     let vote = MsgVoteWeighted {
         proposal_id,
-        voter: _env.contract.address.to_string(),
+        voter: env.contract.address.to_string(),
         options: votes
             .into_iter()
             .map(|vote| WeightedVoteOption {
@@ -64,10 +80,357 @@ pub fn vote_weighted(
         special_fields: SpecialFields::default(),
     };
 
-    let vote = vote.to_cosmos_msg();
+    vote.to_cosmos_msg()
+}
 
-    Ok(Response::<CustomMsgType>::new()
-        .add_message(vote)
+pub fn update_holder_voting(
+    deps: DepsMut<CustomQueryType>,
+    env: Env,
+    sender: Addr,
+    enabled: bool,
+) -> ContractResult {
+    let state = State::default();
+    state.assert_owner(deps.storage, &sender)?;
+
+    let stake = state.stake_token.load(deps.storage)?;
+    let cosmwasm_address = if enabled {
+        env.contract.address.to_string()
+    } else {
+        String::new()
+    };
+    let hook_msg = chain(&env).create_before_send_hook_msg(stake.denom, cosmwasm_address)?;
+
+    // balances of a previous tracking period are outdated, as transfers in between are not tracked
+    if enabled {
+        state.holder_voting_since.save(deps.storage, &env.block.time.seconds())?;
+    } else {
+        state.holder_voting_since.remove(deps.storage);
+    }
+
+    let event =
+        Event::new("erishub/holder_voting_updated").add_attribute("enabled", enabled.to_string());
+
+    Ok(Response::new()
+        .add_message(hook_msg)
         .add_event(event)
-        .add_attribute("action", "erishub/vote_weighted"))
+        .add_attribute("action", "erishub/update_holder_voting"))
+}
+
+/// Tracks the stake token balances of the holders, called by the before send hook of the token
+/// factory before the transfer is applied. The balance of a holder is read from the bank module on
+/// its first tracked transfer, so holders don't need to be seeded. Transferred stake is no longer
+/// attributed to the referrer of the sender.
+pub fn track_before_send(
+    mut deps: DepsMut<CustomQueryType>,
+    env: Env,
+    from: String,
+    to: String,
+    amount: Coin,
+) -> ContractResult {
+    let state = State::default();
+    let stake = state.stake_token.load(deps.storage)?;
+    if amount.denom != stake.denom {
+        return Ok(Response::new());
+    }
+
+    // stake sent to the hub is unbonded, its referred shares are removed when it is received
+    if to != env.contract.address.as_str() {
+        remove_referred_shares(deps.storage, &Addr::unchecked(&from), amount.amount)?;
+    }
+
+    let tracked_since = match state.holder_voting_since.may_load(deps.storage)? {
+        Some(tracked_since) => tracked_since,
+        None => return Ok(Response::new()),
+    };
+
+    // the addresses are provided by the chain and transfers should never fail because of tracking
+    let time = env.block.time.seconds();
+    track_holder_balance(
+        deps.branch(),
+        &state,
+        &from,
+        &stake.denom,
+        tracked_since,
+        time,
+        |balance| Ok(balance.saturating_sub(amount.amount)),
+    )?;
+    track_holder_balance(deps, &state, &to, &stake.denom, tracked_since, time, |balance| {
+        Ok(balance.checked_add(amount.amount)?)
+    })?;
+
+    Ok(Response::new())
+}
+
+/// Updates the balance of the holder from its bank balance before the transfer. The initial balance
+/// is stored on the first transfer of the tracking period.
+fn track_holder_balance<F>(
+    deps: DepsMut<CustomQueryType>,
+    state: &State,
+    holder: &str,
+    denom: &str,
+    tracked_since: u64,
+    time: u64,
+    action: F,
+) -> StdResult<()>
+where
+    F: FnOnce(Uint128) -> StdResult<Uint128>,
+{
+    let holder = Addr::unchecked(holder);
+    let balance = deps.querier.query_balance(&holder, denom)?.amount;
+
+    let initial = state.holder_initial_balances.may_load(deps.storage, &holder)?;
+    if !initial.is_some_and(|initial| initial.tracked_since == tracked_since) {
+        state.holder_initial_balances.save(
+            deps.storage,
+            &holder,
+            &HolderBalance {
+                tracked_since,
+                amount: balance,
+            },
+        )?;
+    }
+
+    state.holder_balances.save(
+        deps.storage,
+        &holder,
+        &HolderBalance {
+            tracked_since,
+            amount: action(balance)?,
+        },
+        time,
+    )
+}
+
+/// Voting power of the holder at the snapshot time, which is not before the tracking started.
+fn holder_power(
+    deps: &DepsMut<CustomQueryType>,
+    state: &State,
+    holder: &Addr,
+    snapshot_time: u64,
+) -> Result<Uint128, ContractError> {
+    let tracked_since = state
+        .holder_voting_since
+        .may_load(deps.storage)?
+        .ok_or(ContractError::HolderVotingDisabled {})?;
+    let current = |balance: Option<HolderBalance>| {
+        balance
+            .filter(|balance| balance.tracked_since == tracked_since)
+            .map(|balance| balance.amount)
+    };
+
+    // transferred before the snapshot
+    if let Some(amount) =
+        current(state.holder_balances.may_load_at_height(deps.storage, holder, snapshot_time)?)
+    {
+        return Ok(amount);
+    }
+
+    // first transferred after the snapshot
+    if let Some(amount) = current(state.holder_initial_balances.may_load(deps.storage, holder)?) {
+        return Ok(amount);
+    }
+
+    // never transferred since the tracking started
+    let stake = state.stake_token.load(deps.storage)?;
+    Ok(deps.querier.query_balance(holder, stake.denom)?.amount)
+}
+
+pub fn open_holder_vote(
+    deps: DepsMut<CustomQueryType>,
+    env: Env,
+    sender: Addr,
+    proposal_id: u64,
+    start_time: u64,
+    end_time: u64,
+) -> ContractResult {
+    let state = State::default();
+    state.assert_vote_operator(deps.storage, &sender)?;
+
+    if end_time <= env.block.time.seconds() {
+        return Err(ContractError::HolderVoteEnded(proposal_id));
+    }
+
+    if start_time > env.block.time.seconds() {
+        return Err(StdError::generic_err("start_time can't be in the future").into());
+    }
+
+    let tracked_since = state
+        .holder_voting_since
+        .may_load(deps.storage)?
+        .ok_or(ContractError::HolderVotingDisabled {})?;
+    if start_time < tracked_since {
+        return Err(ContractError::HolderVoteNotTracked(proposal_id));
+    }
+
+    if state.holder_proposals.has(deps.storage, proposal_id) {
+        return Err(ContractError::HolderVoteExists(proposal_id));
+    }
+
+    state.holder_proposals.save(
+        deps.storage,
+        proposal_id,
+        &HolderProposal {
+            snapshot_time: start_time,
+            end_time,
+            tally: vec![],
+        },
+    )?;
+
+    let event = Event::new("erishub/holder_vote_opened")
+        .add_attribute("prop", proposal_id.to_string())
+        .add_attribute("snapshot_time", start_time.to_string())
+        .add_attribute("end_time", end_time.to_string());
+
+    Ok(Response::new().add_event(event).add_attribute("action", "erishub/open_holder_vote"))
+}
+
+pub fn cast_holder_vote(
+    deps: DepsMut<CustomQueryType>,
+    env: Env,
+    sender: Addr,
+    proposal_id: u64,
+    votes: Vec<(Decimal, cosmwasm_std::VoteOption)>,
+) -> ContractResult {
+    let state = State::default();
+    let mut proposal = load_open_proposal(&state, deps.storage, &env, proposal_id)?;
+
+    let total_weight =
+        votes.iter().try_fold(Decimal::zero(), |acc, (weight, _)| acc.checked_add(*weight))?;
+    if total_weight != Decimal::one() {
+        return Err(StdError::generic_err("sum of vote weights is not 1").into());
+    }
+    if votes.iter().map(|(_, option)| vote_option_key(option)).unique().count() != votes.len() {
+        return Err(StdError::generic_err("duplicate vote options").into());
+    }
+
+    let power = holder_power(&deps, &state, &sender, proposal.snapshot_time)?;
+    if power.is_zero() {
+        return Err(ContractError::NoVotingPower {});
+    }
+
+    // a previous vote of the sender is replaced
+    if let Some(previous) = state.holder_votes.may_load(deps.storage, (proposal_id, &sender))? {
+        for (weight, option) in &previous.votes {
+            let option_power = weight.checked_mul_uint(previous.power)?;
+            update_tally(&mut proposal.tally, option, |tally| tally.checked_sub(option_power))?;
+        }
+    }
+    for (weight, option) in &votes {
+        let option_power = weight.checked_mul_uint(power)?;
+        update_tally(&mut proposal.tally, option, |tally| tally.checked_add(option_power))?;
+    }
+
+    state.holder_votes.save(
+        deps.storage,
+        (proposal_id, &sender),
+        &HolderVote {
+            power,
+            votes,
+        },
+    )?;
+    state.holder_proposals.save(deps.storage, proposal_id, &proposal)?;
+
+    let event = Event::new("erishub/holder_voted")
+        .add_attribute("prop", proposal_id.to_string())
+        .add_attribute("voter", sender)
+        .add_attribute("power", power);
+
+    Ok(Response::new().add_event(event).add_attribute("action", "erishub/cast_holder_vote"))
+}
+
+/// Submits the tally of the holder votes as weighted vote. As a vote can be changed until the end
+/// of the proposal, the tally can be submitted multiple times.
+pub fn submit_holder_vote(
+    deps: DepsMut<CustomQueryType>,
+    env: Env,
+    proposal_id: u64,
+) -> ContractResult {
+    let state = State::default();
+    let proposal = load_open_proposal(&state, deps.storage, &env, proposal_id)?;
+
+    let options = proposal.tally.into_iter().filter(|(_, power)| !power.is_zero()).collect_vec();
+    let total_power: Uint128 = options.iter().map(|(_, power)| *power).sum();
+    if total_power.is_zero() {
+        return Err(ContractError::CantBeZero("holder votes".into()));
+    }
+
+    // the weights need to sum up to exactly 1, so the last option receives the remainder
+    let mut remaining = Decimal::one();
+    let mut votes = vec![];
+    for (i, (option, power)) in options.iter().enumerate() {
+        let weight = if i == options.len() - 1 {
+            remaining
+        } else {
+            Decimal::from_ratio(*power, total_power)
+        };
+        remaining = remaining.checked_sub(weight)?;
+        votes.push((weight, option.clone()));
+    }
+
+    let event = Event::new("erishub/holder_vote_submitted")
+        .add_attribute("prop", proposal_id.to_string())
+        .add_attribute("total_power", total_power);
+
+    Ok(Response::new()
+        .add_message(create_vote_weighted_msg(&env, proposal_id, votes))
+        .add_event(event)
+        .add_attribute("action", "erishub/submit_holder_vote"))
+}
+
+pub fn query_holder_vote(
+    deps: Deps<CustomQueryType>,
+    proposal_id: u64,
+) -> StdResult<HolderVoteResponse> {
+    let state = State::default();
+    let proposal = state.holder_proposals.load(deps.storage, proposal_id)?;
+
+    Ok(HolderVoteResponse {
+        proposal_id,
+        snapshot_time: proposal.snapshot_time,
+        end_time: proposal.end_time,
+        total_power: proposal.tally.iter().map(|(_, power)| *power).sum(),
+        tally: proposal.tally,
+    })
+}
+
+fn load_open_proposal(
+    state: &State,
+    storage: &dyn Storage,
+    env: &Env,
+    proposal_id: u64,
+) -> Result<HolderProposal, ContractError> {
+    let proposal = state
+        .holder_proposals
+        .may_load(storage, proposal_id)?
+        .ok_or(ContractError::HolderVoteNotFound(proposal_id))?;
+
+    if env.block.time.seconds() >= proposal.end_time {
+        return Err(ContractError::HolderVoteEnded(proposal_id));
+    }
+
+    Ok(proposal)
+}
+
+fn update_tally<F>(
+    tally: &mut Vec<(cosmwasm_std::VoteOption, Uint128)>,
+    option: &cosmwasm_std::VoteOption,
+    action: F,
+) -> Result<(), OverflowError>
+where
+    F: FnOnce(Uint128) -> Result<Uint128, OverflowError>,
+{
+    match tally.iter_mut().find(|(o, _)| o == option) {
+        Some((_, power)) => *power = action(*power)?,
+        None => tally.push((option.clone(), action(Uint128::zero())?)),
+    }
+    Ok(())
+}
+
+fn vote_option_key(option: &cosmwasm_std::VoteOption) -> u8 {
+    match option {
+        cosmwasm_std::VoteOption::Yes => 0,
+        cosmwasm_std::VoteOption::No => 1,
+        cosmwasm_std::VoteOption::Abstain => 2,
+        cosmwasm_std::VoteOption::NoWithVeto => 3,
+    }
 }
@@ -9,13 +9,16 @@ use cosmwasm_std::{
 };
 use eris::{
     governance_helper::get_period,
+    helper::capped_shares,
     helpers::bps::BasicPoints,
     hub::{DelegationStrategy, WantedDelegationsShare},
+    uptime_oracle::get_uptimes,
 };
 use eris_chain_adapter::types::CustomQueryType;
 use itertools::Itertools;
 
 use crate::{
+    error::ContractError,
     state::State,
     types::{gauges::GaugeLoader, Delegation},
 };
@@ -109,6 +112,20 @@ pub fn assert_validators_exists(
     Ok(())
 }
 
+/// Rejects strategies that can't be computed by the hub, also as part of a composite strategy.
+pub fn assert_strategy_supported<T>(strategy: &DelegationStrategy<T>) -> Result<(), ContractError> {
+    match strategy {
+        // the alliance module is not available to the hub
+        DelegationStrategy::AllianceRewards {
+            ..
+        } => Err(ContractError::DelegationStrategyNotSupported("alliance_rewards".to_string())),
+        DelegationStrategy::Composite {
+            parts,
+        } => parts.iter().try_for_each(|(strategy, _)| assert_strategy_supported(strategy)),
+        _ => Ok(()),
+    }
+}
+
 /// Dedupes a Vector of strings using a hashset.
 pub fn dedupe(validators: &mut Vec<String>) {
     let mut set = HashSet::new();
@@ -129,6 +146,17 @@ pub(crate) fn get_wanted_delegations(
     let delegation_strategy =
         state.delegation_strategy.may_load(storage)?.unwrap_or(DelegationStrategy::Uniform {});
 
+    get_strategy_delegations(state, env, storage, querier, &loader, delegation_strategy)
+}
+
+fn get_strategy_delegations(
+    state: &State,
+    env: &Env,
+    storage: &dyn Storage,
+    querier: &QuerierWrapper<CustomQueryType>,
+    loader: &impl GaugeLoader,
+    delegation_strategy: DelegationStrategy<Addr>,
+) -> StdResult<(WantedDelegationsShare, bool)> {
     match delegation_strategy {
         DelegationStrategy::Uniform {} => {
             let validators = state.validators.load(storage)?;
@@ -143,11 +171,16 @@ pub(crate) fn get_wanted_delegations(
                         .into_iter()
                         .map(|val| (val, share_per_validator))
                         .collect_vec(),
+                    parts: None,
                 },
                 // no need to store it
                 false,
             ))
         },
+        // rejected when the config is validated
+        DelegationStrategy::AllianceRewards {
+            ..
+        } => Err(StdError::generic_err("Delegation strategy alliance_rewards is not supported")),
         DelegationStrategy::Defined {
             shares_bps,
         } => Ok((
@@ -158,6 +191,7 @@ pub(crate) fn get_wanted_delegations(
                     .into_iter()
                     .map(|(validator, bps)| Ok((validator, BasicPoints::try_from(bps)?.decimal())))
                     .collect::<StdResult<Vec<(String, Decimal)>>>()?,
+                parts: None,
             },
             // store it for get_utoken_per_validator
             true,
@@ -176,8 +210,8 @@ pub(crate) fn get_wanted_delegations(
             let vamp_factor = BasicPoints::try_from(amp_factor_bps)?.decimal();
             let emp_factor = Decimal::one().checked_sub(vamp_factor)?;
 
-            let vamp_context = Context::from_amps(&loader, querier, amp_gauges)?;
-            let emp_context = Context::from_emps(&loader, querier, emp_gauges)?;
+            let vamp_context = Context::from_amps(loader, querier, amp_gauges)?;
+            let emp_context = Context::from_emps(loader, querier, emp_gauges)?;
 
             let validators: Vec<_> = state
                 .validators
@@ -228,6 +262,95 @@ pub(crate) fn get_wanted_delegations(
                     shares: validators,
                     tune_time: env.block.time.seconds(),
                     tune_period: get_period(env.block.time.seconds())?,
+                    parts: None,
+                },
+                true,
+            ))
+        },
+        DelegationStrategy::Performance {
+            commission_cap_bps,
+            uptime_oracle,
+            min_score,
+            max_delegation_bps,
+            validator_count,
+        } => {
+            let commission_cap = BasicPoints::try_from(commission_cap_bps)?.decimal();
+            let max_delegation = BasicPoints::try_from(max_delegation_bps)?.decimal();
+
+            let validators = state.validators.load(storage)?;
+            let uptimes = uptime_oracle
+                .map(|uptime_oracle| get_uptimes(querier, uptime_oracle, &validators))
+                .transpose()?;
+
+            let scores: Vec<_> = validators
+                .into_iter()
+                .map(|val| -> StdResult<Option<(String, Decimal)>> {
+                    // jailed or inactive validators are not returned by the staking module
+                    let validator = match querier.query_validator(&val)? {
+                        Some(validator) => validator,
+                        None => return Ok(None),
+                    };
+
+                    if validator.commission > commission_cap {
+                        return Ok(None);
+                    }
+
+                    // without an oracle every validator is expected to be fully online
+                    let uptime = match &uptimes {
+                        Some(uptimes) => uptimes.get(&val).copied().unwrap_or_default(),
+                        None => Decimal::one(),
+                    };
+
+                    let score =
+                        Decimal::one().checked_sub(validator.commission)?.checked_mul(uptime)?;
+                    Ok(Some((val, score)))
+                })
+                .collect::<StdResult<Vec<_>>>()?
+                .into_iter()
+                .flatten()
+                .filter(|(_, score)| !score.is_zero() && *score >= min_score)
+                .collect();
+
+            let scores: Vec<_> = scores
+                .into_iter()
+                .sorted_by(|(_, a), (_, b)| b.cmp(a)) // Sort in descending order
+                .take(validator_count.into())
+                .collect();
+
+            if scores.is_empty() {
+                return Err(StdError::generic_err(
+                    "No validator fulfills the performance criteria.",
+                ));
+            }
+
+            Ok((
+                WantedDelegationsShare {
+                    shares: capped_shares(scores, max_delegation)?,
+                    tune_time: env.block.time.seconds(),
+                    tune_period: get_period(env.block.time.seconds())?,
+                    parts: None,
+                },
+                true,
+            ))
+        },
+        DelegationStrategy::Composite {
+            parts,
+        } => {
+            let parts = parts
+                .into_iter()
+                .map(|(strategy, weight_bps)| -> StdResult<(u16, Vec<(String, Decimal)>)> {
+                    let (share, _) =
+                        get_strategy_delegations(state, env, storage, querier, loader, strategy)?;
+                    Ok((weight_bps, share.shares))
+                })
+                .collect::<StdResult<Vec<_>>>()?;
+
+            Ok((
+                WantedDelegationsShare {
+                    shares: WantedDelegationsShare::merge_parts(&parts)?,
+                    tune_time: env.block.time.seconds(),
+                    tune_period: get_period(env.block.time.seconds())?,
+                    parts: Some(parts),
                 },
                 true,
             ))
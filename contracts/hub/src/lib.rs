@@ -8,10 +8,14 @@ pub mod queries;
 pub mod state;
 pub mod types;
 
+pub mod circuit_breaker;
 pub mod claim;
 mod constants;
 pub mod error;
 pub mod gov;
+pub mod prices;
 pub mod protos;
+pub mod referral;
+pub mod slashing;
 #[cfg(test)]
 mod testing;
@@ -4,10 +4,10 @@ use std::{
     collections::{HashMap, HashSet},
 };
 
-use cosmwasm_std::{attr, Addr, Attribute, QuerierWrapper, StdResult, Storage, Uint128};
+use cosmwasm_std::{attr, Addr, Attribute, QuerierWrapper, StdError, StdResult, Storage, Uint128};
 
 use eris::{
-    hub::{Batch, WantedDelegationsShare},
+    hub::{Batch, InstantUnbondConfig, InstantUnbondQuoteResponse, WantedDelegationsShare},
     DecimalCheckedOps,
 };
 use eris_chain_adapter::types::CustomQueryType;
@@ -54,16 +54,58 @@ pub(crate) fn compute_unbond_amount(
     Uint128::new(utoken_bonded).multiply_ratio(ustake_to_burn, ustake_supply)
 }
 
+/// Compute the amount of `utoken` paid out of the reserve for instantly unbonding a specific
+/// `ustake` amount. The fee depends on how much the payout drains the reserve below its target.
+pub(crate) fn compute_instant_unbond(
+    config: &InstantUnbondConfig,
+    ustake_supply: Uint128,
+    ustake_to_burn: Uint128,
+    current_delegations: &[Delegation],
+    reserve: Uint128,
+) -> StdResult<InstantUnbondQuoteResponse> {
+    let utoken_amount = compute_unbond_amount(ustake_supply, ustake_to_burn, current_delegations);
+    let fee = config.get_fee(reserve, utoken_amount);
+    let fee_amount = fee.checked_mul_uint(utoken_amount)?;
+
+    Ok(InstantUnbondQuoteResponse {
+        utoken_amount,
+        fee,
+        fee_amount,
+        receive_amount: utoken_amount.checked_sub(fee_amount)?,
+        reserve,
+    })
+}
+
 //--------------------------------------------------------------------------------------------------
 // Delegation logics
 //--------------------------------------------------------------------------------------------------
 
+/// Number of validators a batch undelegates from, such that rotating across all validators keeps
+/// each of them below the maximum of concurrent unbonding entries during an unbonding period.
+pub(crate) fn compute_validators_per_batch(
+    validator_count: usize,
+    max_unbonding_entries: u32,
+    epoch_period: u64,
+    unbond_period: u64,
+) -> usize {
+    let batches_per_unbond_period = unbond_period.div_ceil(epoch_period.max(1)).max(1);
+    let validators = (max_unbonding_entries as u64).saturating_mul(validator_count as u64)
+        / batches_per_unbond_period;
+    (validators as usize).clamp(1, validator_count.max(1))
+}
+
 /// Given the current delegations made to validators, and a specific amount of `utoken` to unstake,
 /// compute the undelegations to make such that the delegated amount to each validator is as even
 /// as possible.
 ///
+/// Only a subset of at least `max_validators` validators is used, preferring the ones with the
+/// fewest active unbonding entries, so that consecutive batches rotate across validators. The
+/// subset is extended if its delegations can't cover the amount to unstake. Validators that reached
+/// the maximum of concurrent unbonding entries are skipped.
+///
 /// This function is based on Lido's implementation:
 /// https://github.com/lidofinance/lido-terra-contracts/blob/v1.0.2/contracts/lido_terra_validators_registry/src/common.rs#L55-102
+#[allow(clippy::too_many_arguments)]
 pub(crate) fn compute_undelegations(
     state: &State,
     storage: &dyn Storage,
@@ -71,6 +113,9 @@ pub(crate) fn compute_undelegations(
     current_delegations: &[Delegation],
     validators: Vec<String>,
     utoken: &str,
+    unbonding_entries: &HashMap<String, u32>,
+    max_unbonding_entries: u32,
+    max_validators: usize,
 ) -> StdResult<Vec<Undelegation>> {
     let utoken_staked: u128 = current_delegations.iter().map(|d| d.amount).sum();
     let utoken_to_distribute = utoken_staked - utoken_to_unbond.u128();
@@ -78,28 +123,72 @@ pub(crate) fn compute_undelegations(
     let (utoken_per_validator, mut add, mut remove, _) =
         get_utoken_per_validator(state, storage, utoken_to_distribute, &validators, None)?;
 
+    let delegations = merge_with_validators(current_delegations, validators, utoken);
+    let surplus: HashMap<String, u128> = delegations
+        .iter()
+        .map(|d| {
+            let utoken_for_validator =
+                get_utoken_for_validator(&utoken_per_validator, d, &mut add, &mut remove);
+            (d.validator.clone(), d.amount.saturating_sub(utoken_for_validator))
+        })
+        .collect();
+
+    let active_entries =
+        |validator: &str| unbonding_entries.get(validator).copied().unwrap_or_default();
+
+    let mut candidates = delegations
+        .iter()
+        .filter(|d| active_entries(&d.validator) < max_unbonding_entries)
+        .collect::<Vec<_>>();
+    candidates.sort_by_key(|d| (active_entries(&d.validator), cmp::Reverse(surplus[&d.validator])));
+
+    let mut utoken_selected = 0u128;
+    let selected_count = candidates
+        .iter()
+        .take_while(|d| {
+            let take = utoken_selected < utoken_to_unbond.u128();
+            utoken_selected += d.amount;
+            take
+        })
+        .count()
+        .max(max_validators)
+        .min(candidates.len());
+    let selected = &candidates[..selected_count];
+
     let mut new_undelegations: Vec<Undelegation> = vec![];
     let mut utoken_available = utoken_to_unbond.u128();
-    for d in merge_with_validators(current_delegations, validators, utoken).iter() {
-        let utoken_for_validator =
-            get_utoken_for_validator(&utoken_per_validator, d, &mut add, &mut remove);
+    for d in selected {
+        let utoken_to_undelegate = cmp::min(surplus[&d.validator], utoken_available);
+        if utoken_to_undelegate == 0 {
+            continue;
+        }
 
-        let mut utoken_to_undelegate = if d.amount < utoken_for_validator {
-            0
-        } else {
-            d.amount - utoken_for_validator
-        };
+        utoken_available -= utoken_to_undelegate;
+        new_undelegations.push(Undelegation::new(&d.validator, utoken_to_undelegate, utoken));
 
-        if utoken_to_undelegate > 0 {
-            utoken_to_undelegate = std::cmp::min(utoken_to_undelegate, utoken_available);
-            utoken_available -= utoken_to_undelegate;
+        if utoken_available == 0 {
+            break;
+        }
+    }
+
+    // the remaining amount is undelegated from the selected validators below their target
+    if utoken_available > 0 {
+        for d in selected {
+            let existing = new_undelegations.iter_mut().find(|u| u.validator == d.validator);
+            let undelegated = existing.as_ref().map_or(0, |u| u.amount);
+            let utoken_to_undelegate = cmp::min(d.amount - undelegated, utoken_available);
+            if utoken_to_undelegate == 0 {
+                continue;
+            }
 
-            if utoken_to_undelegate > 0 {
-                new_undelegations.push(Undelegation::new(
+            utoken_available -= utoken_to_undelegate;
+            match existing {
+                Some(undelegation) => undelegation.amount += utoken_to_undelegate,
+                None => new_undelegations.push(Undelegation::new(
                     &d.validator,
                     utoken_to_undelegate,
                     utoken,
-                ));
+                )),
             }
 
             if utoken_available == 0 {
@@ -108,6 +197,10 @@ pub(crate) fn compute_undelegations(
         }
     }
 
+    if utoken_available > 0 {
+        return Err(StdError::generic_err("not enough validators with free unbonding entries"));
+    }
+
     Ok(new_undelegations)
 }
 
@@ -124,12 +217,13 @@ pub(crate) fn compute_redelegations_for_removal(
     current_delegations: &[Delegation],
     validators: Vec<String>,
     utoken: &str,
+    goal: Option<WantedDelegationsShare>,
 ) -> StdResult<Vec<Redelegation>> {
     let utoken_staked: u128 = current_delegations.iter().map(|d| d.amount).sum();
     let utoken_to_distribute = utoken_staked + delegation_to_remove.amount;
 
     let (utoken_per_validator, mut add, mut remove, _) =
-        get_utoken_per_validator(state, storage, utoken_to_distribute, &validators, None)?;
+        get_utoken_per_validator(state, storage, utoken_to_distribute, &validators, goal)?;
 
     let mut new_redelegations: Vec<Redelegation> = vec![];
     let mut utoken_available = delegation_to_remove.amount;
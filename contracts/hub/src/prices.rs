@@ -0,0 +1,138 @@
+use cosmwasm_std::{Addr, Decimal, Deps, DepsMut, Env, Event, Order, Response, StdResult};
+use eris::hub::{PriceFeed, PriceFeedResponse, PriceFeedsResponse, PriceSource};
+use eris::price_oracle::get_price;
+use eris_chain_adapter::types::{chain, CustomQueryType};
+use eris_chain_shared::chain_trait::ChainInterface;
+
+use crate::error::{ContractError, ContractResult};
+use crate::state::State;
+
+/// Sets the price feed of the denom, removing the feed also removes the last pushed price.
+pub fn update_price_feed(
+    deps: DepsMut<CustomQueryType>,
+    sender: Addr,
+    denom: String,
+    feed: Option<PriceFeed>,
+) -> ContractResult {
+    let state = State::default();
+    state.assert_owner(deps.storage, &sender)?;
+
+    match feed {
+        Some(feed) => {
+            let feed = feed.validate(deps.api)?;
+            state.price_feeds.save(deps.storage, &denom, &feed)?;
+        },
+        None => {
+            state.price_feeds.remove(deps.storage, &denom);
+            state.pushed_prices.remove(deps.storage, &denom);
+        },
+    }
+
+    Ok(Response::new()
+        .add_event(Event::new("erishub/price_feed_updated").add_attribute("denom", denom))
+        .add_attribute("action", "erishub/update_price_feed"))
+}
+
+pub fn push_price(
+    deps: DepsMut<CustomQueryType>,
+    env: Env,
+    sender: Addr,
+    denom: String,
+    price: Decimal,
+) -> ContractResult {
+    let state = State::default();
+    state.assert_operator(deps.storage, &sender)?;
+
+    let feed = state.price_feeds.may_load(deps.storage, &denom)?;
+    if !matches!(feed.map(|feed| feed.source), Some(PriceSource::Pushed {})) {
+        return Err(ContractError::PriceNotPushable(denom));
+    }
+
+    if price.is_zero() {
+        return Err(ContractError::CantBeZero("price".into()));
+    }
+
+    state.pushed_prices.save(deps.storage, &denom, &(price, env.block.time.seconds()))?;
+
+    Ok(Response::new()
+        .add_event(
+            Event::new("erishub/price_pushed")
+                .add_attribute("denom", denom)
+                .add_attribute("price", price.to_string()),
+        )
+        .add_attribute("action", "erishub/push_price"))
+}
+
+/// Computes the belief price of a swap from the price feed of the offered denom. Returns None if
+/// the denom has no price feed, errors if the price is older than the max age of the feed.
+pub fn get_belief_price(
+    deps: &DepsMut<CustomQueryType>,
+    env: &Env,
+    denom: &str,
+) -> Result<Option<Decimal>, ContractError> {
+    let state = State::default();
+    let feed = match state.price_feeds.may_load(deps.storage, denom)? {
+        Some(feed) => feed,
+        None => return Ok(None),
+    };
+
+    let now = env.block.time.seconds();
+    let (price, updated_at) = match feed.source {
+        PriceSource::Twap {
+            contract,
+        } => {
+            let response = get_price(&deps.querier, contract, denom)?;
+            (response.price, response.updated_at)
+        },
+        PriceSource::ChainOracle {
+            offer_symbol,
+            ask_symbol,
+        } => {
+            let chain = chain(env);
+            let offer_price = chain.query_oracle_price(&deps.querier, offer_symbol)?;
+            let ask_price = chain.query_oracle_price(&deps.querier, ask_symbol)?;
+            if ask_price.is_zero() {
+                return Err(ContractError::PriceNotAvailable(denom.to_string()));
+            }
+            // the oracle module only provides current prices
+            (offer_price / ask_price, now)
+        },
+        PriceSource::Pushed {} => state
+            .pushed_prices
+            .may_load(deps.storage, denom)?
+            .ok_or_else(|| ContractError::PriceNotAvailable(denom.to_string()))?,
+    };
+
+    if updated_at.saturating_add(feed.max_age) < now {
+        return Err(ContractError::PriceStale(denom.to_string()));
+    }
+
+    if price.is_zero() {
+        return Err(ContractError::PriceNotAvailable(denom.to_string()));
+    }
+
+    // the belief price is the amount offered for one unit of the ask denom
+    Ok(Some(Decimal::one() / price))
+}
+
+pub fn query_price_feeds(deps: Deps<CustomQueryType>) -> StdResult<PriceFeedsResponse> {
+    let state = State::default();
+
+    let feeds = state
+        .price_feeds
+        .range(deps.storage, None, None, Order::Ascending)
+        .map(|item| {
+            let (denom, feed) = item?;
+            let pushed_price = state.pushed_prices.may_load(deps.storage, &denom)?;
+            Ok(PriceFeedResponse {
+                denom,
+                feed: feed.into(),
+                pushed_price,
+            })
+        })
+        .collect::<StdResult<Vec<_>>>()?;
+
+    Ok(PriceFeedsResponse {
+        feeds,
+    })
+}
@@ -1,23 +1,35 @@
+use std::collections::HashMap;
 use std::ops::Div;
 
-use cosmwasm_std::{Addr, Decimal, Deps, Env, Order, StdResult, Uint128};
-use cw_storage_plus::Bound;
+use cosmwasm_std::{Addr, Coin, Decimal, Deps, Env, Order, StdError, StdResult, Uint128};
+use cw_storage_plus::{Bound, Item};
 
 use eris::governance_helper::get_period;
 // use eris::governance_helper::get_period;
 use eris::hub::{
-    Batch, ConfigResponse, ExchangeRatesResponse, PendingBatch, StateResponse,
-    UnbondRequestsByBatchResponseItem, UnbondRequestsByUserResponseItem,
-    UnbondRequestsByUserResponseItemDetails, WantedDelegationsResponse,
+    Batch, ClaimableUsersResponseItem, ConfigResponse, ExchangeRatesResponse,
+    InstantUnbondQuoteResponse, PendingBatch, SimulateHarvestResponse, SingleSwapConfig,
+    StateResponse, UnbondRequestsByBatchResponseItem, UnbondRequestsByUserResponseItem,
+    UnbondRequestsByUserResponseItemDetails, UnbondingEntriesResponse, ValidatorUnbondingEntries,
+    WantedDelegationsPart, WantedDelegationsResponse, WantedDelegationsShare,
 };
-use eris_chain_adapter::types::CustomQueryType;
+use eris::DecimalCheckedOps;
+use eris_chain_adapter::types::{
+    chain, get_balances_hashmap, CustomQueryType, DenomType, WithdrawType,
+};
+use eris_chain_shared::chain_trait::ChainInterface;
 use itertools::Itertools;
+use serde::{de::DeserializeOwned, Serialize};
 
 use crate::constants::DAY;
-use crate::helpers::{get_wanted_delegations, query_all_delegations_amount};
-use crate::math::get_utoken_per_validator_prepared;
+use crate::error::ContractError;
+use crate::execute::simulate_rebalance_bounties;
+use crate::helpers::{get_wanted_delegations, query_all_delegations, query_all_delegations_amount};
+use crate::math::{compute_instant_unbond, get_utoken_per_validator_prepared};
+use crate::referral::simulate_referral_rewards;
 use crate::state::State;
 use crate::types::gauges::PeriodGaugeLoader;
+use crate::types::Coins;
 // use crate::types::gauges::PeriodGaugeLoader;
 
 const MAX_LIMIT: u32 = 30;
@@ -41,34 +53,23 @@ pub fn config(deps: Deps<CustomQueryType>) -> StdResult<ConfigResponse> {
         stages_preset: state.stages_preset.may_load(deps.storage)?.unwrap_or_default(),
         withdrawals_preset: state.withdrawals_preset.may_load(deps.storage)?.unwrap_or_default(),
         allow_donations: state.allow_donations.may_load(deps.storage)?.unwrap_or(false),
-        delegation_strategy: match state
+        delegation_strategy: state
             .delegation_strategy
             .may_load(deps.storage)?
             .unwrap_or(eris::hub::DelegationStrategy::Uniform)
-        {
-            eris::hub::DelegationStrategy::Uniform => eris::hub::DelegationStrategy::Uniform,
-            eris::hub::DelegationStrategy::Defined {
-                shares_bps,
-            } => eris::hub::DelegationStrategy::Defined {
-                shares_bps,
-            },
-            eris::hub::DelegationStrategy::Gauges {
-                amp_gauges,
-                emp_gauges,
-                amp_factor_bps,
-                min_delegation_bps,
-                max_delegation_bps,
-                validator_count,
-            } => eris::hub::DelegationStrategy::Gauges {
-                amp_gauges: amp_gauges.to_string(),
-                emp_gauges: emp_gauges.map(|a| a.to_string()),
-                amp_factor_bps,
-                min_delegation_bps,
-                max_delegation_bps,
-                validator_count,
-            },
-        },
+            .into(),
         vote_operator: state.vote_operator.may_load(deps.storage)?.map(|addr| addr.into()),
+        instant_unbond_config: state.instant_unbond_config.may_load(deps.storage)?,
+        remove_slashed_validators: state
+            .remove_slashed_validators
+            .may_load(deps.storage)?
+            .unwrap_or(false),
+        max_unbonding_entries: state.get_max_unbonding_entries(deps.storage)?,
+        guardian: state.guardian.may_load(deps.storage)?.map(|addr| addr.into()),
+        circuit_breaker: state.circuit_breaker.may_load(deps.storage)?,
+        paused: state.paused.may_load(deps.storage)?.unwrap_or_default(),
+        referral_share: state.referral_share.may_load(deps.storage)?.unwrap_or_default(),
+        rebalance_config: state.rebalance_config.may_load(deps.storage)?,
     })
 }
 
@@ -124,6 +125,8 @@ pub fn wanted_delegations(
     let state = State::default();
     let stake_token = state.stake_token.load(deps.storage)?;
 
+    let utoken_staked =
+        query_all_delegations_amount(&deps.querier, &env.contract.address, &stake_token.utoken)?;
     let (delegations, _, _, share) = get_utoken_per_validator_prepared(
         &state,
         deps.storage,
@@ -135,6 +138,7 @@ pub fn wanted_delegations(
 
     Ok(WantedDelegationsResponse {
         delegations: sort_delegations(delegations),
+        parts: parts_delegations(&share, utoken_staked)?,
         tune_time_period: share.map(|s| (s.tune_time, s.tune_period)),
     })
 }
@@ -159,6 +163,8 @@ pub fn simulate_wanted_delegations(
         },
     )?;
 
+    let utoken_staked =
+        query_all_delegations_amount(&deps.querier, &env.contract.address, &stake_token.utoken)?;
     let (delegations, _, _, share) = get_utoken_per_validator_prepared(
         &state,
         deps.storage,
@@ -170,9 +176,21 @@ pub fn simulate_wanted_delegations(
 
     Ok(WantedDelegationsResponse {
         delegations: sort_delegations(delegations), // Sort in descending order
+        parts: parts_delegations(&share, utoken_staked)?,
         tune_time_period: share.map(|s| (s.tune_time, s.tune_period)),
     })
 }
+
+/// Breakdown of the wanted delegations for each part of a composite strategy
+fn parts_delegations(
+    share: &Option<WantedDelegationsShare>,
+    utoken_staked: u128,
+) -> StdResult<Option<Vec<WantedDelegationsPart>>> {
+    match share {
+        Some(share) => share.get_parts_delegations(Uint128::new(utoken_staked)),
+        None => Ok(None),
+    }
+}
 /// Sort delegations by amount descending and then by address ascending
 fn sort_delegations(
     delegations: std::collections::HashMap<String, Uint128>,
@@ -334,6 +352,54 @@ pub fn unbond_requests_by_user_details(
         .collect()
 }
 
+/// Users with auto claim enabled are listed in the order of their address, each page scans at most
+/// `limit` users. Only requests of reconciled batches that finished unbonding are counted, so users
+/// can be listed without anything to withdraw.
+pub fn claimable_users(
+    deps: Deps<CustomQueryType>,
+    env: Env,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<Vec<ClaimableUsersResponseItem>> {
+    let state = State::default();
+
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start_after = start_after.map(|user| deps.api.addr_validate(&user)).transpose()?;
+    let start = start_after.as_ref().map(Bound::exclusive);
+    let current_time = env.block.time.seconds();
+
+    state
+        .auto_claim
+        .keys(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|user| {
+            let user = user?;
+            let mut utoken_claimable = Uint128::zero();
+            for item in state.unbond_requests.idx.user.prefix(user.to_string()).range(
+                deps.storage,
+                None,
+                None,
+                Order::Ascending,
+            ) {
+                let (_, request) = item?;
+                match state.previous_batches.may_load(deps.storage, request.id)? {
+                    Some(batch) if batch.reconciled && batch.est_unbond_end_time < current_time => {
+                        utoken_claimable += batch
+                            .utoken_unclaimed
+                            .multiply_ratio(request.shares, batch.total_shares);
+                    },
+                    _ => continue,
+                }
+            }
+
+            Ok(ClaimableUsersResponseItem {
+                user: user.into(),
+                utoken_claimable,
+            })
+        })
+        .collect()
+}
+
 pub fn query_exchange_rates(
     deps: Deps<CustomQueryType>,
     _env: Env,
@@ -367,3 +433,277 @@ pub fn query_exchange_rates(
         apr,
     })
 }
+
+pub fn instant_unbond_quote(
+    deps: Deps<CustomQueryType>,
+    env: Env,
+    ustake_amount: Uint128,
+) -> StdResult<InstantUnbondQuoteResponse> {
+    let state = State::default();
+    let config = state
+        .instant_unbond_config
+        .may_load(deps.storage)?
+        .ok_or_else(|| StdError::generic_err("Instant unbond is disabled"))?;
+    let stake = state.stake_token.load(deps.storage)?;
+    let reserve = state.instant_unbond_reserve.may_load(deps.storage)?.unwrap_or_default();
+
+    let delegations = query_all_delegations(&deps.querier, &env.contract.address, &stake.utoken)?;
+    compute_instant_unbond(&config, stake.total_supply, ustake_amount, &delegations, reserve)
+}
+
+pub fn unbonding_entries(
+    deps: Deps<CustomQueryType>,
+    env: Env,
+) -> StdResult<UnbondingEntriesResponse> {
+    let state = State::default();
+    let current_time = env.block.time.seconds();
+
+    let validators = state
+        .unbonding_entries
+        .range(deps.storage, None, None, Order::Ascending)
+        .map(|item| {
+            let (validator, completion_times) = item?;
+            Ok(ValidatorUnbondingEntries {
+                validator,
+                completion_times: completion_times
+                    .into_iter()
+                    .filter(|t| *t > current_time)
+                    .collect(),
+            })
+        })
+        .filter_ok(|entries| !entries.completion_times.is_empty())
+        .collect::<StdResult<Vec<_>>>()?;
+
+    Ok(UnbondingEntriesResponse {
+        max_entries: state.get_max_unbonding_entries(deps.storage)?,
+        validators,
+    })
+}
+
+/// Simulates the harvest: pending rewards are withdrawn, LPs are deconstructed and each stage is
+/// swapped using the simulation hooks of the chain. The received utoken and ustake are handled
+/// the same way as in `reinvest`.
+pub fn simulate_harvest(
+    deps: Deps<CustomQueryType>,
+    env: Env,
+    validators: Option<Vec<String>>,
+    withdrawals: Option<Vec<(WithdrawType, DenomType)>>,
+    stages: Option<Vec<Vec<SingleSwapConfig>>>,
+) -> StdResult<SimulateHarvestResponse> {
+    let state = State::default();
+    let stake = state.stake_token.load(deps.storage)?;
+    let fee_config = state.fee_config.load(deps.storage)?;
+    let chain = chain(&env);
+    let get_chain_config = || state.chain_config.load(deps.storage);
+
+    let withdrawals = get_or_preset(deps, withdrawals, &state.withdrawals_preset)?;
+    let stages = get_or_preset(deps, stages, &state.stages_preset)?;
+
+    let get_denoms = || {
+        withdrawals
+            .iter()
+            .flatten()
+            .map(|(_, denom)| denom.clone())
+            .chain(stages.iter().flatten().flatten().map(|(_, denom, _, _, _)| denom.clone()))
+            .collect_vec()
+    };
+    let mut balances = get_balances_hashmap(&deps.querier, env.clone(), get_denoms)?;
+    let utoken_before = balances.get(&stake.utoken).copied().unwrap_or_default();
+    let ustake_before = balances.get(&stake.denom).copied().unwrap_or_default();
+
+    // 1. Pending delegation rewards
+    let validators = match validators {
+        Some(validators) => validators,
+        None => query_all_delegations(&deps.querier, &env.contract.address, &stake.utoken)?
+            .into_iter()
+            .map(|d| d.validator)
+            .collect(),
+    };
+    let mut rewards = Coins(vec![]);
+    for validator in validators {
+        if let Some(delegation) = deps.querier.query_delegation(&env.contract.address, validator)? {
+            for coin in delegation.accumulated_rewards {
+                add_balance(&mut balances, coin.denom.clone(), coin.amount)?;
+                rewards.add(&coin)?;
+            }
+        }
+    }
+
+    // 2. LP withdrawals / deconstruction
+    for (withdraw_type, denom) in withdrawals.into_iter().flatten() {
+        let balance = balances.remove(&denom.to_string()).unwrap_or_default();
+        if !balance.is_zero() {
+            let received = chain.simulate_withdraw(
+                &deps.querier,
+                get_chain_config,
+                withdraw_type,
+                denom,
+                balance,
+            )?;
+            for (received_denom, amount) in received {
+                add_balance(&mut balances, received_denom.to_string(), amount)?;
+            }
+        }
+    }
+
+    // 3. Swap stages, each swap uses the balance from before its stage
+    let mut fees = Coins(vec![]);
+    let mut skip_fee = false;
+    for (index, stage) in stages.into_iter().flatten().enumerate() {
+        // same condition as `harvest`, independent of the available balances
+        skip_fee =
+            skip_fee || stage.iter().any(|(_, _, _, _, pay_fee)| pay_fee.unwrap_or_default());
+        let stage_balances = balances.clone();
+        for (stage_type, denom, _, max_amount, fee) in stage {
+            let mut available = stage_balances.get(&denom.to_string()).copied().unwrap_or_default();
+            if available.is_zero() {
+                continue;
+            }
+
+            if fee == Some(true) {
+                if index != 0 {
+                    return Err(StdError::generic_err(
+                        ContractError::FeePaymentNotAllowed {}.to_string(),
+                    ));
+                }
+
+                let protocol_fee = fee_config.protocol_reward_fee.checked_mul_uint(available)?;
+                available = available.saturating_sub(protocol_fee);
+                sub_balance(&mut balances, denom.to_string(), protocol_fee);
+                fees.add(&Coin::new(protocol_fee.u128(), denom.to_string()))?;
+            }
+
+            let used_amount = match max_amount {
+                Some(max_amount) if !max_amount.is_zero() => available.min(max_amount),
+                _ => available,
+            };
+
+            let (received_denom, amount) = chain.simulate_swap(
+                &deps.querier,
+                get_chain_config,
+                stage_type,
+                denom.clone(),
+                used_amount,
+            )?;
+            sub_balance(&mut balances, denom.to_string(), used_amount);
+            add_balance(&mut balances, received_denom.to_string(), amount)?;
+        }
+    }
+
+    // 4. Reinvest the received utoken and burn the received ustake
+    let unlocked_coins = Coins(state.unlocked_coins.load(deps.storage)?);
+    let utoken_received = balances.get(&stake.utoken).copied().unwrap_or_default();
+    let ustake_received = balances.get(&stake.denom).copied().unwrap_or_default();
+    let utoken_available = unlocked_coins
+        .find(&stake.utoken)
+        .amount
+        .checked_add(utoken_received.saturating_sub(utoken_before))?;
+    let ustake_available = unlocked_coins
+        .find(&stake.denom)
+        .amount
+        .checked_add(ustake_received.saturating_sub(ustake_before))?;
+
+    let protocol_reward_fee = if skip_fee {
+        Decimal::zero()
+    } else {
+        fee_config.protocol_reward_fee
+    };
+
+    let utoken_fee = protocol_reward_fee.checked_mul_uint(utoken_available)?;
+    let remaining = utoken_available.checked_sub(utoken_fee)?;
+    // the referral share of the utoken fee stays in the contract until claimed
+    let referral_rewards = simulate_referral_rewards(deps.storage, utoken_fee, stake.total_supply)?;
+    let utoken_fee = utoken_fee.checked_sub(referral_rewards)?;
+    // pending rebalance bounties are paid out of the remaining fee before it is split
+    let bounties = simulate_rebalance_bounties(deps.storage, utoken_fee)?;
+    let utoken_fee = utoken_fee.checked_sub(bounties)?;
+    let reserve = state.instant_unbond_reserve.may_load(deps.storage)?.unwrap_or_default();
+    let utoken_reserved = match state.instant_unbond_config.may_load(deps.storage)? {
+        Some(config) => config
+            .reserve_share
+            .checked_mul_uint(remaining)?
+            .min(config.reserve_target.saturating_sub(reserve)),
+        None => Uint128::zero(),
+    };
+    let utoken_bonded = remaining.checked_sub(utoken_reserved)?;
+
+    let ustake_fee = protocol_reward_fee.checked_mul_uint(ustake_available)?;
+    let ustake_burned = ustake_available.checked_sub(ustake_fee)?;
+
+    let utoken_staked =
+        query_all_delegations_amount(&deps.querier, &env.contract.address, &stake.utoken)?;
+
+    // fees of recipients that receive stake token are delegated as well and minted at the exchange
+    // rate after the rewards are bonded, like in `reinvest`
+    let utoken_after_bond = Uint128::new(utoken_staked).checked_add(utoken_bonded)?;
+    let mut utoken_fee_staked = Uint128::zero();
+    let mut ustake_fee_minted = Uint128::zero();
+    for (recipient, amount) in fee_config.split_fee(utoken_fee)? {
+        if recipient.is_staked() {
+            let ustake_to_mint = if utoken_after_bond.is_zero() {
+                amount
+            } else {
+                stake.total_supply.multiply_ratio(amount, utoken_after_bond)
+            };
+            utoken_fee_staked = utoken_fee_staked.checked_add(amount)?;
+            ustake_fee_minted = ustake_fee_minted.checked_add(ustake_to_mint)?;
+            fees.add(&Coin::new(ustake_to_mint.u128(), stake.denom.clone()))?;
+        } else {
+            fees.add(&Coin::new(amount.u128(), stake.utoken.clone()))?;
+        }
+    }
+    fees.add(&Coin::new(ustake_fee.u128(), stake.denom))?;
+
+    let total_supply =
+        stake.total_supply.checked_sub(ustake_burned)?.checked_add(ustake_fee_minted)?;
+    let exchange_rate = if total_supply.is_zero() {
+        Decimal::one()
+    } else {
+        Decimal::from_ratio(utoken_after_bond.checked_add(utoken_fee_staked)?, total_supply)
+    };
+
+    Ok(SimulateHarvestResponse {
+        rewards: rewards.0,
+        utoken_bonded,
+        utoken_reserved,
+        ustake_burned,
+        fees: fees
+            .0
+            .into_iter()
+            .filter(|coin| !coin.amount.is_zero())
+            .map(|coin| (coin.denom, coin.amount))
+            .collect(),
+        exchange_rate,
+    })
+}
+
+/// same as `State::get_or_preset` without the operator check, as nothing is executed
+fn get_or_preset<T>(
+    deps: Deps<CustomQueryType>,
+    items: Option<Vec<T>>,
+    preset: &Item<'static, Vec<T>>,
+) -> StdResult<Option<Vec<T>>>
+where
+    T: Serialize + DeserializeOwned,
+{
+    match items {
+        Some(items) if items.is_empty() => Ok(None),
+        Some(items) => Ok(Some(items)),
+        None => preset.may_load(deps.storage),
+    }
+}
+
+fn add_balance(
+    balances: &mut HashMap<String, Uint128>,
+    denom: String,
+    amount: Uint128,
+) -> StdResult<()> {
+    let balance = balances.entry(denom).or_default();
+    *balance = balance.checked_add(amount)?;
+    Ok(())
+}
+
+fn sub_balance(balances: &mut HashMap<String, Uint128>, denom: String, amount: Uint128) {
+    let balance = balances.entry(denom).or_default();
+    *balance = balance.saturating_sub(amount);
+}
@@ -0,0 +1,195 @@
+use cosmwasm_std::{
+    Addr, BankMsg, Coin, CosmosMsg, Decimal, Deps, DepsMut, Event, Response, StdResult, Storage,
+    Uint128,
+};
+use eris::hub::{Referral, ReferralInfoResponse, ReferralPool, ReferrerInfo};
+use eris_chain_adapter::types::CustomQueryType;
+
+use crate::error::{ContractError, ContractResult};
+use crate::state::State;
+
+/// Moves the rewards accrued since the last update of the referrer into its pending rewards.
+fn update_rewards(pool: &ReferralPool, info: &mut ReferrerInfo) {
+    info.pending_rewards += (pool.reward_index - info.reward_index) * info.shares;
+    info.reward_index = pool.reward_index;
+}
+
+/// Attributes the minted stake of a bond to the referrer. Users stay attributed to the referrer of
+/// their first referred bond.
+pub fn add_referred_shares(
+    storage: &mut dyn Storage,
+    user: &Addr,
+    referrer: Addr,
+    ustake_minted: Uint128,
+) -> Result<(), ContractError> {
+    if *user == referrer {
+        return Err(ContractError::SelfReferral {});
+    }
+
+    let state = State::default();
+    let mut referral = state.referrals.may_load(storage, user)?.unwrap_or(Referral {
+        referrer,
+        shares: Uint128::zero(),
+    });
+
+    let mut pool = state.referral_pool.may_load(storage)?.unwrap_or_default();
+    let mut info = state.referrers.may_load(storage, &referral.referrer)?.unwrap_or_default();
+    update_rewards(&pool, &mut info);
+
+    referral.shares += ustake_minted;
+    info.shares += ustake_minted;
+    pool.total_shares += ustake_minted;
+
+    state.referrals.save(storage, user, &referral)?;
+    state.referrers.save(storage, &referral.referrer, &info)?;
+    state.referral_pool.save(storage, &pool)?;
+
+    Ok(())
+}
+
+/// Removes unbonded stake of the user from the stake attributed to its referrer.
+pub fn remove_referred_shares(
+    storage: &mut dyn Storage,
+    user: &Addr,
+    ustake_burned: Uint128,
+) -> StdResult<()> {
+    let state = State::default();
+    let mut referral = match state.referrals.may_load(storage, user)? {
+        Some(referral) => referral,
+        None => return Ok(()),
+    };
+
+    let mut pool = state.referral_pool.load(storage)?;
+    let mut info = state.referrers.load(storage, &referral.referrer)?;
+    update_rewards(&pool, &mut info);
+
+    let removed = ustake_burned.min(referral.shares);
+    referral.shares -= removed;
+    info.shares -= removed;
+    pool.total_shares -= removed;
+
+    if referral.shares.is_zero() {
+        state.referrals.remove(storage, user);
+    } else {
+        state.referrals.save(storage, user, &referral)?;
+    }
+    state.referrers.save(storage, &referral.referrer, &info)?;
+    state.referral_pool.save(storage, &pool)?;
+
+    Ok(())
+}
+
+/// Accrues the referral share of the protocol fee earned on the referred stake and returns the
+/// utoken amount that is kept for the referrers.
+pub fn accrue_referral_rewards(
+    storage: &mut dyn Storage,
+    protocol_fee: Uint128,
+    ustake_supply: Uint128,
+) -> StdResult<Uint128> {
+    let state = State::default();
+    let mut pool = state.referral_pool.may_load(storage)?.unwrap_or_default();
+
+    let (rewards, index_increase) =
+        compute_referral_rewards(storage, &pool, protocol_fee, ustake_supply)?;
+    if rewards.is_zero() {
+        return Ok(Uint128::zero());
+    }
+
+    pool.reward_index += index_increase;
+    pool.total_pending += rewards;
+    state.referral_pool.save(storage, &pool)?;
+
+    Ok(rewards)
+}
+
+/// Returns the utoken amount of the protocol fee that `accrue_referral_rewards` would keep for the
+/// referrers, without accruing it.
+pub fn simulate_referral_rewards(
+    storage: &dyn Storage,
+    protocol_fee: Uint128,
+    ustake_supply: Uint128,
+) -> StdResult<Uint128> {
+    let pool = State::default().referral_pool.may_load(storage)?.unwrap_or_default();
+    Ok(compute_referral_rewards(storage, &pool, protocol_fee, ustake_supply)?.0)
+}
+
+/// Returns the referral rewards of the protocol fee and the increase of the reward index.
+fn compute_referral_rewards(
+    storage: &dyn Storage,
+    pool: &ReferralPool,
+    protocol_fee: Uint128,
+    ustake_supply: Uint128,
+) -> StdResult<(Uint128, Decimal)> {
+    let referral_share = State::default().referral_share.may_load(storage)?.unwrap_or_default();
+
+    if referral_share.is_zero() || pool.total_shares.is_zero() || ustake_supply.is_zero() {
+        return Ok((Uint128::zero(), Decimal::zero()));
+    }
+
+    let index_increase = Decimal::from_ratio(protocol_fee, ustake_supply) * referral_share;
+    // rounded up, so that the rewards of all referrers are always covered
+    let rewards = Decimal::from_ratio(pool.total_shares, 1u128) * index_increase;
+    let rewards = (Uint128::one() * rewards.ceil()).min(protocol_fee);
+
+    Ok((rewards, index_increase))
+}
+
+/// Returns the utoken held by the contract for referral rewards that are not claimed yet.
+pub fn pending_referral_rewards(storage: &dyn Storage) -> StdResult<Uint128> {
+    let state = State::default();
+    Ok(state.referral_pool.may_load(storage)?.unwrap_or_default().total_pending)
+}
+
+pub fn claim_referral_rewards(deps: DepsMut<CustomQueryType>, sender: Addr) -> ContractResult {
+    let state = State::default();
+    let stake = state.stake_token.load(deps.storage)?;
+
+    let mut pool = state.referral_pool.may_load(deps.storage)?.unwrap_or_default();
+    let mut info = state.referrers.may_load(deps.storage, &sender)?.unwrap_or_default();
+    update_rewards(&pool, &mut info);
+
+    let rewards = info.pending_rewards;
+    if rewards.is_zero() {
+        return Err(ContractError::CantBeZero("referral rewards".into()));
+    }
+
+    info.pending_rewards = Uint128::zero();
+    info.claimed_rewards += rewards;
+    // the pending total is rounded up when accrued, it always covers the claimed rewards
+    pool.total_pending = pool.total_pending.saturating_sub(rewards);
+    state.referrers.save(deps.storage, &sender, &info)?;
+    state.referral_pool.save(deps.storage, &pool)?;
+
+    let send_msg = CosmosMsg::Bank(BankMsg::Send {
+        to_address: sender.to_string(),
+        amount: vec![Coin::new(rewards.u128(), stake.utoken)],
+    });
+
+    Ok(Response::new()
+        .add_message(send_msg)
+        .add_event(
+            Event::new("erishub/referral_rewards_claimed")
+                .add_attribute("referrer", sender)
+                .add_attribute("utoken_claimed", rewards),
+        )
+        .add_attribute("action", "erishub/claim_referral_rewards"))
+}
+
+pub fn referral_info(
+    deps: Deps<CustomQueryType>,
+    referrer: String,
+) -> StdResult<ReferralInfoResponse> {
+    let state = State::default();
+    let referrer = deps.api.addr_validate(&referrer)?;
+
+    let pool = state.referral_pool.may_load(deps.storage)?.unwrap_or_default();
+    let mut info = state.referrers.may_load(deps.storage, &referrer)?.unwrap_or_default();
+    update_rewards(&pool, &mut info);
+
+    Ok(ReferralInfoResponse {
+        referrer: referrer.into(),
+        referred_ustake: info.shares,
+        pending_rewards: info.pending_rewards,
+        claimed_rewards: info.claimed_rewards,
+    })
+}
@@ -0,0 +1,168 @@
+use std::collections::HashMap;
+
+use cosmwasm_std::{
+    Decimal, Deps, DepsMut, Env, Event, MessageInfo, Order, Response, StdResult, Uint128,
+};
+use cw_storage_plus::Bound;
+use eris::hub::{SlashingEvent, SlashingEventsResponse};
+use eris::CustomResponse;
+use eris_chain_adapter::types::CustomQueryType;
+
+use crate::constants::{SLASHING_THRESHOLD_AMOUNT, SLASHING_THRESHOLD_BPS};
+use crate::error::ContractResult;
+use crate::execute::{check_received_coin_msg, remove_slashed_validator};
+use crate::helpers::query_all_delegations;
+use crate::state::State;
+
+const MAX_LIMIT: u32 = 30;
+const DEFAULT_LIMIT: u32 = 10;
+
+/// Compares the on-chain delegations with the tracked delegations of each validator. Every
+/// validator with less delegated than expected (above the rounding threshold) has been slashed,
+/// which is recorded in the slashing history. Afterwards the tracked amounts are synced with the
+/// on-chain delegations. Slashed validators are only removed when the owner runs the check.
+pub fn check_slashing(
+    mut deps: DepsMut<CustomQueryType>,
+    env: Env,
+    info: MessageInfo,
+) -> ContractResult {
+    let state = State::default();
+    let stake = state.stake_token.load(deps.storage)?;
+
+    let delegations = query_all_delegations(&deps.querier, &env.contract.address, &stake.utoken)?;
+    let actual: HashMap<String, Uint128> =
+        delegations.iter().map(|d| (d.validator.clone(), Uint128::new(d.amount))).collect();
+
+    let tracked = state
+        .validator_bonded
+        .range(deps.storage, None, None, Order::Ascending)
+        .collect::<StdResult<Vec<_>>>()?;
+
+    // validators that are not tracked yet are expected to have their current delegation
+    let untracked_total: Uint128 = actual
+        .iter()
+        .filter(|(validator, _)| !tracked.iter().any(|(v, _)| v == *validator))
+        .map(|(_, amount)| *amount)
+        .sum();
+    let tracked_total: Uint128 = tracked.iter().map(|(_, amount)| *amount).sum();
+    let mut total_utoken = untracked_total.checked_add(tracked_total)?;
+
+    let mut next_id = state
+        .slashing_events
+        .keys(deps.storage, None, None, Order::Descending)
+        .next()
+        .transpose()?
+        .map_or(1, |id| id + 1);
+
+    let mut slashed_validators: Vec<String> = vec![];
+    let mut events: Vec<Event> = vec![];
+    for (validator, expected) in &tracked {
+        let current = actual.get(validator).copied().unwrap_or_default();
+        if current >= *expected {
+            continue;
+        }
+
+        // small shortfalls are caused by rounding of the staking module
+        let amount = *expected - current;
+        let threshold = expected
+            .multiply_ratio(SLASHING_THRESHOLD_BPS, 10000u128)
+            .max(Uint128::new(SLASHING_THRESHOLD_AMOUNT));
+        if amount <= threshold {
+            continue;
+        }
+
+        let rate_before = calc_exchange_rate(total_utoken, stake.total_supply);
+        total_utoken = total_utoken.saturating_sub(amount);
+        let rate_after = calc_exchange_rate(total_utoken, stake.total_supply);
+
+        state.slashing_events.save(
+            deps.storage,
+            next_id,
+            &SlashingEvent {
+                asset: stake.utoken.clone(),
+                validator: validator.clone(),
+                amount,
+                time: env.block.time.seconds(),
+                rate_before,
+                rate_after,
+            },
+        )?;
+        next_id += 1;
+
+        events.push(
+            Event::new("erishub/slashed")
+                .add_attribute("validator", validator)
+                .add_attribute("utoken_slashed", amount)
+                .add_attribute("rate_before", rate_before.to_string())
+                .add_attribute("rate_after", rate_after.to_string()),
+        );
+        slashed_validators.push(validator.clone());
+    }
+
+    // sync the tracked amounts with the on-chain delegations
+    for (validator, _) in &tracked {
+        if !actual.contains_key(validator) {
+            state.validator_bonded.remove(deps.storage, validator);
+        }
+    }
+    for (validator, amount) in &actual {
+        state.validator_bonded.save(deps.storage, validator, amount)?;
+    }
+
+    let mut redelegate_msgs = vec![];
+    let is_owner = state.owner.load(deps.storage)? == info.sender;
+    if is_owner && state.remove_slashed_validators.may_load(deps.storage)?.unwrap_or(false) {
+        let validators = state.validators.load(deps.storage)?;
+        for validator in slashed_validators.iter().filter(|v| validators.contains(v)) {
+            // the last validator can't be removed
+            if state.validators.load(deps.storage)?.len() > 1 {
+                redelegate_msgs
+                    .extend(remove_slashed_validator(&state, &mut deps, &env, validator)?);
+                events.push(
+                    Event::new("erishub/validator_removed").add_attribute("validator", validator),
+                );
+            }
+        }
+    }
+
+    let check_msg = if !redelegate_msgs.is_empty() {
+        // only check coins if a redelegation is happening
+        Some(check_received_coin_msg(&deps, &env, stake, None)?)
+    } else {
+        None
+    };
+
+    Ok(Response::new()
+        .add_messages(redelegate_msgs)
+        .add_optional_message(check_msg)
+        .add_events(events)
+        .add_attribute("action", "erishub/check_slashing"))
+}
+
+fn calc_exchange_rate(total_utoken: Uint128, total_supply: Uint128) -> Decimal {
+    if total_supply.is_zero() {
+        Decimal::one()
+    } else {
+        Decimal::from_ratio(total_utoken, total_supply)
+    }
+}
+
+pub fn query_slashing_events(
+    deps: Deps<CustomQueryType>,
+    start_after: Option<u64>,
+    limit: Option<u32>,
+) -> StdResult<SlashingEventsResponse> {
+    let state = State::default();
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let end = start_after.map(Bound::exclusive);
+
+    let events = state
+        .slashing_events
+        .range(deps.storage, None, end, Order::Descending)
+        .take(limit)
+        .collect::<StdResult<Vec<_>>>()?;
+
+    Ok(SlashingEventsResponse {
+        events,
+    })
+}
@@ -1,14 +1,19 @@
-use cosmwasm_std::{Addr, Coin, Decimal, Storage};
-use cw_storage_plus::{Index, IndexList, IndexedMap, Item, Map, MultiIndex};
+use std::collections::HashMap;
 
+use cosmwasm_std::{Addr, Coin, Decimal, Order, StdResult, Storage, Uint128};
+use cw_storage_plus::{Index, IndexList, IndexedMap, Item, Map, MultiIndex, SnapshotMap, Strategy};
+
+use eris::circuit_breaker::{CircuitBreakerConfig, PauseState, VolumeTracker};
 use eris::hub::{
-    Batch, DelegationStrategy, FeeConfig, PendingBatch, SingleSwapConfig, StakeToken,
-    UnbondRequest, WantedDelegationsShare,
+    Batch, DelegationStrategy, FeeConfig, HolderBalance, HolderProposal, HolderVote,
+    InstantUnbondConfig, PendingBatch, PriceFeed, RebalanceConfig, Referral, ReferralPool,
+    ReferrerInfo, SingleSwapConfig, SlashingEvent, StakeToken, UnbondRequest,
+    WantedDelegationsShare,
 };
 use eris_chain_adapter::types::{DenomType, HubChainConfig, WithdrawType};
 use serde::{de::DeserializeOwned, Serialize};
 
-use crate::{error::ContractError, types::BooleanKey};
+use crate::{constants::DEFAULT_MAX_UNBONDING_ENTRIES, error::ContractError, types::BooleanKey};
 
 pub struct State<'a> {
     /// Account who can call certain privileged functions
@@ -56,6 +61,69 @@ pub struct State<'a> {
     pub exchange_history: Map<'a, u64, Decimal>,
 
     pub default_max_spread: Item<'a, u64>,
+
+    /// Instant unbond config, instant unbonding is disabled if not set
+    pub instant_unbond_config: Item<'a, InstantUnbondConfig>,
+    /// Amount of utoken held by the contract to pay out instant unbonds
+    pub instant_unbond_reserve: Item<'a, Uint128>,
+
+    /// Delegated utoken per validator as expected by the contract, used to detect slashing
+    pub validator_bonded: Map<'a, &'a str, Uint128>,
+    /// History of detected slashings
+    pub slashing_events: Map<'a, u64, SlashingEvent>,
+    /// Specifies whether slashed validators are removed from the whitelist
+    pub remove_slashed_validators: Item<'a, bool>,
+
+    /// Users that allow anyone to withdraw their finished unbondings to them
+    pub auto_claim: Map<'a, &'a Addr, bool>,
+
+    /// Estimated completion times of the active unbonding entries of each validator
+    pub unbonding_entries: Map<'a, &'a str, Vec<u64>>,
+    /// Maximum number of concurrent unbonding entries per validator
+    pub max_unbonding_entries: Item<'a, u32>,
+
+    /// Time in seconds since the holder balances are tracked by the before send hook
+    pub holder_voting_since: Item<'a, u64>,
+    /// Stake token balance of each holder after its transfers, checkpointed by block time
+    pub holder_balances: SnapshotMap<'a, &'a Addr, HolderBalance>,
+    /// Stake token balance of each holder before its first tracked transfer
+    pub holder_initial_balances: Map<'a, &'a Addr, HolderBalance>,
+    /// Proposals that are open for votes of the holders
+    pub holder_proposals: Map<'a, u64, HolderProposal>,
+    /// Votes of the holders for each proposal
+    pub holder_votes: Map<'a, (u64, &'a Addr), HolderVote>,
+
+    /// Price feed of each denom, used for swaps without belief price
+    pub price_feeds: Map<'a, &'a str, PriceFeed<Addr>>,
+    /// Last price pushed by the operator for denoms with a `Pushed` price feed, with its time
+    pub pushed_prices: Map<'a, &'a str, (Decimal, u64)>,
+
+    /// Account that can pause bond, unbond and harvest
+    pub guardian: Item<'a, Addr>,
+    /// Exchange rate and volume limits
+    pub circuit_breaker: Item<'a, CircuitBreakerConfig>,
+    /// Actions that are currently paused
+    pub paused: Item<'a, PauseState>,
+    /// Rolling volume of bonds and unbonds
+    pub volumes: Map<'a, &'a str, VolumeTracker>,
+
+    /// Share of the protocol fee on referred stake that is accrued to the referrers
+    pub referral_share: Item<'a, Decimal>,
+    /// Referred stake of all referrers with the global reward index
+    pub referral_pool: Item<'a, ReferralPool>,
+    /// Referred stake and rewards of each referrer
+    pub referrers: Map<'a, &'a Addr, ReferrerInfo>,
+    /// Referrer and referred stake of each user
+    pub referrals: Map<'a, &'a Addr, Referral>,
+
+    /// Budget and bounty of permissionless rebalances, they are disabled if not set
+    pub rebalance_config: Item<'a, RebalanceConfig>,
+    /// Epoch of the last permissionless rebalance and the utoken redelegated in it
+    pub rebalance_volume: Item<'a, (u64, Uint128)>,
+    /// Estimated completion time of the latest redelegation to each validator
+    pub redelegation_entries: Map<'a, &'a str, u64>,
+    /// Bounties of permissionless rebalances that are paid out of the next utoken protocol fee
+    pub pending_bounties: Map<'a, &'a Addr, Uint128>,
 }
 
 impl Default for State<'static> {
@@ -96,6 +164,38 @@ impl Default for State<'static> {
             chain_config: Item::new("chain_config"),
             exchange_history: Map::new("exchange_history"),
             default_max_spread: Item::new("default_max_spread"),
+            instant_unbond_config: Item::new("instant_unbond_config"),
+            instant_unbond_reserve: Item::new("instant_unbond_reserve"),
+            validator_bonded: Map::new("validator_bonded"),
+            slashing_events: Map::new("slashing_events"),
+            remove_slashed_validators: Item::new("remove_slashed_validators"),
+            auto_claim: Map::new("auto_claim"),
+            unbonding_entries: Map::new("unbonding_entries"),
+            max_unbonding_entries: Item::new("max_unbonding_entries"),
+            holder_voting_since: Item::new("holder_voting_since"),
+            holder_balances: SnapshotMap::new(
+                "holder_balances",
+                "holder_balances__checkpoints",
+                "holder_balances__changelog",
+                Strategy::EveryBlock,
+            ),
+            holder_initial_balances: Map::new("holder_initial_balances"),
+            holder_proposals: Map::new("holder_proposals"),
+            holder_votes: Map::new("holder_votes"),
+            price_feeds: Map::new("price_feeds"),
+            pushed_prices: Map::new("pushed_prices"),
+            guardian: Item::new("guardian"),
+            circuit_breaker: Item::new("circuit_breaker"),
+            paused: Item::new("paused"),
+            volumes: Map::new("volumes"),
+            referral_share: Item::new("referral_share"),
+            referral_pool: Item::new("referral_pool"),
+            referrers: Map::new("referrers"),
+            referrals: Map::new("referrals"),
+            rebalance_config: Item::new("rebalance_config"),
+            rebalance_volume: Item::new("rebalance_volume"),
+            redelegation_entries: Map::new("redelegation_entries"),
+            pending_bounties: Map::new("pending_bounties"),
         }
     }
 }
@@ -123,6 +223,19 @@ impl<'a> State<'a> {
         }
     }
 
+    pub fn assert_guardian_or_owner(
+        &self,
+        storage: &dyn Storage,
+        sender: &Addr,
+    ) -> Result<(), ContractError> {
+        if self.guardian.may_load(storage)?.as_ref() == Some(sender) {
+            return Ok(());
+        }
+
+        self.assert_owner(storage, sender)
+            .map_err(|_| ContractError::UnauthorizedSenderNotGuardian {})
+    }
+
     pub fn assert_vote_operator(
         &self,
         storage: &dyn Storage,
@@ -164,6 +277,105 @@ impl<'a> State<'a> {
         Ok(stages)
     }
 
+    pub fn track_delegation(
+        &self,
+        storage: &mut dyn Storage,
+        validator: &str,
+        amount: u128,
+    ) -> StdResult<()> {
+        self.validator_bonded.update(storage, validator, |bonded| -> StdResult<_> {
+            Ok(bonded.unwrap_or_default().checked_add(Uint128::new(amount))?)
+        })?;
+        Ok(())
+    }
+
+    pub fn track_undelegation(
+        &self,
+        storage: &mut dyn Storage,
+        validator: &str,
+        amount: u128,
+    ) -> StdResult<()> {
+        // validators that are not tracked yet are synced during the next slashing check
+        self.validator_bonded.update(storage, validator, |bonded| -> StdResult<_> {
+            Ok(bonded.unwrap_or_default().saturating_sub(Uint128::new(amount)))
+        })?;
+        Ok(())
+    }
+
+    /// Tracks the moved delegation and records the redelegation entry of the destination, which
+    /// can't redelegate to another validator until the entry completes after the unbond period.
+    pub fn track_redelegation(
+        &self,
+        storage: &mut dyn Storage,
+        src: &str,
+        dst: &str,
+        amount: u128,
+        time: u64,
+    ) -> StdResult<()> {
+        let unbond_period = self.unbond_period.load(storage)?;
+        self.redelegation_entries.save(storage, dst, &(time + unbond_period))?;
+
+        self.track_undelegation(storage, src, amount)?;
+        self.track_delegation(storage, dst, amount)
+    }
+
+    /// Removes the redelegation entries that are completed at the provided time and returns the
+    /// validators that still receive a redelegation. The SDK does not allow redelegating from them
+    /// until then.
+    pub fn prune_redelegation_entries(
+        &self,
+        storage: &mut dyn Storage,
+        time: u64,
+    ) -> StdResult<Vec<String>> {
+        let (pending, completed): (Vec<_>, Vec<_>) = self
+            .redelegation_entries
+            .range(storage, None, None, Order::Ascending)
+            .collect::<StdResult<Vec<_>>>()?
+            .into_iter()
+            .partition(|(_, completion_time)| *completion_time > time);
+
+        for (validator, _) in completed {
+            self.redelegation_entries.remove(storage, &validator);
+        }
+
+        Ok(pending.into_iter().map(|(validator, _)| validator).collect())
+    }
+
+    pub fn get_max_unbonding_entries(&self, storage: &dyn Storage) -> StdResult<u32> {
+        Ok(self.max_unbonding_entries.may_load(storage)?.unwrap_or(DEFAULT_MAX_UNBONDING_ENTRIES))
+    }
+
+    /// Number of unbonding entries of each validator that are not completed at the provided time
+    pub fn get_active_unbonding_entries(
+        &self,
+        storage: &dyn Storage,
+        time: u64,
+    ) -> StdResult<HashMap<String, u32>> {
+        self.unbonding_entries
+            .range(storage, None, None, Order::Ascending)
+            .map(|item| {
+                let (validator, completion_times) = item?;
+                let active = completion_times.iter().filter(|t| **t > time).count() as u32;
+                Ok((validator, active))
+            })
+            .collect()
+    }
+
+    /// Adds an unbonding entry to the validator, completed entries are removed
+    pub fn track_unbonding_entry(
+        &self,
+        storage: &mut dyn Storage,
+        validator: &str,
+        time: u64,
+        completion_time: u64,
+    ) -> StdResult<()> {
+        let mut completion_times =
+            self.unbonding_entries.may_load(storage, validator)?.unwrap_or_default();
+        completion_times.retain(|t| *t > time);
+        completion_times.push(completion_time);
+        self.unbonding_entries.save(storage, validator, &completion_times)
+    }
+
     pub fn get_default_max_spread(&self, storage: &dyn Storage) -> Decimal {
         // by default a max_spread of 10% is used.
         Decimal::percent(self.default_max_spread.load(storage).unwrap_or(10))
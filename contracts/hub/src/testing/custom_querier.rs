@@ -1,17 +1,47 @@
+use std::collections::HashMap;
+
+use cosmwasm_schema::cw_serde;
 use cosmwasm_std::testing::{BankQuerier, StakingQuerier, MOCK_CONTRACT_ADDR};
 use cosmwasm_std::{
-    from_json, Addr, Coin, Empty, FullDelegation, Querier, QuerierResult, QueryRequest,
-    SystemError, WasmQuery,
+    from_json, to_json_binary, Addr, Coin, ContractResult, Decimal, Empty, FullDelegation, Querier,
+    QuerierResult, QueryRequest, SystemError, SystemResult, Uint128, Validator, WasmQuery,
 };
+use eris::price_oracle::{PriceResponse, QueryMsg as PriceOracleQueryMsg};
+use eris::uptime_oracle::{QueryMsg as UptimeOracleQueryMsg, UptimesResponse};
 
 use crate::types::Delegation;
 
 use super::helpers::{err_unsupported_query, MOCK_UTOKEN};
 
+#[cw_serde]
+enum FinQueryMsg {
+    Config {},
+    Simulation {
+        offer_asset: Coin,
+    },
+}
+
+#[cw_serde]
+struct FinConfigResponse {
+    denoms: Vec<String>,
+}
+
+#[cw_serde]
+struct FinSimulationResponse {
+    return_amount: Uint128,
+}
+
 #[derive(Default)]
 pub(super) struct CustomQuerier {
     pub bank_querier: BankQuerier,
     pub staking_querier: StakingQuerier,
+    pub validators: Vec<Validator>,
+    pub delegations: Vec<FullDelegation>,
+    pub uptimes: HashMap<String, Decimal>,
+    /// FIN pairs with their base denom, quote denom and the price of the base denom
+    pub fin_pairs: HashMap<String, (String, String, Decimal)>,
+    /// Prices reported by the TWAP oracle with their update time
+    pub twap_prices: HashMap<String, (Decimal, u64)>,
 }
 
 impl Querier for CustomQuerier {
@@ -47,19 +77,118 @@ impl CustomQuerier {
             })
             .collect::<Vec<_>>();
 
-        self.staking_querier = StakingQuerier::new(MOCK_UTOKEN, &[], &fds);
+        self.delegations = fds;
+        self.staking_querier =
+            StakingQuerier::new(MOCK_UTOKEN, &self.validators, &self.delegations);
+    }
+
+    /// Validators with their commission, validators not in the list are treated as jailed
+    pub fn set_staking_validators(&mut self, validators: &[(&str, Decimal)]) {
+        self.validators = validators
+            .iter()
+            .map(|(address, commission)| Validator {
+                address: address.to_string(),
+                commission: *commission,
+                max_commission: Decimal::one(),
+                max_change_rate: Decimal::one(),
+            })
+            .collect();
+
+        self.staking_querier =
+            StakingQuerier::new(MOCK_UTOKEN, &self.validators, &self.delegations);
+    }
+
+    /// Rewards of each validator that are pending to be withdrawn
+    pub fn set_staking_rewards(&mut self, rewards: &[(&str, Vec<Coin>)]) {
+        for delegation in self.delegations.iter_mut() {
+            delegation.accumulated_rewards = rewards
+                .iter()
+                .find(|(validator, _)| *validator == delegation.validator)
+                .map(|(_, coins)| coins.clone())
+                .unwrap_or_default();
+        }
+
+        self.staking_querier =
+            StakingQuerier::new(MOCK_UTOKEN, &self.validators, &self.delegations);
+    }
+
+    pub fn set_fin_pair(&mut self, contract_addr: &str, base: &str, quote: &str, price: Decimal) {
+        self.fin_pairs
+            .insert(contract_addr.to_string(), (base.to_string(), quote.to_string(), price));
+    }
+
+    pub fn set_twap_price(&mut self, denom: &str, price: Decimal, updated_at: u64) {
+        self.twap_prices.insert(denom.to_string(), (price, updated_at));
+    }
+
+    pub fn set_uptimes(&mut self, uptimes: &[(&str, Decimal)]) {
+        self.uptimes = uptimes.iter().map(|(v, uptime)| (v.to_string(), *uptime)).collect();
     }
 
     pub fn handle_query(&self, request: &QueryRequest<Empty>) -> QuerierResult {
         match request {
             QueryRequest::Wasm(WasmQuery::Smart {
+                contract_addr,
                 msg,
-                ..
             }) => {
                 // if let Ok(query) = from_json::<Cw20QueryMsg>(msg) {
                 //     return self.cw20_querier.handle_query(contract_addr, query);
                 // }
 
+                if let Ok(UptimeOracleQueryMsg::Uptimes {
+                    validators,
+                }) = from_json(msg)
+                {
+                    let uptimes = validators
+                        .into_iter()
+                        .filter_map(|v| self.uptimes.get(&v).map(|uptime| (v, *uptime)))
+                        .collect();
+                    return SystemResult::Ok(ContractResult::Ok(
+                        to_json_binary(&UptimesResponse {
+                            uptimes,
+                        })
+                        .unwrap(),
+                    ));
+                }
+
+                if let Ok(PriceOracleQueryMsg::Price {
+                    denom,
+                }) = from_json(msg)
+                {
+                    return match self.twap_prices.get(&denom) {
+                        Some((price, updated_at)) => SystemResult::Ok(ContractResult::Ok(
+                            to_json_binary(&PriceResponse {
+                                price: *price,
+                                updated_at: *updated_at,
+                            })
+                            .unwrap(),
+                        )),
+                        None => err_unsupported_query(msg),
+                    };
+                }
+
+                if let Some((base, quote, price)) = self.fin_pairs.get(contract_addr) {
+                    let res = match from_json(msg) {
+                        Ok(FinQueryMsg::Config {}) => to_json_binary(&FinConfigResponse {
+                            denoms: vec![base.clone(), quote.clone()],
+                        }),
+                        Ok(FinQueryMsg::Simulation {
+                            offer_asset,
+                        }) => {
+                            let return_amount = if offer_asset.denom == *base {
+                                offer_asset.amount * *price
+                            } else {
+                                offer_asset.amount * (Decimal::one() / *price)
+                            };
+                            to_json_binary(&FinSimulationResponse {
+                                return_amount,
+                            })
+                        },
+                        Err(_) => return err_unsupported_query(msg),
+                    };
+                    return SystemResult::Ok(ContractResult::Ok(res.unwrap()));
+                }
+
                 err_unsupported_query(msg)
             },
 
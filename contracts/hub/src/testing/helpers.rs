@@ -4,12 +4,16 @@ use cosmwasm_std::{
     OwnedDeps, QuerierResult, SubMsg, SystemError, SystemResult, Timestamp, Uint128, WasmMsg,
 };
 use eris_chain_adapter::types::{
-    chain, test_chain_config, CustomMsgType, CustomQueryType, DenomType, HubChainConfig, StageType,
-    WithdrawType,
+    chain, test_chain_config, CustomMsgType, CustomQueryType, DenomType, HubChainConfig,
+    HubChainConfigInput, StageType, WithdrawType,
 };
 use serde::de::DeserializeOwned;
 
-use eris::hub::{CallbackMsg, ExecuteMsg, InstantiateMsg, QueryMsg, StakeToken};
+use eris::circuit_breaker::CircuitBreakerConfig;
+use eris::hub::{
+    CallbackMsg, DelegationStrategy, ExecuteMsg, FeeRecipient, InstantUnbondConfig, InstantiateMsg,
+    QueryMsg, RebalanceConfig, SingleSwapConfig, StakeToken,
+};
 
 use crate::contract::{instantiate, query};
 use crate::state::State;
@@ -139,3 +143,59 @@ pub fn chain_test(
 ) -> impl ChainInterface<CustomMsgType, DenomType, WithdrawType, StageType, HubChainConfig> {
     chain(&mock_env())
 }
+
+/// Builds an `ExecuteMsg::UpdateConfig`, leaving every field that is not set untouched.
+#[derive(Default)]
+pub(super) struct UpdateConfigBuilder {
+    pub protocol_fee_contract: Option<String>,
+    pub protocol_reward_fee: Option<Decimal>,
+    pub fee_recipients: Option<Vec<FeeRecipient>>,
+    pub operator: Option<String>,
+    pub stages_preset: Option<Vec<Vec<SingleSwapConfig>>>,
+    pub withdrawals_preset: Option<Vec<(WithdrawType, DenomType)>>,
+    pub allow_donations: Option<bool>,
+    pub delegation_strategy: Option<DelegationStrategy>,
+    pub vote_operator: Option<String>,
+    pub chain_config: Option<HubChainConfigInput>,
+    pub default_max_spread: Option<u64>,
+    pub epoch_period: Option<u64>,
+    pub unbond_period: Option<u64>,
+    pub instant_unbond_config: Option<InstantUnbondConfig>,
+    pub remove_instant_unbond_config: Option<bool>,
+    pub remove_slashed_validators: Option<bool>,
+    pub max_unbonding_entries: Option<u32>,
+    pub guardian: Option<String>,
+    pub remove_guardian: Option<bool>,
+    pub circuit_breaker: Option<CircuitBreakerConfig>,
+    pub referral_share: Option<Decimal>,
+    pub rebalance_config: Option<RebalanceConfig>,
+}
+
+impl UpdateConfigBuilder {
+    pub fn build(self) -> ExecuteMsg {
+        ExecuteMsg::UpdateConfig {
+            protocol_fee_contract: self.protocol_fee_contract,
+            protocol_reward_fee: self.protocol_reward_fee,
+            fee_recipients: self.fee_recipients,
+            operator: self.operator,
+            stages_preset: self.stages_preset,
+            withdrawals_preset: self.withdrawals_preset,
+            allow_donations: self.allow_donations,
+            delegation_strategy: self.delegation_strategy,
+            vote_operator: self.vote_operator,
+            chain_config: self.chain_config,
+            default_max_spread: self.default_max_spread,
+            epoch_period: self.epoch_period,
+            unbond_period: self.unbond_period,
+            instant_unbond_config: self.instant_unbond_config,
+            remove_instant_unbond_config: self.remove_instant_unbond_config,
+            remove_slashed_validators: self.remove_slashed_validators,
+            max_unbonding_entries: self.max_unbonding_entries,
+            guardian: self.guardian,
+            remove_guardian: self.remove_guardian,
+            circuit_breaker: self.circuit_breaker,
+            referral_share: self.referral_share,
+            rebalance_config: self.rebalance_config,
+        }
+    }
+}
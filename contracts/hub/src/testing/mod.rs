@@ -1,7 +1,21 @@
 mod custom_querier;
 mod helpers;
 pub mod test_defined_delegations;
+pub mod tests_auto_claim;
+pub mod tests_circuit_breaker;
 pub mod tests_claim;
+pub mod tests_composite;
 mod tests_default;
 pub mod tests_exchange_rates;
+pub mod tests_fee_recipients;
 pub mod tests_gauges;
+pub mod tests_holder_votes;
+pub mod tests_instant_unbond;
+pub mod tests_performance;
+pub mod tests_price_feeds;
+pub mod tests_rebalance;
+mod tests_referral;
+pub mod tests_simulate_harvest;
+pub mod tests_slashing;
+pub mod tests_unbond_requests;
+pub mod tests_unbonding_entries;
@@ -3,6 +3,7 @@ use std::str::FromStr;
 use cosmwasm_std::testing::{mock_env, mock_info, MockApi, MockStorage};
 use cosmwasm_std::{coin, Addr, Coin, Decimal, OwnedDeps, StdError, SubMsg, Uint128};
 
+use eris::circuit_breaker::PauseState;
 use eris::governance_helper::{EPOCH_START, WEEK};
 use eris::hub::{
     ConfigResponse, DelegationStrategy, ExecuteMsg, FeeConfig, InstantiateMsg, QueryMsg,
@@ -79,7 +80,8 @@ fn setup_test() -> OwnedDeps<MockStorage, MockApi, CustomQuerier, CustomQueryTyp
             shares: vec![
                 ("alice".into(), Decimal::from_str("0.6").unwrap()),
                 ("bob".into(), Decimal::from_str("0.4").unwrap())
-            ]
+            ],
+            parts: None,
         }
     );
 
@@ -91,7 +93,8 @@ fn setup_test() -> OwnedDeps<MockStorage, MockApi, CustomQuerier, CustomQueryTyp
         WantedDelegationsResponse {
             tune_time_period: Some((EPOCH_START + WEEK, 1)),
             // nothing bonded yet
-            delegations: vec![("alice".into(), Uint128::zero()), ("bob".into(), Uint128::zero())]
+            delegations: vec![("alice".into(), Uint128::zero()), ("bob".into(), Uint128::zero())],
+            parts: None,
         },
     );
 
@@ -118,7 +121,8 @@ fn proper_instantiation() {
             validators: vec!["alice".to_string(), "bob".to_string(), "charlie".to_string()],
             fee_config: FeeConfig {
                 protocol_fee_contract: Addr::unchecked("fee"),
-                protocol_reward_fee: Decimal::from_ratio(1u128, 100u128)
+                protocol_reward_fee: Decimal::from_ratio(1u128, 100u128),
+                fee_recipients: vec![],
             },
             operator: "operator".to_string(),
             stages_preset: vec![],
@@ -128,7 +132,15 @@ fn proper_instantiation() {
                 shares_bps: vec![("alice".into(), 6000), ("bob".into(), 4000)],
             },
             vote_operator: None,
-            utoken: MOCK_UTOKEN.to_string()
+            utoken: MOCK_UTOKEN.to_string(),
+            instant_unbond_config: None,
+            remove_slashed_validators: false,
+            max_unbonding_entries: 7,
+            guardian: None,
+            circuit_breaker: None,
+            paused: PauseState::default(),
+            referral_share: Decimal::zero(),
+            rebalance_config: None,
         }
     );
 
@@ -158,6 +170,7 @@ fn validate_update() {
         ExecuteMsg::UpdateConfig {
             protocol_fee_contract: None,
             protocol_reward_fee: None,
+            fee_recipients: None,
             operator: None,
             stages_preset: None,
             withdrawals_preset: None,
@@ -170,6 +183,15 @@ fn validate_update() {
             default_max_spread: None,
             epoch_period: None,
             unbond_period: None,
+            instant_unbond_config: None,
+            remove_instant_unbond_config: None,
+            remove_slashed_validators: None,
+            max_unbonding_entries: None,
+            guardian: None,
+            remove_guardian: None,
+            circuit_breaker: None,
+            referral_share: None,
+            rebalance_config: None,
         },
     )
     .unwrap_err();
@@ -182,6 +204,7 @@ fn validate_update() {
         ExecuteMsg::UpdateConfig {
             protocol_fee_contract: None,
             protocol_reward_fee: None,
+            fee_recipients: None,
             operator: None,
             stages_preset: None,
             withdrawals_preset: None,
@@ -195,6 +218,15 @@ fn validate_update() {
 
             epoch_period: None,
             unbond_period: None,
+            instant_unbond_config: None,
+            remove_instant_unbond_config: None,
+            remove_slashed_validators: None,
+            max_unbonding_entries: None,
+            guardian: None,
+            remove_guardian: None,
+            circuit_breaker: None,
+            referral_share: None,
+            rebalance_config: None,
         },
     )
     .unwrap_err();
@@ -207,6 +239,7 @@ fn validate_update() {
         ExecuteMsg::UpdateConfig {
             protocol_fee_contract: None,
             protocol_reward_fee: None,
+            fee_recipients: None,
             operator: None,
             stages_preset: None,
             withdrawals_preset: None,
@@ -219,6 +252,15 @@ fn validate_update() {
             default_max_spread: None,
             epoch_period: None,
             unbond_period: None,
+            instant_unbond_config: None,
+            remove_instant_unbond_config: None,
+            remove_slashed_validators: None,
+            max_unbonding_entries: None,
+            guardian: None,
+            remove_guardian: None,
+            circuit_breaker: None,
+            referral_share: None,
+            rebalance_config: None,
         },
     )
     .unwrap_err();
@@ -231,6 +273,7 @@ fn validate_update() {
         ExecuteMsg::UpdateConfig {
             protocol_fee_contract: None,
             protocol_reward_fee: None,
+            fee_recipients: None,
             operator: None,
             stages_preset: None,
             withdrawals_preset: None,
@@ -243,6 +286,15 @@ fn validate_update() {
             default_max_spread: None,
             epoch_period: None,
             unbond_period: None,
+            instant_unbond_config: None,
+            remove_instant_unbond_config: None,
+            remove_slashed_validators: None,
+            max_unbonding_entries: None,
+            guardian: None,
+            remove_guardian: None,
+            circuit_breaker: None,
+            referral_share: None,
+            rebalance_config: None,
         },
     )
     .unwrap();
@@ -262,6 +314,7 @@ fn bonding() {
         mock_info("user_1", &[Coin::new(1000000, MOCK_UTOKEN)]),
         ExecuteMsg::Bond {
             receiver: None,
+            referrer: None,
         },
     )
     .unwrap();
@@ -313,6 +366,7 @@ fn bonding() {
         mock_info("user_2", &[Coin::new(12345, MOCK_UTOKEN)]),
         ExecuteMsg::Bond {
             receiver: Some("user_3".to_string()),
+            referrer: None,
         },
     )
     .unwrap();
@@ -371,7 +425,8 @@ fn bonding() {
             delegations: vec![
                 ("alice".into(), Uint128::new(622407)),
                 ("bob".into(), Uint128::new(414938))
-            ]
+            ],
+            parts: None,
         },
     );
 
@@ -0,0 +1,271 @@
+use cosmwasm_std::testing::{mock_info, MockApi, MockStorage};
+use cosmwasm_std::{coin, Addr, BankMsg, CosmosMsg, Event, OwnedDeps, SubMsg, Uint128};
+
+use eris::hub::{Batch, ClaimableUsersResponseItem, ExecuteMsg, QueryMsg, UnbondRequest};
+use eris_chain_adapter::types::CustomQueryType;
+
+use crate::contract::execute;
+use crate::error::ContractError;
+use crate::state::State;
+use crate::testing::helpers::{query_helper_env, setup_test, MOCK_UTOKEN};
+
+use super::custom_querier::CustomQuerier;
+use super::helpers::mock_env_at_timestamp;
+
+fn setup_auto_claim() -> OwnedDeps<MockStorage, MockApi, CustomQuerier, CustomQueryType> {
+    let mut deps = setup_test();
+    let state = State::default();
+
+    let batches = vec![
+        Batch {
+            id: 1,
+            reconciled: true,
+            total_shares: Uint128::new(200),
+            utoken_unclaimed: Uint128::new(400),
+            est_unbond_end_time: 1000,
+        },
+        Batch {
+            id: 2,
+            reconciled: false,
+            total_shares: Uint128::new(10),
+            utoken_unclaimed: Uint128::new(20),
+            est_unbond_end_time: 1000,
+        },
+    ];
+    for batch in &batches {
+        state.previous_batches.save(deps.as_mut().storage, batch.id, batch).unwrap();
+    }
+
+    let requests =
+        vec![(1, "user_1", 100), (1, "user_2", 50), (1, "user_3", 50), (2, "user_1", 10)];
+    for (id, user, shares) in requests {
+        let user = Addr::unchecked(user);
+        state
+            .unbond_requests
+            .save(
+                deps.as_mut().storage,
+                (id, &user),
+                &UnbondRequest {
+                    id,
+                    user: user.clone(),
+                    shares: Uint128::new(shares),
+                },
+            )
+            .unwrap();
+    }
+
+    for user in ["user_1", "user_3"].iter() {
+        execute(
+            deps.as_mut(),
+            mock_env_at_timestamp(500),
+            mock_info(user, &[]),
+            ExecuteMsg::SetAutoClaim {
+                enabled: true,
+            },
+        )
+        .unwrap();
+    }
+
+    deps
+}
+
+//--------------------------------------------------------------------------------------------------
+// Execution
+//--------------------------------------------------------------------------------------------------
+
+#[test]
+fn setting_auto_claim() {
+    let mut deps = setup_test();
+    let state = State::default();
+
+    let res = execute(
+        deps.as_mut(),
+        mock_env_at_timestamp(500),
+        mock_info("user_1", &[]),
+        ExecuteMsg::SetAutoClaim {
+            enabled: true,
+        },
+    )
+    .unwrap();
+    assert_eq!(
+        res.events,
+        vec![Event::new("erishub/auto_claim_updated")
+            .add_attribute("user", "user_1")
+            .add_attribute("enabled", "true")]
+    );
+    assert!(state.auto_claim.load(deps.as_ref().storage, &Addr::unchecked("user_1")).unwrap());
+
+    execute(
+        deps.as_mut(),
+        mock_env_at_timestamp(500),
+        mock_info("user_1", &[]),
+        ExecuteMsg::SetAutoClaim {
+            enabled: false,
+        },
+    )
+    .unwrap();
+    assert_eq!(
+        state.auto_claim.may_load(deps.as_ref().storage, &Addr::unchecked("user_1")).unwrap(),
+        None
+    );
+}
+
+#[test]
+fn withdrawing_unbonded_for_users() {
+    let mut deps = setup_auto_claim();
+    let state = State::default();
+
+    // user_2 has not enabled auto claim and the invalid address is skipped
+    let res = execute(
+        deps.as_mut(),
+        mock_env_at_timestamp(2000),
+        mock_info("bot", &[]),
+        ExecuteMsg::WithdrawUnbondedFor {
+            users: vec![
+                "user_1".to_string(),
+                "".to_string(),
+                "user_2".to_string(),
+                "user_3".to_string(),
+            ],
+        },
+    )
+    .unwrap();
+
+    // the requests of the unreconciled batch 2 are kept
+    assert_eq!(
+        res.messages,
+        vec![
+            SubMsg::new(CosmosMsg::Bank(BankMsg::Send {
+                to_address: "user_1".to_string(),
+                amount: vec![coin(200, MOCK_UTOKEN)],
+            })),
+            SubMsg::new(CosmosMsg::Bank(BankMsg::Send {
+                to_address: "user_3".to_string(),
+                amount: vec![coin(100, MOCK_UTOKEN)],
+            })),
+        ]
+    );
+    assert_eq!(
+        state.previous_batches.load(deps.as_ref().storage, 1).unwrap(),
+        Batch {
+            id: 1,
+            reconciled: true,
+            total_shares: Uint128::new(50),
+            utoken_unclaimed: Uint128::new(100),
+            est_unbond_end_time: 1000,
+        }
+    );
+    assert!(state.unbond_requests.has(deps.as_ref().storage, (2, &Addr::unchecked("user_1"))));
+    assert!(state.unbond_requests.has(deps.as_ref().storage, (1, &Addr::unchecked("user_2"))));
+
+    let err = execute(
+        deps.as_mut(),
+        mock_env_at_timestamp(2000),
+        mock_info("bot", &[]),
+        ExecuteMsg::WithdrawUnbondedFor {
+            users: vec!["user_1".to_string(), "user_3".to_string()],
+        },
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::CantBeZero("withdrawable amount".into()));
+
+    // a batch of only invalid addresses has nothing to withdraw
+    let err = execute(
+        deps.as_mut(),
+        mock_env_at_timestamp(2000),
+        mock_info("bot", &[]),
+        ExecuteMsg::WithdrawUnbondedFor {
+            users: vec!["".to_string()],
+        },
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::CantBeZero("withdrawable amount".into()));
+}
+
+//--------------------------------------------------------------------------------------------------
+// Queries
+//--------------------------------------------------------------------------------------------------
+
+#[test]
+fn querying_claimable_users() {
+    let deps = setup_auto_claim();
+
+    // user_2 has not enabled auto claim
+    let res: Vec<ClaimableUsersResponseItem> = query_helper_env(
+        deps.as_ref(),
+        QueryMsg::ClaimableUsers {
+            start_after: None,
+            limit: None,
+        },
+        2000,
+    );
+    assert_eq!(
+        res,
+        vec![
+            ClaimableUsersResponseItem {
+                user: "user_1".to_string(),
+                utoken_claimable: Uint128::new(200),
+            },
+            ClaimableUsersResponseItem {
+                user: "user_3".to_string(),
+                utoken_claimable: Uint128::new(100),
+            },
+        ]
+    );
+
+    let res: Vec<ClaimableUsersResponseItem> = query_helper_env(
+        deps.as_ref(),
+        QueryMsg::ClaimableUsers {
+            start_after: Some("user_1".to_string()),
+            limit: Some(1),
+        },
+        2000,
+    );
+    assert_eq!(
+        res,
+        vec![ClaimableUsersResponseItem {
+            user: "user_3".to_string(),
+            utoken_claimable: Uint128::new(100),
+        }]
+    );
+
+    // nothing has finished unbonding yet
+    let res: Vec<ClaimableUsersResponseItem> = query_helper_env(
+        deps.as_ref(),
+        QueryMsg::ClaimableUsers {
+            start_after: None,
+            limit: None,
+        },
+        1000,
+    );
+    assert_eq!(
+        res,
+        vec![
+            ClaimableUsersResponseItem {
+                user: "user_1".to_string(),
+                utoken_claimable: Uint128::zero(),
+            },
+            ClaimableUsersResponseItem {
+                user: "user_3".to_string(),
+                utoken_claimable: Uint128::zero(),
+            },
+        ]
+    );
+
+    // each page only scans the users with auto claim enabled
+    let res: Vec<ClaimableUsersResponseItem> = query_helper_env(
+        deps.as_ref(),
+        QueryMsg::ClaimableUsers {
+            start_after: None,
+            limit: Some(1),
+        },
+        2000,
+    );
+    assert_eq!(
+        res,
+        vec![ClaimableUsersResponseItem {
+            user: "user_1".to_string(),
+            utoken_claimable: Uint128::new(200),
+        }]
+    );
+}
@@ -0,0 +1,358 @@
+use cosmwasm_std::testing::{mock_info, MockApi, MockStorage, MOCK_CONTRACT_ADDR};
+use cosmwasm_std::{Coin, Decimal, Event, OwnedDeps, StdError, Uint128};
+
+use eris::circuit_breaker::{CircuitBreakerConfig, PausableAction, PauseState};
+use eris::hub::{CallbackMsg, ConfigResponse, ExecuteMsg, QueryMsg};
+use eris_chain_adapter::types::CustomQueryType;
+
+use crate::contract::execute;
+use crate::error::ContractError;
+use crate::state::State;
+use crate::testing::helpers::{
+    get_stake_full_denom, query_helper, set_total_stake_supply, setup_test, MOCK_UTOKEN,
+};
+use crate::types::Delegation;
+
+use super::custom_querier::CustomQuerier;
+use super::helpers::{mock_env_at_timestamp, UpdateConfigBuilder};
+
+fn circuit_breaker_config() -> CircuitBreakerConfig {
+    CircuitBreakerConfig {
+        max_exchange_rate_change: Some(Decimal::percent(5)),
+        volume_window: 1000,
+        max_bond_volume: Some(Uint128::new(1000)),
+        max_unbond_volume: Some(Uint128::new(500)),
+    }
+}
+
+fn setup_circuit_breaker() -> OwnedDeps<MockStorage, MockApi, CustomQuerier, CustomQueryType> {
+    let mut deps = setup_test();
+    let state = State::default();
+
+    deps.querier.set_staking_delegations(&[
+        Delegation::new("alice", 333334, MOCK_UTOKEN),
+        Delegation::new("bob", 333333, MOCK_UTOKEN),
+        Delegation::new("charlie", 333333, MOCK_UTOKEN),
+    ]);
+    set_total_stake_supply(&state, &mut deps, 1000000);
+
+    execute(
+        deps.as_mut(),
+        mock_env_at_timestamp(10000),
+        mock_info("owner", &[]),
+        UpdateConfigBuilder {
+            guardian: Some("guardian".to_string()),
+            circuit_breaker: Some(circuit_breaker_config()),
+            ..Default::default()
+        }
+        .build(),
+    )
+    .unwrap();
+
+    deps
+}
+
+fn bond(
+    deps: &mut OwnedDeps<MockStorage, MockApi, CustomQuerier, CustomQueryType>,
+    timestamp: u64,
+    amount: u128,
+) -> Result<(), ContractError> {
+    execute(
+        deps.as_mut(),
+        mock_env_at_timestamp(timestamp),
+        mock_info("user_1", &[Coin::new(amount, MOCK_UTOKEN)]),
+        ExecuteMsg::Bond {
+            receiver: None,
+            referrer: None,
+        },
+    )
+    .map(|_| ())
+}
+
+fn queue_unbond(
+    deps: &mut OwnedDeps<MockStorage, MockApi, CustomQuerier, CustomQueryType>,
+    timestamp: u64,
+    amount: u128,
+) -> Result<(), ContractError> {
+    execute(
+        deps.as_mut(),
+        mock_env_at_timestamp(timestamp),
+        mock_info("user_1", &[Coin::new(amount, get_stake_full_denom())]),
+        ExecuteMsg::QueueUnbond {
+            receiver: None,
+        },
+    )
+    .map(|_| ())
+}
+
+fn set_paused(
+    deps: &mut OwnedDeps<MockStorage, MockApi, CustomQuerier, CustomQueryType>,
+    sender: &str,
+    action: PausableAction,
+    paused: bool,
+) -> Result<(), ContractError> {
+    execute(
+        deps.as_mut(),
+        mock_env_at_timestamp(10000),
+        mock_info(sender, &[]),
+        ExecuteMsg::SetPaused {
+            action,
+            paused,
+        },
+    )
+    .map(|_| ())
+}
+
+//--------------------------------------------------------------------------------------------------
+// Execution
+//--------------------------------------------------------------------------------------------------
+
+#[test]
+fn updating_circuit_breaker() {
+    let mut deps = setup_test();
+
+    let err = execute(
+        deps.as_mut(),
+        mock_env_at_timestamp(10000),
+        mock_info("owner", &[]),
+        UpdateConfigBuilder {
+            circuit_breaker: Some(CircuitBreakerConfig {
+                volume_window: 0,
+                ..circuit_breaker_config()
+            }),
+            ..Default::default()
+        }
+        .build(),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::Std(StdError::generic_err("volume_window can't be zero")));
+
+    let err = execute(
+        deps.as_mut(),
+        mock_env_at_timestamp(10000),
+        mock_info("owner", &[]),
+        UpdateConfigBuilder {
+            circuit_breaker: Some(CircuitBreakerConfig {
+                max_exchange_rate_change: Some(Decimal::zero()),
+                ..circuit_breaker_config()
+            }),
+            ..Default::default()
+        }
+        .build(),
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::Std(StdError::generic_err("max_exchange_rate_change can't be zero"))
+    );
+
+    execute(
+        deps.as_mut(),
+        mock_env_at_timestamp(10000),
+        mock_info("owner", &[]),
+        UpdateConfigBuilder {
+            guardian: Some("guardian".to_string()),
+            circuit_breaker: Some(circuit_breaker_config()),
+            ..Default::default()
+        }
+        .build(),
+    )
+    .unwrap();
+
+    let res: ConfigResponse = query_helper(deps.as_ref(), QueryMsg::Config {});
+    assert_eq!(res.guardian, Some("guardian".to_string()));
+    assert_eq!(res.circuit_breaker, Some(circuit_breaker_config()));
+    assert_eq!(res.paused, PauseState::default());
+
+    execute(
+        deps.as_mut(),
+        mock_env_at_timestamp(10000),
+        mock_info("owner", &[]),
+        UpdateConfigBuilder {
+            remove_guardian: Some(true),
+            ..Default::default()
+        }
+        .build(),
+    )
+    .unwrap();
+
+    let res: ConfigResponse = query_helper(deps.as_ref(), QueryMsg::Config {});
+    assert_eq!(res.guardian, None);
+}
+
+#[test]
+fn pausing_actions() {
+    let mut deps = setup_circuit_breaker();
+
+    let err = set_paused(&mut deps, "user_1", PausableAction::Bond, true).unwrap_err();
+    assert_eq!(err, ContractError::UnauthorizedSenderNotGuardian {});
+
+    let res = execute(
+        deps.as_mut(),
+        mock_env_at_timestamp(10000),
+        mock_info("guardian", &[]),
+        ExecuteMsg::SetPaused {
+            action: PausableAction::Bond,
+            paused: true,
+        },
+    )
+    .unwrap();
+    assert_eq!(
+        res.events,
+        vec![Event::new("erishub/paused_updated")
+            .add_attribute("pausable_action", "bond")
+            .add_attribute("paused", "true")]
+    );
+    set_paused(&mut deps, "owner", PausableAction::Unbond, true).unwrap();
+    set_paused(&mut deps, "guardian", PausableAction::Harvest, true).unwrap();
+
+    let res: ConfigResponse = query_helper(deps.as_ref(), QueryMsg::Config {});
+    assert_eq!(
+        res.paused,
+        PauseState {
+            bond: true,
+            unbond: true,
+            harvest: true,
+        }
+    );
+
+    assert_eq!(bond(&mut deps, 10000, 100).unwrap_err(), ContractError::Paused("bond".into()));
+    assert_eq!(
+        queue_unbond(&mut deps, 10000, 100).unwrap_err(),
+        ContractError::Paused("unbond".into())
+    );
+    let err = execute(
+        deps.as_mut(),
+        mock_env_at_timestamp(10000),
+        mock_info("operator", &[]),
+        ExecuteMsg::Harvest {
+            validators: None,
+            withdrawals: None,
+            stages: None,
+        },
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::Paused("harvest".into()));
+
+    // only the owner can resume
+    let err = set_paused(&mut deps, "guardian", PausableAction::Bond, false).unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+
+    set_paused(&mut deps, "owner", PausableAction::Bond, false).unwrap();
+    bond(&mut deps, 10000, 100).unwrap();
+    assert_eq!(
+        queue_unbond(&mut deps, 10000, 100).unwrap_err(),
+        ContractError::Paused("unbond".into())
+    );
+}
+
+#[test]
+fn limiting_volumes() {
+    let mut deps = setup_circuit_breaker();
+
+    bond(&mut deps, 10000, 600).unwrap();
+    bond(&mut deps, 10500, 400).unwrap();
+    assert_eq!(
+        bond(&mut deps, 10999, 1).unwrap_err(),
+        ContractError::VolumeLimitExceeded("bond".into())
+    );
+
+    // the bucket of the first bond left the rolling window
+    bond(&mut deps, 11050, 600).unwrap();
+    assert_eq!(
+        bond(&mut deps, 11050, 1).unwrap_err(),
+        ContractError::VolumeLimitExceeded("bond".into())
+    );
+
+    queue_unbond(&mut deps, 10000, 500).unwrap();
+    assert_eq!(
+        queue_unbond(&mut deps, 10999, 1).unwrap_err(),
+        ContractError::VolumeLimitExceeded("unbond".into())
+    );
+    queue_unbond(&mut deps, 11050, 500).unwrap();
+
+    // unbonds are valued in utoken at the last recorded exchange rate
+    let state = State::default();
+    state.exchange_history.save(deps.as_mut().storage, 15000, &Decimal::percent(200)).unwrap();
+    queue_unbond(&mut deps, 20000, 250).unwrap();
+    assert_eq!(
+        queue_unbond(&mut deps, 20000, 1).unwrap_err(),
+        ContractError::VolumeLimitExceeded("unbond".into())
+    );
+}
+
+#[test]
+fn tripping_on_exchange_rate_change() {
+    let mut deps = setup_circuit_breaker();
+    let state = State::default();
+
+    state.exchange_history.save(deps.as_mut().storage, 5000, &Decimal::one()).unwrap();
+    state.unlocked_coins.save(deps.as_mut().storage, &vec![Coin::new(234, MOCK_UTOKEN)]).unwrap();
+
+    let res = execute(
+        deps.as_mut(),
+        mock_env_at_timestamp(10000),
+        mock_info(MOCK_CONTRACT_ADDR, &[]),
+        ExecuteMsg::Callback(CallbackMsg::Reinvest {
+            skip_fee: false,
+        }),
+    )
+    .unwrap();
+    assert_eq!(res.events.len(), 1);
+
+    let res: ConfigResponse = query_helper(deps.as_ref(), QueryMsg::Config {});
+    assert_eq!(res.paused, PauseState::default());
+
+    // a bad reconcile reduced the exchange rate by 10%
+    set_total_stake_supply(&state, &mut deps, 1111111);
+    state.unlocked_coins.save(deps.as_mut().storage, &vec![Coin::new(234, MOCK_UTOKEN)]).unwrap();
+
+    let res = execute(
+        deps.as_mut(),
+        mock_env_at_timestamp(20000),
+        mock_info(MOCK_CONTRACT_ADDR, &[]),
+        ExecuteMsg::Callback(CallbackMsg::Reinvest {
+            skip_fee: false,
+        }),
+    )
+    .unwrap();
+    assert_eq!(res.events[1].ty, "erishub/circuit_breaker_tripped");
+
+    let res: ConfigResponse = query_helper(deps.as_ref(), QueryMsg::Config {});
+    assert_eq!(
+        res.paused,
+        PauseState {
+            bond: true,
+            unbond: true,
+            harvest: true,
+        }
+    );
+}
+
+#[test]
+fn canceling_unbond_releases_volume() {
+    let mut deps = setup_circuit_breaker();
+
+    queue_unbond(&mut deps, 10000, 500).unwrap();
+    assert_eq!(
+        queue_unbond(&mut deps, 10100, 1).unwrap_err(),
+        ContractError::VolumeLimitExceeded("unbond".into())
+    );
+
+    execute(
+        deps.as_mut(),
+        mock_env_at_timestamp(10100),
+        mock_info("user_1", &[]),
+        ExecuteMsg::CancelUnbond {
+            amount: Some(Uint128::new(200)),
+        },
+    )
+    .unwrap();
+
+    // the canceled amount can be unbonded again
+    queue_unbond(&mut deps, 10100, 200).unwrap();
+    assert_eq!(
+        queue_unbond(&mut deps, 10100, 1).unwrap_err(),
+        ContractError::VolumeLimitExceeded("unbond".into())
+    );
+}
@@ -0,0 +1,245 @@
+use std::str::FromStr;
+
+use cosmwasm_std::testing::{mock_info, MockApi, MockStorage};
+use cosmwasm_std::{Decimal, OwnedDeps, StdError, Uint128};
+
+use eris::governance_helper::{EPOCH_START, WEEK};
+use eris::hub::{
+    ConfigResponse, DelegationStrategy, ExecuteMsg, QueryMsg, WantedDelegationsPart,
+    WantedDelegationsResponse,
+};
+use eris_chain_adapter::types::CustomQueryType;
+
+use crate::contract::execute;
+use crate::error::ContractError;
+use crate::state::State;
+use crate::testing::helpers::{query_helper, setup_test, MOCK_UTOKEN};
+use crate::types::Delegation;
+
+use super::custom_querier::CustomQuerier;
+use super::helpers::{mock_env_at_timestamp, UpdateConfigBuilder};
+
+fn composite_strategy(defined_bps: u16, performance_bps: u16) -> DelegationStrategy {
+    DelegationStrategy::Composite {
+        parts: vec![
+            (
+                DelegationStrategy::Defined {
+                    shares_bps: vec![("alice".into(), 10000)],
+                },
+                defined_bps,
+            ),
+            (
+                DelegationStrategy::Performance {
+                    commission_cap_bps: 1500,
+                    uptime_oracle: None,
+                    min_score: Decimal::zero(),
+                    max_delegation_bps: 10000,
+                    validator_count: 3,
+                },
+                performance_bps,
+            ),
+        ],
+    }
+}
+
+fn setup_composite() -> OwnedDeps<MockStorage, MockApi, CustomQuerier, CustomQueryType> {
+    let mut deps = setup_test();
+
+    deps.querier.set_staking_validators(&[
+        ("alice", Decimal::percent(10)),
+        ("bob", Decimal::percent(10)),
+        ("charlie", Decimal::percent(20)),
+    ]);
+    deps.querier.set_staking_delegations(&[
+        Delegation::new("alice", 500000, MOCK_UTOKEN),
+        Delegation::new("bob", 300000, MOCK_UTOKEN),
+        Delegation::new("charlie", 200000, MOCK_UTOKEN),
+    ]);
+
+    execute(
+        deps.as_mut(),
+        mock_env_at_timestamp(12345),
+        mock_info("owner", &[]),
+        UpdateConfigBuilder {
+            delegation_strategy: Some(composite_strategy(5000, 5000)),
+            ..Default::default()
+        }
+        .build(),
+    )
+    .unwrap();
+
+    deps
+}
+
+//--------------------------------------------------------------------------------------------------
+// Execution
+//--------------------------------------------------------------------------------------------------
+
+#[test]
+fn updating_composite_strategy() {
+    let mut deps = setup_test();
+
+    let err = execute(
+        deps.as_mut(),
+        mock_env_at_timestamp(12345),
+        mock_info("owner", &[]),
+        UpdateConfigBuilder {
+            delegation_strategy: Some(composite_strategy(5000, 4000)),
+            ..Default::default()
+        }
+        .build(),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::Std(StdError::generic_err("sum of weights is not 10000")));
+
+    let err = execute(
+        deps.as_mut(),
+        mock_env_at_timestamp(12345),
+        mock_info("owner", &[]),
+        UpdateConfigBuilder {
+            delegation_strategy: Some(DelegationStrategy::Composite {
+                parts: vec![(composite_strategy(5000, 5000), 10000)],
+            }),
+            ..Default::default()
+        }
+        .build(),
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::Std(StdError::generic_err("composite strategies can't be nested"))
+    );
+
+    // parts are validated as well
+    let err = execute(
+        deps.as_mut(),
+        mock_env_at_timestamp(12345),
+        mock_info("owner", &[]),
+        UpdateConfigBuilder {
+            delegation_strategy: Some(DelegationStrategy::Composite {
+                parts: vec![(
+                    DelegationStrategy::Defined {
+                        shares_bps: vec![("dave".into(), 10000)],
+                    },
+                    10000,
+                )],
+            }),
+            ..Default::default()
+        }
+        .build(),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::Std(StdError::generic_err("validator dave not whitelisted")));
+
+    execute(
+        deps.as_mut(),
+        mock_env_at_timestamp(12345),
+        mock_info("owner", &[]),
+        UpdateConfigBuilder {
+            delegation_strategy: Some(composite_strategy(5000, 5000)),
+            ..Default::default()
+        }
+        .build(),
+    )
+    .unwrap();
+
+    let res: ConfigResponse = query_helper(deps.as_ref(), QueryMsg::Config {});
+    assert_eq!(res.delegation_strategy, composite_strategy(5000, 5000));
+}
+
+#[test]
+fn rejecting_alliance_rewards_strategy() {
+    let mut deps = setup_test();
+
+    let alliance_rewards = DelegationStrategy::AllianceRewards {
+        commission_cap_bps: 1000,
+        max_delegation_bps: 10000,
+        validator_count: 3,
+    };
+
+    for delegation_strategy in [
+        alliance_rewards.clone(),
+        DelegationStrategy::Composite {
+            parts: vec![(DelegationStrategy::Uniform, 5000), (alliance_rewards, 5000)],
+        },
+    ] {
+        let err = execute(
+            deps.as_mut(),
+            mock_env_at_timestamp(12345),
+            mock_info("owner", &[]),
+            UpdateConfigBuilder {
+                delegation_strategy: Some(delegation_strategy),
+                ..Default::default()
+            }
+            .build(),
+        )
+        .unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::DelegationStrategyNotSupported("alliance_rewards".to_string())
+        );
+    }
+
+    let res: ConfigResponse = query_helper(deps.as_ref(), QueryMsg::Config {});
+    assert_eq!(res.delegation_strategy, DelegationStrategy::Uniform);
+}
+
+#[test]
+fn tuning_composite_delegations() {
+    let mut deps = setup_composite();
+    let state = State::default();
+
+    execute(
+        deps.as_mut(),
+        mock_env_at_timestamp(EPOCH_START + WEEK),
+        mock_info("owner", &[]),
+        ExecuteMsg::TuneDelegations {},
+    )
+    .unwrap();
+
+    // defined: alice = 1.0, performance: alice = 0.5, bob = 0.5 (charlie above commission cap)
+    let goal = state.delegation_goal.load(deps.as_ref().storage).unwrap();
+    assert_eq!(
+        goal.shares,
+        vec![
+            ("alice".into(), Decimal::from_str("0.75").unwrap()),
+            ("bob".into(), Decimal::from_str("0.25").unwrap())
+        ]
+    );
+    assert_eq!(
+        goal.parts,
+        Some(vec![
+            (5000, vec![("alice".into(), Decimal::one())]),
+            (
+                5000,
+                vec![("alice".into(), Decimal::percent(50)), ("bob".into(), Decimal::percent(50))]
+            ),
+        ])
+    );
+
+    let res: WantedDelegationsResponse =
+        query_helper(deps.as_ref(), QueryMsg::WantedDelegations {});
+    assert_eq!(
+        res,
+        WantedDelegationsResponse {
+            tune_time_period: Some((EPOCH_START + WEEK, 1)),
+            delegations: vec![
+                ("alice".into(), Uint128::new(750000)),
+                ("bob".into(), Uint128::new(250000))
+            ],
+            parts: Some(vec![
+                WantedDelegationsPart {
+                    weight_bps: 5000,
+                    delegations: vec![("alice".into(), Uint128::new(500000))],
+                },
+                WantedDelegationsPart {
+                    weight_bps: 5000,
+                    delegations: vec![
+                        ("alice".into(), Uint128::new(250000)),
+                        ("bob".into(), Uint128::new(250000))
+                    ],
+                },
+            ]),
+        }
+    );
+}
@@ -1,3 +1,4 @@
+use std::collections::HashMap;
 use std::ops::Sub;
 use std::str::FromStr;
 
@@ -8,6 +9,7 @@ use cosmwasm_std::{
 };
 use eris::DecimalCheckedOps;
 
+use eris::circuit_breaker::PauseState;
 use eris::helper::validate_received_funds;
 use eris::hub::{
     Batch, CallbackMsg, ConfigResponse, DelegationStrategy, ExecuteMsg, FeeConfig, PendingBatch,
@@ -19,6 +21,7 @@ use eris_chain_shared::chain_trait::ChainInterface;
 use itertools::Itertools;
 use protobuf::SpecialFields;
 
+use crate::constants::DEFAULT_MAX_UNBONDING_ENTRIES;
 use crate::contract::execute;
 use crate::error::ContractError;
 use crate::helpers::dedupe;
@@ -55,7 +58,8 @@ fn proper_instantiation() {
             validators: vec!["alice".to_string(), "bob".to_string(), "charlie".to_string()],
             fee_config: FeeConfig {
                 protocol_fee_contract: Addr::unchecked("fee"),
-                protocol_reward_fee: Decimal::from_ratio(1u128, 100u128)
+                protocol_reward_fee: Decimal::from_ratio(1u128, 100u128),
+                fee_recipients: vec![],
             },
 
             operator: "operator".to_string(),
@@ -64,7 +68,15 @@ fn proper_instantiation() {
             allow_donations: false,
             delegation_strategy: DelegationStrategy::Uniform,
             vote_operator: None,
-            utoken: MOCK_UTOKEN.to_string()
+            utoken: MOCK_UTOKEN.to_string(),
+            instant_unbond_config: None,
+            remove_slashed_validators: false,
+            max_unbonding_entries: 7,
+            guardian: None,
+            circuit_breaker: None,
+            paused: PauseState::default(),
+            referral_share: Decimal::zero(),
+            rebalance_config: None,
         }
     );
 
@@ -107,6 +119,7 @@ fn bonding() {
         mock_info("user_1", &[Coin::new(1000000, MOCK_UTOKEN)]),
         ExecuteMsg::Bond {
             receiver: None,
+            referrer: None,
         },
     )
     .unwrap();
@@ -158,6 +171,7 @@ fn bonding() {
         mock_info("user_2", &[Coin::new(12345, MOCK_UTOKEN)]),
         ExecuteMsg::Bond {
             receiver: Some("user_3".to_string()),
+            referrer: None,
         },
     )
     .unwrap();
@@ -218,6 +232,7 @@ fn donating() {
         mock_info("user_1", &[Coin::new(1000000, MOCK_UTOKEN)]),
         ExecuteMsg::Bond {
             receiver: None,
+            referrer: None,
         },
     )
     .unwrap();
@@ -285,6 +300,7 @@ fn donating() {
         ExecuteMsg::UpdateConfig {
             protocol_fee_contract: None,
             protocol_reward_fee: None,
+            fee_recipients: None,
             operator: None,
             stages_preset: None,
             withdrawals_preset: None,
@@ -295,6 +311,15 @@ fn donating() {
             default_max_spread: None,
             epoch_period: None,
             unbond_period: None,
+            instant_unbond_config: None,
+            remove_instant_unbond_config: None,
+            remove_slashed_validators: None,
+            max_unbonding_entries: None,
+            guardian: None,
+            remove_guardian: None,
+            circuit_breaker: None,
+            referral_share: None,
+            rebalance_config: None,
         },
     )
     .unwrap();
@@ -1518,7 +1543,8 @@ fn update_fee() {
         config,
         FeeConfig {
             protocol_fee_contract: Addr::unchecked("fee"),
-            protocol_reward_fee: Decimal::from_ratio(1u128, 100u128)
+            protocol_reward_fee: Decimal::from_ratio(1u128, 100u128),
+            fee_recipients: vec![],
         }
     );
 
@@ -1529,6 +1555,7 @@ fn update_fee() {
         ExecuteMsg::UpdateConfig {
             protocol_fee_contract: None,
             protocol_reward_fee: Some(Decimal::from_ratio(11u128, 100u128)),
+            fee_recipients: None,
             operator: None,
             stages_preset: None,
             withdrawals_preset: None,
@@ -1539,6 +1566,15 @@ fn update_fee() {
             default_max_spread: None,
             epoch_period: None,
             unbond_period: None,
+            instant_unbond_config: None,
+            remove_instant_unbond_config: None,
+            remove_slashed_validators: None,
+            max_unbonding_entries: None,
+            guardian: None,
+            remove_guardian: None,
+            circuit_breaker: None,
+            referral_share: None,
+            rebalance_config: None,
         },
     )
     .unwrap_err();
@@ -1551,6 +1587,7 @@ fn update_fee() {
         ExecuteMsg::UpdateConfig {
             protocol_fee_contract: None,
             protocol_reward_fee: Some(Decimal::from_ratio(11u128, 100u128)),
+            fee_recipients: None,
             operator: None,
             stages_preset: None,
             withdrawals_preset: None,
@@ -1561,6 +1598,15 @@ fn update_fee() {
             default_max_spread: None,
             epoch_period: None,
             unbond_period: None,
+            instant_unbond_config: None,
+            remove_instant_unbond_config: None,
+            remove_slashed_validators: None,
+            max_unbonding_entries: None,
+            guardian: None,
+            remove_guardian: None,
+            circuit_breaker: None,
+            referral_share: None,
+            rebalance_config: None,
         },
     )
     .unwrap_err();
@@ -1573,6 +1619,7 @@ fn update_fee() {
         ExecuteMsg::UpdateConfig {
             protocol_fee_contract: Some("fee-new".to_string()),
             protocol_reward_fee: Some(Decimal::from_ratio(10u128, 100u128)),
+            fee_recipients: None,
             operator: None,
             stages_preset: None,
             withdrawals_preset: None,
@@ -1583,6 +1630,15 @@ fn update_fee() {
             default_max_spread: None,
             epoch_period: None,
             unbond_period: None,
+            instant_unbond_config: None,
+            remove_instant_unbond_config: None,
+            remove_slashed_validators: None,
+            max_unbonding_entries: None,
+            guardian: None,
+            remove_guardian: None,
+            circuit_breaker: None,
+            referral_share: None,
+            rebalance_config: None,
         },
     )
     .unwrap();
@@ -1594,7 +1650,8 @@ fn update_fee() {
         config,
         FeeConfig {
             protocol_fee_contract: Addr::unchecked("fee-new"),
-            protocol_reward_fee: Decimal::from_ratio(10u128, 100u128)
+            protocol_reward_fee: Decimal::from_ratio(10u128, 100u128),
+            fee_recipients: vec![],
         }
     );
 }
@@ -1625,6 +1682,7 @@ fn vote() {
         ExecuteMsg::UpdateConfig {
             protocol_fee_contract: None,
             protocol_reward_fee: None,
+            fee_recipients: None,
             delegation_strategy: None,
             allow_donations: None,
             vote_operator: Some("vote_operator".to_string()),
@@ -1635,6 +1693,15 @@ fn vote() {
             default_max_spread: None,
             epoch_period: None,
             unbond_period: None,
+            instant_unbond_config: None,
+            remove_instant_unbond_config: None,
+            remove_slashed_validators: None,
+            max_unbonding_entries: None,
+            guardian: None,
+            remove_guardian: None,
+            circuit_breaker: None,
+            referral_share: None,
+            rebalance_config: None,
         },
     )
     .unwrap();
@@ -1697,6 +1764,7 @@ fn vote_weighted() {
         ExecuteMsg::UpdateConfig {
             protocol_fee_contract: None,
             protocol_reward_fee: None,
+            fee_recipients: None,
             delegation_strategy: None,
             allow_donations: None,
             vote_operator: Some("vote_operator".to_string()),
@@ -1707,6 +1775,15 @@ fn vote_weighted() {
             default_max_spread: None,
             epoch_period: None,
             unbond_period: None,
+            instant_unbond_config: None,
+            remove_instant_unbond_config: None,
+            remove_slashed_validators: None,
+            max_unbonding_entries: None,
+            guardian: None,
+            remove_guardian: None,
+            circuit_breaker: None,
+            referral_share: None,
+            rebalance_config: None,
         },
     )
     .unwrap();
@@ -2107,6 +2184,9 @@ fn computing_undelegations() -> StdResult<()> {
         &current_delegations,
         current_delegations.iter().map(|a| a.validator.to_string()).collect_vec(),
         MOCK_UTOKEN,
+        &HashMap::new(),
+        DEFAULT_MAX_UNBONDING_ENTRIES,
+        3,
     )?;
     let expected = vec![
         Undelegation::new("alice", 249, MOCK_UTOKEN),
@@ -2146,6 +2226,7 @@ fn computing_redelegations_for_removal() -> StdResult<()> {
             &current_delegations[..3],
             current_delegations[..3].iter().map(|a| a.validator.to_string()).collect_vec(),
             MOCK_UTOKEN,
+            None,
         )?,
         expected,
     );
@@ -2219,6 +2300,7 @@ fn computing_redelegations_for_rebalancing_complex() -> StdResult<()> {
                 ("alice".to_string(), Decimal::from_str("0.25")?),
                 ("bob".to_string(), Decimal::from_str("0.25")?),
             ],
+            parts: None,
         },
     )?;
     // ratio is good
@@ -0,0 +1,203 @@
+use cosmwasm_std::testing::{mock_info, MOCK_CONTRACT_ADDR};
+use cosmwasm_std::{Addr, Coin, Decimal, StdError, Uint128};
+
+use eris::hub::{
+    CallbackMsg, ConfigResponse, ExecuteMsg, FeeConfig, FeeDelivery, FeeRecipient, QueryMsg,
+};
+use eris_chain_shared::chain_trait::ChainInterface;
+
+use crate::contract::execute;
+use crate::error::ContractError;
+use crate::state::State;
+use crate::testing::helpers::{
+    chain_test, get_stake_full_denom, query_helper, set_total_stake_supply, setup_test, MOCK_UTOKEN,
+};
+use crate::types::{Delegation, SendFee};
+
+use super::helpers::{mock_env_at_timestamp, UpdateConfigBuilder};
+
+fn fee_recipients() -> Vec<FeeRecipient> {
+    vec![
+        FeeRecipient {
+            address: "fee".to_string(),
+            weight: Decimal::percent(50),
+            delivery: None,
+        },
+        FeeRecipient {
+            address: "treasury".to_string(),
+            weight: Decimal::percent(50),
+            delivery: Some(FeeDelivery::Staked),
+        },
+    ]
+}
+
+//--------------------------------------------------------------------------------------------------
+// Execution
+//--------------------------------------------------------------------------------------------------
+
+#[test]
+fn updating_fee_recipients() {
+    let mut deps = setup_test();
+
+    let mut recipients = fee_recipients();
+    recipients[1].weight = Decimal::percent(40);
+    let err = execute(
+        deps.as_mut(),
+        mock_env_at_timestamp(10000),
+        mock_info("owner", &[]),
+        UpdateConfigBuilder {
+            fee_recipients: Some(recipients),
+            ..Default::default()
+        }
+        .build(),
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::Std(StdError::generic_err("sum of fee recipient weights is not 1"))
+    );
+
+    let mut recipients = fee_recipients();
+    recipients.push(FeeRecipient {
+        address: "other".to_string(),
+        weight: Decimal::zero(),
+        delivery: None,
+    });
+    let err = execute(
+        deps.as_mut(),
+        mock_env_at_timestamp(10000),
+        mock_info("owner", &[]),
+        UpdateConfigBuilder {
+            fee_recipients: Some(recipients),
+            ..Default::default()
+        }
+        .build(),
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::Std(StdError::generic_err("fee recipient weight can't be zero"))
+    );
+
+    execute(
+        deps.as_mut(),
+        mock_env_at_timestamp(10000),
+        mock_info("owner", &[]),
+        UpdateConfigBuilder {
+            fee_recipients: Some(fee_recipients()),
+            ..Default::default()
+        }
+        .build(),
+    )
+    .unwrap();
+
+    let res: ConfigResponse = query_helper(deps.as_ref(), QueryMsg::Config {});
+    assert_eq!(
+        res.fee_config,
+        FeeConfig {
+            protocol_fee_contract: Addr::unchecked("fee"),
+            protocol_reward_fee: Decimal::percent(1),
+            fee_recipients: vec![
+                FeeRecipient {
+                    address: Addr::unchecked("fee"),
+                    weight: Decimal::percent(50),
+                    delivery: None,
+                },
+                FeeRecipient {
+                    address: Addr::unchecked("treasury"),
+                    weight: Decimal::percent(50),
+                    delivery: Some(FeeDelivery::Staked),
+                },
+            ],
+        }
+    );
+
+    // the single address form replaces the recipients
+    execute(
+        deps.as_mut(),
+        mock_env_at_timestamp(10000),
+        mock_info("owner", &[]),
+        UpdateConfigBuilder {
+            protocol_fee_contract: Some("new_fee".to_string()),
+            ..Default::default()
+        }
+        .build(),
+    )
+    .unwrap();
+
+    let res: ConfigResponse = query_helper(deps.as_ref(), QueryMsg::Config {});
+    assert_eq!(res.fee_config.fee_recipients, vec![]);
+    assert_eq!(
+        res.fee_config.recipients(),
+        vec![FeeRecipient {
+            address: Addr::unchecked("new_fee"),
+            weight: Decimal::one(),
+            delivery: None,
+        }]
+    );
+}
+
+#[test]
+fn reinvesting_with_fee_recipients() {
+    let mut deps = setup_test();
+    let state = State::default();
+
+    deps.querier.set_staking_delegations(&[
+        Delegation::new("alice", 333334, MOCK_UTOKEN),
+        Delegation::new("bob", 333333, MOCK_UTOKEN),
+        Delegation::new("charlie", 333333, MOCK_UTOKEN),
+    ]);
+    set_total_stake_supply(&state, &mut deps, 100000);
+
+    execute(
+        deps.as_mut(),
+        mock_env_at_timestamp(10000),
+        mock_info("owner", &[]),
+        UpdateConfigBuilder {
+            fee_recipients: Some(fee_recipients()),
+            ..Default::default()
+        }
+        .build(),
+    )
+    .unwrap();
+
+    state
+        .unlocked_coins
+        .save(
+            deps.as_mut().storage,
+            &vec![Coin::new(100000, MOCK_UTOKEN), Coin::new(1000, get_stake_full_denom())],
+        )
+        .unwrap();
+
+    let res = execute(
+        deps.as_mut(),
+        mock_env_at_timestamp(10000),
+        mock_info(MOCK_CONTRACT_ADDR, &[]),
+        ExecuteMsg::Callback(CallbackMsg::Reinvest {
+            skip_fee: false,
+        }),
+    )
+    .unwrap();
+
+    // the fee of 1000 utoken is split in half, the staked half is delegated together with the
+    // rewards and minted at the exchange rate after the rewards are bonded:
+    // 500 * 100000 / (1000000 + 99000) = 45 ustake
+    let mut expected = vec![Delegation::new("bob", 99500, MOCK_UTOKEN).to_cosmos_msg()];
+    expected.extend(chain_test().create_mint_msgs(
+        get_stake_full_denom(),
+        Uint128::new(45),
+        Addr::unchecked("treasury"),
+    ));
+    expected.push(SendFee::new(Addr::unchecked("fee"), 500, MOCK_UTOKEN).to_cosmos_msg());
+    // ustake fees are already staked, they are sent as is
+    expected.push(chain_test().create_burn_msg(get_stake_full_denom(), Uint128::new(990)));
+    expected.push(SendFee::new(Addr::unchecked("fee"), 5, get_stake_full_denom()).to_cosmos_msg());
+    expected
+        .push(SendFee::new(Addr::unchecked("treasury"), 5, get_stake_full_denom()).to_cosmos_msg());
+
+    assert_eq!(res.messages.into_iter().map(|msg| msg.msg).collect::<Vec<_>>(), expected);
+    assert_eq!(
+        state.stake_token.load(deps.as_ref().storage).unwrap().total_supply,
+        Uint128::new(100000 + 45 - 990)
+    );
+}
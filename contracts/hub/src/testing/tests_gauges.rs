@@ -1,3 +1,4 @@
+use std::collections::HashMap;
 use std::ops::Sub;
 use std::str::FromStr;
 
@@ -8,6 +9,7 @@ use cosmwasm_std::{
 };
 use eris::DecimalCheckedOps;
 
+use eris::circuit_breaker::PauseState;
 use eris::helper::validate_received_funds;
 use eris::hub::{
     Batch, CallbackMsg, ConfigResponse, DelegationStrategy, ExecuteMsg, FeeConfig, InstantiateMsg,
@@ -20,6 +22,7 @@ use eris_chain_shared::chain_trait::ChainInterface;
 use itertools::Itertools;
 use protobuf::SpecialFields;
 
+use crate::constants::DEFAULT_MAX_UNBONDING_ENTRIES;
 use crate::contract::{execute, instantiate};
 use crate::error::ContractError;
 use crate::helpers::dedupe;
@@ -105,7 +108,8 @@ fn proper_instantiation() {
             validators: vec!["alice".to_string(), "bob".to_string(), "charlie".to_string()],
             fee_config: FeeConfig {
                 protocol_fee_contract: Addr::unchecked("fee"),
-                protocol_reward_fee: Decimal::from_ratio(1u128, 100u128)
+                protocol_reward_fee: Decimal::from_ratio(1u128, 100u128),
+                fee_recipients: vec![],
             },
             operator: "operator".to_string(),
             stages_preset: vec![],
@@ -120,7 +124,15 @@ fn proper_instantiation() {
             },
             withdrawals_preset: vec![],
             vote_operator: None,
-            utoken: MOCK_UTOKEN.to_string()
+            utoken: MOCK_UTOKEN.to_string(),
+            instant_unbond_config: None,
+            remove_slashed_validators: false,
+            max_unbonding_entries: 7,
+            guardian: None,
+            circuit_breaker: None,
+            paused: PauseState::default(),
+            referral_share: Decimal::zero(),
+            rebalance_config: None,
         }
     );
 
@@ -163,6 +175,7 @@ fn bonding() {
         mock_info("user_1", &[Coin::new(1000000, MOCK_UTOKEN)]),
         ExecuteMsg::Bond {
             receiver: None,
+            referrer: None,
         },
     )
     .unwrap();
@@ -214,6 +227,7 @@ fn bonding() {
         mock_info("user_2", &[Coin::new(12345, MOCK_UTOKEN)]),
         ExecuteMsg::Bond {
             receiver: Some("user_3".to_string()),
+            referrer: None,
         },
     )
     .unwrap();
@@ -274,6 +288,7 @@ fn donating() {
         mock_info("user_1", &[Coin::new(1000000, MOCK_UTOKEN)]),
         ExecuteMsg::Bond {
             receiver: None,
+            referrer: None,
         },
     )
     .unwrap();
@@ -341,6 +356,7 @@ fn donating() {
         ExecuteMsg::UpdateConfig {
             protocol_fee_contract: None,
             protocol_reward_fee: None,
+            fee_recipients: None,
             operator: None,
             stages_preset: None,
             withdrawals_preset: None,
@@ -351,6 +367,15 @@ fn donating() {
             default_max_spread: None,
             epoch_period: None,
             unbond_period: None,
+            instant_unbond_config: None,
+            remove_instant_unbond_config: None,
+            remove_slashed_validators: None,
+            max_unbonding_entries: None,
+            guardian: None,
+            remove_guardian: None,
+            circuit_breaker: None,
+            referral_share: None,
+            rebalance_config: None,
         },
     )
     .unwrap();
@@ -1566,7 +1591,8 @@ fn update_fee() {
         config,
         FeeConfig {
             protocol_fee_contract: Addr::unchecked("fee"),
-            protocol_reward_fee: Decimal::from_ratio(1u128, 100u128)
+            protocol_reward_fee: Decimal::from_ratio(1u128, 100u128),
+            fee_recipients: vec![],
         }
     );
 
@@ -1577,6 +1603,7 @@ fn update_fee() {
         ExecuteMsg::UpdateConfig {
             protocol_fee_contract: None,
             protocol_reward_fee: Some(Decimal::from_ratio(11u128, 100u128)),
+            fee_recipients: None,
             operator: None,
             stages_preset: None,
             withdrawals_preset: None,
@@ -1587,6 +1614,15 @@ fn update_fee() {
             default_max_spread: None,
             epoch_period: None,
             unbond_period: None,
+            instant_unbond_config: None,
+            remove_instant_unbond_config: None,
+            remove_slashed_validators: None,
+            max_unbonding_entries: None,
+            guardian: None,
+            remove_guardian: None,
+            circuit_breaker: None,
+            referral_share: None,
+            rebalance_config: None,
         },
     )
     .unwrap_err();
@@ -1599,6 +1635,7 @@ fn update_fee() {
         ExecuteMsg::UpdateConfig {
             protocol_fee_contract: None,
             protocol_reward_fee: Some(Decimal::from_ratio(11u128, 100u128)),
+            fee_recipients: None,
             operator: None,
             stages_preset: None,
             withdrawals_preset: None,
@@ -1609,6 +1646,15 @@ fn update_fee() {
             default_max_spread: None,
             epoch_period: None,
             unbond_period: None,
+            instant_unbond_config: None,
+            remove_instant_unbond_config: None,
+            remove_slashed_validators: None,
+            max_unbonding_entries: None,
+            guardian: None,
+            remove_guardian: None,
+            circuit_breaker: None,
+            referral_share: None,
+            rebalance_config: None,
         },
     )
     .unwrap_err();
@@ -1621,6 +1667,7 @@ fn update_fee() {
         ExecuteMsg::UpdateConfig {
             protocol_fee_contract: Some("fee-new".to_string()),
             protocol_reward_fee: Some(Decimal::from_ratio(10u128, 100u128)),
+            fee_recipients: None,
             operator: None,
             stages_preset: None,
             withdrawals_preset: None,
@@ -1631,6 +1678,15 @@ fn update_fee() {
             default_max_spread: None,
             epoch_period: None,
             unbond_period: None,
+            instant_unbond_config: None,
+            remove_instant_unbond_config: None,
+            remove_slashed_validators: None,
+            max_unbonding_entries: None,
+            guardian: None,
+            remove_guardian: None,
+            circuit_breaker: None,
+            referral_share: None,
+            rebalance_config: None,
         },
     )
     .unwrap();
@@ -1642,7 +1698,8 @@ fn update_fee() {
         config,
         FeeConfig {
             protocol_fee_contract: Addr::unchecked("fee-new"),
-            protocol_reward_fee: Decimal::from_ratio(10u128, 100u128)
+            protocol_reward_fee: Decimal::from_ratio(10u128, 100u128),
+            fee_recipients: vec![],
         }
     );
 }
@@ -1673,6 +1730,7 @@ fn vote() {
         ExecuteMsg::UpdateConfig {
             protocol_fee_contract: None,
             protocol_reward_fee: None,
+            fee_recipients: None,
             delegation_strategy: None,
             allow_donations: None,
             vote_operator: Some("vote_operator".to_string()),
@@ -1683,6 +1741,15 @@ fn vote() {
             default_max_spread: None,
             epoch_period: None,
             unbond_period: None,
+            instant_unbond_config: None,
+            remove_instant_unbond_config: None,
+            remove_slashed_validators: None,
+            max_unbonding_entries: None,
+            guardian: None,
+            remove_guardian: None,
+            circuit_breaker: None,
+            referral_share: None,
+            rebalance_config: None,
         },
     )
     .unwrap();
@@ -1745,6 +1812,7 @@ fn vote_weighted() {
         ExecuteMsg::UpdateConfig {
             protocol_fee_contract: None,
             protocol_reward_fee: None,
+            fee_recipients: None,
             delegation_strategy: None,
             allow_donations: None,
             vote_operator: Some("vote_operator".to_string()),
@@ -1755,6 +1823,15 @@ fn vote_weighted() {
             default_max_spread: None,
             epoch_period: None,
             unbond_period: None,
+            instant_unbond_config: None,
+            remove_instant_unbond_config: None,
+            remove_slashed_validators: None,
+            max_unbonding_entries: None,
+            guardian: None,
+            remove_guardian: None,
+            circuit_breaker: None,
+            referral_share: None,
+            rebalance_config: None,
         },
     )
     .unwrap();
@@ -2155,6 +2232,9 @@ fn computing_undelegations() -> StdResult<()> {
         &current_delegations,
         current_delegations.iter().map(|a| a.validator.to_string()).collect_vec(),
         MOCK_UTOKEN,
+        &HashMap::new(),
+        DEFAULT_MAX_UNBONDING_ENTRIES,
+        3,
     )?;
     let expected = vec![
         Undelegation::new("alice", 249, MOCK_UTOKEN),
@@ -2194,6 +2274,7 @@ fn computing_redelegations_for_removal() -> StdResult<()> {
             &current_delegations[..3],
             current_delegations[..3].iter().map(|a| a.validator.to_string()).collect_vec(),
             MOCK_UTOKEN,
+            None,
         )?,
         expected,
     );
@@ -2267,6 +2348,7 @@ fn computing_redelegations_for_rebalancing_complex() -> StdResult<()> {
                 ("alice".to_string(), Decimal::from_str("0.25")?),
                 ("bob".to_string(), Decimal::from_str("0.25")?),
             ],
+            parts: None,
         },
     )?;
     // ratio is good
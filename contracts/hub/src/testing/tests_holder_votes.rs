@@ -0,0 +1,406 @@
+use cosmwasm_std::testing::{mock_info, MockApi, MockStorage};
+use cosmwasm_std::{coin, coins, Addr, Decimal, OwnedDeps, StdError, Uint128, VoteOption};
+
+use eris::hub::{ExecuteMsg, HolderBalance, HolderVoteResponse, QueryMsg, SudoMsg};
+use eris_chain_adapter::types::CustomQueryType;
+
+use crate::contract::{execute, sudo};
+use crate::error::ContractError;
+use crate::state::State;
+use crate::testing::helpers::{get_stake_full_denom, query_helper, setup_test};
+
+use super::custom_querier::CustomQuerier;
+use super::helpers::mock_env_at_timestamp;
+
+fn transfer(
+    deps: &mut OwnedDeps<MockStorage, MockApi, CustomQuerier, CustomQueryType>,
+    timestamp: u64,
+    from: &str,
+    to: &str,
+    amount: u128,
+) {
+    sudo(
+        deps.as_mut(),
+        mock_env_at_timestamp(timestamp),
+        SudoMsg::TrackBeforeSend {
+            from: from.to_string(),
+            to: to.to_string(),
+            amount: coin(amount, get_stake_full_denom()),
+        },
+    )
+    .unwrap();
+
+    // the hook is called before the bank module applies the transfer
+    let querier = deps.as_ref().querier;
+    let from_balance = querier.query_balance(from, get_stake_full_denom()).unwrap().amount;
+    let to_balance = querier.query_balance(to, get_stake_full_denom()).unwrap().amount;
+    let from_balance = from_balance.u128().saturating_sub(amount);
+    let to_balance = to_balance.u128() + amount;
+    deps.querier.bank_querier.update_balance(from, coins(from_balance, get_stake_full_denom()));
+    deps.querier.bank_querier.update_balance(to, coins(to_balance, get_stake_full_denom()));
+}
+
+fn cast_vote(
+    deps: &mut OwnedDeps<MockStorage, MockApi, CustomQuerier, CustomQueryType>,
+    voter: &str,
+    votes: Vec<(Decimal, VoteOption)>,
+) -> Result<(), ContractError> {
+    execute(
+        deps.as_mut(),
+        mock_env_at_timestamp(2000),
+        mock_info(voter, &[]),
+        ExecuteMsg::CastHolderVote {
+            proposal_id: 1,
+            votes,
+        },
+    )
+    .map(|_| ())
+}
+
+fn setup_holder_vote() -> OwnedDeps<MockStorage, MockApi, CustomQuerier, CustomQueryType> {
+    let mut deps = setup_test();
+    let state = State::default();
+
+    state.vote_operator.save(deps.as_mut().storage, &Addr::unchecked("vote_operator")).unwrap();
+    // the test chain doesn't support before send hooks
+    state.holder_voting_since.save(deps.as_mut().storage, &500).unwrap();
+
+    transfer(&mut deps, 600, "mint_module", "user_1", 600);
+    transfer(&mut deps, 600, "mint_module", "user_2", 400);
+
+    let err = execute(
+        deps.as_mut(),
+        mock_env_at_timestamp(1000),
+        mock_info("user_1", &[]),
+        ExecuteMsg::OpenHolderVote {
+            proposal_id: 1,
+            start_time: 900,
+            end_time: 5000,
+        },
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::UnauthorizedSenderNotVoteOperator {});
+
+    execute(
+        deps.as_mut(),
+        mock_env_at_timestamp(1000),
+        mock_info("vote_operator", &[]),
+        ExecuteMsg::OpenHolderVote {
+            proposal_id: 1,
+            start_time: 900,
+            end_time: 5000,
+        },
+    )
+    .unwrap();
+
+    // transfers after the snapshot don't change the voting power
+    transfer(&mut deps, 950, "user_1", "user_2", 300);
+
+    deps
+}
+
+//--------------------------------------------------------------------------------------------------
+// Execution
+//--------------------------------------------------------------------------------------------------
+
+#[test]
+fn updating_holder_voting() {
+    let mut deps = setup_test();
+
+    let err = execute(
+        deps.as_mut(),
+        mock_env_at_timestamp(1000),
+        mock_info("user_1", &[]),
+        ExecuteMsg::UpdateHolderVoting {
+            enabled: true,
+        },
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+
+    let err = execute(
+        deps.as_mut(),
+        mock_env_at_timestamp(1000),
+        mock_info("owner", &[]),
+        ExecuteMsg::UpdateHolderVoting {
+            enabled: true,
+        },
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::Std(StdError::generic_err("before send hooks not supported")));
+}
+
+#[test]
+fn tracking_holder_balances() {
+    let mut deps = setup_test();
+    let state = State::default();
+
+    // transfers are not tracked before the hook is enabled
+    transfer(&mut deps, 100, "mint_module", "user_1", 600);
+    state.holder_voting_since.save(deps.as_mut().storage, &500).unwrap();
+
+    transfer(&mut deps, 600, "user_1", "user_2", 200);
+    transfer(&mut deps, 700, "mint_module", "user_2", 100);
+
+    // other denoms are ignored
+    sudo(
+        deps.as_mut(),
+        mock_env_at_timestamp(700),
+        SudoMsg::TrackBeforeSend {
+            from: "user_1".to_string(),
+            to: "user_2".to_string(),
+            amount: coin(100, "uother"),
+        },
+    )
+    .unwrap();
+
+    let balance = |user: &str, time: u64| {
+        state
+            .holder_balances
+            .may_load_at_height(deps.as_ref().storage, &Addr::unchecked(user), time)
+            .unwrap()
+            .map(|balance| balance.amount)
+    };
+    assert_eq!(balance("user_1", 100), None);
+    assert_eq!(balance("user_1", 600), None);
+    assert_eq!(balance("user_1", 601), Some(Uint128::new(400)));
+    assert_eq!(balance("user_2", 601), Some(Uint128::new(200)));
+    assert_eq!(balance("user_2", 701), Some(Uint128::new(300)));
+
+    // the balance before the first tracked transfer is read from the bank module
+    let initial_balance = |user: &str| {
+        state.holder_initial_balances.load(deps.as_ref().storage, &Addr::unchecked(user)).unwrap()
+    };
+    assert_eq!(
+        initial_balance("user_1"),
+        HolderBalance {
+            tracked_since: 500,
+            amount: Uint128::new(600),
+        }
+    );
+    assert_eq!(
+        initial_balance("user_2"),
+        HolderBalance {
+            tracked_since: 500,
+            amount: Uint128::zero(),
+        }
+    );
+}
+
+#[test]
+fn opening_holder_votes() {
+    let mut deps = setup_test();
+    let state = State::default();
+    state.vote_operator.save(deps.as_mut().storage, &Addr::unchecked("vote_operator")).unwrap();
+
+    let open = |deps: &mut OwnedDeps<MockStorage, MockApi, CustomQuerier, CustomQueryType>,
+                start_time: u64| {
+        execute(
+            deps.as_mut(),
+            mock_env_at_timestamp(1000),
+            mock_info("vote_operator", &[]),
+            ExecuteMsg::OpenHolderVote {
+                proposal_id: 1,
+                start_time,
+                end_time: 5000,
+            },
+        )
+    };
+
+    assert_eq!(open(&mut deps, 900).unwrap_err(), ContractError::HolderVotingDisabled {});
+
+    state.holder_voting_since.save(deps.as_mut().storage, &500).unwrap();
+    assert_eq!(open(&mut deps, 400).unwrap_err(), ContractError::HolderVoteNotTracked(1));
+    assert_eq!(
+        open(&mut deps, 1001).unwrap_err(),
+        ContractError::Std(StdError::generic_err("start_time can't be in the future"))
+    );
+    open(&mut deps, 500).unwrap();
+    assert_eq!(open(&mut deps, 500).unwrap_err(), ContractError::HolderVoteExists(1));
+}
+
+#[test]
+fn casting_holder_votes() {
+    let mut deps = setup_holder_vote();
+
+    let err =
+        cast_vote(&mut deps, "user_1", vec![(Decimal::percent(50), VoteOption::Yes)]).unwrap_err();
+    assert_eq!(err, ContractError::Std(StdError::generic_err("sum of vote weights is not 1")));
+
+    let err = cast_vote(
+        &mut deps,
+        "user_1",
+        vec![(Decimal::percent(50), VoteOption::Yes), (Decimal::percent(50), VoteOption::Yes)],
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::Std(StdError::generic_err("duplicate vote options")));
+
+    let err = cast_vote(&mut deps, "user_5", vec![(Decimal::one(), VoteOption::Yes)]).unwrap_err();
+    assert_eq!(err, ContractError::NoVotingPower {});
+
+    cast_vote(&mut deps, "user_1", vec![(Decimal::one(), VoteOption::Yes)]).unwrap();
+    cast_vote(
+        &mut deps,
+        "user_2",
+        vec![(Decimal::percent(50), VoteOption::No), (Decimal::percent(50), VoteOption::Abstain)],
+    )
+    .unwrap();
+
+    let res: HolderVoteResponse = query_helper(
+        deps.as_ref(),
+        QueryMsg::HolderVote {
+            proposal_id: 1,
+        },
+    );
+    assert_eq!(
+        res,
+        HolderVoteResponse {
+            proposal_id: 1,
+            snapshot_time: 900,
+            end_time: 5000,
+            tally: vec![
+                (VoteOption::Yes, Uint128::new(600)),
+                (VoteOption::No, Uint128::new(200)),
+                (VoteOption::Abstain, Uint128::new(200)),
+            ],
+            total_power: Uint128::new(1000),
+        }
+    );
+
+    // the previous vote is replaced
+    cast_vote(&mut deps, "user_1", vec![(Decimal::one(), VoteOption::No)]).unwrap();
+
+    let res: HolderVoteResponse = query_helper(
+        deps.as_ref(),
+        QueryMsg::HolderVote {
+            proposal_id: 1,
+        },
+    );
+    assert_eq!(
+        res.tally,
+        vec![
+            (VoteOption::Yes, Uint128::zero()),
+            (VoteOption::No, Uint128::new(800)),
+            (VoteOption::Abstain, Uint128::new(200)),
+        ]
+    );
+    assert_eq!(res.total_power, Uint128::new(1000));
+}
+
+#[test]
+fn submitting_holder_votes() {
+    let mut deps = setup_holder_vote();
+
+    let err = execute(
+        deps.as_mut(),
+        mock_env_at_timestamp(2000),
+        mock_info("anyone", &[]),
+        ExecuteMsg::SubmitHolderVote {
+            proposal_id: 1,
+        },
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::CantBeZero("holder votes".into()));
+
+    cast_vote(&mut deps, "user_1", vec![(Decimal::one(), VoteOption::No)]).unwrap();
+    cast_vote(&mut deps, "user_2", vec![(Decimal::one(), VoteOption::Abstain)]).unwrap();
+
+    let res = execute(
+        deps.as_mut(),
+        mock_env_at_timestamp(2000),
+        mock_info("anyone", &[]),
+        ExecuteMsg::SubmitHolderVote {
+            proposal_id: 1,
+        },
+    )
+    .unwrap();
+
+    // the hub votes the same as the vote operator would with the tallied weights
+    let expected = execute(
+        deps.as_mut(),
+        mock_env_at_timestamp(2000),
+        mock_info("vote_operator", &[]),
+        ExecuteMsg::VoteWeighted {
+            proposal_id: 1,
+            votes: vec![
+                (Decimal::percent(60), VoteOption::No),
+                (Decimal::percent(40), VoteOption::Abstain),
+            ],
+        },
+    )
+    .unwrap();
+    assert_eq!(res.messages, expected.messages);
+
+    let err = execute(
+        deps.as_mut(),
+        mock_env_at_timestamp(5000),
+        mock_info("anyone", &[]),
+        ExecuteMsg::SubmitHolderVote {
+            proposal_id: 1,
+        },
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::HolderVoteEnded(1));
+
+    let err = execute(
+        deps.as_mut(),
+        mock_env_at_timestamp(2000),
+        mock_info("anyone", &[]),
+        ExecuteMsg::SubmitHolderVote {
+            proposal_id: 2,
+        },
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::HolderVoteNotFound(2));
+}
+
+#[test]
+fn voting_with_untracked_balances() {
+    let mut deps = setup_holder_vote();
+    deps.querier.bank_querier.update_balance("user_3", coins(250, get_stake_full_denom()));
+    deps.querier.bank_querier.update_balance("user_4", coins(100, get_stake_full_denom()));
+
+    // user_3 never transferred, user_4 first transferred after the snapshot
+    transfer(&mut deps, 950, "user_4", "user_1", 100);
+
+    cast_vote(&mut deps, "user_3", vec![(Decimal::one(), VoteOption::Yes)]).unwrap();
+    cast_vote(&mut deps, "user_4", vec![(Decimal::one(), VoteOption::No)]).unwrap();
+    cast_vote(&mut deps, "user_1", vec![(Decimal::one(), VoteOption::No)]).unwrap();
+
+    let res: HolderVoteResponse = query_helper(
+        deps.as_ref(),
+        QueryMsg::HolderVote {
+            proposal_id: 1,
+        },
+    );
+    assert_eq!(
+        res.tally,
+        vec![(VoteOption::Yes, Uint128::new(250)), (VoteOption::No, Uint128::new(700))]
+    );
+
+    // balances of a previous tracking period are outdated
+    let state = State::default();
+    state.holder_voting_since.save(deps.as_mut().storage, &1500).unwrap();
+    execute(
+        deps.as_mut(),
+        mock_env_at_timestamp(2000),
+        mock_info("vote_operator", &[]),
+        ExecuteMsg::OpenHolderVote {
+            proposal_id: 2,
+            start_time: 1500,
+            end_time: 5000,
+        },
+    )
+    .unwrap();
+    let res = execute(
+        deps.as_mut(),
+        mock_env_at_timestamp(2000),
+        mock_info("user_1", &[]),
+        ExecuteMsg::CastHolderVote {
+            proposal_id: 2,
+            votes: vec![(Decimal::one(), VoteOption::Yes)],
+        },
+    )
+    .unwrap();
+    assert_eq!(res.events[0].attributes[2].value, "400");
+}
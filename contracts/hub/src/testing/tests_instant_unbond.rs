@@ -0,0 +1,386 @@
+use cosmwasm_std::testing::{mock_info, MockApi, MockStorage, MOCK_CONTRACT_ADDR};
+use cosmwasm_std::{Addr, BankMsg, Coin, CosmosMsg, Decimal, OwnedDeps, StdError, Uint128};
+
+use eris::hub::{
+    Batch, CallbackMsg, ExecuteMsg, InstantUnbondConfig, InstantUnbondQuoteResponse, PendingBatch,
+    QueryMsg, UnbondRequest,
+};
+use eris_chain_adapter::types::CustomQueryType;
+
+use crate::contract::execute;
+use crate::error::ContractError;
+use crate::state::State;
+use crate::testing::helpers::{
+    get_stake_full_denom, query_helper, set_total_stake_supply, setup_test, MOCK_UTOKEN,
+};
+use crate::types::{Delegation, SendFee};
+
+use super::custom_querier::CustomQuerier;
+use super::helpers::{mock_env_at_timestamp, UpdateConfigBuilder};
+
+fn instant_unbond_config() -> InstantUnbondConfig {
+    InstantUnbondConfig {
+        reserve_share: Decimal::percent(50),
+        reserve_target: Uint128::new(10000),
+        min_fee: Decimal::permille(5),
+        max_fee: Decimal::percent(5),
+    }
+}
+
+fn setup_instant_unbond(
+) -> (OwnedDeps<MockStorage, MockApi, CustomQuerier, CustomQueryType>, State<'static>) {
+    let mut deps = setup_test();
+    let state = State::default();
+
+    deps.querier.set_staking_delegations(&[
+        Delegation::new("alice", 333334, MOCK_UTOKEN),
+        Delegation::new("bob", 333333, MOCK_UTOKEN),
+        Delegation::new("charlie", 333333, MOCK_UTOKEN),
+    ]);
+    set_total_stake_supply(&state, &mut deps, 1000000);
+
+    execute(
+        deps.as_mut(),
+        mock_env_at_timestamp(12345),
+        mock_info("owner", &[]),
+        UpdateConfigBuilder {
+            instant_unbond_config: Some(instant_unbond_config()),
+            ..Default::default()
+        }
+        .build(),
+    )
+    .unwrap();
+
+    execute(
+        deps.as_mut(),
+        mock_env_at_timestamp(12345),
+        mock_info("user_1", &[Coin::new(10000, MOCK_UTOKEN)]),
+        ExecuteMsg::DonateReserve {},
+    )
+    .unwrap();
+
+    (deps, state)
+}
+
+//--------------------------------------------------------------------------------------------------
+// Execution
+//--------------------------------------------------------------------------------------------------
+
+#[test]
+fn updating_instant_unbond_config() {
+    let mut deps = setup_test();
+    let state = State::default();
+
+    let err = execute(
+        deps.as_mut(),
+        mock_env_at_timestamp(12345),
+        mock_info("jake", &[]),
+        UpdateConfigBuilder {
+            instant_unbond_config: Some(instant_unbond_config()),
+            ..Default::default()
+        }
+        .build(),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+
+    let err = execute(
+        deps.as_mut(),
+        mock_env_at_timestamp(12345),
+        mock_info("owner", &[]),
+        UpdateConfigBuilder {
+            instant_unbond_config: Some(InstantUnbondConfig {
+                min_fee: Decimal::percent(6),
+                ..instant_unbond_config()
+            }),
+            ..Default::default()
+        }
+        .build(),
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::Std(StdError::generic_err("min_fee greater than max_fee".to_string()))
+    );
+
+    let err = execute(
+        deps.as_mut(),
+        mock_env_at_timestamp(12345),
+        mock_info("owner", &[]),
+        UpdateConfigBuilder {
+            instant_unbond_config: Some(InstantUnbondConfig {
+                reserve_target: Uint128::zero(),
+                ..instant_unbond_config()
+            }),
+            ..Default::default()
+        }
+        .build(),
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::Std(StdError::generic_err("reserve_target can't be zero".to_string()))
+    );
+
+    execute(
+        deps.as_mut(),
+        mock_env_at_timestamp(12345),
+        mock_info("owner", &[]),
+        UpdateConfigBuilder {
+            instant_unbond_config: Some(instant_unbond_config()),
+            ..Default::default()
+        }
+        .build(),
+    )
+    .unwrap();
+
+    let config = state.instant_unbond_config.load(deps.as_ref().storage).unwrap();
+    assert_eq!(config, instant_unbond_config());
+
+    execute(
+        deps.as_mut(),
+        mock_env_at_timestamp(12345),
+        mock_info("owner", &[]),
+        UpdateConfigBuilder {
+            remove_instant_unbond_config: Some(true),
+            ..Default::default()
+        }
+        .build(),
+    )
+    .unwrap();
+
+    let config = state.instant_unbond_config.may_load(deps.as_ref().storage).unwrap();
+    assert_eq!(config, None);
+
+    let err = execute(
+        deps.as_mut(),
+        mock_env_at_timestamp(12345),
+        mock_info("user_1", &[Coin::new(1000, get_stake_full_denom())]),
+        ExecuteMsg::InstantUnbond {
+            min_received: None,
+            receiver: None,
+        },
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::InstantUnbondDisabled {});
+}
+
+#[test]
+fn donating_reserve() {
+    let (mut deps, state) = setup_instant_unbond();
+
+    let err = execute(
+        deps.as_mut(),
+        mock_env_at_timestamp(12345),
+        mock_info("user_1", &[Coin::new(100, "random_token")]),
+        ExecuteMsg::DonateReserve {},
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::Std(StdError::generic_err(format!(
+            "expected {} deposit, received random_token",
+            MOCK_UTOKEN
+        )))
+    );
+
+    let res = execute(
+        deps.as_mut(),
+        mock_env_at_timestamp(12345),
+        mock_info("user_2", &[Coin::new(500, MOCK_UTOKEN)]),
+        ExecuteMsg::DonateReserve {},
+    )
+    .unwrap();
+    assert_eq!(res.messages.len(), 0);
+
+    let reserve = state.instant_unbond_reserve.load(deps.as_ref().storage).unwrap();
+    assert_eq!(reserve, Uint128::new(10500));
+}
+
+#[test]
+fn instant_unbonding() {
+    let (mut deps, state) = setup_instant_unbond();
+
+    // Only Stake token is accepted for instant unbonding
+    let err = execute(
+        deps.as_mut(),
+        mock_env_at_timestamp(12345),
+        mock_info("user_1", &[Coin::new(2000, MOCK_UTOKEN)]),
+        ExecuteMsg::InstantUnbond {
+            min_received: None,
+            receiver: None,
+        },
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::ExpectingStakeToken(MOCK_UTOKEN.into()));
+
+    // 2000 utoken drain the reserve to 80% of its target -> fee = 5% - 4.5% * 0.8 = 1.4%
+    let quote: InstantUnbondQuoteResponse = query_helper(
+        deps.as_ref(),
+        QueryMsg::InstantUnbondQuote {
+            ustake_amount: Uint128::new(2000),
+        },
+    );
+    assert_eq!(
+        quote,
+        InstantUnbondQuoteResponse {
+            utoken_amount: Uint128::new(2000),
+            fee: Decimal::permille(14),
+            fee_amount: Uint128::new(28),
+            receive_amount: Uint128::new(1972),
+            reserve: Uint128::new(10000),
+        }
+    );
+
+    let err = execute(
+        deps.as_mut(),
+        mock_env_at_timestamp(12345),
+        mock_info("user_1", &[Coin::new(2000, get_stake_full_denom())]),
+        ExecuteMsg::InstantUnbond {
+            min_received: Some(Uint128::new(1973)),
+            receiver: None,
+        },
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::MinReceivedNotReached(Uint128::new(1973)));
+
+    let err = execute(
+        deps.as_mut(),
+        mock_env_at_timestamp(12345),
+        mock_info("user_1", &[Coin::new(20000, get_stake_full_denom())]),
+        ExecuteMsg::InstantUnbond {
+            min_received: None,
+            receiver: None,
+        },
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::InstantUnbondReserveTooLow(Uint128::new(10000)));
+
+    let res = execute(
+        deps.as_mut(),
+        mock_env_at_timestamp(12345),
+        mock_info("user_1", &[Coin::new(2000, get_stake_full_denom())]),
+        ExecuteMsg::InstantUnbond {
+            min_received: Some(Uint128::new(1972)),
+            receiver: Some("user_2".to_string()),
+        },
+    )
+    .unwrap();
+
+    assert_eq!(res.messages.len(), 1);
+    assert_eq!(
+        res.messages[0].msg,
+        CosmosMsg::Bank(BankMsg::Send {
+            to_address: "user_2".to_string(),
+            amount: vec![Coin::new(1972, MOCK_UTOKEN)]
+        })
+    );
+
+    let reserve = state.instant_unbond_reserve.load(deps.as_ref().storage).unwrap();
+    assert_eq!(reserve, Uint128::new(8028));
+
+    // The contract takes over the unbonding position
+    let ubr = state
+        .unbond_requests
+        .load(deps.as_ref().storage, (1u64, &Addr::unchecked(MOCK_CONTRACT_ADDR)))
+        .unwrap();
+    assert_eq!(
+        ubr,
+        UnbondRequest {
+            id: 1,
+            user: Addr::unchecked(MOCK_CONTRACT_ADDR),
+            shares: Uint128::new(2000)
+        }
+    );
+
+    let pending_batch = state.pending_batch.load(deps.as_ref().storage).unwrap();
+    assert_eq!(
+        pending_batch,
+        PendingBatch {
+            id: 1,
+            ustake_to_burn: Uint128::new(2000),
+            est_unbond_start_time: 269200
+        }
+    );
+}
+
+#[test]
+fn instant_unbonding_disabled() {
+    let mut deps = setup_test();
+
+    let err = execute(
+        deps.as_mut(),
+        mock_env_at_timestamp(12345),
+        mock_info("user_1", &[Coin::new(2000, get_stake_full_denom())]),
+        ExecuteMsg::InstantUnbond {
+            min_received: None,
+            receiver: None,
+        },
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::InstantUnbondDisabled {});
+}
+
+#[test]
+fn reinvesting_into_reserve() {
+    let (mut deps, state) = setup_instant_unbond();
+
+    execute(
+        deps.as_mut(),
+        mock_env_at_timestamp(12345),
+        mock_info("user_1", &[Coin::new(2000, get_stake_full_denom())]),
+        ExecuteMsg::InstantUnbond {
+            min_received: None,
+            receiver: None,
+        },
+    )
+    .unwrap();
+
+    // The batch of the instant unbond has finished unbonding
+    state
+        .previous_batches
+        .save(
+            deps.as_mut().storage,
+            1,
+            &Batch {
+                id: 1,
+                reconciled: true,
+                total_shares: Uint128::new(2000),
+                utoken_unclaimed: Uint128::new(2100),
+                est_unbond_end_time: 10000,
+            },
+        )
+        .unwrap();
+
+    state.unlocked_coins.save(deps.as_mut().storage, &vec![Coin::new(234, MOCK_UTOKEN)]).unwrap();
+
+    let res = execute(
+        deps.as_mut(),
+        mock_env_at_timestamp(12345),
+        mock_info(MOCK_CONTRACT_ADDR, &[]),
+        ExecuteMsg::Callback(CallbackMsg::Reinvest {
+            skip_fee: false,
+        }),
+    )
+    .unwrap();
+
+    // fee = 2, remaining = 232, half of it goes to the reserve
+    assert_eq!(res.messages.len(), 2);
+    assert_eq!(res.messages[0].msg, Delegation::new("bob", 116, MOCK_UTOKEN).to_cosmos_msg());
+    assert_eq!(
+        res.messages[1].msg,
+        SendFee::new(Addr::unchecked("fee"), 2, MOCK_UTOKEN).to_cosmos_msg()
+    );
+
+    // 8028 + 116 + 2100
+    let reserve = state.instant_unbond_reserve.load(deps.as_ref().storage).unwrap();
+    assert_eq!(reserve, Uint128::new(10244));
+
+    // The claimed request and batch are removed
+    assert!(state
+        .unbond_requests
+        .may_load(deps.as_ref().storage, (1u64, &Addr::unchecked(MOCK_CONTRACT_ADDR)))
+        .unwrap()
+        .is_none());
+    assert!(state.previous_batches.may_load(deps.as_ref().storage, 1).unwrap().is_none());
+}
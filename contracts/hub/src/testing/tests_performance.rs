@@ -0,0 +1,261 @@
+use std::str::FromStr;
+
+use cosmwasm_std::testing::{mock_info, MockApi, MockStorage};
+use cosmwasm_std::{Decimal, OwnedDeps, StdError, Uint128};
+
+use eris::governance_helper::{EPOCH_START, WEEK};
+use eris::hub::{
+    ConfigResponse, DelegationStrategy, ExecuteMsg, QueryMsg, WantedDelegationsResponse,
+    WantedDelegationsShare,
+};
+use eris_chain_adapter::types::CustomQueryType;
+
+use crate::contract::execute;
+use crate::error::ContractError;
+use crate::state::State;
+use crate::testing::helpers::{query_helper, query_helper_env, setup_test, MOCK_UTOKEN};
+use crate::types::Delegation;
+
+use super::custom_querier::CustomQuerier;
+use super::helpers::{mock_env_at_timestamp, UpdateConfigBuilder};
+
+fn performance_strategy(
+    uptime_oracle: Option<&str>,
+    min_score: &str,
+    validator_count: u8,
+) -> DelegationStrategy {
+    DelegationStrategy::Performance {
+        commission_cap_bps: 1500,
+        uptime_oracle: uptime_oracle.map(|a| a.to_string()),
+        min_score: Decimal::from_str(min_score).unwrap(),
+        max_delegation_bps: 10000,
+        validator_count,
+    }
+}
+
+fn setup_performance(
+    delegation_strategy: DelegationStrategy,
+) -> OwnedDeps<MockStorage, MockApi, CustomQuerier, CustomQueryType> {
+    let mut deps = setup_test();
+
+    deps.querier.set_staking_validators(&[
+        ("alice", Decimal::percent(10)),
+        ("bob", Decimal::percent(10)),
+        ("charlie", Decimal::percent(20)),
+    ]);
+    deps.querier.set_staking_delegations(&[
+        Delegation::new("alice", 500000, MOCK_UTOKEN),
+        Delegation::new("bob", 300000, MOCK_UTOKEN),
+        Delegation::new("charlie", 200000, MOCK_UTOKEN),
+    ]);
+
+    execute(
+        deps.as_mut(),
+        mock_env_at_timestamp(12345),
+        mock_info("owner", &[]),
+        UpdateConfigBuilder {
+            delegation_strategy: Some(delegation_strategy),
+            ..Default::default()
+        }
+        .build(),
+    )
+    .unwrap();
+
+    deps
+}
+
+//--------------------------------------------------------------------------------------------------
+// Execution
+//--------------------------------------------------------------------------------------------------
+
+#[test]
+fn updating_performance_strategy() {
+    let mut deps = setup_test();
+
+    let err = execute(
+        deps.as_mut(),
+        mock_env_at_timestamp(12345),
+        mock_info("owner", &[]),
+        UpdateConfigBuilder {
+            delegation_strategy: Some(performance_strategy(None, "1.1", 3)),
+            ..Default::default()
+        }
+        .build(),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::Std(StdError::generic_err("min_score greater than 1")));
+
+    let err = execute(
+        deps.as_mut(),
+        mock_env_at_timestamp(12345),
+        mock_info("owner", &[]),
+        UpdateConfigBuilder {
+            delegation_strategy: Some(DelegationStrategy::Performance {
+                commission_cap_bps: 10001,
+                uptime_oracle: None,
+                min_score: Decimal::zero(),
+                max_delegation_bps: 10000,
+                validator_count: 3,
+            }),
+            ..Default::default()
+        }
+        .build(),
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::Std(StdError::generic_err("Basic points conversion error. 10001 > 10000"))
+    );
+
+    execute(
+        deps.as_mut(),
+        mock_env_at_timestamp(12345),
+        mock_info("owner", &[]),
+        UpdateConfigBuilder {
+            delegation_strategy: Some(performance_strategy(Some("uptime_oracle"), "0.5", 3)),
+            ..Default::default()
+        }
+        .build(),
+    )
+    .unwrap();
+
+    let res: ConfigResponse = query_helper(deps.as_ref(), QueryMsg::Config {});
+    assert_eq!(res.delegation_strategy, performance_strategy(Some("uptime_oracle"), "0.5", 3));
+}
+
+#[test]
+fn tuning_performance_delegations() {
+    let mut deps = setup_performance(performance_strategy(Some("uptime_oracle"), "0.5", 3));
+    let state = State::default();
+
+    // charlie is above the commission cap, the score of bob is reduced by his uptime
+    deps.querier.set_uptimes(&[("alice", Decimal::one()), ("bob", Decimal::percent(60))]);
+
+    execute(
+        deps.as_mut(),
+        mock_env_at_timestamp(EPOCH_START + WEEK),
+        mock_info("owner", &[]),
+        ExecuteMsg::TuneDelegations {},
+    )
+    .unwrap();
+
+    // alice = 0.9 * 1.0 = 0.9, bob = 0.9 * 0.6 = 0.54
+    assert_eq!(
+        state.delegation_goal.load(deps.as_ref().storage).unwrap(),
+        WantedDelegationsShare {
+            tune_time: EPOCH_START + WEEK,
+            tune_period: 1,
+            shares: vec![
+                ("alice".into(), Decimal::from_str("0.625").unwrap()),
+                ("bob".into(), Decimal::from_str("0.375").unwrap())
+            ],
+            parts: None,
+        }
+    );
+
+    let res: WantedDelegationsResponse =
+        query_helper(deps.as_ref(), QueryMsg::WantedDelegations {});
+    assert_eq!(
+        res.delegations,
+        vec![("alice".into(), Uint128::new(625000)), ("bob".into(), Uint128::new(375000))]
+    );
+
+    // the uptime of alice dropped below the min score
+    deps.querier.set_uptimes(&[("alice", Decimal::percent(50)), ("bob", Decimal::percent(60))]);
+
+    let res: WantedDelegationsResponse = query_helper_env(
+        deps.as_ref(),
+        QueryMsg::SimulateWantedDelegations {
+            period: None,
+        },
+        EPOCH_START + WEEK,
+    );
+    assert_eq!(res.delegations, vec![("bob".into(), Uint128::new(1000000))]);
+}
+
+#[test]
+fn tuning_performance_delegations_without_oracle() {
+    let mut deps = setup_performance(performance_strategy(None, "0.5", 1));
+    let state = State::default();
+
+    // alice is jailed and not returned by the staking module
+    deps.querier
+        .set_staking_validators(&[("bob", Decimal::percent(10)), ("charlie", Decimal::percent(5))]);
+
+    execute(
+        deps.as_mut(),
+        mock_env_at_timestamp(EPOCH_START + WEEK),
+        mock_info("owner", &[]),
+        ExecuteMsg::TuneDelegations {},
+    )
+    .unwrap();
+
+    assert_eq!(
+        state.delegation_goal.load(deps.as_ref().storage).unwrap().shares,
+        vec![("charlie".into(), Decimal::one())]
+    );
+
+    execute(
+        deps.as_mut(),
+        mock_env_at_timestamp(12345),
+        mock_info("owner", &[]),
+        UpdateConfigBuilder {
+            delegation_strategy: Some(performance_strategy(None, "0.99", 3)),
+            ..Default::default()
+        }
+        .build(),
+    )
+    .unwrap();
+
+    let err = execute(
+        deps.as_mut(),
+        mock_env_at_timestamp(EPOCH_START + WEEK),
+        mock_info("owner", &[]),
+        ExecuteMsg::TuneDelegations {},
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::Std(StdError::generic_err(
+            "No validator fulfills the performance criteria."
+        ))
+    );
+}
+
+#[test]
+fn tuning_performance_delegations_respects_max_delegation() {
+    let mut deps = setup_performance(DelegationStrategy::Performance {
+        commission_cap_bps: 10000,
+        uptime_oracle: None,
+        min_score: Decimal::zero(),
+        max_delegation_bps: 4000,
+        validator_count: 3,
+    });
+    let state = State::default();
+
+    deps.querier.set_staking_validators(&[
+        ("alice", Decimal::zero()),
+        ("bob", Decimal::percent(25)),
+        ("charlie", Decimal::percent(75)),
+    ]);
+
+    execute(
+        deps.as_mut(),
+        mock_env_at_timestamp(EPOCH_START + WEEK),
+        mock_info("owner", &[]),
+        ExecuteMsg::TuneDelegations {},
+    )
+    .unwrap();
+
+    // alice = 1.0 / 2.0 is capped, the excess pushes bob = 0.6 * 0.75 over the cap as well
+    let shares = state.delegation_goal.load(deps.as_ref().storage).unwrap().shares;
+    assert_eq!(
+        shares,
+        vec![
+            ("alice".into(), Decimal::percent(40)),
+            ("bob".into(), Decimal::percent(40)),
+            ("charlie".into(), Decimal::percent(20))
+        ]
+    );
+    assert!(shares.iter().all(|(_, share)| *share <= Decimal::percent(40)));
+}
@@ -0,0 +1,293 @@
+use cosmwasm_std::testing::{mock_info, MockApi, MockStorage, MOCK_CONTRACT_ADDR};
+use cosmwasm_std::{coin, CosmosMsg, Decimal, OwnedDeps, StdError, Uint128};
+
+use eris::hub::{
+    CallbackMsg, ExecuteMsg, PriceFeed, PriceFeedResponse, PriceFeedsResponse, PriceSource,
+    QueryMsg,
+};
+use eris_chain_adapter::types::{CustomMsgType, CustomQueryType, StageType};
+use eris_chain_shared::chain_trait::ChainInterface;
+
+use crate::contract::execute;
+use crate::error::ContractError;
+use crate::state::State;
+use crate::testing::helpers::{chain_test, query_helper, setup_test};
+
+use super::custom_querier::CustomQuerier;
+use super::helpers::mock_env_at_timestamp;
+
+fn update_price_feed(
+    deps: &mut OwnedDeps<MockStorage, MockApi, CustomQuerier, CustomQueryType>,
+    source: PriceSource,
+) {
+    execute(
+        deps.as_mut(),
+        mock_env_at_timestamp(10000),
+        mock_info("owner", &[]),
+        ExecuteMsg::UpdatePriceFeed {
+            denom: "ukuji".to_string(),
+            feed: Some(PriceFeed {
+                source,
+                max_age: 600,
+            }),
+        },
+    )
+    .unwrap();
+}
+
+fn single_stage_swap_msg() -> ExecuteMsg {
+    ExecuteMsg::Callback(CallbackMsg::SingleStageSwap {
+        stage: vec![(StageType::fin("fin"), "ukuji".into(), None, None, None)],
+        index: 0,
+    })
+}
+
+fn expected_swap_msg(
+    deps: &OwnedDeps<MockStorage, MockApi, CustomQuerier, CustomQueryType>,
+    belief_price: Option<Decimal>,
+) -> CosmosMsg<CustomMsgType> {
+    chain_test()
+        .create_single_stage_swap_msgs(
+            || State::default().chain_config.load(deps.as_ref().storage),
+            StageType::fin("fin"),
+            "ukuji".into(),
+            Uint128::new(1000),
+            belief_price,
+            Decimal::percent(10),
+        )
+        .unwrap()
+}
+
+//--------------------------------------------------------------------------------------------------
+// Execution
+//--------------------------------------------------------------------------------------------------
+
+#[test]
+fn updating_price_feeds() {
+    let mut deps = setup_test();
+
+    let err = execute(
+        deps.as_mut(),
+        mock_env_at_timestamp(10000),
+        mock_info("operator", &[]),
+        ExecuteMsg::UpdatePriceFeed {
+            denom: "ukuji".to_string(),
+            feed: None,
+        },
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+
+    let err = execute(
+        deps.as_mut(),
+        mock_env_at_timestamp(10000),
+        mock_info("owner", &[]),
+        ExecuteMsg::UpdatePriceFeed {
+            denom: "ukuji".to_string(),
+            feed: Some(PriceFeed {
+                source: PriceSource::Pushed {},
+                max_age: 0,
+            }),
+        },
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::Std(StdError::generic_err("max_age can't be zero")));
+
+    let err = execute(
+        deps.as_mut(),
+        mock_env_at_timestamp(10000),
+        mock_info("owner", &[]),
+        ExecuteMsg::UpdatePriceFeed {
+            denom: "ukuji".to_string(),
+            feed: Some(PriceFeed {
+                source: PriceSource::Pushed {},
+                max_age: u64::MAX,
+            }),
+        },
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::Std(StdError::generic_err("max_age can't be above 604800")));
+
+    update_price_feed(&mut deps, PriceSource::Pushed {});
+
+    let err = execute(
+        deps.as_mut(),
+        mock_env_at_timestamp(10000),
+        mock_info("owner", &[]),
+        ExecuteMsg::PushPrice {
+            denom: "ukuji".to_string(),
+            price: Decimal::percent(50),
+        },
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::UnauthorizedSenderNotOperator {});
+
+    let err = execute(
+        deps.as_mut(),
+        mock_env_at_timestamp(10000),
+        mock_info("operator", &[]),
+        ExecuteMsg::PushPrice {
+            denom: "uusdc".to_string(),
+            price: Decimal::percent(50),
+        },
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::PriceNotPushable("uusdc".to_string()));
+
+    execute(
+        deps.as_mut(),
+        mock_env_at_timestamp(10000),
+        mock_info("operator", &[]),
+        ExecuteMsg::PushPrice {
+            denom: "ukuji".to_string(),
+            price: Decimal::percent(50),
+        },
+    )
+    .unwrap();
+
+    let res: PriceFeedsResponse = query_helper(deps.as_ref(), QueryMsg::PriceFeeds {});
+    assert_eq!(
+        res,
+        PriceFeedsResponse {
+            feeds: vec![PriceFeedResponse {
+                denom: "ukuji".to_string(),
+                feed: PriceFeed {
+                    source: PriceSource::Pushed {},
+                    max_age: 600,
+                },
+                pushed_price: Some((Decimal::percent(50), 10000)),
+            }],
+        }
+    );
+
+    // removing the feed also removes the pushed price
+    execute(
+        deps.as_mut(),
+        mock_env_at_timestamp(10000),
+        mock_info("owner", &[]),
+        ExecuteMsg::UpdatePriceFeed {
+            denom: "ukuji".to_string(),
+            feed: None,
+        },
+    )
+    .unwrap();
+
+    let res: PriceFeedsResponse = query_helper(deps.as_ref(), QueryMsg::PriceFeeds {});
+    assert_eq!(res.feeds, vec![]);
+}
+
+#[test]
+fn swapping_with_pushed_price() {
+    let mut deps = setup_test();
+    deps.querier.set_bank_balances(&[coin(1000, "ukuji")]);
+
+    // without a price feed no belief price is used
+    let res = execute(
+        deps.as_mut(),
+        mock_env_at_timestamp(10000),
+        mock_info(MOCK_CONTRACT_ADDR, &[]),
+        single_stage_swap_msg(),
+    )
+    .unwrap();
+    assert_eq!(res.messages[0].msg, expected_swap_msg(&deps, None));
+
+    update_price_feed(&mut deps, PriceSource::Pushed {});
+
+    let err = execute(
+        deps.as_mut(),
+        mock_env_at_timestamp(10000),
+        mock_info(MOCK_CONTRACT_ADDR, &[]),
+        single_stage_swap_msg(),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::PriceNotAvailable("ukuji".to_string()));
+
+    execute(
+        deps.as_mut(),
+        mock_env_at_timestamp(10000),
+        mock_info("operator", &[]),
+        ExecuteMsg::PushPrice {
+            denom: "ukuji".to_string(),
+            price: Decimal::percent(50),
+        },
+    )
+    .unwrap();
+
+    // 1 ukuji = 0.5 utoken -> 2 ukuji are offered per utoken
+    let res = execute(
+        deps.as_mut(),
+        mock_env_at_timestamp(10600),
+        mock_info(MOCK_CONTRACT_ADDR, &[]),
+        single_stage_swap_msg(),
+    )
+    .unwrap();
+    assert_eq!(res.messages.len(), 1);
+    assert_eq!(
+        res.messages[0].msg,
+        expected_swap_msg(&deps, Some(Decimal::from_ratio(2u128, 1u128)))
+    );
+
+    let err = execute(
+        deps.as_mut(),
+        mock_env_at_timestamp(10601),
+        mock_info(MOCK_CONTRACT_ADDR, &[]),
+        single_stage_swap_msg(),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::PriceStale("ukuji".to_string()));
+}
+
+#[test]
+fn swapping_with_twap_price() {
+    let mut deps = setup_test();
+    deps.querier.set_bank_balances(&[coin(1000, "ukuji")]);
+    deps.querier.set_twap_price("ukuji", Decimal::percent(25), 9900);
+
+    update_price_feed(
+        &mut deps,
+        PriceSource::Twap {
+            contract: "twap".to_string(),
+        },
+    );
+
+    let res = execute(
+        deps.as_mut(),
+        mock_env_at_timestamp(10000),
+        mock_info(MOCK_CONTRACT_ADDR, &[]),
+        single_stage_swap_msg(),
+    )
+    .unwrap();
+    assert_eq!(
+        res.messages[0].msg,
+        expected_swap_msg(&deps, Some(Decimal::from_ratio(4u128, 1u128)))
+    );
+
+    // the oracle has not been updated for longer than the max age
+    let err = execute(
+        deps.as_mut(),
+        mock_env_at_timestamp(10501),
+        mock_info(MOCK_CONTRACT_ADDR, &[]),
+        single_stage_swap_msg(),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::PriceStale("ukuji".to_string()));
+
+    // an explicit belief price has priority over the price feed
+    let res = execute(
+        deps.as_mut(),
+        mock_env_at_timestamp(10501),
+        mock_info(MOCK_CONTRACT_ADDR, &[]),
+        ExecuteMsg::Callback(CallbackMsg::SingleStageSwap {
+            stage: vec![(
+                StageType::fin("fin"),
+                "ukuji".into(),
+                Some(Decimal::percent(300)),
+                None,
+                None,
+            )],
+            index: 0,
+        }),
+    )
+    .unwrap();
+    assert_eq!(res.messages[0].msg, expected_swap_msg(&deps, Some(Decimal::percent(300))));
+}
@@ -0,0 +1,270 @@
+use cosmwasm_std::testing::{mock_info, MockApi, MockStorage, MOCK_CONTRACT_ADDR};
+use cosmwasm_std::{
+    Addr, BankMsg, Coin, CosmosMsg, Order, OwnedDeps, StakingMsg, StdError, StdResult, Uint128,
+};
+
+use eris::hub::{
+    CallbackMsg, ConfigResponse, DelegationStrategy, ExecuteMsg, QueryMsg, RebalanceConfig,
+};
+use eris_chain_adapter::types::CustomQueryType;
+
+use crate::contract::execute;
+use crate::error::ContractError;
+use crate::state::State;
+use crate::testing::helpers::{query_helper, set_total_stake_supply, setup_test, MOCK_UTOKEN};
+use crate::types::{Delegation, Redelegation};
+
+use super::custom_querier::CustomQuerier;
+use super::helpers::{mock_env_at_timestamp, UpdateConfigBuilder};
+
+fn rebalance_config() -> RebalanceConfig {
+    RebalanceConfig {
+        max_redelegation_per_epoch: Uint128::new(200000),
+        min_redelegation: Uint128::new(1000),
+        bounty: Uint128::new(100),
+    }
+}
+
+fn setup_rebalance() -> OwnedDeps<MockStorage, MockApi, CustomQuerier, CustomQueryType> {
+    let mut deps = setup_test();
+    let state = State::default();
+
+    deps.querier.set_staking_delegations(&[
+        Delegation::new("alice", 333334, MOCK_UTOKEN),
+        Delegation::new("bob", 333333, MOCK_UTOKEN),
+        Delegation::new("charlie", 333333, MOCK_UTOKEN),
+    ]);
+    set_total_stake_supply(&state, &mut deps, 1000000);
+
+    execute(
+        deps.as_mut(),
+        mock_env_at_timestamp(300000),
+        mock_info("owner", &[]),
+        UpdateConfigBuilder {
+            delegation_strategy: Some(DelegationStrategy::Defined {
+                shares_bps: vec![("alice".into(), 6000), ("bob".into(), 4000)],
+            }),
+            rebalance_config: Some(rebalance_config()),
+            ..Default::default()
+        }
+        .build(),
+    )
+    .unwrap();
+
+    deps
+}
+
+fn tune(deps: &mut OwnedDeps<MockStorage, MockApi, CustomQuerier, CustomQueryType>) {
+    execute(
+        deps.as_mut(),
+        mock_env_at_timestamp(300000),
+        mock_info("owner", &[]),
+        ExecuteMsg::TuneDelegations {},
+    )
+    .unwrap();
+}
+
+fn rebalance(
+    deps: &mut OwnedDeps<MockStorage, MockApi, CustomQuerier, CustomQueryType>,
+    sender: &str,
+    timestamp: u64,
+) -> Result<Vec<CosmosMsg>, ContractError> {
+    execute(
+        deps.as_mut(),
+        mock_env_at_timestamp(timestamp),
+        mock_info(sender, &[]),
+        ExecuteMsg::Rebalance {
+            min_redelegation: None,
+        },
+    )
+    .map(|res| res.messages.into_iter().map(|msg| msg.msg).collect())
+}
+
+fn pending_bounties(
+    deps: &OwnedDeps<MockStorage, MockApi, CustomQuerier, CustomQueryType>,
+) -> Vec<(Addr, Uint128)> {
+    State::default()
+        .pending_bounties
+        .range(deps.as_ref().storage, None, None, Order::Ascending)
+        .collect::<StdResult<_>>()
+        .unwrap()
+}
+
+fn redelegation_entries(
+    deps: &OwnedDeps<MockStorage, MockApi, CustomQuerier, CustomQueryType>,
+) -> Vec<(String, u64)> {
+    State::default()
+        .redelegation_entries
+        .range(deps.as_ref().storage, None, None, Order::Ascending)
+        .collect::<StdResult<_>>()
+        .unwrap()
+}
+
+//--------------------------------------------------------------------------------------------------
+// Execution
+//--------------------------------------------------------------------------------------------------
+
+#[test]
+fn updating_rebalance_config() {
+    let mut deps = setup_test();
+
+    let err = execute(
+        deps.as_mut(),
+        mock_env_at_timestamp(10000),
+        mock_info("owner", &[]),
+        UpdateConfigBuilder {
+            rebalance_config: Some(RebalanceConfig {
+                max_redelegation_per_epoch: Uint128::zero(),
+                ..rebalance_config()
+            }),
+            ..Default::default()
+        }
+        .build(),
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::Std(StdError::generic_err("max_redelegation_per_epoch can't be zero"))
+    );
+
+    // without a rebalance config only the owner can rebalance
+    let err = rebalance(&mut deps, "keeper", 10000).unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+
+    execute(
+        deps.as_mut(),
+        mock_env_at_timestamp(10000),
+        mock_info("owner", &[]),
+        UpdateConfigBuilder {
+            rebalance_config: Some(rebalance_config()),
+            ..Default::default()
+        }
+        .build(),
+    )
+    .unwrap();
+
+    let res: ConfigResponse = query_helper(deps.as_ref(), QueryMsg::Config {});
+    assert_eq!(res.rebalance_config, Some(rebalance_config()));
+}
+
+#[test]
+fn rebalancing_permissionless() {
+    let mut deps = setup_rebalance();
+    let state = State::default();
+
+    let err = rebalance(&mut deps, "keeper", 300000).unwrap_err();
+    assert_eq!(err, ContractError::DelegationGoalNotTuned {});
+
+    tune(&mut deps);
+
+    // only the budget of the epoch is moved from charlie
+    let msgs = rebalance(&mut deps, "keeper", 300000).unwrap();
+    assert_eq!(msgs.len(), 2);
+    assert_eq!(msgs[0], Redelegation::new("charlie", "alice", 200000, MOCK_UTOKEN).to_cosmos_msg());
+    assert_eq!(pending_bounties(&deps), vec![(Addr::unchecked("keeper"), Uint128::new(100))]);
+
+    let err = rebalance(&mut deps, "keeper", 300000).unwrap_err();
+    assert_eq!(err, ContractError::NothingToRebalance {});
+
+    // the owner is not limited by the budget
+    let msgs = rebalance(&mut deps, "owner", 300000).unwrap();
+    assert!(msgs.len() > 1);
+    assert_eq!(pending_bounties(&deps).len(), 1);
+
+    // the budget is reset in the next epoch
+    rebalance(&mut deps, "keeper", 600000).unwrap();
+    assert_eq!(pending_bounties(&deps), vec![(Addr::unchecked("keeper"), Uint128::new(200))]);
+}
+
+#[test]
+fn skipping_pending_redelegations() {
+    let mut deps = setup_rebalance();
+    let state = State::default();
+    tune(&mut deps);
+
+    // charlie received a redelegation which completes at 400000
+    state.redelegation_entries.save(deps.as_mut().storage, "charlie", &400000).unwrap();
+
+    let err = rebalance(&mut deps, "keeper", 300000).unwrap_err();
+    assert_eq!(err, ContractError::NothingToRebalance {});
+
+    let msgs = rebalance(&mut deps, "keeper", 400000).unwrap();
+    assert_eq!(msgs[0], Redelegation::new("charlie", "alice", 200000, MOCK_UTOKEN).to_cosmos_msg());
+
+    // the completed entry of charlie is removed, alice can't redelegate until the unbond period
+    // has passed
+    assert_eq!(redelegation_entries(&deps), vec![("alice".to_string(), 400000 + 1814400)]);
+    assert_eq!(
+        state.prune_redelegation_entries(deps.as_mut().storage, 400000).unwrap(),
+        vec!["alice".to_string()]
+    );
+    assert_eq!(
+        state.prune_redelegation_entries(deps.as_mut().storage, 400000 + 1814400).unwrap(),
+        Vec::<String>::new()
+    );
+    assert_eq!(redelegation_entries(&deps), vec![]);
+}
+
+#[test]
+fn owner_rebalancing_pending_redelegations() {
+    let mut deps = setup_rebalance();
+    let state = State::default();
+    tune(&mut deps);
+
+    state.redelegation_entries.save(deps.as_mut().storage, "charlie", &400000).unwrap();
+
+    // the owner is not restricted by pending redelegations
+    let msgs = rebalance(&mut deps, "owner", 300000).unwrap();
+    assert!(matches!(
+        &msgs[0],
+        CosmosMsg::Staking(StakingMsg::Redelegate { src_validator, .. }) if src_validator == "charlie"
+    ));
+}
+
+#[test]
+fn paying_bounties_from_fee() {
+    let mut deps = setup_rebalance();
+    let state = State::default();
+
+    state
+        .pending_bounties
+        .save(deps.as_mut().storage, &Addr::unchecked("keeper_1"), &Uint128::new(1000))
+        .unwrap();
+    state
+        .pending_bounties
+        .save(deps.as_mut().storage, &Addr::unchecked("keeper_2"), &Uint128::new(500))
+        .unwrap();
+    state
+        .unlocked_coins
+        .save(deps.as_mut().storage, &vec![Coin::new(120000, MOCK_UTOKEN)])
+        .unwrap();
+
+    let res = execute(
+        deps.as_mut(),
+        mock_env_at_timestamp(300000),
+        mock_info(MOCK_CONTRACT_ADDR, &[]),
+        ExecuteMsg::Callback(CallbackMsg::Reinvest {
+            skip_fee: false,
+        }),
+    )
+    .unwrap();
+
+    // the fee of 1200 utoken covers the first bounty and part of the second
+    assert_eq!(
+        res.messages[0].msg,
+        CosmosMsg::Bank(BankMsg::Send {
+            to_address: "keeper_1".to_string(),
+            amount: vec![Coin::new(1000, MOCK_UTOKEN)],
+        })
+    );
+    assert_eq!(
+        res.messages[1].msg,
+        CosmosMsg::Bank(BankMsg::Send {
+            to_address: "keeper_2".to_string(),
+            amount: vec![Coin::new(200, MOCK_UTOKEN)],
+        })
+    );
+    // nothing is left for the fee recipient
+    assert_eq!(res.messages.len(), 3);
+    assert_eq!(pending_bounties(&deps), vec![(Addr::unchecked("keeper_2"), Uint128::new(300))]);
+}
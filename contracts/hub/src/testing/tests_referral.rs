@@ -0,0 +1,281 @@
+use cosmwasm_std::testing::{mock_info, MockApi, MockStorage, MOCK_CONTRACT_ADDR};
+use cosmwasm_std::{Addr, BankMsg, Coin, CosmosMsg, Decimal, OwnedDeps, SubMsg, Uint128};
+
+use eris::hub::{Batch, CallbackMsg, ExecuteMsg, QueryMsg, ReferralInfoResponse, SudoMsg};
+use eris_chain_adapter::types::CustomQueryType;
+
+use crate::contract::{execute, sudo};
+use crate::error::ContractError;
+use crate::state::State;
+use crate::testing::helpers::{
+    get_stake_full_denom, query_helper, set_total_stake_supply, setup_test, MOCK_UTOKEN,
+};
+use crate::types::{Delegation, SendFee};
+
+use super::custom_querier::CustomQuerier;
+use super::helpers::{mock_env_at_timestamp, UpdateConfigBuilder};
+
+fn setup_referral() -> OwnedDeps<MockStorage, MockApi, CustomQuerier, CustomQueryType> {
+    let mut deps = setup_test();
+    let state = State::default();
+
+    deps.querier.set_staking_delegations(&[
+        Delegation::new("alice", 333334, MOCK_UTOKEN),
+        Delegation::new("bob", 333333, MOCK_UTOKEN),
+        Delegation::new("charlie", 333333, MOCK_UTOKEN),
+    ]);
+    set_total_stake_supply(&state, &mut deps, 1000000);
+
+    execute(
+        deps.as_mut(),
+        mock_env_at_timestamp(10000),
+        mock_info("owner", &[]),
+        UpdateConfigBuilder {
+            referral_share: Some(Decimal::percent(10)),
+            ..Default::default()
+        }
+        .build(),
+    )
+    .unwrap();
+
+    deps
+}
+
+fn bond(
+    deps: &mut OwnedDeps<MockStorage, MockApi, CustomQuerier, CustomQueryType>,
+    user: &str,
+    referrer: &str,
+    amount: u128,
+) -> Result<(), ContractError> {
+    execute(
+        deps.as_mut(),
+        mock_env_at_timestamp(10000),
+        mock_info(user, &[Coin::new(amount, MOCK_UTOKEN)]),
+        ExecuteMsg::Bond {
+            receiver: None,
+            referrer: Some(referrer.to_string()),
+        },
+    )
+    .map(|_| ())
+}
+
+fn referral_info(
+    deps: &OwnedDeps<MockStorage, MockApi, CustomQuerier, CustomQueryType>,
+    referrer: &str,
+) -> ReferralInfoResponse {
+    query_helper(
+        deps.as_ref(),
+        QueryMsg::ReferralInfo {
+            referrer: referrer.to_string(),
+        },
+    )
+}
+
+//--------------------------------------------------------------------------------------------------
+// Execution
+//--------------------------------------------------------------------------------------------------
+
+#[test]
+fn bonding_with_referrer() {
+    let mut deps = setup_referral();
+
+    let err = bond(&mut deps, "user_1", "user_1", 1000).unwrap_err();
+    assert_eq!(err, ContractError::SelfReferral {});
+
+    bond(&mut deps, "user_1", "frontend", 1000).unwrap();
+    bond(&mut deps, "user_2", "frontend", 500).unwrap();
+
+    // users stay attributed to their first referrer
+    bond(&mut deps, "user_1", "other", 1000).unwrap();
+
+    assert_eq!(
+        referral_info(&deps, "frontend"),
+        ReferralInfoResponse {
+            referrer: "frontend".to_string(),
+            referred_ustake: Uint128::new(2500),
+            pending_rewards: Uint128::zero(),
+            claimed_rewards: Uint128::zero(),
+        }
+    );
+    assert_eq!(referral_info(&deps, "other").referred_ustake, Uint128::zero());
+
+    // unbonded stake is no longer referred
+    execute(
+        deps.as_mut(),
+        mock_env_at_timestamp(10000),
+        mock_info("user_1", &[Coin::new(1500, get_stake_full_denom())]),
+        ExecuteMsg::QueueUnbond {
+            receiver: None,
+        },
+    )
+    .unwrap();
+    assert_eq!(referral_info(&deps, "frontend").referred_ustake, Uint128::new(1000));
+}
+
+#[test]
+fn transferring_referred_stake() {
+    let mut deps = setup_referral();
+
+    bond(&mut deps, "user_1", "frontend", 1000).unwrap();
+
+    // transferred stake is no longer referred
+    sudo(
+        deps.as_mut(),
+        mock_env_at_timestamp(10000),
+        SudoMsg::TrackBeforeSend {
+            from: "user_1".to_string(),
+            to: "user_2".to_string(),
+            amount: Coin::new(400, get_stake_full_denom()),
+        },
+    )
+    .unwrap();
+    assert_eq!(referral_info(&deps, "frontend").referred_ustake, Uint128::new(600));
+
+    // stake sent to the hub is removed when the unbond is received
+    sudo(
+        deps.as_mut(),
+        mock_env_at_timestamp(10000),
+        SudoMsg::TrackBeforeSend {
+            from: "user_1".to_string(),
+            to: MOCK_CONTRACT_ADDR.to_string(),
+            amount: Coin::new(100, get_stake_full_denom()),
+        },
+    )
+    .unwrap();
+    assert_eq!(referral_info(&deps, "frontend").referred_ustake, Uint128::new(600));
+
+    execute(
+        deps.as_mut(),
+        mock_env_at_timestamp(10000),
+        mock_info("user_1", &[Coin::new(100, get_stake_full_denom())]),
+        ExecuteMsg::QueueUnbond {
+            receiver: None,
+        },
+    )
+    .unwrap();
+    assert_eq!(referral_info(&deps, "frontend").referred_ustake, Uint128::new(500));
+}
+
+#[test]
+fn claiming_referral_rewards() {
+    let mut deps = setup_referral();
+    let state = State::default();
+
+    bond(&mut deps, "user_1", "frontend", 100000).unwrap();
+
+    state
+        .unlocked_coins
+        .save(deps.as_mut().storage, &vec![Coin::new(110000, MOCK_UTOKEN)])
+        .unwrap();
+
+    let res = execute(
+        deps.as_mut(),
+        mock_env_at_timestamp(20000),
+        mock_info(MOCK_CONTRACT_ADDR, &[]),
+        ExecuteMsg::Callback(CallbackMsg::Reinvest {
+            skip_fee: false,
+        }),
+    )
+    .unwrap();
+
+    // 100000 of 1100000 ustake are referred, 10% of their 100 utoken fee is kept for the referrer
+    assert_eq!(
+        res.messages[1].msg,
+        SendFee::new(Addr::unchecked("fee"), 1090, MOCK_UTOKEN).to_cosmos_msg()
+    );
+    assert_eq!(referral_info(&deps, "frontend").pending_rewards, Uint128::new(10));
+    assert_eq!(
+        state.referral_pool.load(deps.as_ref().storage).unwrap().total_pending,
+        Uint128::new(10)
+    );
+
+    let res = execute(
+        deps.as_mut(),
+        mock_env_at_timestamp(20000),
+        mock_info("frontend", &[]),
+        ExecuteMsg::ClaimReferralRewards {},
+    )
+    .unwrap();
+    assert_eq!(
+        res.messages,
+        vec![SubMsg::new(CosmosMsg::Bank(BankMsg::Send {
+            to_address: "frontend".to_string(),
+            amount: vec![Coin::new(10, MOCK_UTOKEN)],
+        }))]
+    );
+
+    assert_eq!(
+        referral_info(&deps, "frontend"),
+        ReferralInfoResponse {
+            referrer: "frontend".to_string(),
+            referred_ustake: Uint128::new(100000),
+            pending_rewards: Uint128::zero(),
+            claimed_rewards: Uint128::new(10),
+        }
+    );
+    assert_eq!(
+        state.referral_pool.load(deps.as_ref().storage).unwrap().total_pending,
+        Uint128::zero()
+    );
+
+    let err = execute(
+        deps.as_mut(),
+        mock_env_at_timestamp(20000),
+        mock_info("frontend", &[]),
+        ExecuteMsg::ClaimReferralRewards {},
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::CantBeZero("referral rewards".into()));
+}
+
+#[test]
+fn reconciling_without_pending_referral_rewards() {
+    let mut deps = setup_referral();
+    let state = State::default();
+
+    bond(&mut deps, "user_1", "frontend", 100000).unwrap();
+
+    state
+        .unlocked_coins
+        .save(deps.as_mut().storage, &vec![Coin::new(110000, MOCK_UTOKEN)])
+        .unwrap();
+    execute(
+        deps.as_mut(),
+        mock_env_at_timestamp(20000),
+        mock_info(MOCK_CONTRACT_ADDR, &[]),
+        ExecuteMsg::Callback(CallbackMsg::Reinvest {
+            skip_fee: false,
+        }),
+    )
+    .unwrap();
+
+    state
+        .previous_batches
+        .save(
+            deps.as_mut().storage,
+            1u64,
+            &Batch {
+                id: 1,
+                reconciled: false,
+                total_shares: Uint128::new(1000),
+                utoken_unclaimed: Uint128::new(1000),
+                est_unbond_end_time: 20000,
+            },
+        )
+        .unwrap();
+
+    // the balance includes the 10 utoken of unclaimed referral rewards
+    deps.querier.set_bank_balances(&[Coin::new(1000, MOCK_UTOKEN)]);
+
+    execute(
+        deps.as_mut(),
+        mock_env_at_timestamp(30000),
+        mock_info("worker", &[]),
+        ExecuteMsg::Reconcile {},
+    )
+    .unwrap();
+
+    let batch = state.previous_batches.load(deps.as_ref().storage, 1u64).unwrap();
+    assert!(batch.reconciled);
+    assert_eq!(batch.utoken_unclaimed, Uint128::new(990));
+}
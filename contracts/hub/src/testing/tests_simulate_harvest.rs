@@ -0,0 +1,291 @@
+use std::str::FromStr;
+
+use cosmwasm_std::testing::{mock_env, mock_info, MockApi, MockStorage};
+use cosmwasm_std::{coin, Addr, Decimal, OwnedDeps, StdError, Uint128};
+
+use eris::hub::{FeeDelivery, FeeRecipient, QueryMsg, ReferralPool, SimulateHarvestResponse};
+use eris_chain_adapter::types::{CustomQueryType, StageType, WithdrawType};
+
+use crate::contract::{execute, query};
+use crate::state::State;
+use crate::testing::helpers::{
+    get_stake_full_denom, query_helper, set_total_stake_supply, setup_test, MOCK_UTOKEN,
+};
+use crate::types::Delegation;
+
+use super::custom_querier::CustomQuerier;
+use super::helpers::{mock_env_at_timestamp, UpdateConfigBuilder};
+
+fn setup_simulation() -> OwnedDeps<MockStorage, MockApi, CustomQuerier, CustomQueryType> {
+    let mut deps = setup_test();
+    let state = State::default();
+
+    deps.querier.set_staking_delegations(&[
+        Delegation::new("alice", 341667, MOCK_UTOKEN),
+        Delegation::new("bob", 341667, MOCK_UTOKEN),
+        Delegation::new("charlie", 341666, MOCK_UTOKEN),
+    ]);
+    deps.querier.set_staking_rewards(&[
+        ("alice", vec![coin(100, MOCK_UTOKEN), coin(1000, "ukuji")]),
+        ("bob", vec![coin(200, MOCK_UTOKEN)]),
+    ]);
+    deps.querier.set_bank_balances(&[coin(500, "ukuji")]);
+    deps.querier.set_fin_pair("fin", "ukuji", MOCK_UTOKEN, Decimal::percent(50));
+    set_total_stake_supply(&state, &mut deps, 1000000);
+
+    deps
+}
+
+//--------------------------------------------------------------------------------------------------
+// Queries
+//--------------------------------------------------------------------------------------------------
+
+#[test]
+fn simulating_harvest() {
+    let deps = setup_simulation();
+
+    let res: SimulateHarvestResponse = query_helper(
+        deps.as_ref(),
+        QueryMsg::SimulateHarvest {
+            validators: None,
+            withdrawals: None,
+            stages: Some(vec![vec![(StageType::fin("fin"), "ukuji".into(), None, None, None)]]),
+        },
+    );
+
+    // 1500 ukuji are swapped for 750 utoken, 1% fee of the 1050 utoken received
+    assert_eq!(
+        res,
+        SimulateHarvestResponse {
+            rewards: vec![coin(300, MOCK_UTOKEN), coin(1000, "ukuji")],
+            utoken_bonded: Uint128::new(1040),
+            utoken_reserved: Uint128::zero(),
+            ustake_burned: Uint128::zero(),
+            fees: vec![(MOCK_UTOKEN.to_string(), Uint128::new(10))],
+            exchange_rate: Decimal::from_str("1.02604").unwrap(),
+        }
+    );
+
+    // only alice is harvested, the swap is limited by the max amount
+    let res: SimulateHarvestResponse = query_helper(
+        deps.as_ref(),
+        QueryMsg::SimulateHarvest {
+            validators: Some(vec!["alice".to_string()]),
+            withdrawals: None,
+            stages: Some(vec![vec![(
+                StageType::fin("fin"),
+                "ukuji".into(),
+                None,
+                Some(Uint128::new(1000)),
+                None,
+            )]]),
+        },
+    );
+    assert_eq!(res.rewards, vec![coin(100, MOCK_UTOKEN), coin(1000, "ukuji")]);
+    assert_eq!(res.utoken_bonded, Uint128::new(594));
+    assert_eq!(res.fees, vec![(MOCK_UTOKEN.to_string(), Uint128::new(6))]);
+}
+
+#[test]
+fn simulating_harvest_with_stage_fee() {
+    let deps = setup_simulation();
+
+    let res: SimulateHarvestResponse = query_helper(
+        deps.as_ref(),
+        QueryMsg::SimulateHarvest {
+            validators: None,
+            withdrawals: None,
+            stages: Some(vec![vec![(
+                StageType::fin("fin"),
+                "ukuji".into(),
+                None,
+                None,
+                Some(true),
+            )]]),
+        },
+    );
+
+    // the fee is paid in ukuji, the received utoken are bonded without fee
+    assert_eq!(res.utoken_bonded, Uint128::new(1042));
+    assert_eq!(res.fees, vec![("ukuji".to_string(), Uint128::new(15))]);
+    assert_eq!(res.exchange_rate, Decimal::from_str("1.026042").unwrap());
+
+    // like `harvest`, the reward fee is skipped even if the fee stage has nothing to swap
+    let res: SimulateHarvestResponse = query_helper(
+        deps.as_ref(),
+        QueryMsg::SimulateHarvest {
+            validators: None,
+            withdrawals: None,
+            stages: Some(vec![vec![(
+                StageType::fin("fin"),
+                "uusdc".into(),
+                None,
+                None,
+                Some(true),
+            )]]),
+        },
+    );
+    assert_eq!(res.utoken_bonded, Uint128::new(300));
+    assert_eq!(res.fees, vec![]);
+
+    let err = query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::SimulateHarvest {
+            validators: None,
+            withdrawals: None,
+            stages: Some(vec![
+                vec![],
+                vec![(StageType::fin("fin"), "ukuji".into(), None, None, Some(true))],
+            ]),
+        },
+    )
+    .unwrap_err();
+    assert_eq!(err, StdError::generic_err("Can only set fee payment to the first stage"));
+}
+
+#[test]
+fn simulating_harvest_with_staked_fees() {
+    let mut deps = setup_simulation();
+
+    execute(
+        deps.as_mut(),
+        mock_env_at_timestamp(10000),
+        mock_info("owner", &[]),
+        UpdateConfigBuilder {
+            fee_recipients: Some(vec![
+                FeeRecipient {
+                    address: "fee".to_string(),
+                    weight: Decimal::percent(50),
+                    delivery: None,
+                },
+                FeeRecipient {
+                    address: "treasury".to_string(),
+                    weight: Decimal::percent(50),
+                    delivery: Some(FeeDelivery::Staked),
+                },
+            ]),
+            ..Default::default()
+        }
+        .build(),
+    )
+    .unwrap();
+
+    let res: SimulateHarvestResponse = query_helper(
+        deps.as_ref(),
+        QueryMsg::SimulateHarvest {
+            validators: None,
+            withdrawals: None,
+            stages: Some(vec![vec![(StageType::fin("fin"), "ukuji".into(), None, None, None)]]),
+        },
+    );
+
+    // half of the 10 utoken fee is delegated and minted at 1026040 utoken per 1000000 ustake
+    assert_eq!(res.utoken_bonded, Uint128::new(1040));
+    assert_eq!(
+        res.fees,
+        vec![(MOCK_UTOKEN.to_string(), Uint128::new(5)), (get_stake_full_denom(), Uint128::new(4))]
+    );
+    assert_eq!(res.exchange_rate, Decimal::from_str("1.026040895836416654").unwrap());
+}
+
+#[test]
+fn simulating_harvest_with_referral_rewards() {
+    let mut deps = setup_simulation();
+    let state = State::default();
+
+    execute(
+        deps.as_mut(),
+        mock_env_at_timestamp(10000),
+        mock_info("owner", &[]),
+        UpdateConfigBuilder {
+            referral_share: Some(Decimal::percent(50)),
+            ..Default::default()
+        }
+        .build(),
+    )
+    .unwrap();
+    state
+        .referral_pool
+        .save(
+            deps.as_mut().storage,
+            &ReferralPool {
+                total_shares: Uint128::new(200000),
+                reward_index: Decimal::zero(),
+                total_pending: Uint128::zero(),
+            },
+        )
+        .unwrap();
+
+    let res: SimulateHarvestResponse = query_helper(
+        deps.as_ref(),
+        QueryMsg::SimulateHarvest {
+            validators: None,
+            withdrawals: None,
+            stages: Some(vec![vec![(StageType::fin("fin"), "ukuji".into(), None, None, None)]]),
+        },
+    );
+
+    // half of the fee earned on the referred 20% of the stake is kept for the referrers
+    assert_eq!(res.utoken_bonded, Uint128::new(1040));
+    assert_eq!(res.fees, vec![(MOCK_UTOKEN.to_string(), Uint128::new(9))]);
+    assert_eq!(res.exchange_rate, Decimal::from_str("1.02604").unwrap());
+}
+
+#[test]
+fn simulating_harvest_with_rebalance_bounties() {
+    let mut deps = setup_simulation();
+    let state = State::default();
+
+    state
+        .pending_bounties
+        .save(deps.as_mut().storage, &Addr::unchecked("alice"), &Uint128::new(3))
+        .unwrap();
+    state
+        .pending_bounties
+        .save(deps.as_mut().storage, &Addr::unchecked("bob"), &Uint128::new(20))
+        .unwrap();
+
+    let res: SimulateHarvestResponse = query_helper(
+        deps.as_ref(),
+        QueryMsg::SimulateHarvest {
+            validators: None,
+            withdrawals: None,
+            stages: Some(vec![vec![(StageType::fin("fin"), "ukuji".into(), None, None, None)]]),
+        },
+    );
+
+    // the whole fee of 10 is used for the bounties, the uncovered part stays pending
+    assert_eq!(res.utoken_bonded, Uint128::new(1040));
+    assert_eq!(res.fees, vec![]);
+    assert_eq!(res.exchange_rate, Decimal::from_str("1.02604").unwrap());
+
+    state.pending_bounties.remove(deps.as_mut().storage, &Addr::unchecked("bob"));
+    let res: SimulateHarvestResponse = query_helper(
+        deps.as_ref(),
+        QueryMsg::SimulateHarvest {
+            validators: None,
+            withdrawals: None,
+            stages: Some(vec![vec![(StageType::fin("fin"), "ukuji".into(), None, None, None)]]),
+        },
+    );
+    assert_eq!(res.fees, vec![(MOCK_UTOKEN.to_string(), Uint128::new(7))]);
+}
+
+#[test]
+fn simulating_harvest_unsupported_withdrawal() {
+    let mut deps = setup_simulation();
+    deps.querier.set_bank_balances(&[coin(500, "ukuji"), coin(100, "lp")]);
+
+    let err = query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::SimulateHarvest {
+            validators: None,
+            withdrawals: Some(vec![(WithdrawType::bow("bow"), "lp".into())]),
+            stages: None,
+        },
+    )
+    .unwrap_err();
+    assert_eq!(err, StdError::generic_err("withdraw simulation not supported"));
+}
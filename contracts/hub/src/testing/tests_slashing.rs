@@ -0,0 +1,323 @@
+use cosmwasm_std::testing::{mock_info, MockApi, MockStorage};
+use cosmwasm_std::{attr, Coin, Decimal, Event, OwnedDeps, Uint128};
+
+use eris::hub::{DelegationStrategy, ExecuteMsg, QueryMsg, SlashingEvent, SlashingEventsResponse};
+use eris_chain_adapter::types::CustomQueryType;
+
+use crate::contract::execute;
+use crate::state::State;
+use crate::testing::helpers::{
+    check_received_coin, query_helper, set_total_stake_supply, setup_test, MOCK_UTOKEN,
+};
+use crate::types::{Delegation, Redelegation};
+
+use super::custom_querier::CustomQuerier;
+use super::helpers::{mock_env_at_timestamp, UpdateConfigBuilder};
+
+fn setup_slashing() -> OwnedDeps<MockStorage, MockApi, CustomQuerier, CustomQueryType> {
+    let mut deps = setup_test();
+    let state = State::default();
+
+    deps.querier.set_staking_delegations(&[
+        Delegation::new("alice", 333334, MOCK_UTOKEN),
+        Delegation::new("bob", 333333, MOCK_UTOKEN),
+        Delegation::new("charlie", 333333, MOCK_UTOKEN),
+    ]);
+    set_total_stake_supply(&state, &mut deps, 1000000);
+
+    // The first check only syncs the tracked delegations
+    let res = execute(
+        deps.as_mut(),
+        mock_env_at_timestamp(12345),
+        mock_info("anyone", &[]),
+        ExecuteMsg::CheckSlashing {},
+    )
+    .unwrap();
+    assert_eq!(res.events.len(), 0);
+
+    deps
+}
+
+//--------------------------------------------------------------------------------------------------
+// Execution
+//--------------------------------------------------------------------------------------------------
+
+#[test]
+fn checking_slashing() {
+    let mut deps = setup_slashing();
+    let state = State::default();
+
+    deps.querier.set_staking_delegations(&[
+        Delegation::new("alice", 333334, MOCK_UTOKEN),
+        Delegation::new("bob", 333333, MOCK_UTOKEN),
+        Delegation::new("charlie", 323333, MOCK_UTOKEN),
+    ]);
+
+    let res = execute(
+        deps.as_mut(),
+        mock_env_at_timestamp(12345),
+        mock_info("anyone", &[]),
+        ExecuteMsg::CheckSlashing {},
+    )
+    .unwrap();
+
+    assert_eq!(res.messages.len(), 0);
+    assert_eq!(
+        res.events,
+        vec![Event::new("erishub/slashed").add_attributes(vec![
+            attr("validator", "charlie"),
+            attr("utoken_slashed", "10000"),
+            attr("rate_before", "1"),
+            attr("rate_after", "0.99"),
+        ])]
+    );
+
+    let tracked = state.validator_bonded.load(deps.as_ref().storage, "charlie").unwrap();
+    assert_eq!(tracked, Uint128::new(323333));
+
+    // Bonding is tracked, Charlie has the smallest delegation
+    deps.querier.set_bank_balances(&[Coin::new(1000, MOCK_UTOKEN)]);
+    execute(
+        deps.as_mut(),
+        mock_env_at_timestamp(12345),
+        mock_info("user_1", &[Coin::new(1000, MOCK_UTOKEN)]),
+        ExecuteMsg::Bond {
+            receiver: None,
+            referrer: None,
+        },
+    )
+    .unwrap();
+
+    let tracked = state.validator_bonded.load(deps.as_ref().storage, "charlie").unwrap();
+    assert_eq!(tracked, Uint128::new(324333));
+
+    deps.querier.set_staking_delegations(&[
+        Delegation::new("alice", 333334, MOCK_UTOKEN),
+        Delegation::new("bob", 333333, MOCK_UTOKEN),
+        Delegation::new("charlie", 324000, MOCK_UTOKEN),
+    ]);
+
+    execute(
+        deps.as_mut(),
+        mock_env_at_timestamp(23456),
+        mock_info("anyone", &[]),
+        ExecuteMsg::CheckSlashing {},
+    )
+    .unwrap();
+
+    let res: SlashingEventsResponse = query_helper(
+        deps.as_ref(),
+        QueryMsg::SlashingEvents {
+            start_after: None,
+            limit: None,
+        },
+    );
+    assert_eq!(res.events.len(), 2);
+    assert_eq!(res.events[1].0, 1);
+    assert_eq!(
+        res.events[0],
+        (
+            2,
+            SlashingEvent {
+                asset: MOCK_UTOKEN.to_string(),
+                validator: "charlie".to_string(),
+                amount: Uint128::new(333),
+                time: 23456,
+                // the bond minted 1010 ustake
+                rate_before: Decimal::from_ratio(991000u128, 1001010u128),
+                rate_after: Decimal::from_ratio(990667u128, 1001010u128),
+            }
+        )
+    );
+
+    let res: SlashingEventsResponse = query_helper(
+        deps.as_ref(),
+        QueryMsg::SlashingEvents {
+            start_after: Some(2),
+            limit: None,
+        },
+    );
+    assert_eq!(res.events.len(), 1);
+    assert_eq!(res.events[0].1.amount, Uint128::new(10000));
+}
+
+#[test]
+fn ignoring_rounding_shortfalls() {
+    let mut deps = setup_slashing();
+    let state = State::default();
+
+    // 30 utoken is below 1 bps of the delegation
+    deps.querier.set_staking_delegations(&[
+        Delegation::new("alice", 333304, MOCK_UTOKEN),
+        Delegation::new("bob", 333333, MOCK_UTOKEN),
+        Delegation::new("charlie", 333333, MOCK_UTOKEN),
+    ]);
+
+    let res = execute(
+        deps.as_mut(),
+        mock_env_at_timestamp(12345),
+        mock_info("anyone", &[]),
+        ExecuteMsg::CheckSlashing {},
+    )
+    .unwrap();
+    assert_eq!(res.events.len(), 0);
+
+    let res: SlashingEventsResponse = query_helper(
+        deps.as_ref(),
+        QueryMsg::SlashingEvents {
+            start_after: None,
+            limit: None,
+        },
+    );
+    assert_eq!(res.events.len(), 0);
+
+    // the tracked delegation is still synced
+    let tracked = state.validator_bonded.load(deps.as_ref().storage, "alice").unwrap();
+    assert_eq!(tracked, Uint128::new(333304));
+}
+
+#[test]
+fn removing_slashed_validators() {
+    let mut deps = setup_slashing();
+    let state = State::default();
+
+    execute(
+        deps.as_mut(),
+        mock_env_at_timestamp(12345),
+        mock_info("owner", &[]),
+        UpdateConfigBuilder {
+            remove_slashed_validators: Some(true),
+            ..Default::default()
+        }
+        .build(),
+    )
+    .unwrap();
+
+    deps.querier.set_staking_delegations(&[
+        Delegation::new("alice", 333000, MOCK_UTOKEN),
+        Delegation::new("bob", 333333, MOCK_UTOKEN),
+        Delegation::new("charlie", 333333, MOCK_UTOKEN),
+    ]);
+
+    // Anyone can record the slashing, but only the owner removes validators
+    let res = execute(
+        deps.as_mut(),
+        mock_env_at_timestamp(12345),
+        mock_info("anyone", &[]),
+        ExecuteMsg::CheckSlashing {},
+    )
+    .unwrap();
+    assert_eq!(res.messages.len(), 0);
+    assert_eq!(res.events.len(), 1);
+    assert_eq!(state.validators.load(deps.as_ref().storage).unwrap().len(), 3);
+
+    deps.querier.set_staking_delegations(&[
+        Delegation::new("alice", 332000, MOCK_UTOKEN),
+        Delegation::new("bob", 333333, MOCK_UTOKEN),
+        Delegation::new("charlie", 333333, MOCK_UTOKEN),
+    ]);
+
+    let res = execute(
+        deps.as_mut(),
+        mock_env_at_timestamp(12345),
+        mock_info("owner", &[]),
+        ExecuteMsg::CheckSlashing {},
+    )
+    .unwrap();
+
+    assert_eq!(res.messages.len(), 3);
+    assert_eq!(
+        res.messages[0].msg,
+        Redelegation::new("alice", "bob", 166000, MOCK_UTOKEN).to_cosmos_msg()
+    );
+    assert_eq!(
+        res.messages[1].msg,
+        Redelegation::new("alice", "charlie", 166000, MOCK_UTOKEN).to_cosmos_msg()
+    );
+    assert_eq!(res.messages[2], check_received_coin(0, 0));
+
+    assert_eq!(res.events.len(), 2);
+    assert_eq!(
+        res.events[1],
+        Event::new("erishub/validator_removed").add_attribute("validator", "alice")
+    );
+
+    let validators = state.validators.load(deps.as_ref().storage).unwrap();
+    assert_eq!(validators, vec!["bob".to_string(), "charlie".to_string()]);
+
+    // Redelegations are tracked
+    assert_eq!(
+        state.validator_bonded.load(deps.as_ref().storage, "alice").unwrap(),
+        Uint128::zero()
+    );
+    assert_eq!(
+        state.validator_bonded.load(deps.as_ref().storage, "bob").unwrap(),
+        Uint128::new(499333)
+    );
+}
+
+#[test]
+fn removing_slashed_validators_with_defined_strategy() {
+    let mut deps = setup_slashing();
+    let state = State::default();
+
+    execute(
+        deps.as_mut(),
+        mock_env_at_timestamp(12345),
+        mock_info("owner", &[]),
+        UpdateConfigBuilder {
+            remove_slashed_validators: Some(true),
+            delegation_strategy: Some(DelegationStrategy::Defined {
+                shares_bps: vec![
+                    ("alice".into(), 2000),
+                    ("bob".into(), 6000),
+                    ("charlie".into(), 2000),
+                ],
+            }),
+            ..Default::default()
+        }
+        .build(),
+    )
+    .unwrap();
+
+    deps.querier.set_staking_delegations(&[
+        Delegation::new("alice", 332000, MOCK_UTOKEN),
+        Delegation::new("bob", 333333, MOCK_UTOKEN),
+        Delegation::new("charlie", 333333, MOCK_UTOKEN),
+    ]);
+
+    let res = execute(
+        deps.as_mut(),
+        mock_env_at_timestamp(12345),
+        mock_info("owner", &[]),
+        ExecuteMsg::CheckSlashing {},
+    )
+    .unwrap();
+
+    // The share of alice is spread over the remaining validators
+    // Bob:     998666 * 0.75 + 1 - 333333 = 415667, limited to the 332000 of alice
+    assert_eq!(res.messages.len(), 2);
+    assert_eq!(
+        res.messages[0].msg,
+        Redelegation::new("alice", "bob", 332000, MOCK_UTOKEN).to_cosmos_msg()
+    );
+    assert_eq!(res.messages[1], check_received_coin(0, 0));
+
+    let goal = state.delegation_goal.load(deps.as_ref().storage).unwrap();
+    assert_eq!(
+        goal.shares,
+        vec![
+            ("bob".to_string(), Decimal::percent(75)),
+            ("charlie".to_string(), Decimal::percent(25))
+        ]
+    );
+
+    let validators = state.validators.load(deps.as_ref().storage).unwrap();
+    assert_eq!(validators, vec!["bob".to_string(), "charlie".to_string()]);
+
+    // bob can't be the source of a redelegation until the redelegation completes
+    assert_eq!(
+        state.redelegation_entries.load(deps.as_ref().storage, "bob").unwrap(),
+        12345 + 1814400
+    );
+}
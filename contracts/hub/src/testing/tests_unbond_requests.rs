@@ -0,0 +1,391 @@
+use cosmwasm_std::testing::{mock_info, MockApi, MockStorage, MOCK_CONTRACT_ADDR};
+use cosmwasm_std::{
+    attr, coin, Addr, BankMsg, Coin, CosmosMsg, Decimal, Event, Order, OwnedDeps, StdError, Uint128,
+};
+
+use eris::hub::{Batch, ExecuteMsg, InstantUnbondConfig, PendingBatch, UnbondRequest};
+use eris_chain_adapter::types::CustomQueryType;
+use eris_chain_shared::chain_trait::ChainInterface;
+
+use crate::contract::execute;
+use crate::error::ContractError;
+use crate::state::State;
+use crate::testing::helpers::{
+    chain_test, check_received_coin, get_stake_full_denom, set_total_stake_supply, setup_test,
+    MOCK_UTOKEN,
+};
+use crate::types::Delegation;
+
+use super::custom_querier::CustomQuerier;
+use super::helpers::mock_env_at_timestamp;
+
+fn setup_unbond_requests() -> OwnedDeps<MockStorage, MockApi, CustomQuerier, CustomQueryType> {
+    let mut deps = setup_test();
+
+    for (user, amount) in [("user_1", 23456), ("user_2", 69420)] {
+        execute(
+            deps.as_mut(),
+            mock_env_at_timestamp(12345),
+            mock_info(user, &[Coin::new(amount, get_stake_full_denom())]),
+            ExecuteMsg::QueueUnbond {
+                receiver: None,
+            },
+        )
+        .unwrap();
+    }
+
+    deps
+}
+
+fn load_request(
+    deps: &OwnedDeps<MockStorage, MockApi, CustomQuerier, CustomQueryType>,
+    id: u64,
+    user: &str,
+) -> Option<UnbondRequest> {
+    State::default()
+        .unbond_requests
+        .may_load(deps.as_ref().storage, (id, &Addr::unchecked(user)))
+        .unwrap()
+}
+
+//--------------------------------------------------------------------------------------------------
+// Execution
+//--------------------------------------------------------------------------------------------------
+
+#[test]
+fn transferring_unbond_request() {
+    let mut deps = setup_unbond_requests();
+
+    let err = execute(
+        deps.as_mut(),
+        mock_env_at_timestamp(12345),
+        mock_info("user_3", &[]),
+        ExecuteMsg::TransferUnbondRequest {
+            id: 1,
+            recipient: "user_1".to_string(),
+            shares: None,
+        },
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::Std(StdError::not_found("eris::hub::UnbondRequest")));
+
+    let err = execute(
+        deps.as_mut(),
+        mock_env_at_timestamp(12345),
+        mock_info("user_1", &[]),
+        ExecuteMsg::TransferUnbondRequest {
+            id: 1,
+            recipient: "user_1".to_string(),
+            shares: None,
+        },
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::CantTransferToSender {});
+
+    let err = execute(
+        deps.as_mut(),
+        mock_env_at_timestamp(12345),
+        mock_info("user_1", &[]),
+        ExecuteMsg::TransferUnbondRequest {
+            id: 1,
+            recipient: "user_3".to_string(),
+            shares: Some(Uint128::new(23457)),
+        },
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::NotEnoughShares(Uint128::new(23456)));
+
+    // Part of the request is moved to a new recipient
+    let res = execute(
+        deps.as_mut(),
+        mock_env_at_timestamp(12345),
+        mock_info("user_1", &[]),
+        ExecuteMsg::TransferUnbondRequest {
+            id: 1,
+            recipient: "user_3".to_string(),
+            shares: Some(Uint128::new(3456)),
+        },
+    )
+    .unwrap();
+
+    assert_eq!(res.messages.len(), 0);
+    assert_eq!(
+        res.events,
+        vec![Event::new("erishub/unbond_request_transferred").add_attributes(vec![
+            attr("id", "1"),
+            attr("sender", "user_1"),
+            attr("recipient", "user_3"),
+            attr("shares", "3456"),
+        ])]
+    );
+
+    assert_eq!(
+        load_request(&deps, 1, "user_1"),
+        Some(UnbondRequest {
+            id: 1,
+            user: Addr::unchecked("user_1"),
+            shares: Uint128::new(20000)
+        })
+    );
+    assert_eq!(
+        load_request(&deps, 1, "user_3"),
+        Some(UnbondRequest {
+            id: 1,
+            user: Addr::unchecked("user_3"),
+            shares: Uint128::new(3456)
+        })
+    );
+
+    // The remaining request is merged into the existing request of the recipient
+    execute(
+        deps.as_mut(),
+        mock_env_at_timestamp(12345),
+        mock_info("user_1", &[]),
+        ExecuteMsg::TransferUnbondRequest {
+            id: 1,
+            recipient: "user_2".to_string(),
+            shares: None,
+        },
+    )
+    .unwrap();
+
+    assert_eq!(load_request(&deps, 1, "user_1"), None);
+    assert_eq!(
+        load_request(&deps, 1, "user_2"),
+        Some(UnbondRequest {
+            id: 1,
+            user: Addr::unchecked("user_2"),
+            shares: Uint128::new(89420)
+        })
+    );
+
+    // The user index follows the new owner
+    let requests = State::default()
+        .unbond_requests
+        .idx
+        .user
+        .prefix("user_1".to_string())
+        .range(deps.as_ref().storage, None, None, Order::Ascending)
+        .count();
+    assert_eq!(requests, 0);
+}
+
+#[test]
+fn canceling_unbond() {
+    let mut deps = setup_unbond_requests();
+    let state = State::default();
+
+    let err = execute(
+        deps.as_mut(),
+        mock_env_at_timestamp(12345),
+        mock_info("user_1", &[]),
+        ExecuteMsg::CancelUnbond {
+            amount: Some(Uint128::new(23457)),
+        },
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::NotEnoughShares(Uint128::new(23456)));
+
+    let res = execute(
+        deps.as_mut(),
+        mock_env_at_timestamp(12345),
+        mock_info("user_1", &[]),
+        ExecuteMsg::CancelUnbond {
+            amount: Some(Uint128::new(3456)),
+        },
+    )
+    .unwrap();
+
+    assert_eq!(res.messages.len(), 1);
+    assert_eq!(
+        res.messages[0].msg,
+        CosmosMsg::Bank(BankMsg::Send {
+            to_address: "user_1".to_string(),
+            amount: vec![Coin::new(3456, get_stake_full_denom())]
+        })
+    );
+
+    assert_eq!(
+        load_request(&deps, 1, "user_1"),
+        Some(UnbondRequest {
+            id: 1,
+            user: Addr::unchecked("user_1"),
+            shares: Uint128::new(20000)
+        })
+    );
+
+    // Cancel the rest of the request
+    execute(
+        deps.as_mut(),
+        mock_env_at_timestamp(12345),
+        mock_info("user_1", &[]),
+        ExecuteMsg::CancelUnbond {
+            amount: None,
+        },
+    )
+    .unwrap();
+
+    assert_eq!(load_request(&deps, 1, "user_1"), None);
+
+    let pending_batch = state.pending_batch.load(deps.as_ref().storage).unwrap();
+    assert_eq!(
+        pending_batch,
+        PendingBatch {
+            id: 1,
+            ustake_to_burn: Uint128::new(69420),
+            est_unbond_start_time: 269200
+        }
+    );
+
+    let err = execute(
+        deps.as_mut(),
+        mock_env_at_timestamp(12345),
+        mock_info("user_1", &[]),
+        ExecuteMsg::CancelUnbond {
+            amount: None,
+        },
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::Std(StdError::not_found("eris::hub::UnbondRequest")));
+}
+
+#[test]
+fn rebonding() {
+    let mut deps = setup_unbond_requests();
+    let state = State::default();
+
+    deps.querier.set_staking_delegations(&[
+        Delegation::new("alice", 333334, MOCK_UTOKEN),
+        Delegation::new("bob", 333333, MOCK_UTOKEN),
+        Delegation::new("charlie", 333333, MOCK_UTOKEN),
+    ]);
+    deps.querier.set_bank_balances(&[coin(50000, MOCK_UTOKEN)]);
+    set_total_stake_supply(&state, &mut deps, 1000000);
+
+    state
+        .previous_batches
+        .save(
+            deps.as_mut().storage,
+            1,
+            &Batch {
+                id: 1,
+                reconciled: false,
+                total_shares: Uint128::new(92876),
+                utoken_unclaimed: Uint128::new(95197),
+                est_unbond_end_time: 30000,
+            },
+        )
+        .unwrap();
+    let err = execute(
+        deps.as_mut(),
+        mock_env_at_timestamp(20000),
+        mock_info("user_1", &[]),
+        ExecuteMsg::Rebond {
+            id: 1,
+        },
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::InstantUnbondDisabled {});
+
+    state
+        .instant_unbond_config
+        .save(
+            deps.as_mut().storage,
+            &InstantUnbondConfig {
+                reserve_share: Decimal::percent(50),
+                reserve_target: Uint128::new(100000),
+                min_fee: Decimal::percent(1),
+                max_fee: Decimal::percent(5),
+            },
+        )
+        .unwrap();
+
+    // the reserve is empty
+    let err = execute(
+        deps.as_mut(),
+        mock_env_at_timestamp(20000),
+        mock_info("user_1", &[]),
+        ExecuteMsg::Rebond {
+            id: 1,
+        },
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::InstantUnbondReserveTooLow(Uint128::zero()));
+
+    state.instant_unbond_reserve.save(deps.as_mut().storage, &Uint128::new(20000)).unwrap();
+
+    let err = execute(
+        deps.as_mut(),
+        mock_env_at_timestamp(20000),
+        mock_info("user_1", &[]),
+        ExecuteMsg::Rebond {
+            id: 1,
+        },
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::InstantUnbondReserveTooLow(Uint128::new(20000)));
+
+    state.instant_unbond_reserve.save(deps.as_mut().storage, &Uint128::new(50000)).unwrap();
+
+    let err = execute(
+        deps.as_mut(),
+        mock_env_at_timestamp(30000),
+        mock_info("user_1", &[]),
+        ExecuteMsg::Rebond {
+            id: 1,
+        },
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::BatchAlreadyUnbonded(1));
+
+    // 95197 * 23456 / 92876 = 24042 utoken are unbonding
+    // fee = 0.05 - 0.04 * (50000 - 24042) / 100000 = 0.0396168
+    // 24042 - 24042 * 0.0396168 = 23090 utoken are delegated from the reserve
+    let res = execute(
+        deps.as_mut(),
+        mock_env_at_timestamp(20000),
+        mock_info("user_1", &[]),
+        ExecuteMsg::Rebond {
+            id: 1,
+        },
+    )
+    .unwrap();
+
+    let mint_msgs = chain_test().create_mint_msgs(
+        get_stake_full_denom(),
+        Uint128::new(23090),
+        Addr::unchecked("user_1"),
+    );
+    assert_eq!(res.messages.len(), 2 + mint_msgs.len());
+
+    let mut index = 0;
+    assert_eq!(res.messages[index].msg, Delegation::new("bob", 23090, MOCK_UTOKEN).to_cosmos_msg());
+    index += 1;
+    for msg in mint_msgs {
+        assert_eq!(res.messages[index].msg, msg);
+        index += 1;
+    }
+    assert_eq!(res.messages[index], check_received_coin(50000 - 23090, 0));
+
+    let reserve = state.instant_unbond_reserve.load(deps.as_ref().storage).unwrap();
+    assert_eq!(reserve, Uint128::new(50000 - 23090));
+
+    // The contract took over the unbonding position, the batch is unchanged
+    assert_eq!(load_request(&deps, 1, "user_1"), None);
+    assert_eq!(
+        load_request(&deps, 1, MOCK_CONTRACT_ADDR),
+        Some(UnbondRequest {
+            id: 1,
+            user: Addr::unchecked(MOCK_CONTRACT_ADDR),
+            shares: Uint128::new(23456)
+        })
+    );
+    let batch = state.previous_batches.load(deps.as_ref().storage, 1).unwrap();
+    assert_eq!(batch.total_shares, Uint128::new(92876));
+    assert_eq!(batch.utoken_unclaimed, Uint128::new(95197));
+
+    assert_eq!(
+        state.stake_token.load(deps.as_ref().storage).unwrap().total_supply,
+        Uint128::new(1000000 + 23090)
+    );
+}
@@ -0,0 +1,267 @@
+use std::collections::HashMap;
+
+use cosmwasm_std::testing::{mock_info, MockApi, MockStorage, MOCK_CONTRACT_ADDR};
+use cosmwasm_std::{OwnedDeps, StdError, SubMsg, Uint128};
+use itertools::Itertools;
+
+use eris::hub::{
+    ConfigResponse, ExecuteMsg, PendingBatch, QueryMsg, UnbondingEntriesResponse,
+    ValidatorUnbondingEntries,
+};
+use eris_chain_adapter::types::CustomQueryType;
+
+use crate::constants::{DAY, DEFAULT_MAX_UNBONDING_ENTRIES};
+use crate::contract::execute;
+use crate::error::ContractError;
+use crate::math::{compute_undelegations, compute_validators_per_batch};
+use crate::state::State;
+use crate::testing::helpers::{
+    query_helper, query_helper_env, set_total_stake_supply, setup_test, MOCK_UTOKEN,
+};
+use crate::types::{Delegation, Undelegation};
+
+use super::custom_querier::CustomQuerier;
+use super::helpers::{mock_env_at_timestamp, UpdateConfigBuilder};
+
+fn queue_batch(
+    deps: &mut OwnedDeps<MockStorage, MockApi, CustomQuerier, CustomQueryType>,
+    id: u64,
+    delegations: &[(&str, u128)],
+    ustake_to_burn: u128,
+    est_unbond_start_time: u64,
+) {
+    let state = State::default();
+    let delegations = delegations
+        .iter()
+        .map(|(v, amount)| Delegation::new(v, *amount, MOCK_UTOKEN))
+        .collect_vec();
+    let total: u128 = delegations.iter().map(|d| d.amount).sum();
+
+    deps.querier.set_staking_delegations(&delegations);
+    set_total_stake_supply(&state, deps, total);
+    state
+        .pending_batch
+        .save(
+            deps.as_mut().storage,
+            &PendingBatch {
+                id,
+                ustake_to_burn: Uint128::new(ustake_to_burn),
+                est_unbond_start_time,
+            },
+        )
+        .unwrap();
+}
+
+//--------------------------------------------------------------------------------------------------
+// Execution
+//--------------------------------------------------------------------------------------------------
+
+#[test]
+fn updating_max_unbonding_entries() {
+    let mut deps = setup_test();
+
+    let res: ConfigResponse = query_helper(deps.as_ref(), QueryMsg::Config {});
+    assert_eq!(res.max_unbonding_entries, DEFAULT_MAX_UNBONDING_ENTRIES);
+
+    let err = execute(
+        deps.as_mut(),
+        mock_env_at_timestamp(10000),
+        mock_info("owner", &[]),
+        UpdateConfigBuilder {
+            max_unbonding_entries: Some(0),
+            ..Default::default()
+        }
+        .build(),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::CantBeZero("max_unbonding_entries".into()));
+
+    execute(
+        deps.as_mut(),
+        mock_env_at_timestamp(10000),
+        mock_info("owner", &[]),
+        UpdateConfigBuilder {
+            max_unbonding_entries: Some(3),
+            ..Default::default()
+        }
+        .build(),
+    )
+    .unwrap();
+
+    let res: ConfigResponse = query_helper(deps.as_ref(), QueryMsg::Config {});
+    assert_eq!(res.max_unbonding_entries, 3);
+}
+
+#[test]
+fn computing_undelegations_with_unbonding_entries() {
+    let deps = setup_test();
+    let state = State::default();
+
+    let delegations = vec![
+        Delegation::new("alice", 500, MOCK_UTOKEN),
+        Delegation::new("bob", 400, MOCK_UTOKEN),
+        Delegation::new("charlie", 300, MOCK_UTOKEN),
+    ];
+    let validators = delegations.iter().map(|d| d.validator.clone()).collect_vec();
+    let compute = |utoken_to_unbond: u128, entries: &[(&str, u32)], max_validators: usize| {
+        let entries: HashMap<String, u32> =
+            entries.iter().map(|(v, count)| (v.to_string(), *count)).collect();
+        compute_undelegations(
+            &state,
+            deps.as_ref().storage,
+            Uint128::new(utoken_to_unbond),
+            &delegations,
+            validators.clone(),
+            MOCK_UTOKEN,
+            &entries,
+            DEFAULT_MAX_UNBONDING_ENTRIES,
+            max_validators,
+        )
+    };
+
+    // every validator is reduced to 300
+    assert_eq!(
+        compute(300, &[], 3).unwrap(),
+        vec![
+            Undelegation::new("alice", 200, MOCK_UTOKEN),
+            Undelegation::new("bob", 100, MOCK_UTOKEN)
+        ]
+    );
+
+    // validators with less active entries are used first
+    assert_eq!(
+        compute(300, &[("alice", 2), ("bob", 1)], 3).unwrap(),
+        vec![
+            Undelegation::new("bob", 100, MOCK_UTOKEN),
+            Undelegation::new("alice", 200, MOCK_UTOKEN)
+        ]
+    );
+
+    // alice has no free entry, her share is undelegated from the other validators
+    assert_eq!(
+        compute(300, &[("alice", 7)], 3).unwrap(),
+        vec![Undelegation::new("bob", 300, MOCK_UTOKEN)]
+    );
+    assert_eq!(
+        compute(300, &[("alice", 7), ("bob", 7)], 3).unwrap(),
+        vec![Undelegation::new("charlie", 300, MOCK_UTOKEN)]
+    );
+
+    let err = compute(300, &[("alice", 7), ("bob", 7), ("charlie", 7)], 3).unwrap_err();
+    assert_eq!(err, StdError::generic_err("not enough validators with free unbonding entries"));
+
+    // a single validator per batch, the one with the fewest active entries
+    assert_eq!(compute(300, &[], 1).unwrap(), vec![Undelegation::new("alice", 300, MOCK_UTOKEN)]);
+    assert_eq!(
+        compute(300, &[("alice", 1)], 1).unwrap(),
+        vec![Undelegation::new("bob", 300, MOCK_UTOKEN)]
+    );
+    assert_eq!(
+        compute(300, &[("alice", 1), ("bob", 1)], 1).unwrap(),
+        vec![Undelegation::new("charlie", 300, MOCK_UTOKEN)]
+    );
+
+    // the subset is extended if its delegations can't cover the amount
+    assert_eq!(
+        compute(600, &[], 1).unwrap(),
+        vec![
+            Undelegation::new("alice", 400, MOCK_UTOKEN),
+            Undelegation::new("bob", 200, MOCK_UTOKEN)
+        ]
+    );
+}
+
+#[test]
+fn computing_validators_per_batch() {
+    // 7 batches per unbonding period with 7 entries, every validator can be used in every batch
+    assert_eq!(compute_validators_per_batch(3, 7, 3 * DAY, 21 * DAY), 3);
+    // 21 batches per unbonding period, each validator is used in every third batch
+    assert_eq!(compute_validators_per_batch(3, 7, DAY, 21 * DAY), 1);
+    assert_eq!(compute_validators_per_batch(30, 7, DAY, 21 * DAY), 10);
+    // at least one validator is always used
+    assert_eq!(compute_validators_per_batch(3, 1, DAY, 21 * DAY), 1);
+    assert_eq!(compute_validators_per_batch(3, 7, 0, 21 * DAY), 1);
+}
+
+#[test]
+fn submitting_batches_with_unbonding_entries() {
+    let mut deps = setup_test();
+
+    // 7 batches per unbonding period, with 3 entries each batch can only use one validator
+    execute(
+        deps.as_mut(),
+        mock_env_at_timestamp(10000),
+        mock_info("owner", &[]),
+        UpdateConfigBuilder {
+            max_unbonding_entries: Some(3),
+            ..Default::default()
+        }
+        .build(),
+    )
+    .unwrap();
+
+    // consecutive batches rotate across the validators
+    let validators = ["alice", "bob", "charlie"];
+    for index in 0..7u64 {
+        let time = 269201 + index * 259200;
+        queue_batch(
+            &mut deps,
+            index + 1,
+            &[("alice", 1000), ("bob", 1000), ("charlie", 1000)],
+            30,
+            time,
+        );
+        let res = execute(
+            deps.as_mut(),
+            mock_env_at_timestamp(time),
+            mock_info(MOCK_CONTRACT_ADDR, &[]),
+            ExecuteMsg::SubmitBatch {},
+        )
+        .unwrap();
+        assert_eq!(res.messages.len(), 3);
+        assert_eq!(
+            res.messages[0],
+            SubMsg::new(
+                Undelegation::new(validators[index as usize % 3], 30, MOCK_UTOKEN).to_cosmos_msg()
+            )
+        );
+    }
+
+    // unbonding completes after the unbond period of 21 days, the first entry of alice is no longer
+    // active and every validator has 2 active entries
+    queue_batch(&mut deps, 8, &[("alice", 1000), ("bob", 1000), ("charlie", 1000)], 30, 2083601);
+    let res = execute(
+        deps.as_mut(),
+        mock_env_at_timestamp(2083601),
+        mock_info(MOCK_CONTRACT_ADDR, &[]),
+        ExecuteMsg::SubmitBatch {},
+    )
+    .unwrap();
+    assert_eq!(
+        res.messages[0],
+        SubMsg::new(Undelegation::new("alice", 30, MOCK_UTOKEN).to_cosmos_msg())
+    );
+
+    let res: UnbondingEntriesResponse =
+        query_helper_env(deps.as_ref(), QueryMsg::UnbondingEntries {}, 2083601);
+    assert_eq!(
+        res,
+        UnbondingEntriesResponse {
+            max_entries: 3,
+            validators: vec![
+                ValidatorUnbondingEntries {
+                    validator: "alice".to_string(),
+                    completion_times: vec![2861201, 3638801, 3898001],
+                },
+                ValidatorUnbondingEntries {
+                    validator: "bob".to_string(),
+                    completion_times: vec![2342801, 3120401],
+                },
+                ValidatorUnbondingEntries {
+                    validator: "charlie".to_string(),
+                    completion_times: vec![2602001, 3379601],
+                },
+            ],
+        }
+    );
+}
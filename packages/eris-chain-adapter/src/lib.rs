@@ -1,7 +1,7 @@
 #[cfg(feature = "X-kujira-X")]
 pub mod types {
-    use cosmwasm_std::DepsMut;
     use cosmwasm_std::Env;
+    use cosmwasm_std::QuerierWrapper;
     use cosmwasm_std::StdResult;
     use cosmwasm_std::Uint128;
     use std::collections::HashMap;
@@ -37,14 +37,14 @@ pub mod types {
 
     /// queries all balances and converts it to a hashmap
     pub fn get_balances_hashmap<F>(
-        deps: &DepsMut<CustomQueryType>,
+        querier: &QuerierWrapper<CustomQueryType>,
         env: Env,
         _get_denoms: F,
     ) -> StdResult<HashMap<String, Uint128>>
     where
         F: FnOnce() -> Vec<DenomType>,
     {
-        let balances = deps.querier.query_all_balances(env.contract.address)?;
+        let balances = querier.query_all_balances(env.contract.address)?;
         let balances: HashMap<_, _> =
             balances.into_iter().map(|item| (item.denom.clone(), item.amount)).collect();
         Ok(balances)
@@ -53,8 +53,8 @@ pub mod types {
 
 #[cfg(feature = "X-whitewhale-X")]
 pub mod types {
-    use cosmwasm_std::DepsMut;
     use cosmwasm_std::Env;
+    use cosmwasm_std::QuerierWrapper;
     use cosmwasm_std::StdError;
     use cosmwasm_std::StdResult;
     use cosmwasm_std::Uint128;
@@ -95,7 +95,7 @@ pub mod types {
 
     /// queries all balances and converts it to a hashmap
     pub fn get_balances_hashmap<F>(
-        deps: &DepsMut<CustomQueryType>,
+        querier: &QuerierWrapper<CustomQueryType>,
         env: Env,
         get_denoms: F,
     ) -> StdResult<HashMap<String, Uint128>>
@@ -106,7 +106,7 @@ pub mod types {
             .into_iter()
             .map(|denom| {
                 let balance = denom
-                    .query_balance(&deps.querier, env.contract.address.clone())
+                    .query_balance(querier, env.contract.address.clone())
                     .map_err(|e| StdError::generic_err(e.to_string()))?;
 
                 Ok(get_asset(denom, balance))
@@ -121,8 +121,8 @@ pub mod types {
 }
 #[cfg(feature = "X-nibiru-X")]
 pub mod types {
-    use cosmwasm_std::DepsMut;
     use cosmwasm_std::Env;
+    use cosmwasm_std::QuerierWrapper;
     use cosmwasm_std::StdError;
     use cosmwasm_std::StdResult;
     use cosmwasm_std::Uint128;
@@ -163,7 +163,7 @@ pub mod types {
 
     /// queries all balances and converts it to a hashmap
     pub fn get_balances_hashmap<F>(
-        deps: &DepsMut<CustomQueryType>,
+        querier: &QuerierWrapper<CustomQueryType>,
         env: Env,
         get_denoms: F,
     ) -> StdResult<HashMap<String, Uint128>>
@@ -174,7 +174,7 @@ pub mod types {
             .into_iter()
             .map(|denom| {
                 let balance = denom
-                    .query_balance(&deps.querier, env.contract.address.clone())
+                    .query_balance(querier, env.contract.address.clone())
                     .map_err(|e| StdError::generic_err(e.to_string()))?;
 
                 Ok(get_asset(denom, balance))
@@ -190,8 +190,8 @@ pub mod types {
 
 #[cfg(feature = "X-injective-X")]
 pub mod types {
-    use cosmwasm_std::DepsMut;
     use cosmwasm_std::Env;
+    use cosmwasm_std::QuerierWrapper;
     use cosmwasm_std::StdError;
     use cosmwasm_std::StdResult;
     use cosmwasm_std::Uint128;
@@ -228,7 +228,7 @@ pub mod types {
 
     /// queries all balances and converts it to a hashmap
     pub fn get_balances_hashmap<F>(
-        deps: &DepsMut<CustomQueryType>,
+        querier: &QuerierWrapper<CustomQueryType>,
         env: Env,
         get_denoms: F,
     ) -> StdResult<HashMap<String, Uint128>>
@@ -239,7 +239,7 @@ pub mod types {
             .into_iter()
             .map(|denom| {
                 let balance = denom
-                    .query_balance(&deps.querier, env.contract.address.clone())
+                    .query_balance(querier, env.contract.address.clone())
                     .map_err(|e| StdError::generic_err(e.to_string()))?;
 
                 Ok(get_asset(denom, balance))
@@ -255,8 +255,8 @@ pub mod types {
 
 #[cfg(feature = "X-osmosis-X")]
 pub mod types {
-    use cosmwasm_std::DepsMut;
     use cosmwasm_std::Env;
+    use cosmwasm_std::QuerierWrapper;
     use cosmwasm_std::StdResult;
     use cosmwasm_std::Uint128;
     use std::collections::HashMap;
@@ -291,14 +291,14 @@ pub mod types {
 
     /// queries all balances and converts it to a hashmap
     pub fn get_balances_hashmap<F>(
-        deps: &DepsMut<CustomQueryType>,
+        querier: &QuerierWrapper<CustomQueryType>,
         env: Env,
         _get_denoms: F,
     ) -> StdResult<HashMap<String, Uint128>>
     where
         F: FnOnce() -> Vec<DenomType>,
     {
-        let balances = deps.querier.query_all_balances(env.contract.address)?;
+        let balances = querier.query_all_balances(env.contract.address)?;
         let balances: HashMap<_, _> =
             balances.into_iter().map(|item| (item.denom.clone(), item.amount)).collect();
         Ok(balances)
@@ -307,8 +307,8 @@ pub mod types {
 
 #[cfg(feature = "X-terra-X")]
 pub mod types {
-    use cosmwasm_std::DepsMut;
     use cosmwasm_std::Env;
+    use cosmwasm_std::QuerierWrapper;
     use cosmwasm_std::StdError;
     use cosmwasm_std::StdResult;
     use cosmwasm_std::Uint128;
@@ -346,7 +346,7 @@ pub mod types {
 
     /// queries all balances and converts it to a hashmap
     pub fn get_balances_hashmap<F>(
-        deps: &DepsMut<CustomQueryType>,
+        querier: &QuerierWrapper<CustomQueryType>,
         env: Env,
         get_denoms: F,
     ) -> StdResult<HashMap<String, Uint128>>
@@ -357,7 +357,7 @@ pub mod types {
             .into_iter()
             .map(|denom| {
                 let balance = denom
-                    .query_pool(&deps.querier.into_empty(), env.contract.address.clone())
+                    .query_pool(&querier.into_empty(), env.contract.address.clone())
                     .map_err(|e| StdError::generic_err(e.to_string()))?;
 
                 Ok(get_asset(denom, balance))
@@ -378,8 +378,8 @@ pub mod types {
 
 #[cfg(feature = "X-sei-X")]
 pub mod types {
-    use cosmwasm_std::DepsMut;
     use cosmwasm_std::Env;
+    use cosmwasm_std::QuerierWrapper;
     use cosmwasm_std::StdResult;
     use cosmwasm_std::Uint128;
     use std::collections::HashMap;
@@ -414,14 +414,14 @@ pub mod types {
 
     /// queries all balances and converts it to a hashmap
     pub fn get_balances_hashmap<F>(
-        deps: &DepsMut<CustomQueryType>,
+        querier: &QuerierWrapper<CustomQueryType>,
         env: Env,
         _get_denoms: F,
     ) -> StdResult<HashMap<String, Uint128>>
     where
         F: FnOnce() -> Vec<DenomType>,
     {
-        let balances = deps.querier.query_all_balances(env.contract.address)?;
+        let balances = querier.query_all_balances(env.contract.address)?;
         let balances: HashMap<_, _> =
             balances.into_iter().map(|item| (item.denom.clone(), item.amount)).collect();
         Ok(balances)
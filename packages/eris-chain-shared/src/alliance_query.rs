@@ -10,6 +10,12 @@ impl CustomQuery for AllianceQuery {}
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 #[serde(rename_all = "snake_case")]
 pub enum AllianceQuery {
+    Alliance {
+        denom: String,
+    },
+    Validator {
+        validator_addr: String,
+    },
     Delegation {
         denom: String,
         delegator: String,
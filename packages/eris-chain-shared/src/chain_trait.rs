@@ -1,4 +1,6 @@
-use cosmwasm_std::{Addr, Api, CosmosMsg, Decimal, StdResult, Uint128};
+use cosmwasm_std::{
+    Addr, Api, CosmosMsg, CustomQuery, Decimal, QuerierWrapper, StdError, StdResult, Uint128,
+};
 
 pub trait ChainInterface<TCustom, TDenomType, TWithdrawType, TStageType, THubChainConfig> {
     fn get_token_denom(&self, contract_addr: impl Into<String>, sub_denom: String) -> String {
@@ -16,6 +18,15 @@ pub trait ChainInterface<TCustom, TDenomType, TWithdrawType, TStageType, THubCha
 
     fn create_burn_msg(&self, full_denom: String, amount: Uint128) -> CosmosMsg<TCustom>;
 
+    /// sets the contract called before each transfer of the denom, an empty address removes it.
+    fn create_before_send_hook_msg(
+        &self,
+        _full_denom: String,
+        _cosmwasm_address: String,
+    ) -> StdResult<CosmosMsg<TCustom>> {
+        Err(StdError::generic_err("before send hooks not supported"))
+    }
+
     fn create_withdraw_msg<F>(
         &self,
         get_chain_config: F,
@@ -37,6 +48,88 @@ pub trait ChainInterface<TCustom, TDenomType, TWithdrawType, TStageType, THubCha
     ) -> StdResult<CosmosMsg<TCustom>>
     where
         F: FnOnce() -> StdResult<THubChainConfig>;
+
+    /// whether swapping the denom in the stage needs a belief price to limit the trade. Presets of
+    /// these stages are allowed to contain a belief price. Chains with route stages require it, as
+    /// the output of a route is only limited by the min return of the belief price.
+    fn requires_belief_price(&self, _stage_type: &TStageType, _denom: &TDenomType) -> bool {
+        false
+    }
+
+    /// swaps the denom through multiple hops of a route stage. The belief price is the price of the
+    /// whole route, so only the output of the last hop is checked.
+    fn create_route_swap_msgs<F>(
+        &self,
+        _get_chain_config: F,
+        _stage_type: TStageType,
+        _denom: TDenomType,
+        _amount: Uint128,
+        _belief_price: Option<Decimal>,
+        _max_spread: Decimal,
+    ) -> StdResult<CosmosMsg<TCustom>>
+    where
+        F: FnOnce() -> StdResult<THubChainConfig>,
+    {
+        Err(StdError::generic_err("route swaps not supported"))
+    }
+
+    /// simulates the swap of a stage, returns the received denom and the expected amount.
+    fn simulate_swap<F, Q: CustomQuery>(
+        &self,
+        _querier: &QuerierWrapper<Q>,
+        _get_chain_config: F,
+        _stage_type: TStageType,
+        _denom: TDenomType,
+        _amount: Uint128,
+    ) -> StdResult<(TDenomType, Uint128)>
+    where
+        F: FnOnce() -> StdResult<THubChainConfig>,
+    {
+        Err(StdError::generic_err("swap simulation not supported"))
+    }
+
+    /// simulates withdrawing the LP amount, returns each received denom with its amount.
+    fn simulate_withdraw<F, Q: CustomQuery>(
+        &self,
+        _querier: &QuerierWrapper<Q>,
+        _get_chain_config: F,
+        _withdraw_type: TWithdrawType,
+        _denom: TDenomType,
+        _amount: Uint128,
+    ) -> StdResult<Vec<(TDenomType, Uint128)>>
+    where
+        F: FnOnce() -> StdResult<THubChainConfig>,
+    {
+        Err(StdError::generic_err("withdraw simulation not supported"))
+    }
+
+    /// queries the price of the symbol from the oracle module of the chain.
+    fn query_oracle_price<Q: CustomQuery>(
+        &self,
+        _querier: &QuerierWrapper<Q>,
+        _symbol: String,
+    ) -> StdResult<Decimal> {
+        Err(StdError::generic_err("oracle prices not supported"))
+    }
+}
+
+/// min amount received when swapping the amount at the belief price + max spread
+pub fn get_min_return(
+    amount: Uint128,
+    belief_price: Option<Decimal>,
+    max_spread: Decimal,
+) -> StdResult<Option<Uint128>> {
+    belief_price
+        .map(|belief_price| -> StdResult<Uint128> {
+            let slippage_price =
+                Decimal::one().checked_add(max_spread)?.checked_mul(belief_price)?;
+            let min_return = Decimal::one()
+                .checked_div(slippage_price)
+                .map_err(|_| StdError::generic_err("belief_price can't be zero"))?
+                * amount;
+            Ok(min_return)
+        })
+        .transpose()
 }
 
 pub trait Validateable<T> {
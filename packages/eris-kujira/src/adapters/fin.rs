@@ -1,12 +1,64 @@
 use std::vec;
 
 use cosmwasm_schema::cw_serde;
-use cosmwasm_std::{to_json_binary, Addr, Coin, CosmosMsg, Decimal, StdResult, WasmMsg};
-use kujira::{fin::ExecuteMsg, msg::KujiraMsg};
+use cosmwasm_std::{
+    to_json_binary, Addr, Coin, CosmosMsg, CustomQuery, Decimal, QuerierWrapper, StdError,
+    StdResult, Uint128, WasmMsg,
+};
+use kujira::{denom::Denom, fin::ExecuteMsg, msg::KujiraMsg};
+use serde::Deserialize;
 
 #[cw_serde]
 pub struct Fin(pub Addr);
 
+#[cw_serde]
+pub enum QueryMsg {
+    Config {},
+    Simulation {
+        offer_asset: Coin,
+    },
+}
+
+#[derive(Deserialize)]
+pub struct ConfigResponse {
+    pub denoms: Vec<Denom>,
+}
+
+#[derive(Deserialize)]
+pub struct SimulationResponse {
+    pub return_amount: Uint128,
+}
+
+#[cw_serde]
+pub enum RouterExecuteMsg {
+    Swap {
+        stages: Vec<Vec<(Addr, String)>>,
+        min_return: Vec<Coin>,
+    },
+}
+
+/// Router executing a swap through multiple FIN pairs
+#[cw_serde]
+pub struct FinRouter(pub Addr);
+
+impl FinRouter {
+    pub fn swap_msg(
+        &self,
+        offer_asset: &Coin,
+        stages: Vec<Vec<(Addr, String)>>,
+        min_return: Option<Coin>,
+    ) -> StdResult<CosmosMsg<KujiraMsg>> {
+        Ok(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: self.0.to_string(),
+            funds: vec![offer_asset.clone()],
+            msg: to_json_binary(&RouterExecuteMsg::Swap {
+                stages,
+                min_return: min_return.into_iter().collect(),
+            })?,
+        }))
+    }
+}
+
 impl Fin {
     pub fn swap_msg(
         &self,
@@ -25,6 +77,30 @@ impl Fin {
             })?,
         }))
     }
+
+    /// simulates the swap against the book, returns the other denom of the pair with the return amount
+    pub fn simulate_swap<Q: CustomQuery>(
+        &self,
+        querier: &QuerierWrapper<Q>,
+        offer_asset: &Coin,
+    ) -> StdResult<(Denom, Uint128)> {
+        let config: ConfigResponse =
+            querier.query_wasm_smart(self.0.to_string(), &QueryMsg::Config {})?;
+        let ask = config
+            .denoms
+            .into_iter()
+            .find(|denom| denom.to_string() != offer_asset.denom)
+            .ok_or_else(|| StdError::generic_err("Fin: ask denom not found"))?;
+
+        let simulation: SimulationResponse = querier.query_wasm_smart(
+            self.0.to_string(),
+            &QueryMsg::Simulation {
+                offer_asset: offer_asset.clone(),
+            },
+        )?;
+
+        Ok((ask, simulation.return_amount))
+    }
 }
 
 #[test]
@@ -54,3 +130,32 @@ pub fn test_swap_msg() {
         })
     );
 }
+
+#[test]
+pub fn test_route_swap_msg() {
+    use cosmwasm_std::Uint128;
+
+    let coin = Coin {
+        amount: Uint128::new(123),
+        denom: "denom".to_string(),
+    };
+    let stages = vec![
+        vec![(Addr::unchecked("fin1"), "denom".to_string())],
+        vec![(Addr::unchecked("fin2"), "usk".to_string())],
+    ];
+
+    assert_eq!(
+        FinRouter(Addr::unchecked("router"))
+            .swap_msg(&coin, stages.clone(), Some(cosmwasm_std::coin(100, "ukuji")))
+            .unwrap(),
+        CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: "router".to_string(),
+            funds: vec![coin.clone()],
+            msg: to_json_binary(&RouterExecuteMsg::Swap {
+                stages,
+                min_return: vec![cosmwasm_std::coin(100, "ukuji")],
+            })
+            .unwrap(),
+        })
+    );
+}
@@ -1,3 +1,4 @@
 pub mod bow_vault;
 pub mod bw_vault;
 pub mod fin;
+pub mod oracle;
@@ -0,0 +1,54 @@
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{
+    from_json, to_json_vec, ContractResult, CustomQuery, Decimal, QuerierWrapper, QueryRequest,
+    StdError, StdResult, SystemResult,
+};
+use serde::Deserialize;
+
+/// Subset of the custom queries of the Kujira chain
+#[cw_serde]
+pub enum KujiraQuery {
+    Oracle(OracleQuery),
+}
+
+#[cw_serde]
+pub enum OracleQuery {
+    ExchangeRate {
+        denom: String,
+    },
+}
+
+#[derive(Deserialize)]
+pub struct ExchangeRateResponse {
+    pub rate: Decimal,
+}
+
+/// Oracle module of the Kujira chain
+pub struct Oracle;
+
+impl Oracle {
+    /// queries the exchange rate of the symbol. The custom query is sent as raw query, as the
+    /// querier of the contract does not use the Kujira query type.
+    pub fn query_price<Q: CustomQuery>(
+        &self,
+        querier: &QuerierWrapper<Q>,
+        symbol: String,
+    ) -> StdResult<Decimal> {
+        let request =
+            to_json_vec(&QueryRequest::Custom(KujiraQuery::Oracle(OracleQuery::ExchangeRate {
+                denom: symbol,
+            })))?;
+
+        let response: ExchangeRateResponse = match querier.raw_query(&request) {
+            SystemResult::Err(system_err) => {
+                Err(StdError::generic_err(format!("Querier system error: {}", system_err)))
+            },
+            SystemResult::Ok(ContractResult::Err(contract_err)) => {
+                Err(StdError::generic_err(format!("Querier contract error: {}", contract_err)))
+            },
+            SystemResult::Ok(ContractResult::Ok(value)) => from_json(&value),
+        }?;
+
+        Ok(response.rate)
+    }
+}
@@ -1,9 +1,16 @@
-use cosmwasm_std::{coin, Addr, CosmosMsg, Decimal, StdResult, Uint128};
-use eris_chain_shared::chain_trait::ChainInterface;
+use cosmwasm_std::{
+    coin, Addr, CosmosMsg, CustomQuery, Decimal, QuerierWrapper, StdError, StdResult, Uint128,
+};
+use eris_chain_shared::chain_trait::{get_min_return, ChainInterface};
 use kujira::msg::DenomMsg;
 
 use crate::{
-    adapters::{bow_vault::BowVault, bw_vault::BlackWhaleVault, fin::Fin},
+    adapters::{
+        bow_vault::BowVault,
+        bw_vault::BlackWhaleVault,
+        fin::{Fin, FinRouter},
+        oracle::Oracle,
+    },
     kujira_types::{CustomMsgType, DenomType, HubChainConfig, StageType, WithdrawType},
 };
 
@@ -63,7 +70,7 @@ impl ChainInterface<CustomMsgType, DenomType, WithdrawType, StageType, HubChainC
 
     fn create_single_stage_swap_msgs<F>(
         &self,
-        _get_chain_config: F,
+        get_chain_config: F,
         stage_type: StageType,
         denom: DenomType,
         amount: Uint128,
@@ -81,6 +88,93 @@ impl ChainInterface<CustomMsgType, DenomType, WithdrawType, StageType, HubChainC
                 belief_price,
                 Some(max_spread),
             ),
+            StageType::Route {
+                ..
+            } => self.create_route_swap_msgs(
+                get_chain_config,
+                stage_type,
+                denom,
+                amount,
+                belief_price,
+                max_spread,
+            ),
         }
     }
+
+    fn requires_belief_price(&self, stage_type: &StageType, _denom: &DenomType) -> bool {
+        matches!(stage_type, StageType::Route { .. })
+    }
+
+    fn create_route_swap_msgs<F>(
+        &self,
+        _get_chain_config: F,
+        stage_type: StageType,
+        denom: DenomType,
+        amount: Uint128,
+        belief_price: Option<Decimal>,
+        max_spread: Decimal,
+    ) -> StdResult<CosmosMsg<CustomMsgType>>
+    where
+        F: FnOnce() -> StdResult<HubChainConfig>,
+    {
+        match stage_type {
+            StageType::Route {
+                router,
+                stages,
+                output_denom,
+            } => {
+                let min_return = get_min_return(amount, belief_price, max_spread)?
+                    .ok_or_else(|| StdError::generic_err("belief_price required"))?;
+
+                FinRouter(router).swap_msg(
+                    &coin(amount.u128(), denom.to_string()),
+                    stages,
+                    Some(coin(min_return.u128(), output_denom)),
+                )
+            },
+            _ => Err(StdError::generic_err("not a route stage")),
+        }
+    }
+
+    fn simulate_swap<F, Q: CustomQuery>(
+        &self,
+        querier: &QuerierWrapper<Q>,
+        _get_chain_config: F,
+        stage_type: StageType,
+        denom: DenomType,
+        amount: Uint128,
+    ) -> StdResult<(DenomType, Uint128)>
+    where
+        F: FnOnce() -> StdResult<HubChainConfig>,
+    {
+        match stage_type {
+            StageType::Fin {
+                addr,
+            } => Fin(addr).simulate_swap(querier, &coin(amount.u128(), denom.to_string())),
+            StageType::Route {
+                stages,
+                output_denom,
+                ..
+            } => {
+                // the router uses the first pair of each stage
+                let mut amount = amount;
+                for stage in stages {
+                    let (pair, offer_denom) = stage
+                        .into_iter()
+                        .next()
+                        .ok_or_else(|| StdError::generic_err("route stage is empty"))?;
+                    amount = Fin(pair).simulate_swap(querier, &coin(amount.u128(), offer_denom))?.1;
+                }
+                Ok((output_denom.into(), amount))
+            },
+        }
+    }
+
+    fn query_oracle_price<Q: CustomQuery>(
+        &self,
+        querier: &QuerierWrapper<Q>,
+        symbol: String,
+    ) -> StdResult<Decimal> {
+        Oracle.query_price(querier, symbol)
+    }
 }
@@ -36,6 +36,12 @@ pub enum StageType {
     Fin {
         addr: Addr,
     },
+    /// swaps through multiple FIN pairs via a router, each hop is (pair, offer denom)
+    Route {
+        router: Addr,
+        stages: Vec<Vec<(Addr, String)>>,
+        output_denom: String,
+    },
 }
 
 impl StageType {
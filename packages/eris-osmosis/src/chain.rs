@@ -1,16 +1,20 @@
 use std::ops::Div;
+use std::str::FromStr;
 
-use cosmwasm_std::{coins, StdError};
+use cosmwasm_std::{coins, CustomQuery, QuerierWrapper, StdError};
 use cosmwasm_std::{Addr, CosmosMsg, Decimal, StdResult, Uint128};
-use eris_chain_shared::chain_trait::ChainInterface;
+use eris_chain_shared::chain_trait::{get_min_return, ChainInterface};
 use osmosis_std::types::cosmos::base::v1beta1::Coin;
 use osmosis_std::types::osmosis::gamm::v1beta1::MsgExitPool;
 use osmosis_std::types::osmosis::gamm::v1beta1::MsgSwapExactAmountIn;
+use osmosis_std::types::osmosis::gamm::v1beta1::QueryCalcExitPoolCoinsFromSharesRequest;
+use osmosis_std::types::osmosis::poolmanager::v1beta1::EstimateSwapExactAmountInRequest;
 use osmosis_std::types::osmosis::poolmanager::v1beta1::SwapAmountInRoute;
 use osmosis_std::types::osmosis::tokenfactory::v1beta1::MsgBurn;
 use osmosis_std::types::osmosis::tokenfactory::v1beta1::MsgCreateDenom;
 use osmosis_std::types::osmosis::tokenfactory::v1beta1::MsgMint;
 
+use crate::hooks::MsgSetBeforeSendHook;
 use crate::types::{CustomMsgType, DenomType, HubChainConfig, StageType, WithdrawType};
 
 pub struct OsmosisChain {
@@ -64,6 +68,19 @@ impl ChainInterface<CustomMsgType, DenomType, WithdrawType, StageType, HubChainC
         .into()
     }
 
+    fn create_before_send_hook_msg(
+        &self,
+        full_denom: String,
+        cosmwasm_address: String,
+    ) -> StdResult<CosmosMsg<CustomMsgType>> {
+        Ok(MsgSetBeforeSendHook {
+            sender: self.contract.to_string(),
+            denom: full_denom,
+            cosmwasm_address,
+        }
+        .into())
+    }
+
     fn create_withdraw_msg<F>(
         &self,
         _get_chain_config: F,
@@ -98,7 +115,7 @@ impl ChainInterface<CustomMsgType, DenomType, WithdrawType, StageType, HubChainC
 
     fn create_single_stage_swap_msgs<F>(
         &self,
-        _get_chain_config: F,
+        get_chain_config: F,
         stage_type: StageType,
         denom: DenomType,
         amount: Uint128,
@@ -142,6 +159,130 @@ impl ChainInterface<CustomMsgType, DenomType, WithdrawType, StageType, HubChainC
                     Err(StdError::generic_err("belief_price required"))
                 }
             },
+            StageType::Route {
+                ..
+            } => self.create_route_swap_msgs(
+                get_chain_config,
+                stage_type,
+                denom,
+                amount,
+                belief_price,
+                max_spread,
+            ),
+        }
+    }
+
+    fn requires_belief_price(&self, stage_type: &StageType, _denom: &DenomType) -> bool {
+        matches!(stage_type, StageType::Route { .. })
+    }
+
+    fn create_route_swap_msgs<F>(
+        &self,
+        _get_chain_config: F,
+        stage_type: StageType,
+        denom: DenomType,
+        amount: Uint128,
+        belief_price: Option<Decimal>,
+        max_spread: Decimal,
+    ) -> StdResult<CosmosMsg<CustomMsgType>>
+    where
+        F: FnOnce() -> StdResult<HubChainConfig>,
+    {
+        match stage_type {
+            StageType::Route {
+                routes,
+            } => {
+                let out_amount = get_min_return(amount, belief_price, max_spread)?
+                    .ok_or_else(|| StdError::generic_err("belief_price required"))?;
+
+                Ok(MsgSwapExactAmountIn {
+                    sender: self.contract.to_string(),
+                    routes: routes
+                        .into_iter()
+                        .map(|(pool_id, token_out_denom)| SwapAmountInRoute {
+                            pool_id,
+                            token_out_denom,
+                        })
+                        .collect(),
+                    token_in: Some(osmosis_std::types::cosmos::base::v1beta1::Coin {
+                        denom,
+                        amount: amount.to_string(),
+                    }),
+                    token_out_min_amount: out_amount.to_string(),
+                }
+                .into())
+            },
+            _ => Err(StdError::generic_err("not a route stage")),
+        }
+    }
+
+    fn simulate_swap<F, Q: CustomQuery>(
+        &self,
+        querier: &QuerierWrapper<Q>,
+        _get_chain_config: F,
+        stage_type: StageType,
+        denom: DenomType,
+        amount: Uint128,
+    ) -> StdResult<(DenomType, Uint128)>
+    where
+        F: FnOnce() -> StdResult<HubChainConfig>,
+    {
+        let routes = match stage_type {
+            StageType::Osmo {
+                pool_id,
+                token_out_denom,
+            } => vec![(pool_id, token_out_denom)],
+            StageType::Route {
+                routes,
+            } => routes,
+        };
+
+        let pool_id = routes.first().map(|(pool_id, _)| *pool_id);
+        let token_out_denom = routes.last().map(|(_, token_out_denom)| token_out_denom.clone());
+        let (pool_id, token_out_denom) =
+            pool_id.zip(token_out_denom).ok_or_else(|| StdError::generic_err("route is empty"))?;
+
+        let estimate = EstimateSwapExactAmountInRequest {
+            sender: self.contract.to_string(),
+            pool_id,
+            token_in: format!("{0}{1}", amount, denom),
+            routes: routes
+                .into_iter()
+                .map(|(pool_id, token_out_denom)| SwapAmountInRoute {
+                    pool_id,
+                    token_out_denom,
+                })
+                .collect(),
+        }
+        .query(querier)?;
+
+        Ok((token_out_denom, Uint128::from_str(&estimate.token_out_amount)?))
+    }
+
+    fn simulate_withdraw<F, Q: CustomQuery>(
+        &self,
+        querier: &QuerierWrapper<Q>,
+        _get_chain_config: F,
+        withdraw_type: WithdrawType,
+        _denom: DenomType,
+        amount: Uint128,
+    ) -> StdResult<Vec<(DenomType, Uint128)>>
+    where
+        F: FnOnce() -> StdResult<HubChainConfig>,
+    {
+        match withdraw_type {
+            WithdrawType::Withdraw {
+                pool_id,
+                ..
+            } => QueryCalcExitPoolCoinsFromSharesRequest {
+                pool_id,
+                share_in_amount: amount.to_string(),
+            }
+            .query(querier)?
+            .tokens_out
+            .into_iter()
+            .map(|coin| Ok((coin.denom, Uint128::from_str(&coin.amount)?)))
+            .collect(),
         }
     }
 }
@@ -0,0 +1,27 @@
+use osmosis_std_derive::CosmwasmExt;
+use std::convert::TryFrom;
+use std::convert::TryInto;
+
+// see https://github.com/osmosis-labs/osmosis/blob/v16.0.0/proto/osmosis/tokenfactory/v1beta1/tx.proto
+
+/// MsgSetBeforeSendHook is the sdk.Msg type for allowing an admin account to
+/// assign a CosmWasm contract to call with a BeforeSend hook
+#[derive(
+    Clone,
+    PartialEq,
+    Eq,
+    ::prost::Message,
+    serde::Serialize,
+    serde::Deserialize,
+    schemars::JsonSchema,
+    CosmwasmExt,
+)]
+#[proto_message(type_url = "/osmosis.tokenfactory.v1beta1.MsgSetBeforeSendHook")]
+pub struct MsgSetBeforeSendHook {
+    #[prost(string, tag = "1")]
+    pub sender: ::prost::alloc::string::String,
+    #[prost(string, tag = "2")]
+    pub denom: ::prost::alloc::string::String,
+    #[prost(string, tag = "3")]
+    pub cosmwasm_address: ::prost::alloc::string::String,
+}
@@ -1,4 +1,4 @@
-
 pub mod chain;
-pub mod types;
+pub mod hooks;
 pub mod test;
+pub mod types;
@@ -19,3 +19,72 @@ impl
         HubChainConfigInput {}
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use cosmwasm_std::{Addr, CosmosMsg, Decimal, Empty, StdError, Uint128};
+    use eris_chain_shared::chain_trait::ChainInterface;
+    use osmosis_std::types::cosmos::base::v1beta1::Coin;
+    use osmosis_std::types::osmosis::gamm::v1beta1::MsgSwapExactAmountIn;
+    use osmosis_std::types::osmosis::poolmanager::v1beta1::SwapAmountInRoute;
+
+    use crate::chain::OsmosisChain;
+    use crate::types::{HubChainConfig, StageType};
+
+    #[test]
+    fn route_swap() {
+        let chain = OsmosisChain {
+            contract: Addr::unchecked("hub"),
+        };
+        let route = StageType::Route {
+            routes: vec![(1, "uosmo".to_string()), (2, "uatom".to_string())],
+        };
+
+        // 1000 / (1.5 + 10%) = 606
+        let msg = chain
+            .create_single_stage_swap_msgs(
+                || Ok(HubChainConfig {}),
+                route.clone(),
+                "ibc/usdc".to_string(),
+                Uint128::new(1000),
+                Some(Decimal::from_str("1.5").unwrap()),
+                Decimal::percent(10),
+            )
+            .unwrap();
+
+        let expected: CosmosMsg<Empty> = MsgSwapExactAmountIn {
+            sender: "hub".to_string(),
+            routes: vec![
+                SwapAmountInRoute {
+                    pool_id: 1,
+                    token_out_denom: "uosmo".to_string(),
+                },
+                SwapAmountInRoute {
+                    pool_id: 2,
+                    token_out_denom: "uatom".to_string(),
+                },
+            ],
+            token_in: Some(Coin {
+                denom: "ibc/usdc".to_string(),
+                amount: "1000".to_string(),
+            }),
+            token_out_min_amount: "606".to_string(),
+        }
+        .into();
+        assert_eq!(msg, expected);
+
+        let err = chain
+            .create_single_stage_swap_msgs(
+                || Ok(HubChainConfig {}),
+                route,
+                "ibc/usdc".to_string(),
+                Uint128::new(1000),
+                None,
+                Decimal::percent(10),
+            )
+            .unwrap_err();
+        assert_eq!(err, StdError::generic_err("belief_price required"));
+    }
+}
@@ -16,6 +16,10 @@ pub enum StageType {
         pool_id: u64,
         token_out_denom: String,
     },
+    /// swaps through multiple pools, each hop is (pool_id, token_out_denom)
+    Route {
+        routes: Vec<(u64, String)>,
+    },
 }
 
 pub type DenomType = String;
@@ -0,0 +1,2 @@
+pub mod order_book;
+pub mod pair;
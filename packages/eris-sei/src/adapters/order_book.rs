@@ -0,0 +1,63 @@
+use cosmwasm_std::{coins, Addr, CosmosMsg, Decimal, StdError, StdResult, Uint128};
+use sei_cosmwasm::{Order, OrderType, PositionDirection, SeiMsg};
+
+use crate::types::{CustomMsgType, DenomType};
+
+/// Market of the native Sei order book registered by a dex contract
+pub struct OrderBook {
+    pub contract_addr: Addr,
+    pub price_denom: String,
+    pub asset_denom: String,
+}
+
+impl OrderBook {
+    /// Places a market order selling the offered denom.
+    /// The belief price is the amount of offered denom per received denom.
+    pub fn swap_msg(
+        &self,
+        denom: DenomType,
+        amount: Uint128,
+        belief_price: Option<Decimal>,
+        max_spread: Decimal,
+    ) -> StdResult<CosmosMsg<CustomMsgType>> {
+        let (position_direction, price, quantity) = if denom == self.asset_denom {
+            // selling the asset, the price is the min price accepted
+            let price = match belief_price {
+                Some(belief_price) => Decimal::one()
+                    .checked_div(belief_price)
+                    .map_err(|_| StdError::generic_err("belief_price can't be zero"))?
+                    .checked_mul(Decimal::one().checked_sub(max_spread)?)?,
+                None => Decimal::zero(),
+            };
+            (PositionDirection::Short, price, Decimal::from_ratio(amount, 1u128))
+        } else if denom == self.price_denom {
+            // buying the asset, the price is the max price paid
+            let price = belief_price
+                .ok_or_else(|| StdError::generic_err("belief_price required for order book"))?
+                .checked_mul(Decimal::one().checked_add(max_spread)?)?;
+            let quantity = Decimal::from_ratio(amount, 1u128)
+                .checked_div(price)
+                .map_err(|_| StdError::generic_err("belief_price can't be zero"))?;
+            (PositionDirection::Long, price, quantity)
+        } else {
+            return Err(StdError::generic_err(format!("denom {0} not part of the market", denom)));
+        };
+
+        Ok(SeiMsg::PlaceOrders {
+            orders: vec![Order {
+                price,
+                quantity,
+                price_denom: self.price_denom.clone(),
+                asset_denom: self.asset_denom.clone(),
+                order_type: OrderType::Market,
+                position_direction,
+                data: "".to_string(),
+                status_description: "".to_string(),
+                nonce: 0,
+            }],
+            funds: coins(amount.u128(), denom),
+            contract_address: self.contract_addr.clone(),
+        }
+        .into())
+    }
+}
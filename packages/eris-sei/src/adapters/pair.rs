@@ -0,0 +1,167 @@
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{
+    coins, to_json_binary, Addr, CosmosMsg, CustomQuery, Decimal, QuerierWrapper, StdError,
+    StdResult, Uint128, WasmMsg,
+};
+use serde::Deserialize;
+
+use crate::types::{CustomMsgType, DenomType};
+
+#[cw_serde]
+pub enum AssetInfo {
+    Token {
+        contract_addr: String,
+    },
+    NativeToken {
+        denom: String,
+    },
+}
+
+#[cw_serde]
+pub struct Asset {
+    pub info: AssetInfo,
+    pub amount: Uint128,
+}
+
+#[cw_serde]
+pub enum ExecuteMsg {
+    /// Swap an offer asset to the other
+    Swap {
+        offer_asset: Asset,
+        ask_asset_info: Option<AssetInfo>,
+        belief_price: Option<Decimal>,
+        max_spread: Option<Decimal>,
+        to: Option<String>,
+    },
+    /// Withdraws liquidity, the LP token is sent as funds
+    WithdrawLiquidity {
+        #[serde(default)]
+        assets: Vec<Asset>,
+    },
+}
+
+#[cw_serde]
+pub enum QueryMsg {
+    Pair {},
+    Simulation {
+        offer_asset: Asset,
+        ask_asset_info: Option<AssetInfo>,
+    },
+    /// Returns the assets received when withdrawing the amount of LP tokens
+    Share {
+        amount: Uint128,
+    },
+}
+
+#[derive(Deserialize)]
+pub struct PairInfo {
+    pub asset_infos: Vec<AssetInfo>,
+}
+
+#[derive(Deserialize)]
+pub struct SimulationResponse {
+    pub return_amount: Uint128,
+}
+
+/// Astroport-style pair contract
+#[cw_serde]
+pub struct Pair(pub Addr);
+
+impl Pair {
+    pub fn swap_msg(
+        &self,
+        denom: DenomType,
+        amount: Uint128,
+        belief_price: Option<Decimal>,
+        max_spread: Option<Decimal>,
+    ) -> StdResult<CosmosMsg<CustomMsgType>> {
+        Ok(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: self.0.to_string(),
+            funds: coins(amount.u128(), denom.clone()),
+            msg: to_json_binary(&ExecuteMsg::Swap {
+                offer_asset: Asset {
+                    info: AssetInfo::NativeToken {
+                        denom,
+                    },
+                    amount,
+                },
+                ask_asset_info: None,
+                belief_price,
+                max_spread,
+                to: None,
+            })?,
+        }))
+    }
+
+    pub fn withdraw_msg(
+        &self,
+        denom: DenomType,
+        amount: Uint128,
+    ) -> StdResult<CosmosMsg<CustomMsgType>> {
+        Ok(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: self.0.to_string(),
+            funds: coins(amount.u128(), denom),
+            msg: to_json_binary(&ExecuteMsg::WithdrawLiquidity {
+                assets: vec![],
+            })?,
+        }))
+    }
+
+    /// simulates the swap, returns the other asset of the pair with the return amount
+    pub fn simulate_swap<Q: CustomQuery>(
+        &self,
+        querier: &QuerierWrapper<Q>,
+        denom: DenomType,
+        amount: Uint128,
+    ) -> StdResult<(DenomType, Uint128)> {
+        let offer = AssetInfo::NativeToken {
+            denom,
+        };
+        let pair: PairInfo = querier.query_wasm_smart(self.0.to_string(), &QueryMsg::Pair {})?;
+        let ask = pair
+            .asset_infos
+            .into_iter()
+            .find(|info| *info != offer)
+            .ok_or_else(|| StdError::generic_err("Pair: ask asset not found"))?;
+
+        let simulation: SimulationResponse = querier.query_wasm_smart(
+            self.0.to_string(),
+            &QueryMsg::Simulation {
+                offer_asset: Asset {
+                    info: offer,
+                    amount,
+                },
+                ask_asset_info: None,
+            },
+        )?;
+
+        Ok((native_denom(ask)?, simulation.return_amount))
+    }
+
+    /// simulates withdrawing the amount of LP tokens
+    pub fn simulate_withdraw<Q: CustomQuery>(
+        &self,
+        querier: &QuerierWrapper<Q>,
+        amount: Uint128,
+    ) -> StdResult<Vec<(DenomType, Uint128)>> {
+        let assets: Vec<Asset> = querier.query_wasm_smart(
+            self.0.to_string(),
+            &QueryMsg::Share {
+                amount,
+            },
+        )?;
+
+        assets.into_iter().map(|asset| Ok((native_denom(asset.info)?, asset.amount))).collect()
+    }
+}
+
+fn native_denom(info: AssetInfo) -> StdResult<DenomType> {
+    match info {
+        AssetInfo::NativeToken {
+            denom,
+        } => Ok(denom),
+        AssetInfo::Token {
+            ..
+        } => Err(StdError::generic_err("Pair: only native assets are supported")),
+    }
+}
@@ -1,8 +1,11 @@
-use cosmwasm_std::{coin, coins, Coin, StdError};
-use cosmwasm_std::{Addr, CosmosMsg, Decimal, StdResult, Uint128};
+use cosmwasm_std::{coin, coins, Coin};
+use cosmwasm_std::{
+    Addr, CosmosMsg, CustomQuery, Decimal, QuerierWrapper, StdError, StdResult, Uint128,
+};
 use eris_chain_shared::chain_trait::ChainInterface;
 use sei_cosmwasm::SeiMsg;
 
+use crate::adapters::{order_book::OrderBook, pair::Pair};
 use crate::types::{CustomMsgType, DenomType, HubChainConfig, StageType, WithdrawType};
 
 pub struct Chain {
@@ -48,28 +51,96 @@ impl ChainInterface<CustomMsgType, DenomType, WithdrawType, StageType, HubChainC
     fn create_withdraw_msg<F>(
         &self,
         _get_chain_config: F,
-        _withdraw_type: WithdrawType,
-        _denom: DenomType,
-        _amount: Uint128,
+        withdraw_type: WithdrawType,
+        denom: DenomType,
+        amount: Uint128,
     ) -> StdResult<Option<CosmosMsg<CustomMsgType>>>
     where
         F: FnOnce() -> StdResult<HubChainConfig>,
     {
-        Err(StdError::generic_err("not supported"))
+        match withdraw_type {
+            WithdrawType::Dex {
+                addr,
+            } => Ok(Some(Pair(addr).withdraw_msg(denom, amount)?)),
+        }
     }
 
     fn create_single_stage_swap_msgs<F>(
         &self,
         _get_chain_config: F,
-        _stage_type: StageType,
-        _denom: DenomType,
-        _amount: Uint128,
-        _belief_price: Option<Decimal>,
-        _max_spread: Decimal,
+        stage_type: StageType,
+        denom: DenomType,
+        amount: Uint128,
+        belief_price: Option<Decimal>,
+        max_spread: Decimal,
     ) -> StdResult<CosmosMsg<CustomMsgType>>
     where
         F: FnOnce() -> StdResult<HubChainConfig>,
     {
-        Err(StdError::generic_err("not supported"))
+        match stage_type {
+            StageType::Dex {
+                addr,
+            } => Pair(addr).swap_msg(denom, amount, belief_price, Some(max_spread)),
+            StageType::OrderBook {
+                contract_addr,
+                price_denom,
+                asset_denom,
+            } => OrderBook {
+                contract_addr,
+                price_denom,
+                asset_denom,
+            }
+            .swap_msg(denom, amount, belief_price, max_spread),
+        }
+    }
+
+    fn requires_belief_price(&self, stage_type: &StageType, denom: &DenomType) -> bool {
+        // buying on the order book needs the max price to compute the quantity
+        match stage_type {
+            StageType::OrderBook {
+                price_denom,
+                ..
+            } => price_denom == denom,
+            _ => false,
+        }
+    }
+
+    fn simulate_swap<F, Q: CustomQuery>(
+        &self,
+        querier: &QuerierWrapper<Q>,
+        _get_chain_config: F,
+        stage_type: StageType,
+        denom: DenomType,
+        amount: Uint128,
+    ) -> StdResult<(DenomType, Uint128)>
+    where
+        F: FnOnce() -> StdResult<HubChainConfig>,
+    {
+        match stage_type {
+            StageType::Dex {
+                addr,
+            } => Pair(addr).simulate_swap(querier, denom, amount),
+            StageType::OrderBook {
+                ..
+            } => Err(StdError::generic_err("swap simulation not supported for order books")),
+        }
+    }
+
+    fn simulate_withdraw<F, Q: CustomQuery>(
+        &self,
+        querier: &QuerierWrapper<Q>,
+        _get_chain_config: F,
+        withdraw_type: WithdrawType,
+        _denom: DenomType,
+        amount: Uint128,
+    ) -> StdResult<Vec<(DenomType, Uint128)>>
+    where
+        F: FnOnce() -> StdResult<HubChainConfig>,
+    {
+        match withdraw_type {
+            WithdrawType::Dex {
+                addr,
+            } => Pair(addr).simulate_withdraw(querier, amount),
+        }
     }
 }
@@ -1,4 +1,4 @@
-
+pub mod adapters;
 pub mod chain;
-pub mod types;
 pub mod test;
+pub mod types;
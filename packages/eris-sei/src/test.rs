@@ -19,3 +19,191 @@ impl
         HubChainConfigInput {}
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use cosmwasm_std::{
+        coins, to_json_binary, Addr, CosmosMsg, Decimal, StdError, Uint128, WasmMsg,
+    };
+    use eris_chain_shared::chain_trait::ChainInterface;
+    use sei_cosmwasm::{Order, OrderType, PositionDirection, SeiMsg};
+
+    use crate::adapters::pair::{Asset, AssetInfo, ExecuteMsg};
+    use crate::chain::Chain;
+    use crate::types::{HubChainConfig, StageType, WithdrawType};
+
+    fn chain() -> Chain {
+        Chain {
+            contract: Addr::unchecked("hub"),
+        }
+    }
+
+    fn market_order(
+        price: Decimal,
+        quantity: Decimal,
+        position_direction: PositionDirection,
+    ) -> Order {
+        Order {
+            price,
+            quantity,
+            price_denom: "usdc".to_string(),
+            asset_denom: "usei".to_string(),
+            order_type: OrderType::Market,
+            position_direction,
+            data: "".to_string(),
+            status_description: "".to_string(),
+            nonce: 0,
+        }
+    }
+
+    #[test]
+    fn harvest_dex_swap() {
+        let msg = chain()
+            .create_single_stage_swap_msgs(
+                || Ok(HubChainConfig {}),
+                StageType::dex("pair"),
+                "uatom".to_string(),
+                Uint128::new(123),
+                Some(Decimal::from_str("2.3").unwrap()),
+                Decimal::percent(10),
+            )
+            .unwrap();
+
+        assert_eq!(
+            msg,
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: "pair".to_string(),
+                funds: coins(123, "uatom"),
+                msg: to_json_binary(&ExecuteMsg::Swap {
+                    offer_asset: Asset {
+                        info: AssetInfo::NativeToken {
+                            denom: "uatom".to_string(),
+                        },
+                        amount: Uint128::new(123),
+                    },
+                    ask_asset_info: None,
+                    belief_price: Some(Decimal::from_str("2.3").unwrap()),
+                    max_spread: Some(Decimal::percent(10)),
+                    to: None,
+                })
+                .unwrap(),
+            })
+        );
+    }
+
+    #[test]
+    fn harvest_order_book_sell_asset() {
+        // 0.5 usei per usdc -> min price of 2 * 0.9 usdc per usei
+        let msg = chain()
+            .create_single_stage_swap_msgs(
+                || Ok(HubChainConfig {}),
+                StageType::order_book("dex", "usdc", "usei"),
+                "usei".to_string(),
+                Uint128::new(100),
+                Some(Decimal::percent(50)),
+                Decimal::percent(10),
+            )
+            .unwrap();
+
+        assert_eq!(
+            msg,
+            CosmosMsg::Custom(SeiMsg::PlaceOrders {
+                orders: vec![market_order(
+                    Decimal::from_str("1.8").unwrap(),
+                    Decimal::from_str("100").unwrap(),
+                    PositionDirection::Short
+                )],
+                funds: coins(100, "usei"),
+                contract_address: Addr::unchecked("dex"),
+            })
+        );
+    }
+
+    #[test]
+    fn harvest_order_book_buy_asset() {
+        // 2 usdc per usei -> max price of 2 * 1.1 usdc per usei
+        let msg = chain()
+            .create_single_stage_swap_msgs(
+                || Ok(HubChainConfig {}),
+                StageType::order_book("dex", "usdc", "usei"),
+                "usdc".to_string(),
+                Uint128::new(220),
+                Some(Decimal::from_str("2").unwrap()),
+                Decimal::percent(10),
+            )
+            .unwrap();
+
+        assert_eq!(
+            msg,
+            CosmosMsg::Custom(SeiMsg::PlaceOrders {
+                orders: vec![market_order(
+                    Decimal::from_str("2.2").unwrap(),
+                    Decimal::from_str("100").unwrap(),
+                    PositionDirection::Long
+                )],
+                funds: coins(220, "usdc"),
+                contract_address: Addr::unchecked("dex"),
+            })
+        );
+
+        let err = chain()
+            .create_single_stage_swap_msgs(
+                || Ok(HubChainConfig {}),
+                StageType::order_book("dex", "usdc", "usei"),
+                "usdc".to_string(),
+                Uint128::new(220),
+                None,
+                Decimal::percent(10),
+            )
+            .unwrap_err();
+        assert_eq!(err, StdError::generic_err("belief_price required for order book"));
+
+        let err = chain()
+            .create_single_stage_swap_msgs(
+                || Ok(HubChainConfig {}),
+                StageType::order_book("dex", "usdc", "usei"),
+                "uatom".to_string(),
+                Uint128::new(220),
+                None,
+                Decimal::percent(10),
+            )
+            .unwrap_err();
+        assert_eq!(err, StdError::generic_err("denom uatom not part of the market"));
+    }
+
+    #[test]
+    fn order_book_buy_requires_belief_price() {
+        let stage = StageType::order_book("dex", "usdc", "usei");
+
+        // the preset of a buy may contain the belief price, a sell uses the price feed or no limit
+        assert!(chain().requires_belief_price(&stage, &"usdc".to_string()));
+        assert!(!chain().requires_belief_price(&stage, &"usei".to_string()));
+        assert!(!chain().requires_belief_price(&StageType::dex("pair"), &"usdc".to_string()));
+    }
+
+    #[test]
+    fn harvest_dex_withdraw() {
+        let msg = chain()
+            .create_withdraw_msg(
+                || Ok(HubChainConfig {}),
+                WithdrawType::dex("pair"),
+                "factory/pair/lp".to_string(),
+                Uint128::new(1000),
+            )
+            .unwrap();
+
+        assert_eq!(
+            msg,
+            Some(CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: "pair".to_string(),
+                funds: coins(1000, "factory/pair/lp"),
+                msg: to_json_binary(&ExecuteMsg::WithdrawLiquidity {
+                    assets: vec![],
+                })
+                .unwrap(),
+            }))
+        );
+    }
+}
@@ -1,13 +1,53 @@
 use cosmwasm_schema::cw_serde;
-use cosmwasm_std::{Api, Coin, StdResult};
+use cosmwasm_std::{Addr, Api, Coin, StdResult};
 use eris_chain_shared::chain_trait::Validateable;
 use sei_cosmwasm::{SeiMsg, SeiQueryWrapper};
 
 #[cw_serde]
-pub enum WithdrawType {}
+pub enum WithdrawType {
+    /// withdraws liquidity from an astroport-style pair
+    Dex {
+        addr: Addr,
+    },
+}
+
+impl WithdrawType {
+    pub fn dex(addr: &str) -> Self {
+        Self::Dex {
+            addr: Addr::unchecked(addr),
+        }
+    }
+}
 
 #[cw_serde]
-pub enum StageType {}
+pub enum StageType {
+    /// swaps via an astroport-style pair
+    Dex {
+        addr: Addr,
+    },
+    /// places a market order on the native order book of the dex contract
+    OrderBook {
+        contract_addr: Addr,
+        price_denom: String,
+        asset_denom: String,
+    },
+}
+
+impl StageType {
+    pub fn dex(addr: &str) -> Self {
+        Self::Dex {
+            addr: Addr::unchecked(addr),
+        }
+    }
+
+    pub fn order_book(contract_addr: &str, price_denom: &str, asset_denom: &str) -> Self {
+        Self::OrderBook {
+            contract_addr: Addr::unchecked(contract_addr),
+            price_denom: price_denom.to_string(),
+            asset_denom: asset_denom.to_string(),
+        }
+    }
+}
 
 pub type DenomType = String;
 pub type CustomMsgType = SeiMsg;
@@ -1,6 +1,10 @@
 use cosmwasm_schema::cw_serde;
-use cosmwasm_std::{coins, to_json_binary, Addr, CosmosMsg, Decimal, StdResult, Uint128, WasmMsg};
+use cosmwasm_std::{
+    coins, to_json_binary, Addr, CosmosMsg, CustomQuery, Decimal, QuerierWrapper, StdError,
+    StdResult, Uint128, WasmMsg,
+};
 use cw20::{Cw20ExecuteMsg, Cw20ReceiveMsg};
+use serde::Deserialize;
 
 use crate::types::{CustomMsgType, DenomType};
 
@@ -47,6 +51,28 @@ pub enum Cw20HookMsg {
     WithdrawLiquidity {},
 }
 
+#[cw_serde]
+pub enum QueryMsg {
+    Pair {},
+    Simulation {
+        offer_asset: Asset,
+    },
+    /// Returns the assets received when withdrawing the amount of LP tokens
+    Share {
+        amount: Uint128,
+    },
+}
+
+#[derive(Deserialize)]
+pub struct PairInfo {
+    pub asset_infos: Vec<AssetInfo>,
+}
+
+#[derive(Deserialize)]
+pub struct SimulationResponse {
+    pub return_amount: Uint128,
+}
+
 #[cw_serde]
 pub struct WhiteWhalePair(pub Addr);
 
@@ -120,4 +146,348 @@ impl WhiteWhalePair {
             })),
         }
     }
+
+    /// simulates the swap, returns the other asset of the pair with the return amount
+    pub fn simulate_swap<Q: CustomQuery>(
+        &self,
+        querier: &QuerierWrapper<Q>,
+        denom: DenomType,
+        amount: Uint128,
+    ) -> StdResult<(DenomType, Uint128)> {
+        let offer = to_asset_info(denom);
+        let pair: PairInfo = querier.query_wasm_smart(self.0.to_string(), &QueryMsg::Pair {})?;
+        let ask = pair
+            .asset_infos
+            .into_iter()
+            .find(|info| *info != offer)
+            .ok_or_else(|| StdError::generic_err("WhiteWhalePair: ask asset not found"))?;
+
+        let simulation: SimulationResponse = querier.query_wasm_smart(
+            self.0.to_string(),
+            &QueryMsg::Simulation {
+                offer_asset: Asset {
+                    info: offer,
+                    amount,
+                },
+            },
+        )?;
+
+        Ok((from_asset_info(ask), simulation.return_amount))
+    }
+
+    /// simulates withdrawing the amount of LP tokens
+    pub fn simulate_withdraw<Q: CustomQuery>(
+        &self,
+        querier: &QuerierWrapper<Q>,
+        amount: Uint128,
+    ) -> StdResult<Vec<(DenomType, Uint128)>> {
+        let assets: Vec<Asset> = querier.query_wasm_smart(
+            self.0.to_string(),
+            &QueryMsg::Share {
+                amount,
+            },
+        )?;
+
+        Ok(assets.into_iter().map(|asset| (from_asset_info(asset.info), asset.amount)).collect())
+    }
+}
+
+#[cw_serde]
+pub enum SwapOperation {
+    TerraSwap {
+        offer_asset_info: AssetInfo,
+        ask_asset_info: AssetInfo,
+    },
+}
+
+#[cw_serde]
+pub enum RouterExecuteMsg {
+    /// Executes the swap operations, checking the minimum received after the last operation
+    ExecuteSwapOperations {
+        operations: Vec<SwapOperation>,
+        minimum_receive: Option<Uint128>,
+        to: Option<String>,
+        max_spread: Option<Decimal>,
+    },
+}
+
+#[cw_serde]
+pub enum RouterQueryMsg {
+    SimulateSwapOperations {
+        offer_amount: Uint128,
+        operations: Vec<SwapOperation>,
+    },
+}
+
+#[cw_serde]
+pub struct SimulateSwapOperationsResponse {
+    pub amount: Uint128,
+}
+
+#[cw_serde]
+pub enum RouterCw20HookMsg {
+    ExecuteSwapOperations {
+        operations: Vec<SwapOperation>,
+        minimum_receive: Option<Uint128>,
+        to: Option<String>,
+        max_spread: Option<Decimal>,
+    },
+}
+
+/// Router swapping through multiple pairs
+#[cw_serde]
+pub struct WhiteWhaleRouter(pub Addr);
+
+impl WhiteWhaleRouter {
+    /// swaps the denom along the path, each entry of the path is the asset received by a hop
+    pub fn swap_msg(
+        &self,
+        denom: DenomType,
+        path: Vec<DenomType>,
+        amount: Uint128,
+        minimum_receive: Option<Uint128>,
+        max_spread: Option<Decimal>,
+    ) -> StdResult<CosmosMsg<CustomMsgType>> {
+        let operations = to_swap_operations(denom.clone(), path);
+
+        match denom {
+            astroport::asset::AssetInfo::Token {
+                contract_addr,
+            } => Ok(CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: contract_addr.to_string(),
+                funds: vec![],
+                msg: to_json_binary(&Cw20ExecuteMsg::Send {
+                    contract: self.0.to_string(),
+                    amount,
+                    msg: to_json_binary(&RouterCw20HookMsg::ExecuteSwapOperations {
+                        operations,
+                        minimum_receive,
+                        to: None,
+                        max_spread,
+                    })?,
+                })?,
+            })),
+            astroport::asset::AssetInfo::NativeToken {
+                denom,
+            } => Ok(CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: self.0.to_string(),
+                funds: coins(amount.u128(), denom),
+                msg: to_json_binary(&RouterExecuteMsg::ExecuteSwapOperations {
+                    operations,
+                    minimum_receive,
+                    to: None,
+                    max_spread,
+                })?,
+            })),
+        }
+    }
+
+    /// simulates the swap along the path, returns the last asset of the path with the return amount
+    pub fn simulate_swap<Q: CustomQuery>(
+        &self,
+        querier: &QuerierWrapper<Q>,
+        denom: DenomType,
+        path: Vec<DenomType>,
+        amount: Uint128,
+    ) -> StdResult<(DenomType, Uint128)> {
+        let ask = path
+            .last()
+            .cloned()
+            .ok_or_else(|| StdError::generic_err("WhiteWhaleRouter: path is empty"))?;
+
+        let simulation: SimulateSwapOperationsResponse = querier.query_wasm_smart(
+            self.0.to_string(),
+            &RouterQueryMsg::SimulateSwapOperations {
+                offer_amount: amount,
+                operations: to_swap_operations(denom, path),
+            },
+        )?;
+
+        Ok((ask, simulation.amount))
+    }
+}
+
+fn to_asset_info(denom: DenomType) -> AssetInfo {
+    match denom {
+        astroport::asset::AssetInfo::Token {
+            contract_addr,
+        } => AssetInfo::Token {
+            contract_addr: contract_addr.to_string(),
+        },
+        astroport::asset::AssetInfo::NativeToken {
+            denom,
+        } => AssetInfo::NativeToken {
+            denom,
+        },
+    }
+}
+
+fn from_asset_info(info: AssetInfo) -> DenomType {
+    match info {
+        AssetInfo::Token {
+            contract_addr,
+        } => astroport::asset::AssetInfo::Token {
+            contract_addr: Addr::unchecked(contract_addr),
+        },
+        AssetInfo::NativeToken {
+            denom,
+        } => astroport::asset::AssetInfo::NativeToken {
+            denom,
+        },
+    }
+}
+
+/// each entry of the path is the asset received by a hop
+fn to_swap_operations(denom: DenomType, path: Vec<DenomType>) -> Vec<SwapOperation> {
+    let mut offer = denom;
+    path.into_iter()
+        .map(|ask| {
+            let operation = SwapOperation::TerraSwap {
+                offer_asset_info: to_asset_info(offer.clone()),
+                ask_asset_info: to_asset_info(ask.clone()),
+            };
+            offer = ask;
+            operation
+        })
+        .collect()
+}
+
+#[test]
+pub fn test_route_swap_msg() {
+    use crate::chain::Chain;
+    use crate::types::{HubChainConfig, StageType};
+    use eris_chain_shared::chain_trait::ChainInterface;
+    use std::str::FromStr;
+
+    let usdc = astroport::asset::AssetInfo::NativeToken {
+        denom: "ibc/usdc".to_string(),
+    };
+    let luna = astroport::asset::AssetInfo::NativeToken {
+        denom: "uluna".to_string(),
+    };
+    let token = astroport::asset::AssetInfo::Token {
+        contract_addr: Addr::unchecked("token"),
+    };
+    let operations = vec![
+        SwapOperation::TerraSwap {
+            offer_asset_info: AssetInfo::NativeToken {
+                denom: "ibc/usdc".to_string(),
+            },
+            ask_asset_info: AssetInfo::NativeToken {
+                denom: "uluna".to_string(),
+            },
+        },
+        SwapOperation::TerraSwap {
+            offer_asset_info: AssetInfo::NativeToken {
+                denom: "uluna".to_string(),
+            },
+            ask_asset_info: AssetInfo::Token {
+                contract_addr: "token".to_string(),
+            },
+        },
+    ];
+
+    let chain = Chain {
+        contract: Addr::unchecked("hub"),
+    };
+    let route = StageType::Route {
+        router: Addr::unchecked("router"),
+        path: vec![luna, token],
+    };
+
+    // 1000 / (1.5 * 110%) = 606
+    assert_eq!(
+        chain
+            .create_single_stage_swap_msgs(
+                || Ok(HubChainConfig {}),
+                route.clone(),
+                usdc.clone(),
+                Uint128::new(1000),
+                Some(Decimal::from_str("1.5").unwrap()),
+                Decimal::percent(10),
+            )
+            .unwrap(),
+        CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: "router".to_string(),
+            funds: coins(1000, "ibc/usdc"),
+            msg: to_json_binary(&RouterExecuteMsg::ExecuteSwapOperations {
+                operations,
+                minimum_receive: Some(Uint128::new(606)),
+                to: None,
+                max_spread: Some(Decimal::percent(10)),
+            })
+            .unwrap(),
+        })
+    );
+
+    // without a belief price every hop could be sandwiched
+    assert_eq!(
+        chain
+            .create_single_stage_swap_msgs(
+                || Ok(HubChainConfig {}),
+                route.clone(),
+                usdc.clone(),
+                Uint128::new(1000),
+                None,
+                Decimal::percent(10),
+            )
+            .unwrap_err(),
+        StdError::generic_err("belief_price required")
+    );
+    assert!(chain.requires_belief_price(&route, &usdc));
+
+    // cw20 offers are sent to the router with the hook message
+    assert_eq!(
+        WhiteWhaleRouter(Addr::unchecked("router"))
+            .swap_msg(
+                astroport::asset::AssetInfo::Token {
+                    contract_addr: Addr::unchecked("token"),
+                },
+                vec![
+                    astroport::asset::AssetInfo::NativeToken {
+                        denom: "uluna".to_string(),
+                    },
+                    astroport::asset::AssetInfo::NativeToken {
+                        denom: "ibc/usdc".to_string(),
+                    },
+                ],
+                Uint128::new(1000),
+                Some(Uint128::new(900)),
+                None,
+            )
+            .unwrap(),
+        CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: "token".to_string(),
+            funds: vec![],
+            msg: to_json_binary(&Cw20ExecuteMsg::Send {
+                contract: "router".to_string(),
+                amount: Uint128::new(1000),
+                msg: to_json_binary(&RouterCw20HookMsg::ExecuteSwapOperations {
+                    operations: vec![
+                        SwapOperation::TerraSwap {
+                            offer_asset_info: AssetInfo::Token {
+                                contract_addr: "token".to_string(),
+                            },
+                            ask_asset_info: AssetInfo::NativeToken {
+                                denom: "uluna".to_string(),
+                            },
+                        },
+                        SwapOperation::TerraSwap {
+                            offer_asset_info: AssetInfo::NativeToken {
+                                denom: "uluna".to_string(),
+                            },
+                            ask_asset_info: AssetInfo::NativeToken {
+                                denom: "ibc/usdc".to_string(),
+                            },
+                        },
+                    ],
+                    minimum_receive: Some(Uint128::new(900)),
+                    to: None,
+                    max_spread: None,
+                })
+                .unwrap(),
+            })
+            .unwrap(),
+        })
+    );
 }
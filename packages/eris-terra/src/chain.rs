@@ -1,10 +1,14 @@
 use cosmwasm_std::{
-    coins, to_json_binary, Addr, CosmosMsg, Decimal, StdError, StdResult, Uint128, WasmMsg,
+    coins, to_json_binary, Addr, CosmosMsg, CustomQuery, Decimal, QuerierWrapper, StdError,
+    StdResult, Uint128, WasmMsg,
 };
-use eris_chain_shared::chain_trait::ChainInterface;
+use eris_chain_shared::chain_trait::{get_min_return, ChainInterface};
 
 use crate::{
-    adapters::{hub::Hub, whitewhaledex::WhiteWhalePair},
+    adapters::{
+        hub::Hub,
+        whitewhaledex::{WhiteWhalePair, WhiteWhaleRouter},
+    },
     custom_execute_msg::CustomExecuteMsg,
     types::{CustomMsgType, DenomType, HubChainConfig, StageType, WithdrawType},
 };
@@ -72,7 +76,7 @@ impl ChainInterface<CustomMsgType, DenomType, WithdrawType, StageType, HubChainC
 
     fn create_single_stage_swap_msgs<F>(
         &self,
-        _get_chain_config: F,
+        get_chain_config: F,
         stage_type: StageType,
         denom: DenomType,
         amount: Uint128,
@@ -104,6 +108,93 @@ impl ChainInterface<CustomMsgType, DenomType, WithdrawType, StageType, HubChainC
                     msg: to_json_binary(&msg)?,
                 })),
             },
+            StageType::Route {
+                ..
+            } => self.create_route_swap_msgs(
+                get_chain_config,
+                stage_type,
+                denom,
+                amount,
+                belief_price,
+                max_spread,
+            ),
+        }
+    }
+
+    fn requires_belief_price(&self, stage_type: &StageType, _denom: &DenomType) -> bool {
+        matches!(stage_type, StageType::Route { .. })
+    }
+
+    fn create_route_swap_msgs<F>(
+        &self,
+        _get_chain_config: F,
+        stage_type: StageType,
+        denom: DenomType,
+        amount: Uint128,
+        belief_price: Option<Decimal>,
+        max_spread: Decimal,
+    ) -> StdResult<CosmosMsg<CustomMsgType>>
+    where
+        F: FnOnce() -> StdResult<HubChainConfig>,
+    {
+        match stage_type {
+            StageType::Route {
+                router,
+                path,
+            } => {
+                let min_return = get_min_return(amount, belief_price, max_spread)?
+                    .ok_or_else(|| StdError::generic_err("belief_price required"))?;
+
+                WhiteWhaleRouter(router).swap_msg(
+                    denom,
+                    path,
+                    amount,
+                    Some(min_return),
+                    Some(max_spread),
+                )
+            },
+            _ => Err(StdError::generic_err("not a route stage")),
+        }
+    }
+
+    fn simulate_swap<F, Q: CustomQuery>(
+        &self,
+        querier: &QuerierWrapper<Q>,
+        _get_chain_config: F,
+        stage_type: StageType,
+        denom: DenomType,
+        amount: Uint128,
+    ) -> StdResult<(DenomType, Uint128)>
+    where
+        F: FnOnce() -> StdResult<HubChainConfig>,
+    {
+        match stage_type {
+            StageType::Dex {
+                addr,
+            } => WhiteWhalePair(addr).simulate_swap(querier, denom, amount),
+            StageType::Route {
+                router,
+                path,
+            } => WhiteWhaleRouter(router).simulate_swap(querier, denom, path, amount),
+            _ => Err(StdError::generic_err("swap simulation not supported for stage")),
+        }
+    }
+
+    fn simulate_withdraw<F, Q: CustomQuery>(
+        &self,
+        querier: &QuerierWrapper<Q>,
+        _get_chain_config: F,
+        withdraw_type: WithdrawType,
+        _denom: DenomType,
+        amount: Uint128,
+    ) -> StdResult<Vec<(DenomType, Uint128)>>
+    where
+        F: FnOnce() -> StdResult<HubChainConfig>,
+    {
+        match withdraw_type {
+            WithdrawType::Dex {
+                addr,
+            } => WhiteWhalePair(addr).simulate_withdraw(querier, amount),
         }
     }
 }
@@ -33,6 +33,11 @@ pub enum StageType {
         addr: Addr,
         msg: MantaMsg,
     },
+    /// swaps through multiple pairs via the router, the path contains the asset received by each hop
+    Route {
+        router: Addr,
+        path: Vec<AssetInfo>,
+    },
 }
 
 #[cw_serde]
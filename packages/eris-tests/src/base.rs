@@ -672,6 +672,7 @@ impl BaseErisTestPackage {
                 &eris::hub::ExecuteMsg::UpdateConfig {
                     protocol_fee_contract: None,
                     protocol_reward_fee: None,
+                    fee_recipients: None,
                     delegation_strategy,
                     allow_donations: None,
                     vote_operator: None,
@@ -682,6 +683,15 @@ impl BaseErisTestPackage {
                     withdrawals_preset: None,
                     epoch_period: None,
                     unbond_period: None,
+                    instant_unbond_config: None,
+                    remove_instant_unbond_config: None,
+                    remove_slashed_validators: None,
+                    max_unbonding_entries: None,
+                    guardian: None,
+                    remove_guardian: None,
+                    circuit_breaker: None,
+                    referral_share: None,
+                    rebalance_config: None,
                 },
                 &[],
             )
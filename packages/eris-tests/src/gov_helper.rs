@@ -426,6 +426,7 @@ impl EscrowHelper {
             self.base.hub.get_address(),
             &eris::hub::ExecuteMsg::Bond {
                 receiver: None,
+                referrer: None,
             },
             &[coin(amount, denom.into())],
         )
@@ -437,6 +438,7 @@ impl EscrowHelper {
             eris::hub::ExecuteMsg::UpdateConfig {
                 protocol_fee_contract: None,
                 protocol_reward_fee: None,
+                fee_recipients: None,
                 allow_donations: Some(true),
                 delegation_strategy: None,
                 vote_operator: None,
@@ -447,6 +449,15 @@ impl EscrowHelper {
                 withdrawals_preset: None,
                 epoch_period: None,
                 unbond_period: None,
+                instant_unbond_config: None,
+                remove_instant_unbond_config: None,
+                remove_slashed_validators: None,
+                max_unbonding_entries: None,
+                guardian: None,
+                remove_guardian: None,
+                circuit_breaker: None,
+                referral_share: None,
+                rebalance_config: None,
             },
         )
     }
@@ -790,6 +801,8 @@ impl EscrowHelper {
                 disable_lsd: None,
                 remove_lsd: Some("eris".to_string()),
                 force_remove_lsd: None,
+                insert_asset: None,
+                remove_asset: None,
                 fee_config: None,
                 set_whitelist: None,
                 remove_whitelist: None,
@@ -838,6 +851,8 @@ impl EscrowHelper {
                 disable_lsd: None,
                 remove_lsd: None,
                 force_remove_lsd: None,
+                insert_asset: None,
+                remove_asset: None,
                 fee_config: None,
                 set_whitelist: None,
                 remove_whitelist: None,
@@ -110,6 +110,7 @@ fn _vote() -> StdResult<()> {
             eris::hub::ExecuteMsg::UpdateConfig {
                 protocol_fee_contract: None,
                 protocol_reward_fee: None,
+                fee_recipients: None,
                 delegation_strategy: None,
                 allow_donations: None,
                 vote_operator: Some(helper.base.prop_gauges.get_address_string()),
@@ -120,6 +121,15 @@ fn _vote() -> StdResult<()> {
                 withdrawals_preset: None,
                 epoch_period: None,
                 unbond_period: None,
+                instant_unbond_config: None,
+                remove_instant_unbond_config: None,
+                remove_slashed_validators: None,
+                max_unbonding_entries: None,
+                guardian: None,
+                remove_guardian: None,
+                circuit_breaker: None,
+                referral_share: None,
+                rebalance_config: None,
             },
         )
         .unwrap();
@@ -1,8 +1,10 @@
-use cosmwasm_std::{Addr, CosmosMsg, Decimal, StdResult, Uint128};
-use eris_chain_shared::chain_trait::ChainInterface;
+use cosmwasm_std::{
+    Addr, CosmosMsg, CustomQuery, Decimal, QuerierWrapper, StdError, StdResult, Uint128,
+};
+use eris_chain_shared::chain_trait::{get_min_return, ChainInterface};
 
 use crate::{
-    adapters::whitewhaledex::WhiteWhalePair,
+    adapters::whitewhaledex::{WhiteWhalePair, WhiteWhaleRouter},
     denom::{MsgBurn, MsgCreateDenom, MsgMint},
     whitewhale_types::{CustomMsgType, DenomType, HubChainConfig, StageType, WithdrawType},
 };
@@ -70,7 +72,7 @@ impl ChainInterface<CustomMsgType, DenomType, WithdrawType, StageType, HubChainC
 
     fn create_single_stage_swap_msgs<F>(
         &self,
-        _get_chain_config: F,
+        get_chain_config: F,
         stage_type: StageType,
         denom: DenomType,
         amount: Uint128,
@@ -84,6 +86,92 @@ impl ChainInterface<CustomMsgType, DenomType, WithdrawType, StageType, HubChainC
             StageType::Dex {
                 addr,
             } => WhiteWhalePair(addr).swap_msg(denom, amount, belief_price, Some(max_spread)),
+            StageType::Route {
+                ..
+            } => self.create_route_swap_msgs(
+                get_chain_config,
+                stage_type,
+                denom,
+                amount,
+                belief_price,
+                max_spread,
+            ),
+        }
+    }
+
+    fn requires_belief_price(&self, stage_type: &StageType, _denom: &DenomType) -> bool {
+        matches!(stage_type, StageType::Route { .. })
+    }
+
+    fn create_route_swap_msgs<F>(
+        &self,
+        _get_chain_config: F,
+        stage_type: StageType,
+        denom: DenomType,
+        amount: Uint128,
+        belief_price: Option<Decimal>,
+        max_spread: Decimal,
+    ) -> StdResult<CosmosMsg<CustomMsgType>>
+    where
+        F: FnOnce() -> StdResult<HubChainConfig>,
+    {
+        match stage_type {
+            StageType::Route {
+                router,
+                path,
+            } => {
+                let min_return = get_min_return(amount, belief_price, max_spread)?
+                    .ok_or_else(|| StdError::generic_err("belief_price required"))?;
+
+                WhiteWhaleRouter(router).swap_msg(
+                    denom,
+                    path,
+                    amount,
+                    Some(min_return),
+                    Some(max_spread),
+                )
+            },
+            _ => Err(StdError::generic_err("not a route stage")),
+        }
+    }
+
+    fn simulate_swap<F, Q: CustomQuery>(
+        &self,
+        querier: &QuerierWrapper<Q>,
+        _get_chain_config: F,
+        stage_type: StageType,
+        denom: DenomType,
+        amount: Uint128,
+    ) -> StdResult<(DenomType, Uint128)>
+    where
+        F: FnOnce() -> StdResult<HubChainConfig>,
+    {
+        match stage_type {
+            StageType::Dex {
+                addr,
+            } => WhiteWhalePair(addr).simulate_swap(querier, denom, amount),
+            StageType::Route {
+                router,
+                path,
+            } => WhiteWhaleRouter(router).simulate_swap(querier, denom, path, amount),
+        }
+    }
+
+    fn simulate_withdraw<F, Q: CustomQuery>(
+        &self,
+        querier: &QuerierWrapper<Q>,
+        _get_chain_config: F,
+        withdraw_type: WithdrawType,
+        _denom: DenomType,
+        amount: Uint128,
+    ) -> StdResult<Vec<(DenomType, Uint128)>>
+    where
+        F: FnOnce() -> StdResult<HubChainConfig>,
+    {
+        match withdraw_type {
+            WithdrawType::Dex {
+                addr,
+            } => WhiteWhalePair(addr).simulate_withdraw(querier, amount),
         }
     }
 }
@@ -23,6 +23,11 @@ pub enum StageType {
     Dex {
         addr: Addr,
     },
+    /// swaps through multiple pairs via the router, the path contains the asset received by each hop
+    Route {
+        router: Addr,
+        path: Vec<AssetInfo>,
+    },
 }
 
 impl StageType {
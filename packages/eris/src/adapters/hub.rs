@@ -20,6 +20,7 @@ impl Hub {
             contract_addr: self.0.to_string(),
             msg: to_json_binary(&ExecuteMsg::Bond {
                 receiver,
+                referrer: None,
             })?,
             funds: vec![coin(amount, denom)],
         }))
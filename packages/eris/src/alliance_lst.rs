@@ -4,12 +4,13 @@ use eris_chain_adapter::types::{DenomType, WithdrawType};
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
+use crate::circuit_breaker::{CircuitBreakerConfig, PausableAction, PauseState};
 #[allow(unused_imports)]
 use crate::hub::{
     Batch, CallbackMsg, ClaimType, DelegationStrategy, DelegationsResponse, ExchangeRatesResponse,
-    FeeConfig, PendingBatch, SingleSwapConfig, StateResponse, UnbondRequestsByBatchResponseItem,
-    UnbondRequestsByUserResponseItem, UnbondRequestsByUserResponseItemDetails,
-    WantedDelegationsResponse,
+    FeeConfig, PendingBatch, SingleSwapConfig, SlashingEventsResponse, StateResponse,
+    UnbondRequestsByBatchResponseItem, UnbondRequestsByUserResponseItem,
+    UnbondRequestsByUserResponseItemDetails, WantedDelegationsResponse,
 };
 
 #[derive(Serialize, Deserialize, Clone, Default, Debug, PartialEq, Eq, JsonSchema)]
@@ -77,26 +78,40 @@ pub enum ExecuteMsg {
         validators: Option<Vec<String>>,
         withdrawals: Option<Vec<(WithdrawType, DenomType)>>,
         stages: Option<Vec<Vec<SingleSwapConfig>>>,
+        asset: Option<String>,
     },
 
     TuneDelegations {},
     /// Use redelegations to balance the amounts of Token delegated to validators
     Rebalance {
         min_redelegation: Option<Uint128>,
+        asset: Option<String>,
     },
     /// Update Token amounts in unbonding batches to reflect any slashing or rounding errors
-    Reconcile {},
+    Reconcile {
+        asset: Option<String>,
+    },
 
-    /// Reconciles if a slashing happened
+    /// Applies the provided delegations as a slashing. Only needed on chains where the alliance
+    /// query is not available, otherwise slashings are detected during harvest and submit batch.
     CheckSlashing {
         /// only when the current state equals the send amount, the slash will be applied
         state_total_utoken_bonded: Uint128,
         /// current delegations
         delegations: Vec<(String, Uint128)>,
+        asset: Option<String>,
     },
     /// Submit the current pending batch of unbonding requests to be unbonded
     SubmitBatch {
         undelegations: Option<Vec<Undelegation>>,
+        asset: Option<String>,
+    },
+    /// Adds an alliance asset with its own stake token. Assets are identified by their utoken in
+    /// all messages and queries, `None` refers to the asset the contract was instantiated with.
+    AddAsset {
+        utoken: String,
+        /// Sub denom of the stake token
+        denom: String,
     },
     /// Callbacks; can only be invoked by the contract itself
     Callback(CallbackMsg),
@@ -126,6 +141,19 @@ pub enum ExecuteMsg {
         unbond_period: Option<u64>,
         /// Specifies a validators proxy contract, so that validators are not locally stored
         validator_proxy: Option<String>,
+        /// Account that can pause bond, unbond and harvest
+        guardian: Option<String>,
+        /// Removes the guardian, only the owner can pause afterwards
+        remove_guardian: Option<bool>,
+        /// Update the exchange rate and volume limits, volumes are limited for every asset
+        circuit_breaker: Option<CircuitBreakerConfig>,
+    },
+
+    /// Pauses or resumes an action for all assets. Pausing can be done by the guardian or the
+    /// owner, resuming only by the owner.
+    SetPaused {
+        action: PausableAction,
+        paused: bool,
     },
 
     /// Submit an unbonding request to the current unbonding queue; automatically invokes `unbond`
@@ -148,19 +176,29 @@ pub enum QueryMsg {
     Config {},
     /// The contract's current state. Response: `StateResponse`
     #[returns(StateResponse)]
-    State {},
+    State {
+        asset: Option<String>,
+    },
+    /// All alliance assets, starting with the primary asset. Response: `Vec<AllianceStakeToken>`
+    #[returns(Vec<AllianceStakeToken>)]
+    Assets {},
     /// The contract's current delegation distribution goal. Response: `WantedDelegationsResponse`
     #[returns(WantedDelegationsResponse)]
-    WantedDelegations {},
+    WantedDelegations {
+        asset: Option<String>,
+    },
     /// The contract's delegation distribution goal based on period. Response: `WantedDelegationsResponse`
     #[returns(WantedDelegationsResponse)]
     SimulateWantedDelegations {
         /// by default uses the next period to look into the future.
         period: Option<u64>,
+        asset: Option<String>,
     },
     /// The current batch on unbonding requests pending submission. Response: `PendingBatch`
     #[returns(PendingBatch)]
-    PendingBatch {},
+    PendingBatch {
+        asset: Option<String>,
+    },
     /// Query an individual batch that has previously been submitted for unbonding but have not yet
     /// fully withdrawn. Response: `Batch`
     #[returns(Batch)]
@@ -199,13 +237,25 @@ pub enum QueryMsg {
         // start after the provided timestamp in s
         start_after: Option<u64>,
         limit: Option<u32>,
+        asset: Option<String>,
     },
 
     #[returns(DelegationsResponse)]
-    Delegations {},
+    Delegations {
+        asset: Option<String>,
+    },
 
     #[returns(Vec<Undelegation>)]
-    SimulateUndelegations {},
+    SimulateUndelegations {
+        asset: Option<String>,
+    },
+
+    /// Detected slashings, newest first. Response: `SlashingEventsResponse`
+    #[returns(SlashingEventsResponse)]
+    SlashingEvents {
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    },
 }
 
 #[cw_serde]
@@ -248,4 +298,11 @@ pub struct ConfigResponse {
     pub delegation_strategy: DelegationStrategy,
 
     pub validator_proxy: String,
+
+    /// Account that can pause bond, unbond and harvest
+    pub guardian: Option<String>,
+    /// Exchange rate and volume limits
+    pub circuit_breaker: Option<CircuitBreakerConfig>,
+    /// Actions that are currently paused
+    pub paused: PauseState,
 }
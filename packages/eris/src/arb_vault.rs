@@ -6,6 +6,9 @@ use cosmwasm_std::{
 };
 use eris_chain_adapter::types::CustomMsgType;
 
+use crate::helpers::json::JsonValue;
+use crate::hub::MAX_PRICE_AGE;
+
 // /// The default swap slippage
 // pub const DEFAULT_SLIPPAGE: &str = "0.005";
 // /// The maximum allowed swap slippage
@@ -61,6 +64,50 @@ impl LsdConfig<String> {
                     addr: api.addr_validate(&addr)?,
                     denom,
                 },
+                LsdType::Generic {
+                    addr,
+                    denom,
+                    spec,
+                } => {
+                    spec.validate()?;
+
+                    LsdType::Generic {
+                        addr: api.addr_validate(&addr)?,
+                        denom,
+                        spec,
+                    }
+                },
+                LsdType::Ibc {
+                    denom,
+                    channel_id,
+                    receiver,
+                    memo,
+                    timeout_s,
+                    exchange_rate,
+                } => {
+                    if exchange_rate.is_zero() {
+                        return Err(StdError::generic_err("exchange_rate cannot be zero"));
+                    }
+
+                    // without the callback the acknowledgement of the transfer is never received
+                    let callback = JsonValue::parse(memo.replace("{amount}", "0").as_bytes())
+                        .ok()
+                        .and_then(|memo| memo.at(&["ibc_callback".to_string()]).cloned());
+                    if callback != Some(JsonValue::String("{wallet}".to_string())) {
+                        return Err(StdError::generic_err(
+                            "memo needs to set ibc_callback to {wallet}",
+                        ));
+                    }
+
+                    LsdType::Ibc {
+                        denom,
+                        channel_id,
+                        receiver,
+                        memo,
+                        timeout_s,
+                        exchange_rate,
+                    }
+                },
             },
         })
     }
@@ -76,6 +123,114 @@ pub enum LsdType<T> {
         addr: T,
         denom: String,
     },
+    Generic {
+        addr: T,
+        denom: String,
+        spec: GenericLsdSpec,
+    },
+    /// LSD hosted on a remote chain, which is unbonded by sending it to a redemption contract / hook
+    /// via an IBC transfer. The utoken is expected to return to the vault via an IBC hook executing
+    /// CompleteIbcRedemptions, otherwise the redemption is settled by SettleIbcRedemptions.
+    Ibc {
+        // IBC denom of the LSD token on this chain
+        denom: String,
+        // transfer channel towards the chain of the LSD hub
+        channel_id: String,
+        // receiver of the LSD token on the remote chain
+        receiver: String,
+        // memo template of the transfer, "{wallet}" and "{amount}" are replaced.
+        // it needs to register the vault for the ibc callbacks: {"ibc_callback":"{wallet}",...}
+        memo: String,
+        // timeout of the transfer in seconds
+        timeout_s: u64,
+        // exchange rate LSD -> utoken on the remote chain, updated by the owner
+        exchange_rate: Decimal,
+    },
+}
+
+/// Describes how to interact with a liquid staking hub without a dedicated adapter.
+/// Messages and queries are JSON templates, in which "{wallet}" is replaced by the vault address
+/// and "{amount}" by the amount of LSD tokens. A paginated unbonding requests query additionally
+/// replaces "{start_after}" by the JSON cursor of the last request (null for the first page) and
+/// "{limit}" by the page size.
+#[cw_serde]
+pub struct GenericLsdSpec {
+    // executed on the hub with the LSD tokens attached, e.g. {"queue_unbond":{}}
+    pub unbond_msg: String,
+    // executed on the hub to claim all unbonded tokens, e.g. {"withdraw_unbonded":{}}
+    pub withdraw_msg: String,
+    // query returning the exchange rate, e.g. {"state":{}}
+    pub exchange_rate_query: String,
+    // path to the exchange rate (LSD -> utoken) in the response, e.g. ["exchange_rate"]
+    pub exchange_rate_path: Vec<String>,
+    // query returning the unbonding requests of the vault
+    pub unbonding_requests_query: String,
+    // path to the list of requests in the response, empty if the response is the list
+    pub unbonding_requests_path: Vec<String>,
+    // path to the utoken amount of a single request
+    pub amount_path: Vec<String>,
+    // path to the boolean marking a single request as withdrawable
+    pub withdrawable_path: Vec<String>,
+    // pages through the unbonding requests, if not set all requests are returned by one query
+    pub unbonding_requests_pagination: Option<GenericLsdPagination>,
+}
+
+#[cw_serde]
+pub struct GenericLsdPagination {
+    // max amount of requests returned by a single query
+    pub limit: u32,
+    // path to the cursor of a single request, e.g. ["id"]
+    pub cursor_path: Vec<String>,
+}
+
+impl GenericLsdSpec {
+    /// Replaces the placeholders of a template, page is the JSON cursor and the limit of a page.
+    pub fn render(
+        template: &str,
+        wallet: &str,
+        amount: Uint128,
+        page: Option<(&str, u32)>,
+    ) -> String {
+        let rendered =
+            template.replace("{wallet}", wallet).replace("{amount}", &amount.to_string());
+
+        match page {
+            Some((start_after, limit)) => rendered
+                .replace("{start_after}", start_after)
+                .replace("{limit}", &limit.to_string()),
+            None => rendered,
+        }
+    }
+
+    /// Checks that every template renders to a JSON object, so that a bad template is rejected
+    /// when it is configured instead of when it is used.
+    pub fn validate(&self) -> StdResult<()> {
+        let page = self.unbonding_requests_pagination.as_ref().map(|page| ("null", page.limit));
+
+        for (name, template, page) in [
+            ("unbond_msg", &self.unbond_msg, None),
+            ("withdraw_msg", &self.withdraw_msg, None),
+            ("exchange_rate_query", &self.exchange_rate_query, None),
+            ("unbonding_requests_query", &self.unbonding_requests_query, page),
+        ] {
+            let rendered = Self::render(template, "wallet", Uint128::zero(), page);
+            match JsonValue::parse(rendered.as_bytes()) {
+                Ok(JsonValue::Object(_)) => {},
+                _ => return Err(StdError::generic_err(format!("{} is not a JSON object", name))),
+            }
+        }
+
+        if let Some(pagination) = &self.unbonding_requests_pagination {
+            if pagination.limit == 0 {
+                return Err(StdError::generic_err("pagination limit cannot be zero"));
+            }
+            if pagination.cursor_path.is_empty() {
+                return Err(StdError::generic_err("pagination cursor path cannot be empty"));
+            }
+        }
+
+        Ok(())
+    }
 }
 
 impl LsdType<String> {
@@ -89,6 +244,16 @@ impl LsdType<String> {
                 addr,
                 ..
             } => format!("backbone_{0}", addr),
+            LsdType::Generic {
+                addr,
+                denom,
+                ..
+            } => format!("generic_{0}_{1}", addr, denom),
+            LsdType::Ibc {
+                channel_id,
+                receiver,
+                ..
+            } => format!("ibc_{0}_{1}", channel_id, receiver),
         }
     }
 }
@@ -98,6 +263,79 @@ pub enum UtilizationMethod {
     Steps(Vec<(Decimal, Decimal)>),
 }
 
+/// Value of one unit of a base asset in the primary utoken
+#[cw_serde]
+pub enum AssetPrice<T> {
+    // fixed conversion ratio
+    Ratio(Decimal),
+    // queried from an oracle contract, the query template supports "{utoken}"
+    Oracle {
+        contract: T,
+        query: String,
+        // path to the price in the query response, e.g. ["price"]
+        path: Vec<String>,
+        // path to the update time of the price in seconds in the query response, e.g. ["updated_at"]
+        updated_at_path: Vec<String>,
+        // maximum age of the price in seconds, older prices are rejected
+        max_age: u64,
+    },
+}
+
+/// Additional base asset accepted by the vault, next to the primary utoken
+#[cw_serde]
+pub struct BaseAssetConfig<T> {
+    pub utoken: String,
+    pub price: AssetPrice<T>,
+    pub utilization_method: UtilizationMethod,
+    pub lsds: Vec<LsdConfig<T>>,
+}
+
+impl BaseAssetConfig<String> {
+    pub fn validate(self, api: &dyn Api) -> StdResult<BaseAssetConfig<Addr>> {
+        Ok(BaseAssetConfig {
+            utoken: self.utoken,
+            price: match self.price {
+                AssetPrice::Ratio(ratio) => {
+                    if ratio.is_zero() {
+                        return Err(StdError::generic_err("price ratio cannot be zero"));
+                    }
+                    AssetPrice::Ratio(ratio)
+                },
+                AssetPrice::Oracle {
+                    contract,
+                    query,
+                    path,
+                    updated_at_path,
+                    max_age,
+                } => {
+                    if max_age == 0 {
+                        return Err(StdError::generic_err("max_age can't be zero"));
+                    }
+                    if max_age > MAX_PRICE_AGE {
+                        return Err(StdError::generic_err(format!(
+                            "max_age can't be above {}",
+                            MAX_PRICE_AGE
+                        )));
+                    }
+                    AssetPrice::Oracle {
+                        contract: api.addr_validate(&contract)?,
+                        query,
+                        path,
+                        updated_at_path,
+                        max_age,
+                    }
+                },
+            },
+            utilization_method: self.utilization_method,
+            lsds: self
+                .lsds
+                .into_iter()
+                .map(|lsd| lsd.validate(api))
+                .collect::<StdResult<Vec<LsdConfig<Addr>>>>()?,
+        })
+    }
+}
+
 #[cw_serde]
 pub struct ExecuteSubMsg {
     pub contract_addr: Option<String>,
@@ -119,21 +357,18 @@ pub enum ExecuteMsg {
         receiver: Option<String>,
     },
 
-    // User action: Withdraw all unbonded funds
+    // User action: Withdraw all unbond items filled by the queue, with the pool fee decaying by the time waited
     WithdrawUnbonded {},
     // User action: Withdraw any unbond item immediate if possible
     WithdrawImmediate {
         id: u64,
     },
 
-    // /// Swap is allowed between the [TOKEN] and the arb[TOKEN]
-    // Swap {
-    //     offer_asset: Asset,
-    //     ask_asset_info: Option<AssetInfo>,
-    //     belief_price: Option<Decimal>,
-    //     max_spread: Option<Decimal>,
-    //     to: Option<String>,
-    // },
+    // User action: Swap a supported LSD token for the utoken at a discount based on the utilization
+    Swap {
+        min_return: Option<Uint128>,
+        receiver: Option<String>,
+    },
 
     // Admin User: Update config
     UpdateConfig {
@@ -149,6 +384,11 @@ pub enum ExecuteMsg {
         // force removes a LSD (DANGER can be executed even when funds are unbonding / withdrawable)
         force_remove_lsd: Option<String>,
 
+        // inserts or replaces an additional base asset
+        insert_asset: Option<BaseAssetConfig<String>>,
+        // removes an additional base asset (only when it does not hold any funds)
+        remove_asset: Option<String>,
+
         fee_config: Option<FeeConfig<String>>,
         set_whitelist: Option<Vec<String>>,
         // opens up executions so anyone can execute.
@@ -163,6 +403,8 @@ pub enum ExecuteMsg {
         result_token: AssetInfo,
         // Specify the goal profit: 0.01 -> 1 %
         wanted_profit: Decimal,
+        // base asset that is borrowed, defaults to the primary utoken
+        utoken: Option<String>,
     },
 
     // Bot: Withdraw unbonded liquidity from liquid staking providers
@@ -175,6 +417,28 @@ pub enum ExecuteMsg {
         // specify which adapters should be withdrawn
         names: Option<Vec<String>>,
     },
+    // Anyone / IBC hook: Reconciles the attached utoken returned by the remote chain with the
+    // acknowledged redemptions in order. A redemption stops being tracked once its expected amount
+    // has been received.
+    CompleteIbcRedemptions {
+        ids: Vec<u64>,
+    },
+    // Bot: Stop counting unacknowledged redemptions after their transfer timed out. An expired
+    // redemption is restored if the acknowledgement still arrives.
+    ExpireIbcRedemptions {
+        ids: Vec<u64>,
+    },
+    // Bot: Stop counting acknowledged redemptions whose utoken returned to the vault without the
+    // IBC hook. The settled amounts need to be covered by the vault's utoken balance.
+    SettleIbcRedemptions {
+        ids: Vec<u64>,
+    },
+
+    // Admin User: Update the exchange rate of an IBC hosted LSD
+    SetIbcExchangeRate {
+        name: String,
+        exchange_rate: Decimal,
+    },
 
     // Internal: Asserts that the execution was a success and the wanted_profit reached.
     /// Creates a request to change the contract's ownership
@@ -212,6 +476,26 @@ impl CallbackMsg {
     }
 }
 
+/// Lifecycle callbacks of IBC transfers sent by the contract (ibc-hooks)
+#[cw_serde]
+pub enum SudoMsg {
+    IbcLifecycleComplete(IbcLifecycleComplete),
+}
+
+#[cw_serde]
+pub enum IbcLifecycleComplete {
+    IbcAck {
+        channel: String,
+        sequence: u64,
+        ack: String,
+        success: bool,
+    },
+    IbcTimeout {
+        channel: String,
+        sequence: u64,
+    },
+}
+
 /// This structure describes the query messages available in the contract.
 #[cw_serde]
 #[derive(QueryResponses)]
@@ -235,6 +519,8 @@ pub enum QueryMsg {
     #[returns(TakeableResponse)]
     Takeable {
         wanted_profit: Option<Decimal>,
+        // base asset, defaults to the primary utoken
+        utoken: Option<String>,
     },
 
     /// Query user funds currently unbonding
@@ -245,6 +531,27 @@ pub enum QueryMsg {
         limit: Option<u32>,
     },
 
+    /// Query the queue position of user funds currently unbonding
+    #[returns(UnbondQueueResponse)]
+    UnbondQueue {
+        address: String,
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    },
+
+    /// Simulates swapping an LSD token for the utoken
+    #[returns(SimulateSwapResponse)]
+    SimulateSwap {
+        offer_asset: Asset,
+    },
+
+    /// Query outstanding redemptions of IBC hosted LSDs
+    #[returns(IbcRedemptionsResponse)]
+    IbcRedemptions {
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    },
+
     #[returns(ExchangeRatesResponse)]
     ExchangeRates {
         // start after the provided timestamp in days
@@ -269,6 +576,60 @@ pub struct UnbondRequestsResponse {
     pub requests: Vec<UnbondItem>,
 }
 
+#[cw_serde]
+pub struct UnbondQueuePosition {
+    pub id: u64,
+    // amount of utoken requested by the unbond items ahead, which is not filled yet
+    pub amount_ahead: Uint128,
+    pub amount_asset: Uint128,
+    // amount that can already be withdrawn
+    pub fillable: Uint128,
+}
+
+#[cw_serde]
+pub struct UnbondQueueResponse {
+    // funds available in the contract to fill the queue
+    pub vault_available: Uint128,
+    // total amount requested by all unbond items in the queue
+    pub queue_total: Uint128,
+    pub positions: Vec<UnbondQueuePosition>,
+}
+
+#[cw_serde]
+pub struct SimulateSwapResponse {
+    // value of the offered LSD token in utoken
+    pub value: Uint128,
+    // discount applied to the value, based on the utilization steps
+    pub discount: Decimal,
+    // amount of utoken received
+    pub return_amount: Uint128,
+}
+
+#[cw_serde]
+pub struct IbcRedemption {
+    pub id: u64,
+    // name of the LSD adapter
+    pub name: String,
+    pub channel_id: String,
+    // sequence of the transfer, set after it has been sent
+    pub sequence: Option<u64>,
+    // amount of LSD tokens sent
+    pub amount: Uint128,
+    // amount of utoken expected to return
+    pub expected_amount: Uint128,
+    // timeout of the transfer in seconds
+    pub timeout: u64,
+    // whether the transfer has been acknowledged by the remote chain
+    pub acknowledged: bool,
+    // whether the transfer timed out without a callback, expired redemptions are not counted
+    pub expired: bool,
+}
+
+#[cw_serde]
+pub struct IbcRedemptionsResponse {
+    pub redemptions: Vec<IbcRedemption>,
+}
+
 #[cw_serde]
 pub struct WithdrawableResponse {
     pub withdrawable: Uint128,
@@ -298,6 +659,9 @@ pub struct Config<T> {
     pub utilization_method: UtilizationMethod,
     pub unbond_time_s: u64,
     pub lsds: Vec<LsdConfig<T>>,
+    // additional base assets, valued in the primary utoken
+    #[serde(default)]
+    pub assets: Vec<BaseAssetConfig<T>>,
 }
 
 #[cw_serde]
@@ -376,9 +740,26 @@ pub struct Balances<T> {
     // amount that is currently in the contract as xtokens denominated in the utoken(amp[TOKEN], b[TOKEN], etc.)
     pub lsd_xvalue: Uint128,
 
+    // breakdown per base asset, the totals above are valued in the primary utoken
+    pub assets: Vec<AssetBalance>,
+
     pub details: T,
 }
 
+#[cw_serde]
+pub struct AssetBalance {
+    pub utoken: String,
+    // value of one unit in the primary utoken
+    pub price: Decimal,
+    // all amounts are denominated in the base asset
+    pub tvl_utoken: Uint128,
+    pub vault_total: Uint128,
+    pub vault_available: Uint128,
+    pub vault_takeable: Uint128,
+    // share of the locked user withdrawals assigned to the base asset
+    pub locked_user_withdrawls: Uint128,
+}
+
 pub type BalancesDetails = Balances<Vec<ClaimBalance>>;
 pub type BalancesOptionalDetails = Balances<Option<Vec<ClaimBalance>>>;
 
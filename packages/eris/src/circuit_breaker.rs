@@ -0,0 +1,372 @@
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{Decimal, OverflowError, StdError, StdResult, Storage, Uint128};
+use cw_storage_plus::{Item, Map};
+
+use crate::constants::DAY;
+
+/// Maximum length of the rolling window for the volume limits
+pub const MAX_VOLUME_WINDOW: u64 = 30 * DAY;
+/// Number of buckets the rolling window is split into
+pub const VOLUME_BUCKETS: u64 = 24;
+
+/// Actions that can be paused independently by the guardian
+#[cw_serde]
+#[derive(Copy)]
+pub enum PausableAction {
+    Bond,
+    Unbond,
+    Harvest,
+}
+
+impl PausableAction {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            PausableAction::Bond => "bond",
+            PausableAction::Unbond => "unbond",
+            PausableAction::Harvest => "harvest",
+        }
+    }
+}
+
+#[cw_serde]
+#[derive(Default)]
+pub struct PauseState {
+    pub bond: bool,
+    pub unbond: bool,
+    pub harvest: bool,
+}
+
+impl PauseState {
+    pub fn is_paused(&self, action: PausableAction) -> bool {
+        match action {
+            PausableAction::Bond => self.bond,
+            PausableAction::Unbond => self.unbond,
+            PausableAction::Harvest => self.harvest,
+        }
+    }
+
+    pub fn set(&mut self, action: PausableAction, paused: bool) {
+        match action {
+            PausableAction::Bond => self.bond = paused,
+            PausableAction::Unbond => self.unbond = paused,
+            PausableAction::Harvest => self.harvest = paused,
+        }
+    }
+
+    pub fn pause_all(&mut self) {
+        self.bond = true;
+        self.unbond = true;
+        self.harvest = true;
+    }
+}
+
+#[cw_serde]
+pub struct CircuitBreakerConfig {
+    /// Maximum relative change of the exchange rate compared to the last recorded exchange rate
+    pub max_exchange_rate_change: Option<Decimal>, // "1 is 100%, 0.05 is 5%"
+    /// Length of the rolling window for the volume limits, in seconds
+    pub volume_window: u64,
+    /// Maximum amount of utoken that can be bonded within the window
+    pub max_bond_volume: Option<Uint128>,
+    /// Maximum amount of utoken that can be unbonded within the window, valued at the exchange rate
+    pub max_unbond_volume: Option<Uint128>,
+}
+
+impl CircuitBreakerConfig {
+    pub fn validate(self) -> StdResult<CircuitBreakerConfig> {
+        if self.max_exchange_rate_change.map_or(false, |change| change.is_zero()) {
+            return Err(StdError::generic_err("max_exchange_rate_change can't be zero"));
+        }
+
+        let has_volume_limit = self.max_bond_volume.is_some() || self.max_unbond_volume.is_some();
+        if has_volume_limit && self.volume_window == 0 {
+            return Err(StdError::generic_err("volume_window can't be zero"));
+        }
+
+        if self.volume_window > MAX_VOLUME_WINDOW {
+            return Err(StdError::generic_err(format!(
+                "volume_window can't be above {}",
+                MAX_VOLUME_WINDOW
+            )));
+        }
+
+        Ok(self)
+    }
+
+    /// Returns true if the exchange rate moved by more than the allowed change
+    pub fn exceeds_exchange_rate_change(&self, previous: Decimal, current: Decimal) -> bool {
+        match self.max_exchange_rate_change {
+            Some(max_change) if !previous.is_zero() => {
+                let diff = if current > previous {
+                    current - previous
+                } else {
+                    previous - current
+                };
+                diff / previous > max_change
+            },
+            _ => false,
+        }
+    }
+
+    pub fn max_volume(&self, action: PausableAction) -> Option<Uint128> {
+        match action {
+            PausableAction::Bond => self.max_bond_volume,
+            PausableAction::Unbond => self.max_unbond_volume,
+            PausableAction::Harvest => None,
+        }
+    }
+}
+
+/// Amounts that were processed within the rolling window, summed up in fixed time buckets
+#[cw_serde]
+#[derive(Default)]
+pub struct VolumeTracker {
+    /// (start time of the bucket in seconds, amount)
+    pub buckets: Vec<(u64, Uint128)>,
+}
+
+impl VolumeTracker {
+    /// Removes buckets that ended before the window and adds the new amount to the current bucket.
+    /// Returns the total amount within the window, which includes up to one bucket more than the
+    /// window.
+    pub fn add(&mut self, time: u64, window: u64, amount: Uint128) -> StdResult<Uint128> {
+        let bucket_size = (window / VOLUME_BUCKETS).max(1);
+        let bucket_start = time - time % bucket_size;
+
+        self.buckets
+            .retain(|(start, _)| start.saturating_add(bucket_size).saturating_add(window) > time);
+        match self.buckets.last_mut() {
+            Some((start, total)) if *start == bucket_start => {
+                *total = total.checked_add(amount)?;
+            },
+            _ => self.buckets.push((bucket_start, amount)),
+        }
+
+        self.buckets
+            .iter()
+            .try_fold(Uint128::zero(), |total, (_, amount)| total.checked_add(*amount))
+            .map_err(StdError::overflow)
+    }
+
+    /// Removes an amount that was added before, starting with the oldest bucket. Amounts of
+    /// buckets that already left the window are not removed again.
+    pub fn remove(&mut self, time: u64, window: u64, amount: Uint128) {
+        let bucket_size = (window / VOLUME_BUCKETS).max(1);
+
+        self.buckets
+            .retain(|(start, _)| start.saturating_add(bucket_size).saturating_add(window) > time);
+
+        let mut remaining = amount;
+        for (_, total) in self.buckets.iter_mut() {
+            let removed = Uint128::min(*total, remaining);
+            *total -= removed;
+            remaining -= removed;
+        }
+        self.buckets.retain(|(_, total)| !total.is_zero());
+    }
+}
+
+/// Errors of the circuit breaker, which every contract maps into its own error type
+#[derive(Debug, PartialEq)]
+pub enum CircuitBreakerError {
+    Std(StdError),
+    Paused(PausableAction),
+    VolumeLimitExceeded(PausableAction),
+}
+
+impl From<StdError> for CircuitBreakerError {
+    fn from(err: StdError) -> Self {
+        CircuitBreakerError::Std(err)
+    }
+}
+
+impl From<OverflowError> for CircuitBreakerError {
+    fn from(err: OverflowError) -> Self {
+        CircuitBreakerError::Std(err.into())
+    }
+}
+
+pub fn set_paused(
+    storage: &mut dyn Storage,
+    paused: &Item<PauseState>,
+    action: PausableAction,
+    is_paused: bool,
+) -> StdResult<()> {
+    let mut pause_state = paused.may_load(storage)?.unwrap_or_default();
+    pause_state.set(action, is_paused);
+    paused.save(storage, &pause_state)
+}
+
+pub fn assert_not_paused(
+    storage: &dyn Storage,
+    paused: &Item<PauseState>,
+    action: PausableAction,
+) -> Result<(), CircuitBreakerError> {
+    if paused.may_load(storage)?.unwrap_or_default().is_paused(action) {
+        return Err(CircuitBreakerError::Paused(action));
+    }
+    Ok(())
+}
+
+/// Adds the amount to the rolling volume stored under the key and verifies it stays within the
+/// limit of the action.
+pub fn track_volume(
+    storage: &mut dyn Storage,
+    config: &Item<CircuitBreakerConfig>,
+    volumes: &Map<&str, VolumeTracker>,
+    key: &str,
+    time: u64,
+    action: PausableAction,
+    amount: Uint128,
+) -> Result<(), CircuitBreakerError> {
+    let config = match config.may_load(storage)? {
+        Some(config) => config,
+        None => return Ok(()),
+    };
+    let max_volume = match config.max_volume(action) {
+        Some(max_volume) => max_volume,
+        None => return Ok(()),
+    };
+
+    let mut tracker = volumes.may_load(storage, key)?.unwrap_or_default();
+    let volume = tracker.add(time, config.volume_window, amount)?;
+    if volume > max_volume {
+        return Err(CircuitBreakerError::VolumeLimitExceeded(action));
+    }
+    volumes.save(storage, key, &tracker)?;
+
+    Ok(())
+}
+
+/// Removes an amount from the rolling volume stored under the key, e.g. when an unbond is canceled.
+pub fn untrack_volume(
+    storage: &mut dyn Storage,
+    config: &Item<CircuitBreakerConfig>,
+    volumes: &Map<&str, VolumeTracker>,
+    key: &str,
+    time: u64,
+    amount: Uint128,
+) -> StdResult<()> {
+    let config = match config.may_load(storage)? {
+        Some(config) => config,
+        None => return Ok(()),
+    };
+
+    if let Some(mut tracker) = volumes.may_load(storage, key)? {
+        tracker.remove(time, config.volume_window, amount);
+        volumes.save(storage, key, &tracker)?;
+    }
+
+    Ok(())
+}
+
+/// Compares the exchange rate with the previous one. If it moved by more than the allowed change,
+/// all actions are paused until the owner resumes them. Returns whether the circuit breaker tripped.
+pub fn check_exchange_rate(
+    storage: &mut dyn Storage,
+    config: &Item<CircuitBreakerConfig>,
+    paused: &Item<PauseState>,
+    previous: Option<Decimal>,
+    exchange_rate: Decimal,
+) -> StdResult<bool> {
+    let config = match config.may_load(storage)? {
+        Some(config) => config,
+        None => return Ok(false),
+    };
+
+    match previous {
+        Some(previous) if config.exceeds_exchange_rate_change(previous, exchange_rate) => {
+            let mut pause_state = paused.may_load(storage)?.unwrap_or_default();
+            pause_state.pause_all();
+            paused.save(storage, &pause_state)?;
+            Ok(true)
+        },
+        _ => Ok(false),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn exchange_rate_change() {
+        let config = CircuitBreakerConfig {
+            max_exchange_rate_change: Some(Decimal::percent(5)),
+            volume_window: 0,
+            max_bond_volume: None,
+            max_unbond_volume: None,
+        };
+
+        assert!(!config.exceeds_exchange_rate_change(Decimal::one(), Decimal::percent(105)));
+        assert!(!config.exceeds_exchange_rate_change(Decimal::one(), Decimal::percent(95)));
+        assert!(config.exceeds_exchange_rate_change(Decimal::one(), Decimal::permille(1051)));
+        assert!(config.exceeds_exchange_rate_change(Decimal::one(), Decimal::permille(949)));
+    }
+
+    #[test]
+    fn rolling_volume() {
+        let mut tracker = VolumeTracker::default();
+
+        // buckets of 10 seconds
+        assert_eq!(tracker.add(100, 240, Uint128::new(10)).unwrap(), Uint128::new(10));
+        assert_eq!(tracker.add(149, 240, Uint128::new(20)).unwrap(), Uint128::new(30));
+        assert_eq!(tracker.add(150, 240, Uint128::new(5)).unwrap(), Uint128::new(35));
+        assert_eq!(tracker.add(349, 240, Uint128::new(1)).unwrap(), Uint128::new(36));
+        // the first bucket left the window
+        assert_eq!(tracker.add(350, 240, Uint128::new(1)).unwrap(), Uint128::new(27));
+        assert_eq!(
+            tracker.buckets,
+            vec![
+                (140, Uint128::new(20)),
+                (150, Uint128::new(5)),
+                (340, Uint128::new(1)),
+                (350, Uint128::new(1))
+            ]
+        );
+
+        // amounts within a bucket don't add entries
+        for time in 350..360 {
+            tracker.add(time, 240, Uint128::new(1)).unwrap();
+        }
+        assert_eq!(tracker.buckets.len(), 4);
+        assert_eq!(tracker.buckets[3], (350, Uint128::new(11)));
+    }
+
+    #[test]
+    fn removing_volume() {
+        let mut tracker = VolumeTracker::default();
+
+        // buckets of 10 seconds
+        tracker.add(100, 240, Uint128::new(10)).unwrap();
+        tracker.add(150, 240, Uint128::new(20)).unwrap();
+
+        // the oldest bucket is emptied first
+        tracker.remove(160, 240, Uint128::new(15));
+        assert_eq!(tracker.buckets, vec![(150, Uint128::new(15))]);
+
+        // nothing is removed beyond the tracked volume
+        tracker.remove(160, 240, Uint128::new(100));
+        assert_eq!(tracker.buckets, vec![]);
+        assert_eq!(tracker.add(160, 240, Uint128::new(1)).unwrap(), Uint128::new(1));
+    }
+
+    #[test]
+    fn capping_volume_window() {
+        let config = CircuitBreakerConfig {
+            max_exchange_rate_change: None,
+            volume_window: u64::MAX,
+            max_bond_volume: Some(Uint128::new(100)),
+            max_unbond_volume: None,
+        };
+        assert_eq!(
+            config.validate().unwrap_err(),
+            StdError::generic_err("volume_window can't be above 2592000")
+        );
+
+        // the window can't overflow
+        let mut tracker = VolumeTracker::default();
+        tracker.add(u64::MAX - 2, u64::MAX, Uint128::new(10)).unwrap();
+        assert_eq!(tracker.add(u64::MAX - 1, u64::MAX, Uint128::new(5)).unwrap(), Uint128::new(15));
+    }
+}
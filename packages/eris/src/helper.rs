@@ -2,8 +2,8 @@ use std::{collections::HashSet, convert::TryInto};
 
 use astroport::asset::Asset;
 use cosmwasm_std::{
-    Addr, Api, Coin, CosmosMsg, Env, MessageInfo, Reply, StdError, StdResult, SubMsgResponse,
-    Uint128, Uint256,
+    Addr, Api, Coin, CosmosMsg, Decimal, Env, MessageInfo, Reply, StdError, StdResult,
+    SubMsgResponse, Uint128, Uint256,
 };
 use cw20::Expiration;
 use eris_chain_adapter::types::CustomMsgType;
@@ -93,6 +93,95 @@ pub fn funds_or_allowance(
     Ok((funds, msgs))
 }
 
+/// Distributes a share of one by score, without any entry exceeding `max_share`. The excess of
+/// capped entries is redistributed among the entries below the cap. If the entries can't reach one
+/// without exceeding the cap, every entry receives an equal share.
+pub fn capped_shares(
+    scores: Vec<(String, Decimal)>,
+    max_share: Decimal,
+) -> StdResult<Vec<(String, Decimal)>> {
+    if scores.is_empty() {
+        return Ok(vec![]);
+    }
+
+    let count = Decimal::from_ratio(scores.len() as u128, 1u128);
+    if max_share.checked_mul(count)? < Decimal::one() {
+        let share = Decimal::from_ratio(1u128, scores.len() as u128);
+        return Ok(scores.into_iter().map(|(key, _)| (key, share)).collect());
+    }
+
+    let mut capped = vec![false; scores.len()];
+    loop {
+        let (remaining, total_score) = capped_remainder(&scores, &capped, max_share)?;
+
+        let mut changed = false;
+        for (index, (_, score)) in scores.iter().enumerate() {
+            if !capped[index]
+                && uncapped_share(*score, remaining, total_score, &capped)? > max_share
+            {
+                capped[index] = true;
+                changed = true;
+            }
+        }
+
+        if !changed {
+            break;
+        }
+    }
+
+    let (remaining, total_score) = capped_remainder(&scores, &capped, max_share)?;
+    scores
+        .into_iter()
+        .zip(capped.iter())
+        .map(|((key, score), is_capped)| {
+            if *is_capped {
+                Ok((key, max_share))
+            } else {
+                Ok((key, uncapped_share(score, remaining, total_score, &capped)?))
+            }
+        })
+        .collect()
+}
+
+/// Returns the share left for the uncapped entries and the sum of their scores
+fn capped_remainder(
+    scores: &[(String, Decimal)],
+    capped: &[bool],
+    max_share: Decimal,
+) -> StdResult<(Decimal, Decimal)> {
+    let capped_count = capped.iter().filter(|c| **c).count();
+    let remaining = Decimal::one()
+        .checked_sub(max_share.checked_mul(Decimal::from_ratio(capped_count as u128, 1u128))?)?;
+    let total_score = scores
+        .iter()
+        .zip(capped.iter())
+        .filter(|(_, is_capped)| !**is_capped)
+        .map(|((_, score), _)| *score)
+        .sum();
+
+    Ok((remaining, total_score))
+}
+
+/// Share of an uncapped entry, split equally if none of the uncapped entries has a score
+fn uncapped_share(
+    score: Decimal,
+    remaining: Decimal,
+    total_score: Decimal,
+    capped: &[bool],
+) -> StdResult<Decimal> {
+    if total_score.is_zero() {
+        let uncapped_count = capped.iter().filter(|c| !**c).count();
+        return remaining
+            .checked_div(Decimal::from_ratio(uncapped_count as u128, 1u128))
+            .map_err(|_| StdError::generic_err("Could not divide by total"));
+    }
+
+    remaining
+        .checked_mul(score)?
+        .checked_div(total_score)
+        .map_err(|_| StdError::generic_err("Could not divide by total"))
+}
+
 pub trait ScalingUint128 {
     fn multiply_ratio_and_ceil(&self, numerator: Uint128, denominator: Uint128) -> Uint128;
 }
@@ -127,6 +216,44 @@ mod tests {
         assert_eq!(b, Uint128::new(41));
     }
 
+    #[test]
+    fn capped_shares_test() {
+        let shares = |scores: &[(&str, u64)], max_share: u64| {
+            capped_shares(
+                scores
+                    .iter()
+                    .map(|(key, score)| (key.to_string(), Decimal::percent(*score)))
+                    .collect(),
+                Decimal::percent(max_share),
+            )
+            .unwrap()
+        };
+
+        // nothing above the cap
+        assert_eq!(
+            shares(&[("a", 30), ("b", 10)], 100),
+            vec![("a".to_string(), Decimal::percent(75)), ("b".to_string(), Decimal::percent(25))]
+        );
+
+        // the excess of a is redistributed to b and c, which pushes b over the cap as well
+        assert_eq!(
+            shares(&[("a", 80), ("b", 15), ("c", 5)], 40),
+            vec![
+                ("a".to_string(), Decimal::percent(40)),
+                ("b".to_string(), Decimal::percent(40)),
+                ("c".to_string(), Decimal::percent(20))
+            ]
+        );
+
+        // the cap can't be reached by all entries together
+        assert_eq!(
+            shares(&[("a", 80), ("b", 20)], 40),
+            vec![("a".to_string(), Decimal::percent(50)), ("b".to_string(), Decimal::percent(50))]
+        );
+
+        assert_eq!(shares(&[], 40), vec![]);
+    }
+
     #[test]
     fn assets_uniq_test() {
         // no duplicate
@@ -0,0 +1,173 @@
+use std::fmt;
+
+use cosmwasm_std::{from_json, to_json_string, StdResult};
+use serde::de::{Deserialize, Deserializer, MapAccess, SeqAccess, Visitor};
+
+/// Untyped JSON value, used to read responses and templates of contracts without known types.
+/// Numbers are kept as their text, as floats are not supported by the contracts.
+#[derive(Clone, Debug, PartialEq)]
+pub enum JsonValue {
+    Null,
+    Bool(bool),
+    Number(String),
+    String(String),
+    Array(Vec<JsonValue>),
+    Object(Vec<(String, JsonValue)>),
+}
+
+impl JsonValue {
+    pub fn parse(json: &[u8]) -> StdResult<JsonValue> {
+        from_json(json)
+    }
+
+    /// Returns the value found by following the object keys of the path.
+    pub fn at(&self, path: &[String]) -> Option<&JsonValue> {
+        path.iter().try_fold(self, |value, key| match value {
+            JsonValue::Object(entries) => entries.iter().find(|(k, _)| k == key).map(|(_, v)| v),
+            _ => None,
+        })
+    }
+
+    /// Text of a string or number, e.g. to parse a Uint128 or Decimal.
+    pub fn as_text(&self) -> Option<&str> {
+        match self {
+            JsonValue::Number(text) | JsonValue::String(text) => Some(text),
+            _ => None,
+        }
+    }
+
+    pub fn as_bool(&self) -> Option<bool> {
+        match self {
+            JsonValue::Bool(value) => Some(*value),
+            _ => None,
+        }
+    }
+
+    pub fn as_array(&self) -> Option<&Vec<JsonValue>> {
+        match self {
+            JsonValue::Array(items) => Some(items),
+            _ => None,
+        }
+    }
+
+    pub fn to_json_string(&self) -> StdResult<String> {
+        Ok(match self {
+            JsonValue::Null => "null".to_string(),
+            JsonValue::Bool(value) => value.to_string(),
+            JsonValue::Number(text) => text.clone(),
+            JsonValue::String(text) => to_json_string(text)?,
+            JsonValue::Array(items) => format!(
+                "[{}]",
+                items
+                    .iter()
+                    .map(|item| item.to_json_string())
+                    .collect::<StdResult<Vec<_>>>()?
+                    .join(",")
+            ),
+            JsonValue::Object(entries) => format!(
+                "{{{}}}",
+                entries
+                    .iter()
+                    .map(|(key, value)| Ok(format!(
+                        "{}:{}",
+                        to_json_string(key)?,
+                        value.to_json_string()?
+                    )))
+                    .collect::<StdResult<Vec<_>>>()?
+                    .join(",")
+            ),
+        })
+    }
+}
+
+impl<'de> Deserialize<'de> for JsonValue {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_any(JsonValueVisitor)
+    }
+}
+
+struct JsonValueVisitor;
+
+impl<'de> Visitor<'de> for JsonValueVisitor {
+    type Value = JsonValue;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a JSON value")
+    }
+
+    fn visit_unit<E>(self) -> Result<JsonValue, E> {
+        Ok(JsonValue::Null)
+    }
+
+    fn visit_none<E>(self) -> Result<JsonValue, E> {
+        Ok(JsonValue::Null)
+    }
+
+    fn visit_bool<E>(self, value: bool) -> Result<JsonValue, E> {
+        Ok(JsonValue::Bool(value))
+    }
+
+    fn visit_u64<E>(self, value: u64) -> Result<JsonValue, E> {
+        Ok(JsonValue::Number(value.to_string()))
+    }
+
+    fn visit_i64<E>(self, value: i64) -> Result<JsonValue, E> {
+        Ok(JsonValue::Number(value.to_string()))
+    }
+
+    fn visit_str<E>(self, value: &str) -> Result<JsonValue, E> {
+        Ok(JsonValue::String(value.to_string()))
+    }
+
+    fn visit_string<E>(self, value: String) -> Result<JsonValue, E> {
+        Ok(JsonValue::String(value))
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<JsonValue, A::Error> {
+        let mut items = vec![];
+        while let Some(item) = seq.next_element()? {
+            items.push(item);
+        }
+        Ok(JsonValue::Array(items))
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<JsonValue, A::Error> {
+        let mut entries = vec![];
+        while let Some(entry) = map.next_entry()? {
+            entries.push(entry);
+        }
+        Ok(JsonValue::Object(entries))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parsing_values() {
+        let value = JsonValue::parse(
+            br#" {"rates":{"redemption":"1.2","count":3},"requests":[{"claimable":true},null]} "#,
+        )
+        .unwrap();
+
+        let path = |keys: &[&str]| keys.iter().map(|key| key.to_string()).collect::<Vec<_>>();
+        assert_eq!(
+            value.at(&path(&["rates", "redemption"])).and_then(|v| v.as_text()),
+            Some("1.2")
+        );
+        assert_eq!(value.at(&path(&["rates", "count"])).and_then(|v| v.as_text()), Some("3"));
+        assert_eq!(value.at(&path(&["rates", "missing"])), None);
+        assert_eq!(
+            value.at(&path(&["requests"])).and_then(|v| v.as_array()).map(|items| items.len()),
+            Some(2)
+        );
+        assert_eq!(
+            value.to_json_string().unwrap(),
+            r#"{"rates":{"redemption":"1.2","count":3},"requests":[{"claimable":true},null]}"#
+        );
+
+        assert!(JsonValue::parse(br#"{"claim":{}"#).is_err());
+        assert!(JsonValue::parse(br#"{"claim":{}} {}"#).is_err());
+    }
+}
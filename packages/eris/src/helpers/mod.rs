@@ -1,2 +1,3 @@
 pub mod bps;
+pub mod json;
 pub mod slope;
@@ -1,4 +1,7 @@
-use std::{collections::HashSet, convert::TryInto};
+use std::{
+    collections::HashSet,
+    convert::{TryFrom, TryInto},
+};
 
 use cosmwasm_schema::{cw_serde, QueryResponses};
 use cosmwasm_std::{
@@ -11,13 +14,22 @@ use eris_chain_adapter::types::{
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
-use crate::{helper::addr_opt_validate, helpers::bps::BasicPoints};
+use crate::{
+    circuit_breaker::{CircuitBreakerConfig, PausableAction, PauseState},
+    constants::DAY,
+    helper::addr_opt_validate,
+    helpers::bps::BasicPoints,
+    DecimalCheckedOps,
+};
 
 // StageType = DEX
 // DenomType = Chain specific denom
 // Option<Decimal> = Price
 // Option<Uint128> = max amount, 0 = unlimited
 // Option<bool> = pay_fee
+/// Maximum age of a price feed, older prices are not considered for swaps
+pub const MAX_PRICE_AGE: u64 = 7 * DAY;
+
 pub type SingleSwapConfig = (StageType, DenomType, Option<Decimal>, Option<Uint128>, Option<bool>);
 
 #[cw_serde]
@@ -42,6 +54,88 @@ pub enum DelegationStrategy<T = String> {
         /// count of validators that should receive delegations
         validator_count: u8,
     },
+    /// validators receive delegations based on their commission, jailed status and uptime
+    Performance {
+        /// validators with a higher commission don't receive delegations
+        commission_cap_bps: u16,
+        /// optional oracle reporting the uptime of each validator between 0 and 1
+        uptime_oracle: Option<T>,
+        /// min score between 0 and 1 needed to receive delegations
+        min_score: Decimal,
+        /// max amount of delegation needed
+        max_delegation_bps: u16,
+        /// count of validators that should receive delegations
+        validator_count: u8,
+    },
+    /// validators known to the alliance module receive delegations based on the alliance rewards
+    /// kept after their commission. Only supported by the alliance-lst.
+    AllianceRewards {
+        /// validators with a higher commission don't receive delegations
+        commission_cap_bps: u16,
+        /// max amount of delegation needed
+        max_delegation_bps: u16,
+        /// count of validators that should receive delegations
+        validator_count: u8,
+    },
+    /// shares of multiple strategies are merged by their weight in bps, summing up to 10000
+    Composite {
+        parts: Vec<(DelegationStrategy<T>, u16)>,
+    },
+}
+
+impl From<DelegationStrategy<Addr>> for DelegationStrategy<String> {
+    fn from(strategy: DelegationStrategy<Addr>) -> Self {
+        match strategy {
+            DelegationStrategy::Uniform => DelegationStrategy::Uniform,
+            DelegationStrategy::Defined {
+                shares_bps,
+            } => DelegationStrategy::Defined {
+                shares_bps,
+            },
+            DelegationStrategy::Gauges {
+                amp_gauges,
+                emp_gauges,
+                amp_factor_bps,
+                min_delegation_bps,
+                max_delegation_bps,
+                validator_count,
+            } => DelegationStrategy::Gauges {
+                amp_gauges: amp_gauges.to_string(),
+                emp_gauges: emp_gauges.map(|a| a.to_string()),
+                amp_factor_bps,
+                min_delegation_bps,
+                max_delegation_bps,
+                validator_count,
+            },
+            DelegationStrategy::Performance {
+                commission_cap_bps,
+                uptime_oracle,
+                min_score,
+                max_delegation_bps,
+                validator_count,
+            } => DelegationStrategy::Performance {
+                commission_cap_bps,
+                uptime_oracle: uptime_oracle.map(|a| a.to_string()),
+                min_score,
+                max_delegation_bps,
+                validator_count,
+            },
+            DelegationStrategy::AllianceRewards {
+                commission_cap_bps,
+                max_delegation_bps,
+                validator_count,
+            } => DelegationStrategy::AllianceRewards {
+                commission_cap_bps,
+                max_delegation_bps,
+                validator_count,
+            },
+            DelegationStrategy::Composite {
+                parts,
+            } => DelegationStrategy::Composite {
+                parts: parts.into_iter().map(|(strategy, bps)| (strategy.into(), bps)).collect(),
+            },
+        }
+    }
 }
 
 impl DelegationStrategy<String> {
@@ -106,11 +200,221 @@ impl DelegationStrategy<String> {
                     shares_bps,
                 }
             },
+            DelegationStrategy::Performance {
+                commission_cap_bps,
+                uptime_oracle,
+                min_score,
+                max_delegation_bps,
+                validator_count,
+            } => {
+                BasicPoints::try_from(commission_cap_bps)?;
+                BasicPoints::try_from(max_delegation_bps)?;
+
+                if min_score > Decimal::one() {
+                    Err(StdError::generic_err("min_score greater than 1"))?;
+                }
+
+                DelegationStrategy::Performance {
+                    commission_cap_bps,
+                    uptime_oracle: addr_opt_validate(api, &uptime_oracle)?,
+                    min_score,
+                    max_delegation_bps,
+                    validator_count,
+                }
+            },
+            DelegationStrategy::AllianceRewards {
+                commission_cap_bps,
+                max_delegation_bps,
+                validator_count,
+            } => {
+                BasicPoints::try_from(commission_cap_bps)?;
+                BasicPoints::try_from(max_delegation_bps)?;
+
+                DelegationStrategy::AllianceRewards {
+                    commission_cap_bps,
+                    max_delegation_bps,
+                    validator_count,
+                }
+            },
+            DelegationStrategy::Composite {
+                parts,
+            } => {
+                let mut total = BasicPoints::default();
+                let parts = parts
+                    .into_iter()
+                    .map(|(strategy, weight_bps)| {
+                        if let DelegationStrategy::Composite {
+                            ..
+                        } = strategy
+                        {
+                            return Err(StdError::generic_err(
+                                "composite strategies can't be nested",
+                            ));
+                        }
+
+                        total = total.checked_add(BasicPoints::try_from(weight_bps)?)?;
+                        Ok((strategy.validate(api, validators)?, weight_bps))
+                    })
+                    .collect::<StdResult<Vec<_>>>()?;
+
+                if !total.is_max() {
+                    Err(StdError::generic_err("sum of weights is not 10000"))?;
+                }
+
+                DelegationStrategy::Composite {
+                    parts,
+                }
+            },
         };
         Ok(result)
     }
 }
 
+#[cw_serde]
+pub struct InstantUnbondConfig {
+    /// Share of the harvested utoken (after protocol fee) that is added to the reserve
+    pub reserve_share: Decimal, // "1 is 100%, 0.05 is 5%"
+    /// Reserve size at which only the min_fee is charged and no more rewards are added
+    pub reserve_target: Uint128,
+    /// Fee charged when the reserve stays at or above its target
+    pub min_fee: Decimal,
+    /// Fee charged when the reserve is fully drained
+    pub max_fee: Decimal,
+}
+
+impl InstantUnbondConfig {
+    pub fn validate(self) -> StdResult<InstantUnbondConfig> {
+        if self.reserve_share > Decimal::one() {
+            return Err(StdError::generic_err("reserve_share greater than 100%"));
+        }
+
+        if self.reserve_target.is_zero() {
+            return Err(StdError::generic_err("reserve_target can't be zero"));
+        }
+
+        if self.min_fee > self.max_fee {
+            return Err(StdError::generic_err("min_fee greater than max_fee"));
+        }
+
+        if self.max_fee >= Decimal::one() {
+            return Err(StdError::generic_err("max_fee needs to be less than 100%"));
+        }
+
+        Ok(self)
+    }
+
+    /// The fee rises linearly from min_fee to max_fee the more the reserve is drained below its
+    /// target after paying out utoken_amount.
+    pub fn get_fee(&self, reserve: Uint128, utoken_amount: Uint128) -> Decimal {
+        let remaining = reserve.saturating_sub(utoken_amount).min(self.reserve_target);
+        let filled = Decimal::from_ratio(remaining, self.reserve_target);
+
+        self.max_fee - (self.max_fee - self.min_fee) * filled
+    }
+}
+
+#[cw_serde]
+pub struct RebalanceConfig {
+    /// Maximum amount of utoken that permissionless rebalances can redelegate per epoch
+    pub max_redelegation_per_epoch: Uint128,
+    /// Minimum amount of a single redelegation in permissionless rebalances
+    pub min_redelegation: Uint128,
+    /// Utoken paid to the caller of a permissionless rebalance out of the next protocol fee
+    pub bounty: Uint128,
+}
+
+impl RebalanceConfig {
+    pub fn validate(self) -> StdResult<RebalanceConfig> {
+        if self.max_redelegation_per_epoch.is_zero() {
+            return Err(StdError::generic_err("max_redelegation_per_epoch can't be zero"));
+        }
+
+        Ok(self)
+    }
+}
+
+/// Source of the price of a denom, used to compute the belief price of swaps without one. The
+/// price is quoted in the ask denom of the swap.
+#[cw_serde]
+pub enum PriceSource<T = String> {
+    /// TWAP contract implementing the `price_oracle` interface
+    Twap {
+        contract: T,
+    },
+    /// Oracle module of the chain, the price is the ratio between the offer and the ask symbol
+    ChainOracle {
+        offer_symbol: String,
+        ask_symbol: String,
+    },
+    /// Price pushed by the operator via `PushPrice`
+    Pushed {},
+}
+
+#[cw_serde]
+pub struct PriceFeed<T = String> {
+    pub source: PriceSource<T>,
+    /// Maximum age of the price in seconds, swaps with an older price are rejected
+    pub max_age: u64,
+}
+
+impl PriceFeed<String> {
+    pub fn validate(self, api: &dyn Api) -> StdResult<PriceFeed<Addr>> {
+        if self.max_age == 0 {
+            return Err(StdError::generic_err("max_age can't be zero"));
+        }
+
+        if self.max_age > MAX_PRICE_AGE {
+            return Err(StdError::generic_err(format!("max_age can't be above {}", MAX_PRICE_AGE)));
+        }
+
+        let source = match self.source {
+            PriceSource::Twap {
+                contract,
+            } => PriceSource::Twap {
+                contract: api.addr_validate(&contract)?,
+            },
+            PriceSource::ChainOracle {
+                offer_symbol,
+                ask_symbol,
+            } => PriceSource::ChainOracle {
+                offer_symbol,
+                ask_symbol,
+            },
+            PriceSource::Pushed {} => PriceSource::Pushed {},
+        };
+
+        Ok(PriceFeed {
+            source,
+            max_age: self.max_age,
+        })
+    }
+}
+
+impl From<PriceFeed<Addr>> for PriceFeed<String> {
+    fn from(feed: PriceFeed<Addr>) -> Self {
+        let source = match feed.source {
+            PriceSource::Twap {
+                contract,
+            } => PriceSource::Twap {
+                contract: contract.to_string(),
+            },
+            PriceSource::ChainOracle {
+                offer_symbol,
+                ask_symbol,
+            } => PriceSource::ChainOracle {
+                offer_symbol,
+                ask_symbol,
+            },
+            PriceSource::Pushed {} => PriceSource::Pushed {},
+        };
+
+        PriceFeed {
+            source,
+            max_age: feed.max_age,
+        }
+    }
+}
+
 #[cw_serde]
 pub struct InstantiateMsg {
     /// Account who can call certain privileged functions
@@ -148,13 +452,27 @@ pub enum ExecuteMsg {
     /// Bond specified amount of Token
     Bond {
         receiver: Option<String>,
+        /// Account that referred the bond, it accrues a share of the protocol fee earned on the
+        /// bonded amount
+        referrer: Option<String>,
     },
     /// Donates specified amount of Token to pool
     Donate {},
+    /// Donates specified amount of Token to the instant unbond reserve
+    DonateReserve {},
     /// Withdraw Token that have finished unbonding in previous batches
     WithdrawUnbonded {
         receiver: Option<String>,
     },
+    /// Withdraw Token that have finished unbonding on behalf of users that enabled auto claim. The
+    /// Token are sent to the users themselves; callable by anyone
+    WithdrawUnbondedFor {
+        users: Vec<String>,
+    },
+    /// Allows anyone to withdraw the sender's finished unbondings to the sender
+    SetAutoClaim {
+        enabled: bool,
+    },
     /// Add a validator to the whitelist; callable by the owner
     AddValidator {
         validator: String,
@@ -180,7 +498,9 @@ pub enum ExecuteMsg {
     },
 
     TuneDelegations {},
-    /// Use redelegations to balance the amounts of Token delegated to validators
+    /// Use redelegations to balance the amounts of Token delegated to validators. If a rebalance
+    /// config is set, anyone can rebalance towards the tuned delegation goal within the per epoch
+    /// budget and receives a bounty.
     Rebalance {
         min_redelegation: Option<Uint128>,
     },
@@ -198,6 +518,32 @@ pub enum ExecuteMsg {
         proposal_id: u64,
         votes: Vec<(Decimal, VoteOption)>,
     },
+    /// Sets or removes the before send hook of the stake token, which tracks the balances of the
+    /// holders for holder votes (only owner)
+    UpdateHolderVoting {
+        enabled: bool,
+    },
+    /// Opens a proposal for votes of the stake token holders, weighted by their balance at the
+    /// start of the proposal (only allowed by the vote_operator)
+    OpenHolderVote {
+        proposal_id: u64,
+        /// Voting start time of the proposal in seconds, used as snapshot of the balances
+        start_time: u64,
+        /// Time in seconds until holders can vote and the tally can be submitted
+        end_time: u64,
+    },
+    /// Votes on an open proposal with the stake token balance of the sender, replacing any
+    /// previous vote of the sender
+    CastHolderVote {
+        proposal_id: u64,
+        votes: Vec<(Decimal, VoteOption)>,
+    },
+    /// Submits the current tally of the holder votes as weighted vote of the hub; callable by anyone
+    SubmitHolderVote {
+        proposal_id: u64,
+    },
+    /// Sends the accrued referral rewards of the sender
+    ClaimReferralRewards {},
     /// Callbacks; can only be invoked by the contract itself
     Callback(CallbackMsg),
 
@@ -207,6 +553,8 @@ pub enum ExecuteMsg {
         protocol_fee_contract: Option<String>,
         /// Fees that are being applied during reinvest of staking rewards
         protocol_reward_fee: Option<Decimal>, // "1 is 100%, 0.05 is 5%"
+        /// Recipients sharing the protocol fee, replaces protocol_fee_contract if not empty
+        fee_recipients: Option<Vec<FeeRecipient>>,
         /// Sets a new operator
         operator: Option<String>,
         /// Sets the stages preset
@@ -228,6 +576,31 @@ pub enum ExecuteMsg {
         epoch_period: Option<u64>,
         /// The staking module's unbonding time, in seconds
         unbond_period: Option<u64>,
+        /// Update the instant unbond config
+        instant_unbond_config: Option<InstantUnbondConfig>,
+        /// Disables instant unbonds, the reserve is kept until they are configured again
+        remove_instant_unbond_config: Option<bool>,
+        /// Specifies whether slashed validators are removed from the whitelist
+        remove_slashed_validators: Option<bool>,
+        /// Maximum number of concurrent unbonding entries per validator (staking module param)
+        max_unbonding_entries: Option<u32>,
+        /// Account that can pause bond, unbond and harvest
+        guardian: Option<String>,
+        /// Removes the guardian, only the owner can pause afterwards
+        remove_guardian: Option<bool>,
+        /// Update the exchange rate and volume limits
+        circuit_breaker: Option<CircuitBreakerConfig>,
+        /// Share of the protocol fee on referred bonds that is accrued to the referrer
+        referral_share: Option<Decimal>, // "1 is 100%, 0.05 is 5%"
+        /// Update the config of permissionless rebalances
+        rebalance_config: Option<RebalanceConfig>,
+    },
+
+    /// Pauses or resumes an action. Pausing can be done by the guardian or the owner, resuming
+    /// only by the owner.
+    SetPaused {
+        action: PausableAction,
+        paused: bool,
     },
 
     /// Submit an unbonding request to the current unbonding queue; automatically invokes `unbond`
@@ -236,12 +609,72 @@ pub enum ExecuteMsg {
         receiver: Option<String>,
     },
 
+    /// Unbond the sent stake token immediately against utoken from the instant unbond reserve.
+    /// The reserve takes over the unbonding position and is refilled once it finished unbonding.
+    InstantUnbond {
+        min_received: Option<Uint128>,
+        receiver: Option<String>,
+    },
+
+    /// Transfer shares of an unbonding request in a pending or previous batch to another address.
+    /// If shares is not set, the whole request is transferred.
+    TransferUnbondRequest {
+        id: u64,
+        recipient: String,
+        shares: Option<Uint128>,
+    },
+
+    /// Remove shares from the unbonding request in the current pending batch and return the stake
+    /// token. If amount is not set, the whole request is canceled.
+    CancelUnbond {
+        amount: Option<Uint128>,
+    },
+
+    /// Rebond the whole unbonding request of a submitted batch, that has not finished unbonding.
+    /// The utoken equivalent is delegated from the instant unbond reserve, which takes over the
+    /// unbonding position, and stake token is minted at the current exchange rate. Requires
+    /// instant unbonds to be enabled and charges the same fee as an instant unbond.
+    Rebond {
+        id: u64,
+    },
+
+    /// Compares the delegations with the amounts tracked by the contract and records a slashing
+    /// event for each validator that has less delegated than expected. When called by the owner,
+    /// slashed validators are removed if `remove_slashed_validators` is set.
+    CheckSlashing {},
+
+    /// Sets or removes the price feed of a denom, used for swaps without belief price (only owner)
+    UpdatePriceFeed {
+        denom: String,
+        feed: Option<PriceFeed>,
+    },
+    /// Pushes the price of a denom with a `Pushed` price feed (only operator)
+    PushPrice {
+        denom: String,
+        price: Decimal,
+    },
+
     // Claim possible airdrops
     Claim {
         claims: Vec<ClaimType>,
     },
 }
 
+/// Before send hooks of the token factory
+#[cw_serde]
+pub enum SudoMsg {
+    TrackBeforeSend {
+        from: String,
+        to: String,
+        amount: Coin,
+    },
+    BlockBeforeSend {
+        from: String,
+        to: String,
+        amount: Coin,
+    },
+}
+
 #[cw_serde]
 pub enum CallbackMsg {
     WithdrawLps {
@@ -327,6 +760,13 @@ pub enum QueryMsg {
         start_after: Option<u64>,
         limit: Option<u32>,
     },
+    /// Enumerate users with auto claim enabled and their withdrawable amount.
+    /// Response: `Vec<ClaimableUsersResponseItem>`
+    #[returns(Vec<ClaimableUsersResponseItem>)]
+    ClaimableUsers {
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
 
     #[returns(ExchangeRatesResponse)]
     ExchangeRates {
@@ -334,6 +774,50 @@ pub enum QueryMsg {
         start_after: Option<u64>,
         limit: Option<u32>,
     },
+
+    /// Simulates an instant unbond of the ustake amount. Response: `InstantUnbondQuoteResponse`
+    #[returns(InstantUnbondQuoteResponse)]
+    InstantUnbondQuote {
+        ustake_amount: Uint128,
+    },
+
+    /// Detected slashings, newest first. Response: `SlashingEventsResponse`
+    #[returns(SlashingEventsResponse)]
+    SlashingEvents {
+        // start after the provided event id
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    },
+
+    /// Active unbonding entries of each validator. Response: `UnbondingEntriesResponse`
+    #[returns(UnbondingEntriesResponse)]
+    UnbondingEntries {},
+
+    /// Simulates a harvest with the provided or preset withdrawals and stages.
+    /// Response: `SimulateHarvestResponse`
+    #[returns(SimulateHarvestResponse)]
+    SimulateHarvest {
+        // specifies which validators should be harvested, by default all delegations
+        validators: Option<Vec<String>>,
+        withdrawals: Option<Vec<(WithdrawType, DenomType)>>,
+        stages: Option<Vec<Vec<SingleSwapConfig>>>,
+    },
+
+    /// Holder vote of a proposal with its current tally. Response: `HolderVoteResponse`
+    #[returns(HolderVoteResponse)]
+    HolderVote {
+        proposal_id: u64,
+    },
+
+    /// Configured price feeds with the last pushed prices. Response: `PriceFeedsResponse`
+    #[returns(PriceFeedsResponse)]
+    PriceFeeds {},
+
+    /// Referred stake and rewards of a referrer. Response: `ReferralInfoResponse`
+    #[returns(ReferralInfoResponse)]
+    ReferralInfo {
+        referrer: String,
+    },
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
@@ -375,6 +859,22 @@ pub struct ConfigResponse {
     pub delegation_strategy: DelegationStrategy, //<String>,
     /// Update the vote_operator
     pub vote_operator: Option<String>,
+    /// Config of the instant unbond reserve
+    pub instant_unbond_config: Option<InstantUnbondConfig>,
+    /// Specifies whether slashed validators are removed from the whitelist
+    pub remove_slashed_validators: bool,
+    /// Maximum number of concurrent unbonding entries per validator
+    pub max_unbonding_entries: u32,
+    /// Account that can pause bond, unbond and harvest
+    pub guardian: Option<String>,
+    /// Exchange rate and volume limits
+    pub circuit_breaker: Option<CircuitBreakerConfig>,
+    /// Actions that are currently paused
+    pub paused: PauseState,
+    /// Share of the protocol fee on referred bonds that is accrued to the referrer
+    pub referral_share: Decimal,
+    /// Permissionless rebalances are disabled if not set
+    pub rebalance_config: Option<RebalanceConfig>,
 }
 
 #[cw_serde]
@@ -399,6 +899,14 @@ pub struct StateResponse {
 pub struct WantedDelegationsResponse {
     pub tune_time_period: Option<(u64, u64)>,
     pub delegations: Vec<(String, Uint128)>,
+    /// breakdown of the delegations for each part of a composite strategy
+    pub parts: Option<Vec<WantedDelegationsPart>>,
+}
+
+#[cw_serde]
+pub struct WantedDelegationsPart {
+    pub weight_bps: u16,
+    pub delegations: Vec<(String, Uint128)>,
 }
 
 #[cw_serde]
@@ -406,6 +914,57 @@ pub struct WantedDelegationsShare {
     pub tune_time: u64,
     pub tune_period: u64,
     pub shares: Vec<(String, Decimal)>,
+    /// shares of each part of a composite strategy with its weight in bps
+    #[serde(default)]
+    pub parts: Option<Vec<(u16, Vec<(String, Decimal)>)>>,
+}
+
+impl WantedDelegationsShare {
+    /// Merges the shares of all parts by their weight
+    pub fn merge_parts(
+        parts: &[(u16, Vec<(String, Decimal)>)],
+    ) -> StdResult<Vec<(String, Decimal)>> {
+        let mut merged: Vec<(String, Decimal)> = vec![];
+        for (weight_bps, shares) in parts {
+            let weight = BasicPoints::try_from(*weight_bps)?.decimal();
+            for (validator, share) in shares {
+                let share = weight.checked_mul(*share)?;
+                match merged.iter_mut().find(|(v, _)| v == validator) {
+                    Some((_, total)) => *total = total.checked_add(share)?,
+                    None => merged.push((validator.clone(), share)),
+                }
+            }
+        }
+        Ok(merged)
+    }
+
+    /// Wanted delegations of each part of a composite strategy based on the staked amount
+    pub fn get_parts_delegations(
+        &self,
+        utoken_staked: Uint128,
+    ) -> StdResult<Option<Vec<WantedDelegationsPart>>> {
+        self.parts
+            .as_ref()
+            .map(|parts| {
+                parts
+                    .iter()
+                    .map(|(weight_bps, shares)| {
+                        let weight = BasicPoints::try_from(*weight_bps)?.decimal();
+                        let part_staked = weight.checked_mul_uint(utoken_staked)?;
+                        Ok(WantedDelegationsPart {
+                            weight_bps: *weight_bps,
+                            delegations: shares
+                                .iter()
+                                .map(|(validator, share)| {
+                                    Ok((validator.clone(), share.checked_mul_uint(part_staked)?))
+                                })
+                                .collect::<StdResult<Vec<_>>>()?,
+                        })
+                    })
+                    .collect::<StdResult<Vec<_>>>()
+            })
+            .transpose()
+    }
 }
 
 #[cw_serde]
@@ -434,6 +993,98 @@ pub struct FeeConfig {
     pub protocol_fee_contract: Addr,
     /// Fees that are being applied during reinvest of staking rewards
     pub protocol_reward_fee: Decimal, // "1 is 100%, 0.05 is 5%"
+    /// Recipients sharing the protocol fee. If empty, the whole fee is sent to protocol_fee_contract.
+    #[serde(default)]
+    pub fee_recipients: Vec<FeeRecipient<Addr>>,
+}
+
+impl FeeConfig {
+    pub fn recipients(&self) -> Vec<FeeRecipient<Addr>> {
+        if self.fee_recipients.is_empty() {
+            vec![FeeRecipient {
+                address: self.protocol_fee_contract.clone(),
+                weight: Decimal::one(),
+                delivery: None,
+            }]
+        } else {
+            self.fee_recipients.clone()
+        }
+    }
+
+    /// Splits the fee by the weights of the recipients, the last recipient receives the rounding
+    /// remainder. Recipients with a zero share are skipped.
+    pub fn split_fee(&self, fee: Uint128) -> StdResult<Vec<(FeeRecipient<Addr>, Uint128)>> {
+        let recipients = self.recipients();
+        let last = recipients.len() - 1;
+        let mut remaining = fee;
+        let mut shares = vec![];
+
+        for (index, recipient) in recipients.into_iter().enumerate() {
+            let share = if index == last {
+                remaining
+            } else {
+                recipient.weight.checked_mul_uint(fee)?.min(remaining)
+            };
+            remaining -= share;
+
+            if !share.is_zero() {
+                shares.push((recipient, share));
+            }
+        }
+
+        Ok(shares)
+    }
+}
+
+#[cw_serde]
+pub enum FeeDelivery {
+    /// The fee is sent in the denom it was collected in
+    Native,
+    /// utoken fees are delegated and the minted stake token is sent instead
+    Staked,
+}
+
+#[cw_serde]
+pub struct FeeRecipient<T = String> {
+    pub address: T,
+    /// Share of the protocol fee, all weights need to sum up to 100%
+    pub weight: Decimal,
+    /// How the fee is delivered, defaults to `Native`
+    pub delivery: Option<FeeDelivery>,
+}
+
+impl FeeRecipient<Addr> {
+    pub fn is_staked(&self) -> bool {
+        self.delivery == Some(FeeDelivery::Staked)
+    }
+}
+
+pub fn validate_fee_recipients(
+    api: &dyn Api,
+    recipients: Vec<FeeRecipient>,
+) -> StdResult<Vec<FeeRecipient<Addr>>> {
+    let mut total = Decimal::zero();
+    let recipients = recipients
+        .into_iter()
+        .map(|recipient| {
+            if recipient.weight.is_zero() {
+                return Err(StdError::generic_err("fee recipient weight can't be zero"));
+            }
+            total = total.checked_add(recipient.weight)?;
+
+            Ok(FeeRecipient {
+                address: api.addr_validate(&recipient.address)?,
+                weight: recipient.weight,
+                delivery: recipient.delivery,
+            })
+        })
+        .collect::<StdResult<Vec<_>>>()?;
+
+    if !recipients.is_empty() && total != Decimal::one() {
+        return Err(StdError::generic_err("sum of fee recipient weights is not 1"));
+    }
+
+    Ok(recipients)
 }
 
 #[cw_serde]
@@ -477,6 +1128,13 @@ impl From<UnbondRequest> for UnbondRequestsByBatchResponseItem {
     }
 }
 
+#[cw_serde]
+pub struct ClaimableUsersResponseItem {
+    pub user: String,
+    /// Amount of Token that can be withdrawn for the user
+    pub utoken_claimable: Uint128,
+}
+
 #[cw_serde]
 pub struct UnbondRequestsByUserResponseItem {
     /// ID of the batch
@@ -518,6 +1176,161 @@ pub struct ExchangeRatesResponse {
     pub apr: Option<Decimal>,
 }
 
+#[cw_serde]
+pub struct InstantUnbondQuoteResponse {
+    /// Amount of utoken the ustake is worth at the current exchange rate
+    pub utoken_amount: Uint128,
+    /// Fee applied to the instant unbond
+    pub fee: Decimal,
+    /// Amount of utoken kept by the reserve
+    pub fee_amount: Uint128,
+    /// Amount of utoken received by the user
+    pub receive_amount: Uint128,
+    /// Amount of utoken currently available in the reserve
+    pub reserve: Uint128,
+}
+
+#[cw_serde]
+pub struct SlashingEvent {
+    /// utoken of the slashed asset
+    pub asset: String,
+    /// Validator that has been slashed
+    pub validator: String,
+    /// Amount of utoken that was slashed
+    pub amount: Uint128,
+    /// Time when the slashing was detected
+    pub time: u64,
+    /// Exchange rate before the slashing
+    pub rate_before: Decimal,
+    /// Exchange rate after the slashing
+    pub rate_after: Decimal,
+}
+
+#[cw_serde]
+pub struct SlashingEventsResponse {
+    pub events: Vec<(u64, SlashingEvent)>,
+}
+
+#[cw_serde]
+pub struct UnbondingEntriesResponse {
+    /// Maximum number of concurrent unbonding entries per validator
+    pub max_entries: u32,
+    pub validators: Vec<ValidatorUnbondingEntries>,
+}
+
+#[cw_serde]
+pub struct ValidatorUnbondingEntries {
+    pub validator: String,
+    /// Estimated completion times of the active unbonding entries, in seconds
+    pub completion_times: Vec<u64>,
+}
+
+#[cw_serde]
+pub struct SimulateHarvestResponse {
+    /// Pending rewards that are withdrawn from the validators
+    pub rewards: Vec<Coin>,
+    /// Expected amount of utoken delegated after the harvest
+    pub utoken_bonded: Uint128,
+    /// Expected amount of utoken added to the instant unbond reserve
+    pub utoken_reserved: Uint128,
+    /// Expected amount of ustake burned after the harvest
+    pub ustake_burned: Uint128,
+    /// Expected protocol fees sent, by denom. utoken fees of recipients with staked delivery are
+    /// included as the minted stake token. Referral rewards and rebalance bounties paid out of the
+    /// utoken fee are not included.
+    pub fees: Vec<(String, Uint128)>,
+    /// Expected exchange rate after the harvest
+    pub exchange_rate: Decimal,
+}
+
+#[cw_serde]
+pub struct HolderBalance {
+    /// Time in seconds since the holder balances are tracked when the balance was stored, balances
+    /// of a previous tracking period are outdated
+    pub tracked_since: u64,
+    pub amount: Uint128,
+}
+
+#[cw_serde]
+pub struct HolderProposal {
+    /// Time in seconds of the balances used as voting power
+    pub snapshot_time: u64,
+    /// Time in seconds until holders can vote and the tally can be submitted
+    pub end_time: u64,
+    /// Voting power for each option
+    pub tally: Vec<(VoteOption, Uint128)>,
+}
+
+#[cw_serde]
+pub struct HolderVote {
+    pub power: Uint128,
+    pub votes: Vec<(Decimal, VoteOption)>,
+}
+
+#[cw_serde]
+pub struct HolderVoteResponse {
+    pub proposal_id: u64,
+    pub snapshot_time: u64,
+    pub end_time: u64,
+    pub tally: Vec<(VoteOption, Uint128)>,
+    pub total_power: Uint128,
+}
+
+#[cw_serde]
+pub struct PriceFeedsResponse {
+    pub feeds: Vec<PriceFeedResponse>,
+}
+
+#[cw_serde]
+pub struct PriceFeedResponse {
+    pub denom: String,
+    pub feed: PriceFeed,
+    /// Last pushed price with its time in seconds, only used by `Pushed` feeds
+    pub pushed_price: Option<(Decimal, u64)>,
+}
+
+#[cw_serde]
+#[derive(Default)]
+pub struct ReferralPool {
+    /// Referred stake of all referrers
+    pub total_shares: Uint128,
+    /// Accrued utoken rewards per referred ustake
+    pub reward_index: Decimal,
+    /// Accrued utoken rewards that are not claimed yet and are held by the contract
+    pub total_pending: Uint128,
+}
+
+#[cw_serde]
+#[derive(Default)]
+pub struct ReferrerInfo {
+    /// Referred stake that is still held by the referred users
+    pub shares: Uint128,
+    /// Reward index when the rewards were last updated
+    pub reward_index: Decimal,
+    /// utoken rewards that have not been claimed yet
+    pub pending_rewards: Uint128,
+    /// utoken rewards claimed in total
+    pub claimed_rewards: Uint128,
+}
+
+#[cw_serde]
+pub struct Referral {
+    pub referrer: Addr,
+    /// Stake minted by bonds referred by the referrer
+    pub shares: Uint128,
+}
+
+#[cw_serde]
+pub struct ReferralInfoResponse {
+    pub referrer: String,
+    /// Referred stake that is still held by the referred users
+    pub referred_ustake: Uint128,
+    /// utoken rewards that can be claimed
+    pub pending_rewards: Uint128,
+    /// utoken rewards claimed in total
+    pub claimed_rewards: Uint128,
+}
+
 #[cw_serde]
 pub enum ClaimType {
     Default(String),
@@ -4,6 +4,7 @@ pub mod alliance_lst;
 pub mod amp_gauges;
 pub mod arb_vault;
 pub mod astroport_farm;
+pub mod circuit_breaker;
 pub mod compound_proxy;
 pub mod constants;
 pub mod emp_gauges;
@@ -12,9 +13,11 @@ pub mod governance_helper;
 pub mod helper;
 pub mod helpers;
 pub mod hub;
+pub mod price_oracle;
 pub mod prop_gauges;
 pub mod querier;
 pub mod restake_gauges;
+pub mod uptime_oracle;
 pub mod voting_escrow;
 
 mod extensions {
@@ -0,0 +1,35 @@
+use cosmwasm_schema::{cw_serde, QueryResponses};
+use cosmwasm_std::{Decimal, QuerierWrapper, StdResult};
+use eris_chain_adapter::types::CustomQueryType;
+
+/// Query interface of a TWAP oracle reporting the price of a denom
+#[cw_serde]
+#[derive(QueryResponses)]
+pub enum QueryMsg {
+    /// Time weighted price of the denom. Response: `PriceResponse`
+    #[returns(PriceResponse)]
+    Price {
+        denom: String,
+    },
+}
+
+#[cw_serde]
+pub struct PriceResponse {
+    pub price: Decimal,
+    /// Time of the last price update in seconds
+    pub updated_at: u64,
+}
+
+/// Queries the price of the denom from the oracle.
+pub fn get_price(
+    querier: &QuerierWrapper<CustomQueryType>,
+    price_oracle_addr: impl Into<String>,
+    denom: impl Into<String>,
+) -> StdResult<PriceResponse> {
+    querier.query_wasm_smart(
+        price_oracle_addr,
+        &QueryMsg::Price {
+            denom: denom.into(),
+        },
+    )
+}
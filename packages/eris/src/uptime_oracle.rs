@@ -0,0 +1,36 @@
+use std::collections::HashMap;
+
+use cosmwasm_schema::{cw_serde, QueryResponses};
+use cosmwasm_std::{Decimal, QuerierWrapper, StdResult};
+use eris_chain_adapter::types::CustomQueryType;
+
+/// Query interface of an oracle reporting the uptime of validators
+#[cw_serde]
+#[derive(QueryResponses)]
+pub enum QueryMsg {
+    /// Uptime of each validator between 0 and 1. Response: `UptimesResponse`
+    #[returns(UptimesResponse)]
+    Uptimes {
+        validators: Vec<String>,
+    },
+}
+
+#[cw_serde]
+pub struct UptimesResponse {
+    pub uptimes: Vec<(String, Decimal)>,
+}
+
+/// Queries the uptimes of the validators. Validators unknown to the oracle are not returned.
+pub fn get_uptimes(
+    querier: &QuerierWrapper<CustomQueryType>,
+    uptime_oracle_addr: impl Into<String>,
+    validators: &[String],
+) -> StdResult<HashMap<String, Decimal>> {
+    let response: UptimesResponse = querier.query_wasm_smart(
+        uptime_oracle_addr,
+        &QueryMsg::Uptimes {
+            validators: validators.to_vec(),
+        },
+    )?;
+    Ok(response.uptimes.into_iter().collect())
+}